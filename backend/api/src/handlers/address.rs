@@ -0,0 +1,202 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use smart_erp_core::models::address::*;
+use infrastructure::db::address::{Party, PostgresAddressRepository};
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_customer_addresses(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PartyAddress>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let addresses = repo.list_addresses(tenant_id, Party::Customer(id)).await?;
+    Ok(Json(addresses))
+}
+
+pub async fn create_customer_address(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateAddress>,
+) -> Result<Json<PartyAddress>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let address = repo.create_address(tenant_id, Party::Customer(id), payload).await?;
+    Ok(Json(address))
+}
+
+pub async fn list_customer_contacts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PartyContact>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let contacts = repo.list_contacts(tenant_id, Party::Customer(id)).await?;
+    Ok(Json(contacts))
+}
+
+pub async fn create_customer_contact(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateContact>,
+) -> Result<Json<PartyContact>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let contact = repo.create_contact(tenant_id, Party::Customer(id), payload).await?;
+    Ok(Json(contact))
+}
+
+pub async fn list_supplier_addresses(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PartyAddress>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let addresses = repo.list_addresses(tenant_id, Party::Supplier(id)).await?;
+    Ok(Json(addresses))
+}
+
+pub async fn create_supplier_address(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateAddress>,
+) -> Result<Json<PartyAddress>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let address = repo.create_address(tenant_id, Party::Supplier(id), payload).await?;
+    Ok(Json(address))
+}
+
+pub async fn list_supplier_contacts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PartyContact>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let contacts = repo.list_contacts(tenant_id, Party::Supplier(id)).await?;
+    Ok(Json(contacts))
+}
+
+pub async fn create_supplier_contact(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateContact>,
+) -> Result<Json<PartyContact>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let contact = repo.create_contact(tenant_id, Party::Supplier(id), payload).await?;
+    Ok(Json(contact))
+}
+
+pub async fn list_employee_addresses(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PartyAddress>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let addresses = repo.list_addresses(tenant_id, Party::Employee(id)).await?;
+    Ok(Json(addresses))
+}
+
+pub async fn create_employee_address(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateAddress>,
+) -> Result<Json<PartyAddress>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let address = repo.create_address(tenant_id, Party::Employee(id), payload).await?;
+    Ok(Json(address))
+}
+
+pub async fn list_employee_contacts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PartyContact>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let contacts = repo.list_contacts(tenant_id, Party::Employee(id)).await?;
+    Ok(Json(contacts))
+}
+
+pub async fn create_employee_contact(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateContact>,
+) -> Result<Json<PartyContact>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let contact = repo.create_contact(tenant_id, Party::Employee(id), payload).await?;
+    Ok(Json(contact))
+}
+
+pub async fn update_address(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateAddress>,
+) -> Result<Json<PartyAddress>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let address = repo.update_address(tenant_id, id, payload).await?;
+    Ok(Json(address))
+}
+
+pub async fn delete_address(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    repo.delete_address(tenant_id, id).await?;
+    Ok(Json(serde_json::json!({"success": true})))
+}
+
+pub async fn update_contact(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateContact>,
+) -> Result<Json<PartyContact>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    let contact = repo.update_contact(tenant_id, id, payload).await?;
+    Ok(Json(contact))
+}
+
+pub async fn delete_contact(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresAddressRepository::new(state.pool);
+    repo.delete_contact(tenant_id, id).await?;
+    Ok(Json(serde_json::json!({"success": true})))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -0,0 +1,186 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::approval::*;
+use infrastructure::db::approval::PostgresApprovalRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_rules(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ApprovalRule>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let rules = repo.list_rules(tenant_id).await?;
+    Ok(Json(rules))
+}
+
+pub async fn create_rule(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateApprovalRule>,
+) -> Result<Json<ApprovalRule>, AppError> {
+    require_admin(&claims)?;
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let rule = repo.create_rule(tenant_id, payload).await?;
+    Ok(Json(rule))
+}
+
+pub async fn update_rule(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateApprovalRule>,
+) -> Result<Json<ApprovalRule>, AppError> {
+    require_admin(&claims)?;
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let rule = repo.update_rule(tenant_id, id, payload).await?;
+    Ok(Json(rule))
+}
+
+pub async fn list_requests(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ApprovalRequestQuery>,
+) -> Result<Json<Vec<ApprovalRequest>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let requests = repo.list_requests(tenant_id, query, claims.sub, &claims.role).await?;
+    Ok(Json(requests))
+}
+
+pub async fn get_request(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApprovalRequestDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let request = repo.get_request(tenant_id, id).await?;
+    Ok(Json(request))
+}
+
+pub async fn request_approval(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<RequestApproval>,
+) -> Result<Json<ApprovalRequestDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let request = repo.request_approval(tenant_id, payload, claims.sub).await?;
+    Ok(Json(request))
+}
+
+pub async fn approve_request(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ApprovalDecision>,
+) -> Result<Json<ApprovalRequestDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let request = repo.approve(tenant_id, id, claims.sub, &claims.role, payload).await?;
+    Ok(Json(request))
+}
+
+pub async fn reject_request(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ApprovalDecision>,
+) -> Result<Json<ApprovalRequestDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let request = repo.reject(tenant_id, id, claims.sub, &claims.role, payload).await?;
+    Ok(Json(request))
+}
+
+pub async fn comment_request(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ApprovalDecision>,
+) -> Result<Json<ApprovalRequestDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let request = repo.comment(tenant_id, id, claims.sub, payload).await?;
+    Ok(Json(request))
+}
+
+pub async fn cancel_request(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ApprovalDecision>,
+) -> Result<Json<ApprovalRequestDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let request = repo.cancel(tenant_id, id, claims.sub, &claims.role, payload).await?;
+    Ok(Json(request))
+}
+
+pub async fn list_delegations(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ApprovalDelegation>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let delegations = repo.list_delegations(tenant_id).await?;
+    Ok(Json(delegations))
+}
+
+pub async fn create_delegation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateApprovalDelegation>,
+) -> Result<Json<ApprovalDelegation>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let delegation = repo.create_delegation(tenant_id, payload, claims.sub).await?;
+    Ok(Json(delegation))
+}
+
+pub async fn revoke_delegation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApprovalDelegation>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresApprovalRepository::new(state.pool);
+    let delegation = repo.revoke_delegation(tenant_id, id, claims.sub, &claims.role).await?;
+    Ok(Json(delegation))
+}
+
+fn require_admin(claims: &Claims) -> Result<(), AppError> {
+    if claims.role != "ADMIN" {
+        return Err(AppError(smart_erp_core::error::Error::BusinessRule(
+            "Only administrators can manage approval rules".to_string()
+        )));
+    }
+    Ok(())
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
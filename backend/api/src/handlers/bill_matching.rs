@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::bill_matching::*;
+use infrastructure::db::bill_matching::PostgresBillMatchingRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn get_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PurchaseMatchSettings>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresBillMatchingRepository::new(state.pool);
+    let settings = repo.get_settings(tenant_id).await?;
+    Ok(Json(settings))
+}
+
+pub async fn update_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdatePurchaseMatchSettings>,
+) -> Result<Json<PurchaseMatchSettings>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresBillMatchingRepository::new(state.pool);
+    let settings = repo.update_settings(tenant_id, payload).await?;
+    Ok(Json(settings))
+}
+
+pub async fn create_bill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateBillFromPurchase>,
+) -> Result<Json<BillMatchDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresBillMatchingRepository::new(state.pool);
+    let bill = repo.create_bill(tenant_id, payload).await?;
+    Ok(Json(bill))
+}
+
+pub async fn get_match(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(bill_id): Path<Uuid>,
+) -> Result<Json<BillMatchDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresBillMatchingRepository::new(state.pool);
+    let bill = repo.get_match(tenant_id, bill_id).await?;
+    Ok(Json(bill))
+}
+
+pub async fn approve_match(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(bill_id): Path<Uuid>,
+) -> Result<Json<BillMatchDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresBillMatchingRepository::new(state.pool);
+    let bill = repo.approve_match(tenant_id, bill_id, claims.sub).await?;
+    Ok(Json(bill))
+}
+
+pub async fn list_payments(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(bill_id): Path<Uuid>,
+) -> Result<Json<Vec<BillPayment>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresBillMatchingRepository::new(state.pool);
+    let payments = repo.list_payments(tenant_id, bill_id).await?;
+    Ok(Json(payments))
+}
+
+pub async fn pay_bill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(bill_id): Path<Uuid>,
+    Json(payload): Json<CreateBillPayment>,
+) -> Result<Json<BillPayment>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresBillMatchingRepository::new(state.pool);
+    let payment = repo.pay_bill(tenant_id, bill_id, payload).await?;
+    Ok(Json(payment))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -0,0 +1,151 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::compliance::*;
+use infrastructure::db::compliance::PostgresComplianceRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_substances(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<RestrictedSubstance>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let substances = repo.list_substances(tenant_id).await?;
+    Ok(Json(substances))
+}
+
+pub async fn create_substance(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateRestrictedSubstance>,
+) -> Result<Json<RestrictedSubstance>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let substance = repo.create_substance(tenant_id, payload).await?;
+    Ok(Json(substance))
+}
+
+pub async fn list_chemicals(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Chemical>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let chemicals = repo.list_chemicals(tenant_id).await?;
+    Ok(Json(chemicals))
+}
+
+pub async fn set_chemical(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(product_id): Path<Uuid>,
+    Json(payload): Json<SetChemical>,
+) -> Result<Json<Vec<ChemicalSubstance>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let substances = repo.set_chemical(tenant_id, product_id, payload).await?;
+    Ok(Json(substances))
+}
+
+pub async fn list_lots(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ChemicalLotQuery>,
+) -> Result<Json<Vec<ChemicalLot>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let lots = repo.list_lots(tenant_id, query).await?;
+    Ok(Json(lots))
+}
+
+pub async fn get_lot(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(lot_id): Path<Uuid>,
+) -> Result<Json<ChemicalLotDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let lot = repo.get_lot(tenant_id, lot_id).await?;
+    Ok(Json(lot))
+}
+
+pub async fn create_lot(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateChemicalLot>,
+) -> Result<Json<ChemicalLot>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let lot = repo.create_lot(tenant_id, payload).await?;
+    Ok(Json(lot))
+}
+
+pub async fn work_order_chemicals(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<Vec<ChemicalConsumption>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let consumptions = repo.work_order_chemicals(tenant_id, work_order_id).await?;
+    Ok(Json(consumptions))
+}
+
+pub async fn list_disposals(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<WasteDisposalQuery>,
+) -> Result<Json<Vec<WasteDisposal>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let disposals = repo.list_disposals(tenant_id, query).await?;
+    Ok(Json(disposals))
+}
+
+pub async fn get_disposal(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(disposal_id): Path<Uuid>,
+) -> Result<Json<WasteDisposalDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let disposal = repo.get_disposal(tenant_id, disposal_id).await?;
+    Ok(Json(disposal))
+}
+
+pub async fn create_disposal(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateWasteDisposal>,
+) -> Result<Json<WasteDisposalDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let disposal = repo.create_disposal(tenant_id, payload, claims.sub).await?;
+    Ok(Json(disposal))
+}
+
+pub async fn compliance_report(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ComplianceReportQuery>,
+) -> Result<Json<ComplianceReport>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresComplianceRepository::new(state.pool);
+    let report = repo.compliance_report(tenant_id, query).await?;
+    Ok(Json(report))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use smart_erp_core::models::costing::*;
+use infrastructure::db::costing::PostgresCostingRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn get_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<InventorySettings>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresCostingRepository::new(state.pool);
+    let settings = repo.get_settings(tenant_id).await?;
+    Ok(Json(settings))
+}
+
+pub async fn update_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateInventorySettings>,
+) -> Result<Json<InventorySettings>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresCostingRepository::new(state.pool);
+    let settings = repo.update_settings(tenant_id, payload).await?;
+    Ok(Json(settings))
+}
+
+pub async fn update_standard_cost(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(product_id): Path<Uuid>,
+    Json(payload): Json<UpdateStandardCost>,
+) -> Result<Json<ValuationLine>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresCostingRepository::new(state.pool);
+    let line = repo.update_standard_cost(tenant_id, product_id, payload).await?;
+    Ok(Json(line))
+}
+
+pub async fn list_cost_layers(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(product_id): Path<Uuid>,
+) -> Result<Json<Vec<CostLayer>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresCostingRepository::new(state.pool);
+    let layers = repo.list_layers(tenant_id, product_id).await?;
+    Ok(Json(layers))
+}
+
+pub async fn inventory_valuation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<InventoryValuation>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresCostingRepository::new(state.pool);
+    let valuation = repo.valuation(tenant_id).await?;
+    Ok(Json(valuation))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
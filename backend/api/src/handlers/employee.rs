@@ -1,7 +1,7 @@
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::http::HeaderMap;
 use axum::Json;
-use smart_erp_core::models::employee::{Employee, CreateEmployee};
+use smart_erp_core::models::employee::{Employee, CreateEmployee, PayrollHours, PayrollHoursQuery};
 use crate::error::AppError;
 use crate::state::AppState;
 use infrastructure::db::employee::PostgresEmployeeRepository;
@@ -39,6 +39,17 @@ pub async fn delete_employee(
     Ok(Json(serde_json::json!({"success": true})))
 }
 
+pub async fn payroll_hours(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<PayrollHoursQuery>,
+) -> Result<Json<Vec<PayrollHours>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresEmployeeRepository::new(state.pool);
+    let hours = repo.payroll_hours(tenant_id, query).await?;
+    Ok(Json(hours))
+}
+
 fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
     headers
         .get("x-tenant-id")
@@ -0,0 +1,50 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use smart_erp_core::models::landed_cost::*;
+use infrastructure::db::landed_cost::PostgresLandedCostRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_allocations(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<LandedCostAllocation>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresLandedCostRepository::new(state.pool);
+    let allocations = repo.list_allocations(tenant_id).await?;
+    Ok(Json(allocations))
+}
+
+pub async fn get_allocation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(allocation_id): Path<Uuid>,
+) -> Result<Json<LandedCostAllocationDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresLandedCostRepository::new(state.pool);
+    let allocation = repo.get_allocation(tenant_id, allocation_id).await?;
+    Ok(Json(allocation))
+}
+
+pub async fn allocate(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateLandedCostAllocation>,
+) -> Result<Json<LandedCostAllocationDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresLandedCostRepository::new(state.pool);
+    let allocation = repo.allocate(tenant_id, payload).await?;
+    Ok(Json(allocation))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
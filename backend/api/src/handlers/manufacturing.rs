@@ -1,11 +1,14 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
-    Json,
+    Extension, Json,
 };
+use smart_erp_core::models::auth::Claims;
 use smart_erp_core::models::manufacturing::{
-    CreateRecipe, CreateWorkOrder, ManufacturingService, Recipe, WorkOrder,
+    ApproveRecipe, CreateRecipe, CreateRecipeVersion, CreateWorkOrder, ManufacturingService, Recipe, RecipeDetail,
+    UpdateRecipeCosts, UpdateRecipeLeadTime, WorkOrder,
 };
+use smart_erp_core::models::bom::{BomQuery, CostRollup, IndentedBom, WhereUsedLine};
 use infrastructure::db::manufacturing::PostgresManufacturingRepository;
 use uuid::Uuid;
 use crate::state::AppState;
@@ -42,6 +45,53 @@ pub async fn create_recipe(
     Ok(Json(recipe))
 }
 
+pub async fn get_recipe(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+) -> Result<Json<RecipeDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let recipe = repo.get_recipe(tenant_id, recipe_id).await?;
+    Ok(Json(recipe))
+}
+
+pub async fn list_recipe_versions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+) -> Result<Json<Vec<Recipe>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let versions = repo.list_recipe_versions(tenant_id, recipe_id).await?;
+    Ok(Json(versions))
+}
+
+pub async fn create_recipe_version(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+    Json(payload): Json<CreateRecipeVersion>,
+) -> Result<Json<RecipeDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let recipe = repo.create_recipe_version(tenant_id, recipe_id, payload).await?;
+    Ok(Json(recipe))
+}
+
+pub async fn approve_recipe(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(recipe_id): Path<Uuid>,
+    Json(payload): Json<ApproveRecipe>,
+) -> Result<Json<Recipe>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let recipe = repo.approve_recipe(tenant_id, recipe_id, payload, claims.sub).await?;
+    Ok(Json(recipe))
+}
+
 pub async fn create_work_order(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -64,6 +114,74 @@ pub async fn complete_work_order(
     Ok(Json(order))
 }
 
+pub async fn update_recipe_costs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+    Json(payload): Json<UpdateRecipeCosts>,
+) -> Result<Json<Recipe>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let recipe = repo.update_recipe_costs(tenant_id, recipe_id, payload).await?;
+    Ok(Json(recipe))
+}
+
+pub async fn update_recipe_lead_time(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+    Json(payload): Json<UpdateRecipeLeadTime>,
+) -> Result<Json<Recipe>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let recipe = repo.update_recipe_lead_time(tenant_id, recipe_id, payload).await?;
+    Ok(Json(recipe))
+}
+
+pub async fn explode_bom(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+    Query(query): Query<BomQuery>,
+) -> Result<Json<IndentedBom>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let bom = repo.explode_bom(tenant_id, recipe_id, query.batches()).await?;
+    Ok(Json(bom))
+}
+
+pub async fn where_used(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(product_id): Path<Uuid>,
+) -> Result<Json<Vec<WhereUsedLine>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let lines = repo.where_used(tenant_id, product_id).await?;
+    Ok(Json(lines))
+}
+
+pub async fn roll_up_cost(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+) -> Result<Json<CostRollup>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let rollup = repo.roll_up_cost(tenant_id, recipe_id).await?;
+    Ok(Json(rollup))
+}
+
+pub async fn roll_up_all_costs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CostRollup>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresManufacturingRepository::new(state.pool);
+    let rollups = repo.roll_up_all_costs(tenant_id).await?;
+    Ok(Json(rollups))
+}
+
 fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
     headers
         .get("x-tenant-id")
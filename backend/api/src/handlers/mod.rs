@@ -8,3 +8,21 @@ pub mod chart_of_accounts;
 pub mod employee;
 pub mod transactions;
 pub mod reports;
+pub mod replenishment;
+pub mod stock_count;
+pub mod stock_ledger;
+pub mod costing;
+pub mod landed_cost;
+pub mod bill_matching;
+pub mod mrp;
+pub mod production;
+pub mod routing;
+pub mod quality;
+pub mod nesting;
+pub mod compliance;
+pub mod payment_terms;
+pub mod requisition;
+pub mod approval;
+pub mod rfq;
+pub mod vendor_credit;
+pub mod address;
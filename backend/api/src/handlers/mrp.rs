@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::mrp::*;
+use infrastructure::db::mrp::PostgresMrpRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn run_mrp(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<RunMrp>,
+) -> Result<Json<MrpRunDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresMrpRepository::new(state.pool);
+    let run = repo.run(tenant_id, payload, claims.sub).await?;
+    Ok(Json(run))
+}
+
+pub async fn list_runs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<MrpRun>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresMrpRepository::new(state.pool);
+    let runs = repo.list_runs(tenant_id).await?;
+    Ok(Json(runs))
+}
+
+pub async fn get_run(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(run_id): Path<Uuid>,
+) -> Result<Json<MrpRunDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresMrpRepository::new(state.pool);
+    let run = repo.get_run(tenant_id, run_id).await?;
+    Ok(Json(run))
+}
+
+pub async fn list_planned_orders(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PlannedOrder>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresMrpRepository::new(state.pool);
+    let orders = repo.list_planned_orders(tenant_id).await?;
+    Ok(Json(orders))
+}
+
+pub async fn firm_planned_order(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(planned_order_id): Path<Uuid>,
+    Json(payload): Json<FirmPlannedOrder>,
+) -> Result<Json<FirmedPlannedOrder>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresMrpRepository::new(state.pool);
+    let order = repo.firm(tenant_id, planned_order_id, payload).await?;
+    Ok(Json(order))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -0,0 +1,113 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::nesting::*;
+use infrastructure::db::nesting::PostgresNestingRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_pattern_pieces(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+) -> Result<Json<Vec<PatternPiece>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresNestingRepository::new(state.pool);
+    let pieces = repo.list_pattern_pieces(tenant_id, recipe_id).await?;
+    Ok(Json(pieces))
+}
+
+pub async fn set_pattern_pieces(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+    Json(payload): Json<SetPatternPieces>,
+) -> Result<Json<Vec<PatternPiece>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresNestingRepository::new(state.pool);
+    let pieces = repo.set_pattern_pieces(tenant_id, recipe_id, payload).await?;
+    Ok(Json(pieces))
+}
+
+pub async fn preview_nesting(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+    Json(payload): Json<NestRequest>,
+) -> Result<Json<NestingPreview>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresNestingRepository::new(state.pool);
+    let preview = repo.preview(tenant_id, recipe_id, payload).await?;
+    Ok(Json(preview))
+}
+
+pub async fn create_layout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(work_order_id): Path<Uuid>,
+    Json(payload): Json<NestRequest>,
+) -> Result<Json<NestingLayout>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresNestingRepository::new(state.pool);
+    let layout = repo.create_layout(tenant_id, work_order_id, payload, claims.sub).await?;
+    Ok(Json(layout))
+}
+
+pub async fn work_order_nesting(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<WorkOrderNesting>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresNestingRepository::new(state.pool);
+    let nesting = repo.work_order_nesting(tenant_id, work_order_id).await?;
+    Ok(Json(nesting))
+}
+
+pub async fn get_layout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(layout_id): Path<Uuid>,
+) -> Result<Json<NestingLayout>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresNestingRepository::new(state.pool);
+    let layout = repo.get_layout(tenant_id, layout_id).await?;
+    Ok(Json(layout))
+}
+
+pub async fn layout_svg(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(layout_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresNestingRepository::new(state.pool);
+    let svg = repo.layout_svg(tenant_id, layout_id).await?;
+    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg))
+}
+
+pub async fn record_cutting(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(layout_id): Path<Uuid>,
+    Json(payload): Json<RecordCutting>,
+) -> Result<Json<NestingLayout>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresNestingRepository::new(state.pool);
+    let layout = repo.record_cutting(tenant_id, layout_id, payload).await?;
+    Ok(Json(layout))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use smart_erp_core::models::payment_terms::*;
+use smart_erp_core::models::sales::Customer;
+use infrastructure::db::payment_terms::PostgresPaymentTermsRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_terms(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PaymentTerms>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPaymentTermsRepository::new(state.pool);
+    let terms = repo.list_terms(tenant_id).await?;
+    Ok(Json(terms))
+}
+
+pub async fn create_terms(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreatePaymentTerms>,
+) -> Result<Json<PaymentTerms>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPaymentTermsRepository::new(state.pool);
+    let terms = repo.create_terms(tenant_id, payload).await?;
+    Ok(Json(terms))
+}
+
+pub async fn update_terms(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(terms_id): Path<Uuid>,
+    Json(payload): Json<UpdatePaymentTerms>,
+) -> Result<Json<PaymentTerms>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPaymentTermsRepository::new(state.pool);
+    let terms = repo.update_terms(tenant_id, terms_id, payload).await?;
+    Ok(Json(terms))
+}
+
+pub async fn set_customer_terms(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(customer_id): Path<Uuid>,
+    Json(payload): Json<SetPaymentTerms>,
+) -> Result<Json<Customer>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPaymentTermsRepository::new(state.pool);
+    let customer = repo.set_customer_terms(tenant_id, customer_id, payload).await?;
+    Ok(Json(customer))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
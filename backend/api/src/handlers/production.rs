@@ -0,0 +1,237 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::manufacturing::WorkOrder;
+use smart_erp_core::models::production::*;
+use infrastructure::db::production::PostgresProductionRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn get_work_order(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<WorkOrderDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let detail = repo.get_work_order(tenant_id, work_order_id).await?;
+    Ok(Json(detail))
+}
+
+pub async fn start_work_order(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<WorkOrder>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let work_order = repo.start(tenant_id, work_order_id).await?;
+    Ok(Json(work_order))
+}
+
+pub async fn issue_materials(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+    Json(payload): Json<IssueMaterials>,
+) -> Result<Json<WorkOrderDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let detail = repo.issue_materials(tenant_id, work_order_id, payload).await?;
+    Ok(Json(detail))
+}
+
+pub async fn return_materials(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+    Json(payload): Json<ReturnMaterials>,
+) -> Result<Json<WorkOrderDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let detail = repo.return_materials(tenant_id, work_order_id, payload).await?;
+    Ok(Json(detail))
+}
+
+pub async fn report_production(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(work_order_id): Path<Uuid>,
+    Json(payload): Json<ReportProduction>,
+) -> Result<Json<WorkOrderDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let detail = repo.report_production(tenant_id, work_order_id, payload, claims.sub).await?;
+    Ok(Json(detail))
+}
+
+pub async fn cancel_work_order(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<WorkOrderDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let detail = repo.cancel(tenant_id, work_order_id).await?;
+    Ok(Json(detail))
+}
+
+pub async fn work_order_yield(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<WorkOrderYield>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let report = repo.work_order_yield(tenant_id, work_order_id).await?;
+    Ok(Json(report))
+}
+
+pub async fn yield_variance(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<YieldVarianceQuery>,
+) -> Result<Json<YieldVarianceReport>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let report = repo.yield_variance(tenant_id, query).await?;
+    Ok(Json(report))
+}
+
+pub async fn log_labor(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(work_order_id): Path<Uuid>,
+    Json(payload): Json<CreateLaborTimeEntry>,
+) -> Result<Json<LaborTimeEntry>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let entry = repo.log_labor(tenant_id, work_order_id, payload, claims.sub).await?;
+    Ok(Json(entry))
+}
+
+pub async fn list_labor(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<Vec<LaborTimeEntry>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let entries = repo.list_labor(tenant_id, work_order_id).await?;
+    Ok(Json(entries))
+}
+
+pub async fn clock_in(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(work_order_id): Path<Uuid>,
+    Json(payload): Json<ClockIn>,
+) -> Result<Json<LaborClock>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let clock = repo.clock_in(tenant_id, work_order_id, payload, claims.sub).await?;
+    Ok(Json(clock))
+}
+
+pub async fn clock_out(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ClockOut>,
+) -> Result<Json<LaborTimeEntry>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let entry = repo.clock_out(tenant_id, payload, claims.sub).await?;
+    Ok(Json(entry))
+}
+
+pub async fn list_clocks(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<LaborClock>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let clocks = repo.list_clocks(tenant_id).await?;
+    Ok(Json(clocks))
+}
+
+pub async fn cancel_clock(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(clock_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    repo.cancel_clock(tenant_id, clock_id).await?;
+    Ok(Json(serde_json::json!({"success": true})))
+}
+
+pub async fn labor_efficiency(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<LaborEfficiencyQuery>,
+) -> Result<Json<LaborEfficiencyReport>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let report = repo.labor_efficiency(tenant_id, query).await?;
+    Ok(Json(report))
+}
+
+pub async fn work_order_cost(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<WorkOrderCost>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let cost = repo.work_order_cost(tenant_id, work_order_id).await?;
+    Ok(Json(cost))
+}
+
+pub async fn list_overhead_rates(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<OverheadRate>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let rates = repo.list_overhead_rates(tenant_id).await?;
+    Ok(Json(rates))
+}
+
+pub async fn create_overhead_rate(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateOverheadRate>,
+) -> Result<Json<OverheadRate>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let rate = repo.create_overhead_rate(tenant_id, payload).await?;
+    Ok(Json(rate))
+}
+
+pub async fn update_overhead_rate(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(rate_id): Path<Uuid>,
+    Json(payload): Json<UpdateOverheadRate>,
+) -> Result<Json<OverheadRate>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresProductionRepository::new(state.pool);
+    let rate = repo.update_overhead_rate(tenant_id, rate_id, payload).await?;
+    Ok(Json(rate))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -1,10 +1,12 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
     Json,
 };
 use smart_erp_core::models::purchasing::{
-    CreatePurchaseOrder, CreateSupplier, PurchaseOrder, PurchasingService, Supplier,
+    CreatePurchaseOrder, CreateSupplier, PriceHistoryQuery, PurchaseOrder, PurchaseReceipt, PurchaseReceiptDetail,
+    PurchasingService, Supplier, SupplierHistory, SupplierPrice, SupplierQuery, SupplierScorecardQuery,
+    SupplierScorecardReport, UpdateSupplier,
 };
 use infrastructure::db::purchasing::PostgresPurchasingRepository;
 use uuid::Uuid;
@@ -14,13 +16,92 @@ use crate::error::AppError;
 pub async fn list_suppliers(
     State(state): State<AppState>,
     headers: HeaderMap,
+    Query(query): Query<SupplierQuery>,
 ) -> Result<Json<Vec<Supplier>>, AppError> {
     let tenant_id = get_tenant_id(&headers)?;
     let repo = PostgresPurchasingRepository::new(state.pool);
-    let suppliers = repo.list_suppliers(tenant_id).await?;
+    let suppliers = repo.list_suppliers(tenant_id, query).await?;
     Ok(Json(suppliers))
 }
 
+pub async fn get_supplier(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(supplier_id): Path<Uuid>,
+) -> Result<Json<Supplier>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let supplier = repo.get_supplier(tenant_id, supplier_id).await?;
+    Ok(Json(supplier))
+}
+
+pub async fn update_supplier(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(supplier_id): Path<Uuid>,
+    Json(payload): Json<UpdateSupplier>,
+) -> Result<Json<Supplier>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let supplier = repo.update_supplier(tenant_id, supplier_id, payload).await?;
+    Ok(Json(supplier))
+}
+
+pub async fn deactivate_supplier(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(supplier_id): Path<Uuid>,
+) -> Result<Json<Supplier>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let supplier = repo.set_supplier_active(tenant_id, supplier_id, false).await?;
+    Ok(Json(supplier))
+}
+
+pub async fn reactivate_supplier(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(supplier_id): Path<Uuid>,
+) -> Result<Json<Supplier>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let supplier = repo.set_supplier_active(tenant_id, supplier_id, true).await?;
+    Ok(Json(supplier))
+}
+
+pub async fn supplier_history(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(supplier_id): Path<Uuid>,
+) -> Result<Json<SupplierHistory>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let history = repo.supplier_history(tenant_id, supplier_id).await?;
+    Ok(Json(history))
+}
+
+pub async fn price_history(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<PriceHistoryQuery>,
+) -> Result<Json<Vec<SupplierPrice>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let prices = repo.price_history(tenant_id, query).await?;
+    Ok(Json(prices))
+}
+
+pub async fn supplier_scorecards(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SupplierScorecardQuery>,
+) -> Result<Json<SupplierScorecardReport>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let report = repo.supplier_scorecards(tenant_id, query).await?;
+    Ok(Json(report))
+}
+
 pub async fn list_purchase_orders(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -64,6 +145,27 @@ pub async fn receive_purchase_order(
     Ok(Json(order))
 }
 
+pub async fn list_receipts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PurchaseReceipt>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let receipts = repo.list_receipts(tenant_id).await?;
+    Ok(Json(receipts))
+}
+
+pub async fn get_receipt(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(receipt_id): Path<Uuid>,
+) -> Result<Json<PurchaseReceiptDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresPurchasingRepository::new(state.pool);
+    let receipt = repo.get_receipt(tenant_id, receipt_id).await?;
+    Ok(Json(receipt))
+}
+
 fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
     headers
         .get("x-tenant-id")
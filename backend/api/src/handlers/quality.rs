@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::quality::*;
+use infrastructure::db::quality::PostgresQualityRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_plans(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<InspectionPlan>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let plans = repo.list_plans(tenant_id).await?;
+    Ok(Json(plans))
+}
+
+pub async fn get_plan(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(plan_id): Path<Uuid>,
+) -> Result<Json<InspectionPlanDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let plan = repo.get_plan(tenant_id, plan_id).await?;
+    Ok(Json(plan))
+}
+
+pub async fn create_plan(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateInspectionPlan>,
+) -> Result<Json<InspectionPlanDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let plan = repo.create_plan(tenant_id, payload).await?;
+    Ok(Json(plan))
+}
+
+pub async fn update_plan(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(plan_id): Path<Uuid>,
+    Json(payload): Json<UpdateInspectionPlan>,
+) -> Result<Json<InspectionPlan>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let plan = repo.update_plan(tenant_id, plan_id, payload).await?;
+    Ok(Json(plan))
+}
+
+pub async fn list_inspections(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<InspectionQuery>,
+) -> Result<Json<Vec<QualityInspection>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let inspections = repo.list_inspections(tenant_id, query).await?;
+    Ok(Json(inspections))
+}
+
+pub async fn get_inspection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(inspection_id): Path<Uuid>,
+) -> Result<Json<InspectionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let inspection = repo.get_inspection(tenant_id, inspection_id).await?;
+    Ok(Json(inspection))
+}
+
+pub async fn create_inspection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateInspection>,
+) -> Result<Json<InspectionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let inspection = repo.create_inspection(tenant_id, payload).await?;
+    Ok(Json(inspection))
+}
+
+pub async fn record_inspection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(inspection_id): Path<Uuid>,
+    Json(payload): Json<RecordInspection>,
+) -> Result<Json<InspectionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let inspection = repo.record_inspection(tenant_id, inspection_id, payload).await?;
+    Ok(Json(inspection))
+}
+
+pub async fn dispose(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(inspection_id): Path<Uuid>,
+    Json(payload): Json<CreateDisposition>,
+) -> Result<Json<InspectionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let inspection = repo.dispose(tenant_id, inspection_id, payload, claims.sub).await?;
+    Ok(Json(inspection))
+}
+
+pub async fn list_quarantine(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<QuarantineLine>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresQualityRepository::new(state.pool);
+    let lines = repo.list_quarantine(tenant_id).await?;
+    Ok(Json(lines))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
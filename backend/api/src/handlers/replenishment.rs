@@ -0,0 +1,73 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use smart_erp_core::models::replenishment::*;
+use smart_erp_core::models::purchasing::PurchaseOrder;
+use infrastructure::db::replenishment::PostgresReplenishmentRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn get_reorder_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(product_id): Path<Uuid>,
+) -> Result<Json<ReorderSettings>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresReplenishmentRepository::new(state.pool);
+    let settings = repo.get_reorder_settings(tenant_id, product_id).await?;
+    Ok(Json(settings))
+}
+
+pub async fn update_reorder_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(product_id): Path<Uuid>,
+    Json(payload): Json<UpdateReorderSettings>,
+) -> Result<Json<ReorderSettings>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresReplenishmentRepository::new(state.pool);
+    let settings = repo.update_reorder_settings(tenant_id, product_id, payload).await?;
+    Ok(Json(settings))
+}
+
+pub async fn list_suggestions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<SupplierReplenishment>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresReplenishmentRepository::new(state.pool);
+    let suggestions = repo.suggestions(tenant_id).await?;
+    Ok(Json(suggestions))
+}
+
+pub async fn generate_purchase_orders(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<GenerateReplenishmentOrders>,
+) -> Result<Json<Vec<PurchaseOrder>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresReplenishmentRepository::new(state.pool);
+    let orders = repo.generate_purchase_orders(tenant_id, payload).await?;
+    Ok(Json(orders))
+}
+
+pub async fn low_stock_report(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<LowStockReport>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresReplenishmentRepository::new(state.pool);
+    let report = repo.low_stock_report(tenant_id).await?;
+    Ok(Json(report))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
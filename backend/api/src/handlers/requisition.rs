@@ -0,0 +1,183 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::purchasing::PurchaseOrder;
+use smart_erp_core::models::requisition::*;
+use infrastructure::db::requisition::PostgresRequisitionRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_rules(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<RequisitionApprovalRule>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let rules = repo.list_rules(tenant_id).await?;
+    Ok(Json(rules))
+}
+
+pub async fn create_rule(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateRequisitionApprovalRule>,
+) -> Result<Json<RequisitionApprovalRule>, AppError> {
+    require_admin(&claims)?;
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let rule = repo.create_rule(tenant_id, payload).await?;
+    Ok(Json(rule))
+}
+
+pub async fn update_rule(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateRequisitionApprovalRule>,
+) -> Result<Json<RequisitionApprovalRule>, AppError> {
+    require_admin(&claims)?;
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let rule = repo.update_rule(tenant_id, id, payload).await?;
+    Ok(Json(rule))
+}
+
+pub async fn list_requisitions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<RequisitionQuery>,
+) -> Result<Json<Vec<PurchaseRequisition>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let requisitions = repo.list_requisitions(tenant_id, query, &claims.role).await?;
+    Ok(Json(requisitions))
+}
+
+pub async fn get_requisition(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RequisitionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let requisition = repo.get_requisition(tenant_id, id).await?;
+    Ok(Json(requisition))
+}
+
+pub async fn create_requisition(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateRequisition>,
+) -> Result<Json<RequisitionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let requisition = repo.create_requisition(tenant_id, payload, claims.sub).await?;
+    Ok(Json(requisition))
+}
+
+pub async fn submit_requisition(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RequisitionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let requisition = repo.submit(tenant_id, id, claims.sub, &claims.role).await?;
+    Ok(Json(requisition))
+}
+
+pub async fn approve_requisition(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RequisitionDecision>,
+) -> Result<Json<RequisitionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let requisition = repo.approve(tenant_id, id, claims.sub, &claims.role, payload).await?;
+    Ok(Json(requisition))
+}
+
+pub async fn reject_requisition(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RequisitionDecision>,
+) -> Result<Json<RequisitionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let requisition = repo.reject(tenant_id, id, claims.sub, &claims.role, payload).await?;
+    Ok(Json(requisition))
+}
+
+pub async fn comment_requisition(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RequisitionDecision>,
+) -> Result<Json<RequisitionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let requisition = repo.comment(tenant_id, id, claims.sub, payload).await?;
+    Ok(Json(requisition))
+}
+
+pub async fn cancel_requisition(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RequisitionDecision>,
+) -> Result<Json<RequisitionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let requisition = repo.cancel(tenant_id, id, claims.sub, &claims.role, payload).await?;
+    Ok(Json(requisition))
+}
+
+/// Raising purchase orders is left to purchasing staff and administrators.
+pub async fn convert_requisitions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ConvertRequisitions>,
+) -> Result<Json<Vec<PurchaseOrder>>, AppError> {
+    if claims.role != "ADMIN" && claims.role != "PURCHASING" {
+        return Err(AppError(smart_erp_core::error::Error::BusinessRule(
+            "Only purchasing staff can convert requisitions".to_string()
+        )));
+    }
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRequisitionRepository::new(state.pool);
+    let orders = repo.convert(tenant_id, payload, claims.sub).await?;
+    Ok(Json(orders))
+}
+
+fn require_admin(claims: &Claims) -> Result<(), AppError> {
+    if claims.role != "ADMIN" {
+        return Err(AppError(smart_erp_core::error::Error::BusinessRule(
+            "Only administrators can manage approval rules".to_string()
+        )));
+    }
+    Ok(())
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::purchasing::PurchaseOrder;
+use smart_erp_core::models::rfq::*;
+use infrastructure::db::rfq::PostgresRfqRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_rfqs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<RfqQuery>,
+) -> Result<Json<Vec<Rfq>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRfqRepository::new(state.pool);
+    let rfqs = repo.list_rfqs(tenant_id, query).await?;
+    Ok(Json(rfqs))
+}
+
+pub async fn get_rfq(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RfqDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRfqRepository::new(state.pool);
+    let rfq = repo.get_rfq(tenant_id, id).await?;
+    Ok(Json(rfq))
+}
+
+pub async fn create_rfq(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateRfq>,
+) -> Result<Json<RfqDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRfqRepository::new(state.pool);
+    let rfq = repo.create_rfq(tenant_id, payload, claims.sub).await?;
+    Ok(Json(rfq))
+}
+
+pub async fn record_quote(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RecordQuote>,
+) -> Result<Json<RfqDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRfqRepository::new(state.pool);
+    let rfq = repo.record_quote(tenant_id, id, payload).await?;
+    Ok(Json(rfq))
+}
+
+pub async fn decline_rfq(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<DeclineRfq>,
+) -> Result<Json<RfqDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRfqRepository::new(state.pool);
+    let rfq = repo.decline(tenant_id, id, payload).await?;
+    Ok(Json(rfq))
+}
+
+pub async fn compare_quotes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<QuoteComparison>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRfqRepository::new(state.pool);
+    let comparison = repo.comparison(tenant_id, id).await?;
+    Ok(Json(comparison))
+}
+
+pub async fn award_rfq(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AwardRfq>,
+) -> Result<Json<PurchaseOrder>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRfqRepository::new(state.pool);
+    let order = repo.award(tenant_id, id, payload).await?;
+    Ok(Json(order))
+}
+
+pub async fn cancel_rfq(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Rfq>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRfqRepository::new(state.pool);
+    let rfq = repo.cancel(tenant_id, id).await?;
+    Ok(Json(rfq))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -0,0 +1,154 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use smart_erp_core::models::manufacturing::Recipe;
+use smart_erp_core::models::routing::*;
+use infrastructure::db::routing::PostgresRoutingRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_work_centers(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WorkCenter>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let work_centers = repo.list_work_centers(tenant_id).await?;
+    Ok(Json(work_centers))
+}
+
+pub async fn create_work_center(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateWorkCenter>,
+) -> Result<Json<WorkCenter>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let work_center = repo.create_work_center(tenant_id, payload).await?;
+    Ok(Json(work_center))
+}
+
+pub async fn update_work_center(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_center_id): Path<Uuid>,
+    Json(payload): Json<UpdateWorkCenter>,
+) -> Result<Json<WorkCenter>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let work_center = repo.update_work_center(tenant_id, work_center_id, payload).await?;
+    Ok(Json(work_center))
+}
+
+pub async fn list_calendar(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_center_id): Path<Uuid>,
+) -> Result<Json<Vec<CalendarException>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let calendar = repo.list_calendar(tenant_id, work_center_id).await?;
+    Ok(Json(calendar))
+}
+
+pub async fn set_calendar(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_center_id): Path<Uuid>,
+    Json(payload): Json<Vec<SetCalendarException>>,
+) -> Result<Json<Vec<CalendarException>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let calendar = repo.set_calendar(tenant_id, work_center_id, payload).await?;
+    Ok(Json(calendar))
+}
+
+pub async fn work_center_load(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_center_id): Path<Uuid>,
+    Query(query): Query<WorkCenterLoadQuery>,
+) -> Result<Json<WorkCenterLoad>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let load = repo.work_center_load(tenant_id, work_center_id, query).await?;
+    Ok(Json(load))
+}
+
+pub async fn list_routings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Routing>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let routings = repo.list_routings(tenant_id).await?;
+    Ok(Json(routings))
+}
+
+pub async fn get_routing(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(routing_id): Path<Uuid>,
+) -> Result<Json<RoutingDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let routing = repo.get_routing(tenant_id, routing_id).await?;
+    Ok(Json(routing))
+}
+
+pub async fn create_routing(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateRouting>,
+) -> Result<Json<RoutingDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let routing = repo.create_routing(tenant_id, payload).await?;
+    Ok(Json(routing))
+}
+
+pub async fn set_recipe_routing(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recipe_id): Path<Uuid>,
+    Json(payload): Json<SetRecipeRouting>,
+) -> Result<Json<Recipe>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let recipe = repo.set_recipe_routing(tenant_id, recipe_id, payload).await?;
+    Ok(Json(recipe))
+}
+
+pub async fn get_schedule(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+) -> Result<Json<WorkOrderSchedule>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let schedule = repo.get_schedule(tenant_id, work_order_id).await?;
+    Ok(Json(schedule))
+}
+
+pub async fn schedule_work_order(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(work_order_id): Path<Uuid>,
+    Json(payload): Json<ScheduleWorkOrder>,
+) -> Result<Json<WorkOrderSchedule>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresRoutingRepository::new(state.pool);
+    let schedule = repo.schedule_work_order(tenant_id, work_order_id, payload).await?;
+    Ok(Json(schedule))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
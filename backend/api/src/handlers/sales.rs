@@ -1,10 +1,12 @@
 use axum::{
     extract::{Path, State},
     http::HeaderMap,
-    Json,
+    Extension, Json,
 };
+use smart_erp_core::models::auth::Claims;
 use smart_erp_core::models::sales::{
-    CreateCustomer, CreateSalesOrder, Customer, SalesOrder, SalesService,
+    CreateCustomer, CreateSalesOrder, Customer, CustomerCenter, ReleaseCreditHold, SalesOrder,
+    SalesService, SetCreditLimit,
 };
 use smart_erp_core::models::analytics::SalesTrend;
 use infrastructure::db::sales::PostgresSalesRepository;
@@ -33,6 +35,31 @@ pub async fn create_customer(
     Ok(Json(customer))
 }
 
+pub async fn customer_center(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(customer_id): Path<Uuid>,
+) -> Result<Json<CustomerCenter>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresSalesRepository::new(state.pool);
+    let center = repo.customer_center(tenant_id, customer_id).await?;
+    Ok(Json(center))
+}
+
+pub async fn set_credit_limit(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(customer_id): Path<Uuid>,
+    Json(payload): Json<SetCreditLimit>,
+) -> Result<Json<Customer>, AppError> {
+    require_credit_manager(&claims)?;
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresSalesRepository::new(state.pool);
+    let customer = repo.set_credit_limit(tenant_id, customer_id, payload).await?;
+    Ok(Json(customer))
+}
+
 pub async fn create_sales_order(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -44,6 +71,31 @@ pub async fn create_sales_order(
     Ok(Json(order))
 }
 
+pub async fn confirm_sales_order(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(order_id): Path<Uuid>,
+) -> Result<Json<SalesOrder>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresSalesRepository::new(state.pool);
+    let order = repo.confirm_order(tenant_id, order_id).await?;
+    Ok(Json(order))
+}
+
+pub async fn release_credit_hold(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(order_id): Path<Uuid>,
+    Json(payload): Json<ReleaseCreditHold>,
+) -> Result<Json<SalesOrder>, AppError> {
+    require_credit_manager(&claims)?;
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresSalesRepository::new(state.pool);
+    let order = repo.release_hold(tenant_id, order_id, payload, claims.sub).await?;
+    Ok(Json(order))
+}
+
 pub async fn ship_sales_order(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -65,6 +117,16 @@ pub async fn get_sales_trend(
     Ok(Json(trend))
 }
 
+/// Credit limits and overrides are left to managers and administrators.
+fn require_credit_manager(claims: &Claims) -> Result<(), AppError> {
+    if claims.role != "ADMIN" && claims.role != "MANAGER" {
+        return Err(AppError(smart_erp_core::error::Error::BusinessRule(
+            "Only managers can change customer credit".to_string()
+        )));
+    }
+    Ok(())
+}
+
 fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
     headers
         .get("x-tenant-id")
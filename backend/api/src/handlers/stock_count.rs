@@ -0,0 +1,152 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::inventory::{CreateStockAdjustment, InventoryTransaction};
+use smart_erp_core::models::stock_count::*;
+use infrastructure::db::inventory::PostgresInventoryRepository;
+use infrastructure::db::stock_count::PostgresStockCountRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn adjust_stock(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateStockAdjustment>,
+) -> Result<Json<InventoryTransaction>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresInventoryRepository::new(state.pool);
+    let transaction = repo.adjust_stock(tenant_id, payload).await?;
+    Ok(Json(transaction))
+}
+
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CountSession>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let sessions = repo.list_sessions(tenant_id).await?;
+    Ok(Json(sessions))
+}
+
+pub async fn create_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateCountSession>,
+) -> Result<Json<CountSessionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let session = repo.create_session(tenant_id, payload).await?;
+    Ok(Json(session))
+}
+
+pub async fn get_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CountSessionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let session = repo.get_session_detail(tenant_id, id).await?;
+    Ok(Json(session))
+}
+
+pub async fn record_counts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RecordCounts>,
+) -> Result<Json<CountSessionDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let session = repo.record_counts(tenant_id, id, payload).await?;
+    Ok(Json(session))
+}
+
+pub async fn submit_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CountSession>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let session = repo.submit_session(tenant_id, id).await?;
+    Ok(Json(session))
+}
+
+pub async fn variance_report(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CountVarianceReport>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let report = repo.variance_report(tenant_id, id).await?;
+    Ok(Json(report))
+}
+
+pub async fn approve_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CountSession>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let session = repo.approve_session(tenant_id, id, claims.sub).await?;
+    Ok(Json(session))
+}
+
+pub async fn cancel_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CountSession>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let session = repo.cancel_session(tenant_id, id).await?;
+    Ok(Json(session))
+}
+
+pub async fn list_schedules(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CycleCountSchedule>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let schedules = repo.list_schedules(tenant_id).await?;
+    Ok(Json(schedules))
+}
+
+pub async fn create_schedule(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateCycleCountSchedule>,
+) -> Result<Json<CycleCountSchedule>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let schedule = repo.create_schedule(tenant_id, payload).await?;
+    Ok(Json(schedule))
+}
+
+pub async fn run_due_schedules(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CountSession>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockCountRepository::new(state.pool);
+    let sessions = repo.run_due_schedules(tenant_id).await?;
+    Ok(Json(sessions))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
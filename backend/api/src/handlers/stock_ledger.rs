@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use smart_erp_core::models::reports::ReportDateRange;
+use smart_erp_core::models::stock_ledger::*;
+use infrastructure::db::stock_ledger::PostgresStockLedgerRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn stock_card(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(product_id): Path<Uuid>,
+    Query(range): Query<ReportDateRange>,
+) -> Result<Json<StockCard>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockLedgerRepository::new(state.pool);
+    let card = repo.stock_card(tenant_id, product_id, range.from, range.to).await?;
+    Ok(Json(card))
+}
+
+pub async fn list_movements(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<MovementQuery>,
+) -> Result<Json<Vec<StockMovement>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockLedgerRepository::new(state.pool);
+    let movements = repo.list_movements(tenant_id, query).await?;
+    Ok(Json(movements))
+}
+
+pub async fn movement_summary(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<MovementQuery>,
+) -> Result<Json<MovementSummary>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockLedgerRepository::new(state.pool);
+    let summary = repo.movement_summary(tenant_id, query).await?;
+    Ok(Json(summary))
+}
+
+pub async fn stock_on_hand(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<StockAsOfQuery>,
+) -> Result<Json<StockOnHandReport>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresStockLedgerRepository::new(state.pool);
+    let report = repo.stock_on_hand(tenant_id, query.date).await?;
+    Ok(Json(report))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
@@ -1,6 +1,7 @@
 use axum::extract::State;
 use axum::http::HeaderMap;
-use axum::Json;
+use axum::{Extension, Json};
+use smart_erp_core::models::auth::Claims;
 use smart_erp_core::models::transactions::*;
 use crate::error::AppError;
 use crate::state::AppState;
@@ -53,10 +54,10 @@ pub async fn list_credit_memos(State(state): State<AppState>, headers: HeaderMap
     Ok(Json(repo.list_credit_memos(tid).await?))
 }
 
-pub async fn create_credit_memo(State(state): State<AppState>, headers: HeaderMap, Json(p): Json<CreateCreditMemo>) -> Result<Json<CreditMemo>, AppError> {
+pub async fn create_credit_memo(State(state): State<AppState>, headers: HeaderMap, Extension(claims): Extension<Claims>, Json(p): Json<CreateCreditMemo>) -> Result<Json<CreditMemo>, AppError> {
     let tid = get_tenant_id(&headers)?;
     let repo = PostgresTransactionsRepository::new(state.pool);
-    Ok(Json(repo.create_credit_memo(tid, p).await?))
+    Ok(Json(repo.create_credit_memo(tid, p, claims.sub).await?))
 }
 
 // --- Journal Entries ---
@@ -66,10 +67,10 @@ pub async fn list_journal_entries(State(state): State<AppState>, headers: Header
     Ok(Json(repo.list_journal_entries(tid).await?))
 }
 
-pub async fn create_journal_entry(State(state): State<AppState>, headers: HeaderMap, Json(p): Json<CreateJournalEntry>) -> Result<Json<JournalEntry>, AppError> {
+pub async fn create_journal_entry(State(state): State<AppState>, headers: HeaderMap, Extension(claims): Extension<Claims>, Json(p): Json<CreateJournalEntry>) -> Result<Json<JournalEntry>, AppError> {
     let tid = get_tenant_id(&headers)?;
     let repo = PostgresTransactionsRepository::new(state.pool);
-    Ok(Json(repo.create_journal_entry(tid, p).await?))
+    Ok(Json(repo.create_journal_entry(tid, p, claims.sub).await?))
 }
 
 // --- Checks ---
@@ -0,0 +1,53 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use smart_erp_core::models::auth::Claims;
+use smart_erp_core::models::vendor_credit::*;
+use infrastructure::db::vendor_credit::PostgresVendorCreditRepository;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::error::AppError;
+
+pub async fn list_credits(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<VendorCreditQuery>,
+) -> Result<Json<Vec<VendorCredit>>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresVendorCreditRepository::new(state.pool);
+    let credits = repo.list_credits(tenant_id, query).await?;
+    Ok(Json(credits))
+}
+
+pub async fn get_credit(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VendorCreditDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresVendorCreditRepository::new(state.pool);
+    let credit = repo.get_credit(tenant_id, id).await?;
+    Ok(Json(credit))
+}
+
+pub async fn create_credit(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateVendorCredit>,
+) -> Result<Json<VendorCreditDetail>, AppError> {
+    let tenant_id = get_tenant_id(&headers)?;
+    let repo = PostgresVendorCreditRepository::new(state.pool);
+    let credit = repo.create_credit(tenant_id, payload, claims.sub).await?;
+    Ok(Json(credit))
+}
+
+fn get_tenant_id(headers: &HeaderMap) -> Result<Uuid, AppError> {
+    headers
+        .get("x-tenant-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or(AppError(smart_erp_core::error::Error::BusinessRule("Missing or invalid x-tenant-id header".to_string())))
+}
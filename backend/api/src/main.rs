@@ -4,7 +4,7 @@ mod error;
 mod middleware;
 
 use axum::{
-    routing::{get, post, delete},
+    routing::{get, post, put, delete},
     Router,
     middleware as axum_middleware,
 };
@@ -154,32 +154,175 @@ async fn main() {
         .route("/api/auth/register", post(handlers::auth::register))
         // Inventory
         .route("/api/products", get(handlers::inventory::list_products).post(handlers::inventory::create_product))
+        .route("/api/products/:id/reorder-settings", get(handlers::replenishment::get_reorder_settings).put(handlers::replenishment::update_reorder_settings))
+        .route("/api/products/:id/stock-card", get(handlers::stock_ledger::stock_card))
+        .route("/api/products/:id/cost-layers", get(handlers::costing::list_cost_layers))
+        .route("/api/products/:id/standard-cost", put(handlers::costing::update_standard_cost))
+        // Costing
+        .route("/api/inventory/settings", get(handlers::costing::get_settings).put(handlers::costing::update_settings))
+        // Stock Ledger
+        .route("/api/inventory/movements", get(handlers::stock_ledger::list_movements))
+        .route("/api/inventory/movements/summary", get(handlers::stock_ledger::movement_summary))
+        .route("/api/inventory/stock-on-hand", get(handlers::stock_ledger::stock_on_hand))
+        // Replenishment
+        .route("/api/inventory/replenishment", get(handlers::replenishment::list_suggestions))
+        .route("/api/inventory/replenishment/orders", post(handlers::replenishment::generate_purchase_orders))
+        // Stock Counts & Adjustments
+        .route("/api/inventory/adjustments", post(handlers::stock_count::adjust_stock))
+        .route("/api/inventory/counts", get(handlers::stock_count::list_sessions).post(handlers::stock_count::create_session))
+        .route("/api/inventory/counts/:id", get(handlers::stock_count::get_session))
+        .route("/api/inventory/counts/:id/lines", post(handlers::stock_count::record_counts))
+        .route("/api/inventory/counts/:id/submit", post(handlers::stock_count::submit_session))
+        .route("/api/inventory/counts/:id/variances", get(handlers::stock_count::variance_report))
+        .route("/api/inventory/counts/:id/approve", post(handlers::stock_count::approve_session))
+        .route("/api/inventory/counts/:id/cancel", post(handlers::stock_count::cancel_session))
+        .route("/api/inventory/cycle-counts", get(handlers::stock_count::list_schedules).post(handlers::stock_count::create_schedule))
+        .route("/api/inventory/cycle-counts/run", post(handlers::stock_count::run_due_schedules))
         // Purchasing
         .route("/api/purchasing/suppliers", get(handlers::purchasing::list_suppliers).post(handlers::purchasing::create_supplier))
+        .route("/api/purchasing/suppliers/:id", get(handlers::purchasing::get_supplier).put(handlers::purchasing::update_supplier))
+        .route("/api/purchasing/suppliers/:id/deactivate", post(handlers::purchasing::deactivate_supplier))
+        .route("/api/purchasing/suppliers/:id/reactivate", post(handlers::purchasing::reactivate_supplier))
+        .route("/api/purchasing/suppliers/:id/transactions", get(handlers::purchasing::supplier_history))
+        .route("/api/purchasing/suppliers/:id/addresses", get(handlers::address::list_supplier_addresses).post(handlers::address::create_supplier_address))
+        .route("/api/purchasing/suppliers/:id/contacts", get(handlers::address::list_supplier_contacts).post(handlers::address::create_supplier_contact))
+        .route("/api/purchasing/requisition-rules", get(handlers::requisition::list_rules).post(handlers::requisition::create_rule))
+        .route("/api/purchasing/requisition-rules/:id", put(handlers::requisition::update_rule))
+        .route("/api/purchasing/requisitions", get(handlers::requisition::list_requisitions).post(handlers::requisition::create_requisition))
+        .route("/api/purchasing/requisitions/convert", post(handlers::requisition::convert_requisitions))
+        .route("/api/purchasing/requisitions/:id", get(handlers::requisition::get_requisition))
+        .route("/api/purchasing/requisitions/:id/submit", post(handlers::requisition::submit_requisition))
+        .route("/api/purchasing/requisitions/:id/approve", post(handlers::requisition::approve_requisition))
+        .route("/api/purchasing/requisitions/:id/reject", post(handlers::requisition::reject_requisition))
+        .route("/api/purchasing/requisitions/:id/comments", post(handlers::requisition::comment_requisition))
+        .route("/api/purchasing/requisitions/:id/cancel", post(handlers::requisition::cancel_requisition))
+        .route("/api/purchasing/rfqs", get(handlers::rfq::list_rfqs).post(handlers::rfq::create_rfq))
+        .route("/api/purchasing/rfqs/:id", get(handlers::rfq::get_rfq))
+        .route("/api/purchasing/rfqs/:id/quotes", post(handlers::rfq::record_quote))
+        .route("/api/purchasing/rfqs/:id/decline", post(handlers::rfq::decline_rfq))
+        .route("/api/purchasing/rfqs/:id/comparison", get(handlers::rfq::compare_quotes))
+        .route("/api/purchasing/rfqs/:id/award", post(handlers::rfq::award_rfq))
+        .route("/api/purchasing/rfqs/:id/cancel", post(handlers::rfq::cancel_rfq))
+        .route("/api/purchasing/price-history", get(handlers::purchasing::price_history))
         .route("/api/purchasing/orders", get(handlers::purchasing::list_purchase_orders).post(handlers::purchasing::create_purchase_order))
         .route("/api/purchasing/orders/:id/receive", post(handlers::purchasing::receive_purchase_order))
+        .route("/api/purchasing/receipts", get(handlers::purchasing::list_receipts))
+        .route("/api/purchasing/receipts/:id", get(handlers::purchasing::get_receipt))
+        .route("/api/purchasing/landed-costs", get(handlers::landed_cost::list_allocations).post(handlers::landed_cost::allocate))
+        .route("/api/purchasing/landed-costs/:id", get(handlers::landed_cost::get_allocation))
+        .route("/api/purchasing/bills", post(handlers::bill_matching::create_bill))
+        .route("/api/purchasing/match-settings", get(handlers::bill_matching::get_settings).put(handlers::bill_matching::update_settings))
         // Manufacturing
         .route("/api/manufacturing/recipes", get(handlers::manufacturing::list_recipes).post(handlers::manufacturing::create_recipe))
         .route("/api/manufacturing/work-orders", get(handlers::manufacturing::list_work_orders).post(handlers::manufacturing::create_work_order))
+        .route("/api/manufacturing/work-orders/:id", get(handlers::production::get_work_order))
+        .route("/api/manufacturing/work-orders/:id/start", post(handlers::production::start_work_order))
+        .route("/api/manufacturing/work-orders/:id/issue", post(handlers::production::issue_materials))
+        .route("/api/manufacturing/work-orders/:id/return", post(handlers::production::return_materials))
+        .route("/api/manufacturing/work-orders/:id/report", post(handlers::production::report_production))
+        .route("/api/manufacturing/work-orders/:id/cancel", post(handlers::production::cancel_work_order))
+        .route("/api/manufacturing/work-orders/:id/yield", get(handlers::production::work_order_yield))
         .route("/api/manufacturing/work-orders/:id/complete", post(handlers::manufacturing::complete_work_order))
+        .route("/api/manufacturing/yield-variance", get(handlers::production::yield_variance))
+        .route("/api/manufacturing/work-orders/:id/labor", get(handlers::production::list_labor).post(handlers::production::log_labor))
+        .route("/api/manufacturing/work-orders/:id/clock-in", post(handlers::production::clock_in))
+        .route("/api/manufacturing/labor/clock-out", post(handlers::production::clock_out))
+        .route("/api/manufacturing/labor/clocks", get(handlers::production::list_clocks))
+        .route("/api/manufacturing/labor/clocks/:id", delete(handlers::production::cancel_clock))
+        .route("/api/manufacturing/labor/efficiency", get(handlers::production::labor_efficiency))
+        .route("/api/manufacturing/work-orders/:id/cost", get(handlers::production::work_order_cost))
+        .route("/api/manufacturing/overhead-rates", get(handlers::production::list_overhead_rates).post(handlers::production::create_overhead_rate))
+        .route("/api/manufacturing/overhead-rates/:id", put(handlers::production::update_overhead_rate))
+        .route("/api/manufacturing/work-centers", get(handlers::routing::list_work_centers).post(handlers::routing::create_work_center))
+        .route("/api/manufacturing/work-centers/:id", put(handlers::routing::update_work_center))
+        .route("/api/manufacturing/work-centers/:id/calendar", get(handlers::routing::list_calendar).put(handlers::routing::set_calendar))
+        .route("/api/manufacturing/work-centers/:id/load", get(handlers::routing::work_center_load))
+        .route("/api/manufacturing/routings", get(handlers::routing::list_routings).post(handlers::routing::create_routing))
+        .route("/api/manufacturing/routings/:id", get(handlers::routing::get_routing))
+        .route("/api/manufacturing/recipes/:id/routing", put(handlers::routing::set_recipe_routing))
+        .route("/api/manufacturing/work-orders/:id/schedule", get(handlers::routing::get_schedule).post(handlers::routing::schedule_work_order))
+        .route("/api/manufacturing/recipes/:id/pattern-pieces", get(handlers::nesting::list_pattern_pieces).put(handlers::nesting::set_pattern_pieces))
+        .route("/api/manufacturing/recipes/:id/nest", post(handlers::nesting::preview_nesting))
+        .route("/api/manufacturing/work-orders/:id/nesting", get(handlers::nesting::work_order_nesting).post(handlers::nesting::create_layout))
+        .route("/api/manufacturing/nesting-layouts/:id", get(handlers::nesting::get_layout))
+        .route("/api/manufacturing/nesting-layouts/:id/svg", get(handlers::nesting::layout_svg))
+        .route("/api/manufacturing/nesting-layouts/:id/cutting", post(handlers::nesting::record_cutting))
+        .route("/api/quality/inspection-plans", get(handlers::quality::list_plans).post(handlers::quality::create_plan))
+        .route("/api/quality/inspection-plans/:id", get(handlers::quality::get_plan).put(handlers::quality::update_plan))
+        .route("/api/quality/inspections", get(handlers::quality::list_inspections).post(handlers::quality::create_inspection))
+        .route("/api/quality/inspections/:id", get(handlers::quality::get_inspection))
+        .route("/api/quality/inspections/:id/results", post(handlers::quality::record_inspection))
+        .route("/api/quality/inspections/:id/dispositions", post(handlers::quality::dispose))
+        .route("/api/quality/quarantine", get(handlers::quality::list_quarantine))
+        .route("/api/compliance/substances", get(handlers::compliance::list_substances).post(handlers::compliance::create_substance))
+        .route("/api/compliance/chemicals", get(handlers::compliance::list_chemicals))
+        .route("/api/compliance/chemicals/:id", put(handlers::compliance::set_chemical))
+        .route("/api/compliance/chemical-lots", get(handlers::compliance::list_lots).post(handlers::compliance::create_lot))
+        .route("/api/compliance/chemical-lots/:id", get(handlers::compliance::get_lot))
+        .route("/api/manufacturing/work-orders/:id/chemicals", get(handlers::compliance::work_order_chemicals))
+        .route("/api/compliance/waste-disposals", get(handlers::compliance::list_disposals).post(handlers::compliance::create_disposal))
+        .route("/api/compliance/waste-disposals/:id", get(handlers::compliance::get_disposal))
+        .route("/api/compliance/report", get(handlers::compliance::compliance_report))
+        .route("/api/manufacturing/recipes/:id", get(handlers::manufacturing::get_recipe))
+        .route("/api/manufacturing/recipes/:id/versions", get(handlers::manufacturing::list_recipe_versions).post(handlers::manufacturing::create_recipe_version))
+        .route("/api/manufacturing/recipes/:id/approve", post(handlers::manufacturing::approve_recipe))
+        .route("/api/manufacturing/recipes/:id/costs", put(handlers::manufacturing::update_recipe_costs))
+        .route("/api/manufacturing/recipes/:id/bom", get(handlers::manufacturing::explode_bom))
+        .route("/api/manufacturing/recipes/:id/cost-rollup", post(handlers::manufacturing::roll_up_cost))
+        .route("/api/manufacturing/cost-rollup", post(handlers::manufacturing::roll_up_all_costs))
+        .route("/api/products/:id/where-used", get(handlers::manufacturing::where_used))
+        .route("/api/manufacturing/recipes/:id/lead-time", put(handlers::manufacturing::update_recipe_lead_time))
+        .route("/api/manufacturing/mrp/runs", get(handlers::mrp::list_runs).post(handlers::mrp::run_mrp))
+        .route("/api/manufacturing/mrp/runs/:id", get(handlers::mrp::get_run))
+        .route("/api/manufacturing/mrp/planned-orders", get(handlers::mrp::list_planned_orders))
+        .route("/api/manufacturing/mrp/planned-orders/:id/firm", post(handlers::mrp::firm_planned_order))
         // Sales
         .route("/api/sales/customers", get(handlers::sales::list_customers).post(handlers::sales::create_customer))
+        .route("/api/sales/customers/:id/payment-terms", put(handlers::payment_terms::set_customer_terms))
+        .route("/api/sales/customers/:id/credit", put(handlers::sales::set_credit_limit))
+        .route("/api/sales/customers/:id/center", get(handlers::sales::customer_center))
+        .route("/api/sales/customers/:id/addresses", get(handlers::address::list_customer_addresses).post(handlers::address::create_customer_address))
+        .route("/api/sales/customers/:id/contacts", get(handlers::address::list_customer_contacts).post(handlers::address::create_customer_contact))
         .route("/api/sales/orders", post(handlers::sales::create_sales_order))
+        .route("/api/sales/orders/:id/confirm", post(handlers::sales::confirm_sales_order))
+        .route("/api/sales/orders/:id/release-hold", post(handlers::sales::release_credit_hold))
         .route("/api/sales/orders/:id/ship", post(handlers::sales::ship_sales_order))
         .route("/api/sales/trend", get(handlers::sales::get_sales_trend))
         // Accounting
         .route("/api/accounting/invoices", get(handlers::accounting::list_invoices).post(handlers::accounting::create_invoice))
         .route("/api/accounting/payments", get(handlers::accounting::list_payments).post(handlers::accounting::record_payment))
+        .route("/api/accounting/payment-terms", get(handlers::payment_terms::list_terms).post(handlers::payment_terms::create_terms))
+        .route("/api/accounting/payment-terms/:id", put(handlers::payment_terms::update_terms))
         // Chart of Accounts
         .route("/api/accounts", get(handlers::chart_of_accounts::list_accounts).post(handlers::chart_of_accounts::create_account))
         .route("/api/accounts/:id", delete(handlers::chart_of_accounts::delete_account))
         // Employees
         .route("/api/employees", get(handlers::employee::list_employees).post(handlers::employee::create_employee))
+        .route("/api/employees/payroll-hours", get(handlers::employee::payroll_hours))
         .route("/api/employees/:id", delete(handlers::employee::delete_employee))
+        .route("/api/employees/:id/addresses", get(handlers::address::list_employee_addresses).post(handlers::address::create_employee_address))
+        .route("/api/employees/:id/contacts", get(handlers::address::list_employee_contacts).post(handlers::address::create_employee_contact))
+        .route("/api/addresses/:id", put(handlers::address::update_address).delete(handlers::address::delete_address))
+        .route("/api/contacts/:id", put(handlers::address::update_contact).delete(handlers::address::delete_contact))
         // Phase 2: Transactions
         .route("/api/estimates", get(handlers::transactions::list_estimates).post(handlers::transactions::create_estimate))
         .route("/api/bills", get(handlers::transactions::list_bills).post(handlers::transactions::create_bill))
+        .route("/api/bills/:id/match", get(handlers::bill_matching::get_match))
+        .route("/api/bills/:id/approve-match", post(handlers::bill_matching::approve_match))
+        .route("/api/bills/:id/payments", get(handlers::bill_matching::list_payments).post(handlers::bill_matching::pay_bill))
         .route("/api/sales-receipts", get(handlers::transactions::list_sales_receipts).post(handlers::transactions::create_sales_receipt))
+        .route("/api/approvals", get(handlers::approval::list_requests).post(handlers::approval::request_approval))
+        .route("/api/approvals/rules", get(handlers::approval::list_rules).post(handlers::approval::create_rule))
+        .route("/api/approvals/rules/:id", put(handlers::approval::update_rule))
+        .route("/api/approvals/delegations", get(handlers::approval::list_delegations).post(handlers::approval::create_delegation))
+        .route("/api/approvals/delegations/:id/revoke", post(handlers::approval::revoke_delegation))
+        .route("/api/approvals/:id", get(handlers::approval::get_request))
+        .route("/api/approvals/:id/approve", post(handlers::approval::approve_request))
+        .route("/api/approvals/:id/reject", post(handlers::approval::reject_request))
+        .route("/api/approvals/:id/comments", post(handlers::approval::comment_request))
+        .route("/api/approvals/:id/cancel", post(handlers::approval::cancel_request))
+        .route("/api/vendor-credits", get(handlers::vendor_credit::list_credits).post(handlers::vendor_credit::create_credit))
+        .route("/api/vendor-credits/:id", get(handlers::vendor_credit::get_credit))
         .route("/api/credit-memos", get(handlers::transactions::list_credit_memos).post(handlers::transactions::create_credit_memo))
         .route("/api/journal-entries", get(handlers::transactions::list_journal_entries).post(handlers::transactions::create_journal_entry))
         .route("/api/checks", get(handlers::transactions::list_checks).post(handlers::transactions::create_check))
@@ -191,6 +334,9 @@ async fn main() {
         .route("/api/reports/ap-aging", get(handlers::reports::ap_aging))
         .route("/api/reports/sales-summary", get(handlers::reports::sales_summary))
         .route("/api/reports/general-ledger", get(handlers::reports::general_ledger))
+        .route("/api/reports/supplier-scorecards", get(handlers::purchasing::supplier_scorecards))
+        .route("/api/reports/low-stock", get(handlers::replenishment::low_stock_report))
+        .route("/api/reports/inventory-valuation", get(handlers::costing::inventory_valuation))
         
         .route_layer(axum_middleware::from_fn_with_state(state.clone(), middleware::auth::auth_middleware));
 
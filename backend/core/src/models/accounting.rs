@@ -33,9 +33,17 @@ pub struct Invoice {
     pub due_date: NaiveDate,
     pub status: InvoiceStatus,
     pub total_amount: Decimal,
-    pub amount_paid: Decimal,
+    pub amount_paid: Decimal, // Cash received plus discounts taken
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub payment_terms_id: Option<Uuid>,
+    pub discount_date: Option<NaiveDate>, // Last day the early-payment discount can be taken
+    pub discount_amount: Decimal,
+    pub discount_taken: Decimal,
+    pub billing_address_id: Option<Uuid>,
+    pub billing_address: Option<String>, // Copied when the invoice is raised
+    pub shipping_address_id: Option<Uuid>,
+    pub shipping_address: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
@@ -62,6 +70,7 @@ pub struct Payment {
     pub reference: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub discount_amount: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,8 +79,11 @@ pub struct CreateInvoice {
     pub sales_order_id: Option<Uuid>,
     pub invoice_number: String,
     pub date: NaiveDate,
-    pub due_date: NaiveDate,
+    pub due_date: Option<NaiveDate>, // Worked out from the payment terms when omitted
     pub total_amount: Decimal,
+    pub payment_terms_id: Option<Uuid>, // Defaults to the customer's terms
+    pub billing_address_id: Option<Uuid>, // Defaults to the sales order's addresses, then the customer's defaults
+    pub shipping_address_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,6 +93,8 @@ pub struct CreatePayment {
     pub date: NaiveDate,
     pub method: PaymentMethod,
     pub reference: Option<String>,
+    #[serde(default)]
+    pub take_discount: bool, // Settle the rest of the invoice with its early-payment discount
 }
 
 #[async_trait]
@@ -94,7 +108,7 @@ pub trait AccountingService: Send + Sync {
     /// Records a payment and updates the invoice status.
     /// Logic:
     /// 1. Insert Payment.
-    /// 2. Update Invoice amount_paid.
+    /// 2. Update Invoice amount_paid, including any early-payment discount taken.
     /// 3. Update Invoice status (PAID if amount_paid >= total_amount, else PARTIALLY_PAID).
     async fn record_payment(
         &self,
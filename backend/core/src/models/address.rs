@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use chrono::{DateTime, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AddressType {
+    #[strum(serialize = "BILLING")]
+    Billing,
+    #[strum(serialize = "SHIPPING")]
+    Shipping,
+    #[strum(serialize = "REMIT_TO")]
+    RemitTo, // Where payments to a supplier are sent
+    #[strum(serialize = "HOME")]
+    Home, // Employees only
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContactRole {
+    #[strum(serialize = "PRIMARY")]
+    Primary,
+    #[strum(serialize = "BILLING")]
+    Billing,
+    #[strum(serialize = "PURCHASING")]
+    Purchasing,
+    #[strum(serialize = "SALES")]
+    Sales,
+    #[strum(serialize = "SHIPPING")]
+    Shipping,
+    #[strum(serialize = "EMERGENCY")]
+    Emergency,
+    #[strum(serialize = "OTHER")]
+    Other,
+}
+
+/// An address belonging to exactly one customer, supplier or employee.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PartyAddress {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub customer_id: Option<Uuid>,
+    pub supplier_id: Option<Uuid>,
+    pub employee_id: Option<Uuid>,
+    pub address_type: AddressType,
+    pub label: Option<String>, // e.g. "Warehouse 2"
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: Option<String>,
+    pub region: Option<String>, // State, province or county
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+    pub is_default: bool, // Used on new documents when none is chosen
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartyAddress {
+    /// The address as printed on documents, one line per row.
+    pub fn formatted(&self) -> String {
+        let region = [self.region.as_deref(), self.postal_code.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let locality = match (self.city.as_deref().filter(|c| !c.is_empty()), region.is_empty()) {
+            (Some(city), false) => format!("{}, {}", city, region),
+            (Some(city), true) => city.to_string(),
+            (None, _) => region,
+        };
+
+        [Some(self.line1.as_str()), self.line2.as_deref(), Some(locality.as_str()), self.country.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAddress {
+    pub address_type: AddressType,
+    pub label: Option<String>,
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+    #[serde(default)]
+    pub is_default: bool, // The first address of each type is the default regardless
+}
+
+impl CreateAddress {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.line1.trim().is_empty() {
+            return Err(Error::BusinessRule("Address line 1 cannot be blank".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Fields left out are kept as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateAddress {
+    pub label: Option<String>,
+    pub line1: Option<String>,
+    pub line2: Option<String>,
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PartyContact {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub customer_id: Option<Uuid>,
+    pub supplier_id: Option<Uuid>,
+    pub employee_id: Option<Uuid>,
+    pub name: String,
+    pub role: ContactRole,
+    pub title: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub is_primary: bool, // At most one per party
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateContact {
+    pub name: String,
+    pub role: Option<ContactRole>, // Defaults to Other
+    pub title: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub is_primary: bool,
+    pub notes: Option<String>,
+}
+
+impl CreateContact {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.trim().is_empty() {
+            return Err(Error::BusinessRule("Contact name cannot be blank".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Fields left out are kept as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateContact {
+    pub name: Option<String>,
+    pub role: Option<ContactRole>,
+    pub title: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub is_primary: Option<bool>,
+    pub notes: Option<String>,
+}
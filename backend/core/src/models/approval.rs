@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+use crate::models::auth::Role;
+use crate::models::requisition::{can_approve, ApprovalStatus};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DocumentType {
+    #[strum(serialize = "PURCHASE_ORDER")]
+    PurchaseOrder, // Gates receiving
+    #[strum(serialize = "BILL")]
+    Bill, // Gates payment
+    #[strum(serialize = "JOURNAL_ENTRY")]
+    JournalEntry, // Manual entries are held until approved
+    #[strum(serialize = "CREDIT_MEMO")]
+    CreditMemo, // Held until approved
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApprovalRequestStatus {
+    #[strum(serialize = "PENDING")]
+    Pending,
+    #[strum(serialize = "APPROVED")]
+    Approved,
+    #[strum(serialize = "REJECTED")]
+    Rejected,
+    #[strum(serialize = "CANCELLED")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApprovalAction {
+    #[strum(serialize = "REQUESTED")]
+    Requested,
+    #[strum(serialize = "APPROVED")]
+    Approved,
+    #[strum(serialize = "REJECTED")]
+    Rejected,
+    #[strum(serialize = "COMMENTED")]
+    Commented,
+    #[strum(serialize = "CANCELLED")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ApprovalRule {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub document_type: DocumentType,
+    pub name: String,
+    pub min_amount: Decimal,
+    pub approver_role: Role,
+    pub sequence: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApprovalRule {
+    pub document_type: DocumentType,
+    pub name: String,
+    pub min_amount: Option<Decimal>,
+    pub approver_role: Role,
+    pub sequence: Option<i32>,
+}
+
+/// Fields left out are kept as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateApprovalRule {
+    pub name: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub approver_role: Option<Role>,
+    pub sequence: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ApprovalRequest {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub document_type: DocumentType,
+    pub document_id: Uuid,
+    pub document_number: String,
+    pub amount: Decimal,
+    pub status: ApprovalRequestStatus,
+    pub requested_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ApprovalStep {
+    pub id: Uuid,
+    pub request_id: Uuid,
+    pub rule_id: Option<Uuid>,
+    pub rule_name: String,
+    pub sequence: i32,
+    pub approver_role: Role,
+    pub status: ApprovalStatus,
+    pub decided_by: Option<Uuid>,
+    pub on_behalf_of: Option<Uuid>, // Delegator when decided under a delegation
+    pub decided_at: Option<DateTime<Utc>>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ApprovalEvent {
+    pub id: Uuid,
+    pub request_id: Uuid,
+    pub action: ApprovalAction,
+    pub user_id: Option<Uuid>,
+    pub username: Option<String>,
+    pub on_behalf_of: Option<Uuid>,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequestDetail {
+    pub request: ApprovalRequest,
+    pub steps: Vec<ApprovalStep>,
+    pub history: Vec<ApprovalEvent>,
+}
+
+impl ApprovalRequestDetail {
+    /// All pending steps sharing the lowest sequence.
+    pub fn current_steps(&self) -> Vec<&ApprovalStep> {
+        let next = self.steps.iter().filter(|s| s.status == ApprovalStatus::Pending).map(|s| s.sequence).min();
+        self.steps
+            .iter()
+            .filter(|s| s.status == ApprovalStatus::Pending && Some(s.sequence) == next)
+            .collect()
+    }
+
+    /// The first current step one of the roles may decide on, with the delegator it was
+    /// reached through if any.
+    pub fn step_for(&self, roles: &[(String, Option<Uuid>)]) -> Option<(&ApprovalStep, Option<Uuid>)> {
+        self.current_steps().into_iter().find_map(|step| {
+            roles
+                .iter()
+                .find(|(role, _)| can_approve(role, &step.approver_role))
+                .map(|(_, delegator)| (step, *delegator))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestApproval {
+    pub document_type: DocumentType,
+    pub document_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalRequestQuery {
+    pub document_type: Option<DocumentType>,
+    pub document_id: Option<Uuid>,
+    pub status: Option<ApprovalRequestStatus>,
+    #[serde(default)]
+    pub awaiting_me: bool, // Only requests whose current step the caller can decide on
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalDecision {
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ApprovalDelegation {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub delegator_id: Uuid,
+    pub delegate_id: Uuid,
+    pub document_type: Option<DocumentType>,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Hands the caller's approvals to another user for a period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApprovalDelegation {
+    pub delegate_id: Uuid,
+    pub document_type: Option<DocumentType>,
+    pub starts_on: Option<NaiveDate>, // Defaults to today
+    pub ends_on: NaiveDate,
+}
+
+impl CreateApprovalDelegation {
+    pub fn validate(&self, delegator_id: Uuid, today: NaiveDate) -> Result<(), Error> {
+        if self.delegate_id == delegator_id {
+            return Err(Error::BusinessRule("You cannot delegate to yourself".to_string()));
+        }
+        if self.ends_on < self.starts_on.unwrap_or(today) {
+            return Err(Error::BusinessRule("Delegation ends before it starts".to_string()));
+        }
+        Ok(())
+    }
+}
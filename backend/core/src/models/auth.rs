@@ -4,13 +4,17 @@ use chrono::{DateTime, Utc};
 use strum::{Display, EnumString};
 use async_trait::async_trait;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
 #[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Role {
     #[strum(serialize = "ADMIN")]
     Admin,
     #[strum(serialize = "USER")]
     User,
+    #[strum(serialize = "MANAGER")]
+    Manager,
+    #[strum(serialize = "PURCHASING")]
+    Purchasing,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::models::transactions::Bill;
+use crate::models::vendor_credit::CreditApplication;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MatchStatus {
+    #[strum(serialize = "UNMATCHED")]
+    Unmatched, // Not billed from a PO/receipt
+    #[strum(serialize = "MATCHED")]
+    Matched,
+    #[strum(serialize = "EXCEPTION")]
+    Exception, // Outside tolerance; payment is blocked until approved
+    #[strum(serialize = "APPROVED")]
+    Approved,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MatchExceptionType {
+    #[strum(serialize = "QUANTITY")]
+    Quantity,
+    #[strum(serialize = "PRICE")]
+    Price,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PurchaseMatchSettings {
+    pub tenant_id: Uuid,
+    pub quantity_tolerance_percent: Decimal,
+    pub price_tolerance_percent: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePurchaseMatchSettings {
+    pub quantity_tolerance_percent: Decimal,
+    pub price_tolerance_percent: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BillLine {
+    pub id: Uuid,
+    pub bill_id: Uuid,
+    pub product_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit_price: Decimal,
+    pub amount: Decimal,
+    pub sort_order: i32,
+    pub receipt_line_id: Option<Uuid>,
+    pub matched_quantity: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BillMatchException {
+    pub id: Uuid,
+    pub bill_id: Uuid,
+    pub bill_line_id: Uuid,
+    pub exception_type: MatchExceptionType,
+    pub expected: Decimal, // Unbilled received quantity, or PO unit price
+    pub actual: Decimal,
+    pub variance_percent: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillMatchDetail {
+    pub bill: Bill,
+    pub lines: Vec<BillLine>,
+    pub exceptions: Vec<BillMatchException>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateMatchedBillLine {
+    pub receipt_line_id: Uuid,
+    pub quantity: Decimal,
+    pub unit_price: Decimal,
+}
+
+/// Bill entered against a PO. Without `receipt_id` every receipt of the PO is billed; without
+/// `lines` the bill is prefilled with the unbilled received quantities at PO price.
+#[derive(Debug, Deserialize)]
+pub struct CreateBillFromPurchase {
+    pub purchase_order_id: Uuid,
+    pub receipt_id: Option<Uuid>,
+    pub bill_number: String,
+    pub date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>, // Worked out from the payment terms when omitted
+    pub terms: Option<String>,
+    pub payment_terms_id: Option<Uuid>, // Defaults to the supplier's terms
+    pub notes: Option<String>,
+    pub lines: Option<Vec<CreateMatchedBillLine>>,
+}
+
+// --- Bill Payments ---
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BillPayment {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub bill_id: Uuid,
+    pub amount: Decimal,
+    pub date: NaiveDate,
+    pub method: String,
+    pub reference: Option<String>,
+    pub account_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub discount_amount: Decimal,
+    pub credit_amount: Decimal, // Settled with vendor credits
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBillPayment {
+    pub amount: Decimal, // Paid from the bank; may be zero when credits settle the bill
+    pub date: Option<NaiveDate>,
+    pub method: Option<String>,
+    pub reference: Option<String>,
+    pub account_id: Option<Uuid>, // Bank account paid from; defaults to 1000 Checking
+    #[serde(default)]
+    pub take_discount: bool, // Settle the rest of the bill with its early-payment discount
+    #[serde(default)]
+    pub credits: Vec<CreditApplication>, // Vendor credits applied before the payment
+}
+
+/// Signed percentage difference of `actual` from `expected`.
+pub fn variance_percent(expected: Decimal, actual: Decimal) -> Decimal {
+    if expected == Decimal::ZERO {
+        return if actual == Decimal::ZERO { Decimal::ZERO } else { Decimal::ONE_HUNDRED };
+    }
+    ((actual - expected) / expected * Decimal::ONE_HUNDRED).round_dp(2)
+}
+
+/// Billing more than was received is an exception beyond the tolerance; billing less is not.
+pub fn quantity_exceeds_tolerance(received: Decimal, billed: Decimal, tolerance_percent: Decimal) -> bool {
+    billed > received * (Decimal::ONE + tolerance_percent / Decimal::ONE_HUNDRED)
+}
+
+/// Price differences either way beyond the tolerance are exceptions.
+pub fn price_exceeds_tolerance(po_price: Decimal, billed_price: Decimal, tolerance_percent: Decimal) -> bool {
+    variance_percent(po_price, billed_price).abs() > tolerance_percent
+}
@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+
+use crate::error::Error;
+use crate::models::manufacturing::{Recipe, RecipeIngredient};
+
+/// Product fields needed to label and cost BOM lines. `unit_cost` is the cost used for
+/// purchased components (standard cost, falling back to the current cost price).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BomProduct {
+    pub id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub unit_cost: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BomQuery {
+    pub quantity: Option<Decimal>, // Batches to explode for; defaults to 1
+}
+
+impl BomQuery {
+    pub fn batches(&self) -> Decimal {
+        self.quantity.unwrap_or(Decimal::ONE)
+    }
+}
+
+/// One line of an indented BOM. Level 0 is the finished product; a component made in-house
+/// carries the recipe that makes it and is followed by its own components one level deeper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndentedBomLine {
+    pub level: u32,
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub quantity_per: Decimal, // Per batch of the parent recipe
+    pub quantity: Decimal,     // Extended for the exploded quantity
+    pub unit_cost: Decimal,
+    pub extended_cost: Decimal,
+    pub recipe_id: Option<Uuid>,
+    pub recipe_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndentedBom {
+    pub recipe_id: Uuid,
+    pub recipe_name: String,
+    pub batches: Decimal,
+    pub lines: Vec<IndentedBomLine>,
+    pub total_cost: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhereUsedLine {
+    pub level: u32, // 1 = used directly, 2 = used in something that is used directly, ...
+    pub recipe_id: Uuid,
+    pub recipe_name: String,
+    pub output_product_id: Uuid,
+    pub output_sku: String,
+    pub output_name: String,
+    pub quantity_per: Decimal, // Of the component per batch of this recipe
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostRollupLine {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub quantity: Decimal,
+    pub unit_cost: Decimal,
+    pub extended_cost: Decimal,
+    pub recipe_id: Option<Uuid>, // Set when the component's cost is itself rolled up
+}
+
+/// Standard cost of one batch of a recipe and of one unit of its output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostRollup {
+    pub recipe_id: Uuid,
+    pub recipe_name: String,
+    pub output_product_id: Uuid,
+    pub output_quantity: Decimal,
+    pub components: Vec<CostRollupLine>,
+    pub material_cost: Decimal,
+    pub labor_cost: Decimal,
+    pub overhead_cost: Decimal,
+    pub batch_cost: Decimal,
+    pub unit_cost: Decimal,
+}
+
+/// A tenant's recipes as a product graph. A product is made by the first recipe listed for it;
+/// anything without a recipe is treated as purchased.
+pub struct BomGraph {
+    recipes: HashMap<Uuid, Recipe>,
+    ingredients: HashMap<Uuid, Vec<RecipeIngredient>>,
+    producing: HashMap<Uuid, Uuid>,
+    used_in: HashMap<Uuid, Vec<(Uuid, Decimal)>>,
+    products: HashMap<Uuid, BomProduct>,
+}
+
+impl BomGraph {
+    pub fn new(recipes: Vec<Recipe>, ingredients: Vec<RecipeIngredient>, products: Vec<BomProduct>) -> Self {
+        let mut producing = HashMap::new();
+        for recipe in &recipes {
+            producing.entry(recipe.output_product_id).or_insert(recipe.id);
+        }
+
+        let mut by_recipe: HashMap<Uuid, Vec<RecipeIngredient>> = HashMap::new();
+        let mut used_in: HashMap<Uuid, Vec<(Uuid, Decimal)>> = HashMap::new();
+        for ingredient in ingredients {
+            used_in.entry(ingredient.input_product_id).or_default().push((ingredient.recipe_id, ingredient.quantity));
+            by_recipe.entry(ingredient.recipe_id).or_default().push(ingredient);
+        }
+
+        Self {
+            recipes: recipes.into_iter().map(|r| (r.id, r)).collect(),
+            ingredients: by_recipe,
+            producing,
+            used_in,
+            products: products.into_iter().map(|p| (p.id, p)).collect(),
+        }
+    }
+
+    pub fn recipe(&self, recipe_id: Uuid) -> Result<&Recipe, Error> {
+        self.recipes.get(&recipe_id).ok_or(Error::NotFound("Recipe not found".to_string()))
+    }
+
+    /// The recipe used to make a product, if it is made in-house.
+    pub fn producing_recipe(&self, product_id: Uuid) -> Option<&Recipe> {
+        self.producing.get(&product_id).and_then(|id| self.recipes.get(id))
+    }
+
+    pub fn ingredients(&self, recipe_id: Uuid) -> &[RecipeIngredient] {
+        self.ingredients.get(&recipe_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Fails when a recipe needs its own output somewhere down its chain of sub-recipes.
+    pub fn check_cycles(&self, recipe_id: Uuid) -> Result<(), Error> {
+        self.visit(recipe_id, &mut Vec::new())
+    }
+
+    fn visit(&self, recipe_id: Uuid, path: &mut Vec<Uuid>) -> Result<(), Error> {
+        let recipe = self.recipe(recipe_id)?;
+        if path.contains(&recipe.output_product_id) {
+            let chain: Vec<String> = path.iter().chain(std::iter::once(&recipe.output_product_id)).map(|id| self.label(*id)).collect();
+            return Err(Error::BusinessRule(format!("Bill of materials has a cycle: {}", chain.join(" -> "))));
+        }
+
+        path.push(recipe.output_product_id);
+        for ingredient in self.ingredients(recipe_id) {
+            if let Some(child) = self.producing_recipe(ingredient.input_product_id) {
+                self.visit(child.id, path)?;
+            }
+        }
+        path.pop();
+        Ok(())
+    }
+
+    /// Sorts products by low-level code: a product comes after every product whose recipe
+    /// consumes it, at any depth.
+    pub fn low_level_order(&self, products: impl Iterator<Item = Uuid>) -> Result<Vec<Uuid>, Error> {
+        let mut levels: HashMap<Uuid, u32> = HashMap::new();
+        for recipe_id in self.producing.values() {
+            self.check_cycles(*recipe_id)?;
+            self.assign_levels(*recipe_id, 0, &mut levels);
+        }
+
+        let mut ordered: Vec<Uuid> = products.collect();
+        ordered.sort_by_key(|id| (levels.get(id).copied().unwrap_or(0), *id));
+        Ok(ordered)
+    }
+
+    fn assign_levels(&self, recipe_id: Uuid, level: u32, levels: &mut HashMap<Uuid, u32>) {
+        for ingredient in self.ingredients(recipe_id) {
+            let current = levels.entry(ingredient.input_product_id).or_insert(0);
+            if *current <= level {
+                *current = level + 1;
+                if let Some(child) = self.producing_recipe(ingredient.input_product_id) {
+                    self.assign_levels(child.id, level + 1, levels);
+                }
+            }
+        }
+    }
+
+    pub fn explode(&self, recipe_id: Uuid, batches: Decimal) -> Result<IndentedBom, Error> {
+        self.check_cycles(recipe_id)?;
+        let recipe = self.recipe(recipe_id)?;
+        let mut memo = HashMap::new();
+
+        let unit_cost = self.recipe_unit_cost(recipe, &mut memo);
+        let quantity = recipe.output_quantity * batches;
+        let product = self.products.get(&recipe.output_product_id);
+        let mut lines = vec![IndentedBomLine {
+            level: 0,
+            product_id: recipe.output_product_id,
+            sku: product.map(|p| p.sku.clone()).unwrap_or_default(),
+            name: product.map(|p| p.name.clone()).unwrap_or_default(),
+            quantity_per: recipe.output_quantity,
+            quantity,
+            unit_cost,
+            extended_cost: (quantity * unit_cost).round_dp(2),
+            recipe_id: Some(recipe.id),
+            recipe_name: Some(recipe.name.clone()),
+        }];
+        self.explode_into(recipe, batches, 1, &mut lines, &mut memo);
+
+        Ok(IndentedBom {
+            recipe_id,
+            recipe_name: recipe.name.clone(),
+            batches,
+            total_cost: lines[0].extended_cost,
+            lines,
+        })
+    }
+
+    fn explode_into(&self, recipe: &Recipe, batches: Decimal, level: u32, lines: &mut Vec<IndentedBomLine>, memo: &mut HashMap<Uuid, Decimal>) {
+        for ingredient in self.ingredients(recipe.id) {
+            let quantity = ingredient.quantity * batches;
+            let child = self.producing_recipe(ingredient.input_product_id);
+            let unit_cost = self.component_cost(ingredient.input_product_id, memo);
+            let product = self.products.get(&ingredient.input_product_id);
+
+            lines.push(IndentedBomLine {
+                level,
+                product_id: ingredient.input_product_id,
+                sku: product.map(|p| p.sku.clone()).unwrap_or_default(),
+                name: product.map(|p| p.name.clone()).unwrap_or_default(),
+                quantity_per: ingredient.quantity,
+                quantity,
+                unit_cost,
+                extended_cost: (quantity * unit_cost).round_dp(2),
+                recipe_id: child.map(|c| c.id),
+                recipe_name: child.map(|c| c.name.clone()),
+            });
+
+            if let Some(child) = child.filter(|c| c.output_quantity > Decimal::ZERO) {
+                self.explode_into(child, quantity / child.output_quantity, level + 1, lines, memo);
+            }
+        }
+    }
+
+    /// Recipes consuming the product, then recipes consuming those outputs, and so on.
+    pub fn where_used(&self, product_id: Uuid) -> Vec<WhereUsedLine> {
+        let mut lines = Vec::new();
+        self.where_used_into(product_id, 1, &mut vec![product_id], &mut lines);
+        lines
+    }
+
+    fn where_used_into(&self, product_id: Uuid, level: u32, path: &mut Vec<Uuid>, lines: &mut Vec<WhereUsedLine>) {
+        let Some(uses) = self.used_in.get(&product_id) else { return };
+        let mut uses: Vec<(&Recipe, Decimal)> = uses.iter()
+            .filter_map(|(recipe_id, qty)| self.recipes.get(recipe_id).map(|r| (r, *qty)))
+            .collect();
+        uses.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+
+        for (recipe, quantity_per) in uses {
+            let output = self.products.get(&recipe.output_product_id);
+            lines.push(WhereUsedLine {
+                level,
+                recipe_id: recipe.id,
+                recipe_name: recipe.name.clone(),
+                output_product_id: recipe.output_product_id,
+                output_sku: output.map(|p| p.sku.clone()).unwrap_or_default(),
+                output_name: output.map(|p| p.name.clone()).unwrap_or_default(),
+                quantity_per,
+            });
+
+            if !path.contains(&recipe.output_product_id) {
+                path.push(recipe.output_product_id);
+                self.where_used_into(recipe.output_product_id, level + 1, path, lines);
+                path.pop();
+            }
+        }
+    }
+
+    pub fn roll_up(&self, recipe_id: Uuid) -> Result<CostRollup, Error> {
+        self.check_cycles(recipe_id)?;
+        let recipe = self.recipe(recipe_id)?;
+        let mut memo = HashMap::new();
+
+        let components: Vec<CostRollupLine> = self.ingredients(recipe_id).iter().map(|ingredient| {
+            let unit_cost = self.component_cost(ingredient.input_product_id, &mut memo);
+            let product = self.products.get(&ingredient.input_product_id);
+            CostRollupLine {
+                product_id: ingredient.input_product_id,
+                sku: product.map(|p| p.sku.clone()).unwrap_or_default(),
+                name: product.map(|p| p.name.clone()).unwrap_or_default(),
+                quantity: ingredient.quantity,
+                unit_cost,
+                extended_cost: (ingredient.quantity * unit_cost).round_dp(2),
+                recipe_id: self.producing_recipe(ingredient.input_product_id).map(|r| r.id),
+            }
+        }).collect();
+
+        let material_cost: Decimal = components.iter().map(|c| c.extended_cost).sum();
+        let batch_cost = material_cost + recipe.labor_cost + recipe.overhead_cost;
+
+        Ok(CostRollup {
+            recipe_id,
+            recipe_name: recipe.name.clone(),
+            output_product_id: recipe.output_product_id,
+            output_quantity: recipe.output_quantity,
+            components,
+            material_cost,
+            labor_cost: recipe.labor_cost,
+            overhead_cost: recipe.overhead_cost,
+            batch_cost,
+            unit_cost: self.recipe_unit_cost(recipe, &mut memo),
+        })
+    }
+
+    /// Per-unit standard cost of the recipe and of every sub-recipe beneath it.
+    pub fn unit_costs(&self, recipe_id: Uuid) -> Result<HashMap<Uuid, Decimal>, Error> {
+        self.check_cycles(recipe_id)?;
+        let mut memo = HashMap::new();
+        self.recipe_unit_cost(self.recipe(recipe_id)?, &mut memo);
+        Ok(memo)
+    }
+
+    /// Callers must have run `check_cycles` first.
+    fn recipe_unit_cost(&self, recipe: &Recipe, memo: &mut HashMap<Uuid, Decimal>) -> Decimal {
+        if let Some(cost) = memo.get(&recipe.id) {
+            return *cost;
+        }
+        let material_cost: Decimal = self.ingredients(recipe.id).iter()
+            .map(|i| (i.quantity * self.component_cost(i.input_product_id, memo)).round_dp(2))
+            .sum();
+        let batch_cost = material_cost + recipe.labor_cost + recipe.overhead_cost;
+        let unit_cost = if recipe.output_quantity > Decimal::ZERO {
+            (batch_cost / recipe.output_quantity).round_dp(4)
+        } else {
+            Decimal::ZERO
+        };
+        memo.insert(recipe.id, unit_cost);
+        unit_cost
+    }
+
+    fn component_cost(&self, product_id: Uuid, memo: &mut HashMap<Uuid, Decimal>) -> Decimal {
+        match self.producing_recipe(product_id) {
+            Some(recipe) => self.recipe_unit_cost(recipe, memo),
+            None => self.products.get(&product_id).map(|p| p.unit_cost).unwrap_or_default(),
+        }
+    }
+
+    fn label(&self, product_id: Uuid) -> String {
+        self.products.get(&product_id).map(|p| p.sku.clone()).unwrap_or_else(|| product_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::models::manufacturing::RecipeStatus;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn recipe(n: u128, name: &str, output: u128, output_quantity: i64, labor_cost: i64) -> Recipe {
+        Recipe {
+            id: id(n),
+            tenant_id: Uuid::nil(),
+            name: name.to_string(),
+            output_product_id: id(output),
+            output_quantity: Decimal::from(output_quantity),
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            labor_cost: Decimal::from(labor_cost),
+            overhead_cost: Decimal::ZERO,
+            standard_unit_cost: None,
+            cost_rolled_up_at: None,
+            lead_time_days: 0,
+            routing_id: None,
+            version: 1,
+            status: RecipeStatus::Approved,
+            effective_from: None,
+            effective_to: None,
+            approved_by: None,
+            approved_at: None,
+        }
+    }
+
+    fn ingredient(recipe: u128, input: u128, quantity: Decimal) -> RecipeIngredient {
+        RecipeIngredient { id: Uuid::new_v4(), recipe_id: id(recipe), input_product_id: id(input), quantity }
+    }
+
+    fn product(n: u128, sku: &str, unit_cost: Decimal) -> BomProduct {
+        BomProduct { id: id(n), sku: sku.to_string(), name: sku.to_string(), unit_cost }
+    }
+
+    const BAG: u128 = 1;
+    const PANEL: u128 = 2;
+    const CRUST: u128 = 3;
+    const RAW_HIDE: u128 = 4;
+    const DYE: u128 = 5;
+    const THREAD: u128 = 6;
+
+    /// Bag <- 2 panels + 5 thread; 2 panels <- 1 crust + 1 thread; 4 crust <- 1 raw hide + 2 dye.
+    fn bag_graph() -> BomGraph {
+        BomGraph::new(
+            vec![
+                recipe(101, "Bag", BAG, 1, 6),
+                recipe(102, "Panel", PANEL, 2, 2),
+                recipe(103, "Crust", CRUST, 4, 4),
+            ],
+            vec![
+                ingredient(101, PANEL, Decimal::TWO),
+                ingredient(101, THREAD, Decimal::from(5)),
+                ingredient(102, CRUST, Decimal::ONE),
+                ingredient(102, THREAD, Decimal::ONE),
+                ingredient(103, RAW_HIDE, Decimal::ONE),
+                ingredient(103, DYE, Decimal::TWO),
+            ],
+            vec![
+                product(BAG, "BAG", Decimal::ZERO),
+                product(PANEL, "PANEL", Decimal::ZERO),
+                product(CRUST, "CRUST", Decimal::ZERO),
+                product(RAW_HIDE, "HIDE", Decimal::from(20)),
+                product(DYE, "DYE", Decimal::from(3)),
+                product(THREAD, "THREAD", Decimal::new(5, 1)),
+            ],
+        )
+    }
+
+    #[test]
+    fn direct_cycle_is_rejected() {
+        let graph = BomGraph::new(
+            vec![recipe(101, "Rework", BAG, 1, 0)],
+            vec![ingredient(101, BAG, Decimal::ONE), ingredient(101, THREAD, Decimal::ONE)],
+            vec![product(BAG, "BAG", Decimal::ZERO), product(THREAD, "THREAD", Decimal::ONE)],
+        );
+
+        match graph.check_cycles(id(101)) {
+            Err(Error::BusinessRule(message)) => assert_eq!(message, "Bill of materials has a cycle: BAG -> BAG"),
+            other => panic!("expected a cycle, got {:?}", other),
+        }
+        assert!(graph.explode(id(101), Decimal::ONE).is_err());
+    }
+
+    #[test]
+    fn indirect_cycle_is_rejected() {
+        let graph = BomGraph::new(
+            vec![recipe(101, "Bag", BAG, 1, 0), recipe(102, "Panel", PANEL, 1, 0), recipe(103, "Crust", CRUST, 1, 0)],
+            vec![ingredient(101, PANEL, Decimal::ONE), ingredient(102, CRUST, Decimal::ONE), ingredient(103, BAG, Decimal::ONE)],
+            vec![product(BAG, "BAG", Decimal::ZERO), product(PANEL, "PANEL", Decimal::ZERO), product(CRUST, "CRUST", Decimal::ZERO)],
+        );
+
+        match graph.check_cycles(id(101)) {
+            Err(Error::BusinessRule(message)) => assert_eq!(message, "Bill of materials has a cycle: BAG -> PANEL -> CRUST -> BAG"),
+            other => panic!("expected a cycle, got {:?}", other),
+        }
+        assert!(graph.roll_up(id(102)).is_err());
+        assert!(graph.low_level_order([id(BAG)].into_iter()).is_err());
+    }
+
+    #[test]
+    fn acyclic_graph_passes() {
+        let graph = bag_graph();
+        assert!(graph.check_cycles(id(101)).is_ok());
+        let order = graph.low_level_order([id(DYE), id(CRUST), id(BAG), id(PANEL)].into_iter()).unwrap();
+        assert_eq!(order, vec![id(BAG), id(PANEL), id(CRUST), id(DYE)]);
+    }
+
+    #[test]
+    fn three_level_explosion() {
+        let bom = bag_graph().explode(id(101), Decimal::from(3)).unwrap();
+
+        let lines: Vec<(u32, &str, Decimal, Decimal)> =
+            bom.lines.iter().map(|l| (l.level, l.sku.as_str(), l.quantity, l.extended_cost)).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (0, "BAG", Decimal::from(3), Decimal::new(555, 1)),
+                (1, "PANEL", Decimal::from(6), Decimal::from(30)),
+                (2, "CRUST", Decimal::from(3), Decimal::new(225, 1)),
+                (3, "HIDE", Decimal::new(75, 2), Decimal::from(15)),
+                (3, "DYE", Decimal::new(15, 1), Decimal::new(45, 1)),
+                (2, "THREAD", Decimal::from(3), Decimal::new(15, 1)),
+                (1, "THREAD", Decimal::from(15), Decimal::new(75, 1)),
+            ]
+        );
+        assert_eq!(bom.lines[1].recipe_id, Some(id(102)));
+        assert_eq!(bom.lines[6].recipe_id, None);
+        assert_eq!(bom.total_cost, Decimal::new(555, 1));
+    }
+
+    #[test]
+    fn roll_up_costs_sub_recipes_first() {
+        let graph = bag_graph();
+        let rollup = graph.roll_up(id(101)).unwrap();
+
+        // Crust: (20 + 2 x 3 + 4) / 4 = 7.5; panel: (7.5 + 0.5 + 2) / 2 = 5; bag: 2 x 5 + 5 x 0.5 + 6
+        assert_eq!(rollup.material_cost, Decimal::new(125, 1));
+        assert_eq!(rollup.batch_cost, Decimal::new(185, 1));
+        assert_eq!(rollup.unit_cost, Decimal::new(185, 1));
+        assert_eq!(rollup.components[0].unit_cost, Decimal::from(5));
+        assert_eq!(rollup.components[0].recipe_id, Some(id(102)));
+
+        let costs = graph.unit_costs(id(101)).unwrap();
+        assert_eq!(costs[&id(103)], Decimal::new(75, 1));
+        assert_eq!(costs[&id(102)], Decimal::from(5));
+    }
+}
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WasteType {
+    #[strum(serialize = "EFFLUENT")]
+    Effluent, // Wastewater discharged under permit
+    #[strum(serialize = "SLUDGE")]
+    Sludge,
+    #[strum(serialize = "SOLID")]
+    Solid, // Shavings, trimmings, fleshings
+    #[strum(serialize = "CHEMICAL")]
+    Chemical,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RestrictedSubstance {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub cas_number: Option<String>,
+    pub regulation: Option<String>,
+    pub reporting_threshold: Option<Decimal>, // kg per reporting period
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRestrictedSubstance {
+    pub name: String,
+    pub cas_number: Option<String>,
+    pub regulation: Option<String>,
+    pub reporting_threshold: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ChemicalSubstance {
+    pub substance_id: Uuid,
+    pub name: String,
+    pub cas_number: Option<String>,
+    pub concentration: Decimal, // Percent by weight
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Chemical {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub stock_quantity: Decimal,
+    pub lot_quantity: Decimal,     // Remaining in unexpired lots
+    pub expired_quantity: Decimal, // Remaining in expired lots
+    #[sqlx(skip)]
+    pub substances: Vec<ChemicalSubstance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubstanceShare {
+    pub substance_id: Uuid,
+    pub concentration: Decimal,
+}
+
+/// Marks a product as a lot-tracked chemical and sets its restricted substance content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetChemical {
+    #[serde(default)]
+    pub substances: Vec<SubstanceShare>,
+}
+
+impl SetChemical {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.substances.iter().any(|s| s.concentration <= Decimal::ZERO || s.concentration > Decimal::ONE_HUNDRED) {
+            return Err(Error::BusinessRule("Concentrations must be above 0 and at most 100 percent".to_string()));
+        }
+        if self.substances.iter().map(|s| s.concentration).sum::<Decimal>() > Decimal::ONE_HUNDRED {
+            return Err(Error::BusinessRule("Concentrations add up to more than 100 percent".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ChemicalLot {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub product_id: Uuid,
+    pub lot_number: String,
+    pub supplier_id: Option<Uuid>,
+    pub sds_reference: String,
+    pub hazard_class: String,
+    pub received_date: NaiveDate,
+    pub expiry_date: Option<NaiveDate>,
+    pub quantity: Decimal,
+    pub remaining_quantity: Decimal,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ChemicalLot {
+    /// A lot can be used up to and including its expiry date.
+    pub fn is_expired(&self, on: NaiveDate) -> bool {
+        self.expiry_date.is_some_and(|d| d < on)
+    }
+}
+
+/// Registers a lot of chemical received into stock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateChemicalLot {
+    pub product_id: Uuid,
+    pub lot_number: String,
+    pub supplier_id: Option<Uuid>,
+    pub sds_reference: String,
+    pub hazard_class: String,
+    pub received_date: Option<NaiveDate>,
+    pub expiry_date: Option<NaiveDate>,
+    pub quantity: Decimal,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChemicalLotQuery {
+    pub product_id: Option<Uuid>,
+    pub expired: Option<bool>,
+    #[serde(default)]
+    pub include_empty: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ChemicalConsumption {
+    pub id: Uuid,
+    pub lot_id: Uuid,
+    pub lot_number: String,
+    pub product_id: Uuid,
+    pub sku: String,
+    pub work_order_id: Uuid,
+    pub quantity: Decimal, // Negative for returns
+    pub consumed_date: NaiveDate,
+    pub sds_reference: String,
+    pub hazard_class: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChemicalLotDetail {
+    pub lot: ChemicalLot,
+    pub consumptions: Vec<ChemicalConsumption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WasteDisposal {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub disposal_number: String,
+    pub waste_type: WasteType,
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit: String,
+    pub disposal_date: NaiveDate,
+    pub manifest_number: Option<String>,
+    pub carrier: Option<String>,
+    pub facility: Option<String>,
+    pub disposal_cost: Option<Decimal>,
+    pub work_order_id: Option<Uuid>,
+    pub lot_id: Option<Uuid>,
+    pub transaction_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DisposedSubstance {
+    pub substance_id: Uuid,
+    pub name: String,
+    pub quantity: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasteDisposalDetail {
+    pub disposal: WasteDisposal,
+    pub substances: Vec<DisposedSubstance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubstanceQuantity {
+    pub substance_id: Uuid,
+    pub quantity: Decimal, // kg
+}
+
+/// Records waste leaving site. Disposing of a chemical lot also writes the quantity off stock;
+/// its restricted substances are then worked out from the chemical's content unless given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWasteDisposal {
+    pub waste_type: WasteType,
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit: Option<String>, // Defaults to the chemical's unit for a lot, otherwise KG
+    pub disposal_date: Option<NaiveDate>,
+    pub manifest_number: Option<String>,
+    pub carrier: Option<String>,
+    pub facility: Option<String>,
+    pub disposal_cost: Option<Decimal>,
+    pub work_order_id: Option<Uuid>,
+    pub lot_id: Option<Uuid>,
+    #[serde(default)]
+    pub substances: Vec<SubstanceQuantity>,
+    pub notes: Option<String>,
+}
+
+impl CreateWasteDisposal {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.quantity <= Decimal::ZERO || self.disposal_cost.is_some_and(|c| c < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Quantity must be positive and the cost not negative".to_string()));
+        }
+        if self.description.trim().is_empty() {
+            return Err(Error::BusinessRule("Describe the waste".to_string()));
+        }
+        if self.waste_type != WasteType::Effluent && self.manifest_number.as_deref().is_none_or(|m| m.trim().is_empty()) {
+            return Err(Error::BusinessRule(format!("A manifest number is required for {} waste", self.waste_type)));
+        }
+        if self.lot_id.is_some() && self.waste_type != WasteType::Chemical {
+            return Err(Error::BusinessRule("Only chemical waste can come from a chemical lot".to_string()));
+        }
+        if self.substances.iter().any(|s| s.quantity < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Substance quantities cannot be negative".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WasteDisposalQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub waste_type: Option<WasteType>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ComplianceReportQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SubstanceReportLine {
+    pub substance_id: Uuid,
+    pub name: String,
+    pub cas_number: Option<String>,
+    pub regulation: Option<String>,
+    pub used_quantity: Decimal,     // Consumed by work orders, net of returns
+    pub disposed_quantity: Decimal,
+    pub reporting_threshold: Option<Decimal>,
+    #[sqlx(skip)]
+    pub over_threshold: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WasteReportLine {
+    pub waste_type: WasteType,
+    pub unit: String,
+    pub disposals: i64,
+    pub quantity: Decimal,
+    pub disposal_cost: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub substances: Vec<SubstanceReportLine>,
+    pub waste: Vec<WasteReportLine>,
+    pub expired_lots: Vec<ChemicalLot>, // Still holding stock at the end of the period
+}
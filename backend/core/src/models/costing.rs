@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use strum::{Display, EnumString};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CostingMethod {
+    #[strum(serialize = "FIFO")]
+    #[sqlx(rename = "FIFO")]
+    Fifo,
+    #[strum(serialize = "MOVING_AVERAGE")]
+    MovingAverage,
+    #[strum(serialize = "STANDARD")]
+    Standard,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct InventorySettings {
+    pub tenant_id: Uuid,
+    pub costing_method: CostingMethod,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateInventorySettings {
+    pub costing_method: CostingMethod,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateStandardCost {
+    pub standard_cost: Decimal,
+}
+
+/// A receipt of stock at a given unit cost. `remaining_quantity` is drawn down by issues,
+/// oldest layer first.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CostLayer {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub product_id: Uuid,
+    pub transaction_id: Option<Uuid>,
+    pub received_at: DateTime<Utc>,
+    pub original_quantity: Decimal,
+    pub remaining_quantity: Decimal,
+    pub unit_cost: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerConsumption {
+    pub layer_id: Uuid,
+    pub quantity: Decimal,
+    pub unit_cost: Decimal,
+}
+
+/// Result of costing an issue of stock. `unlayered_quantity` is stock issued beyond what the
+/// layers hold (negative on-hand); it is valued at the last known cost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostIssue {
+    pub quantity: Decimal,
+    pub total_cost: Decimal,
+    pub consumptions: Vec<LayerConsumption>,
+    pub unlayered_quantity: Decimal,
+}
+
+impl CostIssue {
+    pub fn unit_cost(&self) -> Decimal {
+        if self.quantity == Decimal::ZERO {
+            Decimal::ZERO
+        } else {
+            (self.total_cost / self.quantity).round_dp(4)
+        }
+    }
+}
+
+/// Unit cost a receipt is layered at. Standard costing books receipts at standard; the
+/// difference from the actual price is a purchase price variance.
+pub fn receipt_unit_cost(method: CostingMethod, actual: Decimal, standard_cost: Decimal) -> Decimal {
+    match method {
+        CostingMethod::Standard => standard_cost,
+        CostingMethod::Fifo | CostingMethod::MovingAverage => actual,
+    }
+}
+
+/// New moving average after a receipt. Negative on-hand is ignored so a receipt that
+/// covers a backlog takes the receipt cost.
+pub fn moving_average(on_hand: Decimal, average_cost: Decimal, receipt_quantity: Decimal, receipt_unit_cost: Decimal) -> Decimal {
+    let on_hand = on_hand.max(Decimal::ZERO);
+    let total = on_hand + receipt_quantity;
+    if total <= Decimal::ZERO {
+        return receipt_unit_cost;
+    }
+    ((on_hand * average_cost + receipt_quantity * receipt_unit_cost) / total).round_dp(4)
+}
+
+/// Quantity a receipt leaves open in its layer. Stock issued beyond the layers (negative
+/// on-hand) was valued when it went out, so the receipt covers that backlog first.
+pub fn receipt_layer_quantity(on_hand: Decimal, layered: Decimal, receipt_quantity: Decimal) -> Decimal {
+    (on_hand + receipt_quantity - layered).max(Decimal::ZERO).min(receipt_quantity)
+}
+
+/// Costs an issue against layers ordered oldest first. Layers are always consumed FIFO so
+/// remaining quantities track physical stock; the method only decides the value.
+pub fn cost_issue(
+    method: CostingMethod,
+    layers: &[CostLayer],
+    quantity: Decimal,
+    average_cost: Decimal,
+    standard_cost: Decimal,
+) -> CostIssue {
+    let mut remaining = quantity;
+    let mut fifo_cost = Decimal::ZERO;
+    let mut consumptions = Vec::new();
+    let mut last_cost = average_cost;
+
+    for layer in layers.iter().filter(|l| l.remaining_quantity > Decimal::ZERO) {
+        if remaining <= Decimal::ZERO {
+            break;
+        }
+        let take = remaining.min(layer.remaining_quantity);
+        fifo_cost += take * layer.unit_cost;
+        last_cost = layer.unit_cost;
+        remaining -= take;
+        consumptions.push(LayerConsumption { layer_id: layer.id, quantity: take, unit_cost: layer.unit_cost });
+    }
+
+    if remaining > Decimal::ZERO {
+        if let Some(newest) = layers.last() {
+            last_cost = newest.unit_cost;
+        }
+        fifo_cost += remaining * last_cost;
+    }
+
+    let total_cost = match method {
+        CostingMethod::Fifo => fifo_cost,
+        CostingMethod::MovingAverage => quantity * average_cost,
+        CostingMethod::Standard => quantity * standard_cost,
+    };
+
+    CostIssue {
+        quantity,
+        total_cost: total_cost.round_dp(2),
+        consumptions,
+        unlayered_quantity: remaining.max(Decimal::ZERO),
+    }
+}
+
+// --- Valuation ---
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ValuationLine {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub quantity: Decimal,
+    pub layer_quantity: Decimal,
+    pub layer_value: Decimal,
+    pub average_cost: Decimal,
+    pub standard_cost: Decimal,
+    #[sqlx(default)]
+    pub unit_cost: Decimal,
+    #[sqlx(default)]
+    pub value: Decimal,
+}
+
+impl ValuationLine {
+    /// Fills `unit_cost` and `value` for the tenant's costing method.
+    pub fn valued(mut self, method: CostingMethod) -> Self {
+        self.value = match method {
+            CostingMethod::Fifo => {
+                // Stock not covered by layers (e.g. negative balances) is valued at the layers' average cost.
+                let fifo_unit = if self.layer_quantity > Decimal::ZERO { self.layer_value / self.layer_quantity } else { self.average_cost };
+                self.layer_value + (self.quantity - self.layer_quantity) * fifo_unit
+            }
+            CostingMethod::MovingAverage => self.quantity * self.average_cost,
+            CostingMethod::Standard => self.quantity * self.standard_cost,
+        }
+        .round_dp(2);
+        self.unit_cost = if self.quantity != Decimal::ZERO { (self.value / self.quantity).round_dp(4) } else { Decimal::ZERO };
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryValuation {
+    pub costing_method: CostingMethod,
+    pub lines: Vec<ValuationLine>,
+    pub total_value: Decimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(n: u128, remaining: i64, unit_cost: i64) -> CostLayer {
+        CostLayer {
+            id: Uuid::from_u128(n),
+            tenant_id: Uuid::nil(),
+            product_id: Uuid::nil(),
+            transaction_id: None,
+            received_at: Utc::now(),
+            original_quantity: Decimal::from(remaining),
+            remaining_quantity: Decimal::from(remaining),
+            unit_cost: Decimal::from(unit_cost),
+        }
+    }
+
+    fn line(quantity: i64, layer_quantity: i64, layer_value: i64) -> ValuationLine {
+        ValuationLine {
+            product_id: Uuid::nil(),
+            sku: "SKU".to_string(),
+            name: "Item".to_string(),
+            quantity: Decimal::from(quantity),
+            layer_quantity: Decimal::from(layer_quantity),
+            layer_value: Decimal::from(layer_value),
+            average_cost: Decimal::new(25, 1),
+            standard_cost: Decimal::TWO,
+            unit_cost: Decimal::ZERO,
+            value: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn issue_consumes_layers_oldest_first() {
+        let layers = [layer(1, 0, 1), layer(2, 10, 2), layer(3, 5, 3), layer(4, 20, 4)];
+        let issue = cost_issue(CostingMethod::Fifo, &layers, Decimal::from(12), Decimal::ZERO, Decimal::ZERO);
+
+        assert_eq!(issue.total_cost, Decimal::from(26));
+        assert_eq!(issue.unlayered_quantity, Decimal::ZERO);
+        let taken: Vec<(Uuid, Decimal)> = issue.consumptions.iter().map(|c| (c.layer_id, c.quantity)).collect();
+        assert_eq!(taken, vec![(Uuid::from_u128(2), Decimal::from(10)), (Uuid::from_u128(3), Decimal::TWO)]);
+        assert_eq!(issue.unit_cost(), Decimal::new(21667, 4));
+    }
+
+    #[test]
+    fn issue_past_layers_values_the_rest_at_the_newest_cost() {
+        let layers = [layer(1, 10, 2), layer(2, 5, 3)];
+        let issue = cost_issue(CostingMethod::Fifo, &layers, Decimal::from(20), Decimal::from(9), Decimal::ZERO);
+
+        assert_eq!(issue.consumptions.len(), 2);
+        assert_eq!(issue.unlayered_quantity, Decimal::from(5));
+        assert_eq!(issue.total_cost, Decimal::from(50));
+    }
+
+    #[test]
+    fn issue_without_layers_uses_the_average_cost() {
+        let issue = cost_issue(CostingMethod::Fifo, &[], Decimal::from(4), Decimal::new(75, 1), Decimal::ZERO);
+
+        assert!(issue.consumptions.is_empty());
+        assert_eq!(issue.unlayered_quantity, Decimal::from(4));
+        assert_eq!(issue.total_cost, Decimal::from(30));
+    }
+
+    #[test]
+    fn issue_value_follows_the_costing_method() {
+        let layers = [layer(1, 10, 2)];
+        let average = cost_issue(CostingMethod::MovingAverage, &layers, Decimal::from(4), Decimal::new(25, 1), Decimal::from(3));
+        let standard = cost_issue(CostingMethod::Standard, &layers, Decimal::from(4), Decimal::new(25, 1), Decimal::from(3));
+
+        assert_eq!(average.total_cost, Decimal::from(10));
+        assert_eq!(standard.total_cost, Decimal::from(12));
+        // Layers are drawn down whatever the method
+        assert_eq!(average.consumptions[0].quantity, Decimal::from(4));
+        assert_eq!(standard.consumptions[0].quantity, Decimal::from(4));
+    }
+
+    #[test]
+    fn moving_average_blends_receipts_into_stock() {
+        let average = moving_average(Decimal::from(10), Decimal::TWO, Decimal::from(10), Decimal::from(4));
+        assert_eq!(average, Decimal::from(3));
+    }
+
+    #[test]
+    fn moving_average_ignores_negative_on_hand() {
+        let average = moving_average(Decimal::from(-5), Decimal::from(10), Decimal::from(20), Decimal::from(4));
+        assert_eq!(average, Decimal::from(4));
+
+        let average = moving_average(Decimal::from(-5), Decimal::from(10), Decimal::ZERO, Decimal::from(4));
+        assert_eq!(average, Decimal::from(4));
+    }
+
+    #[test]
+    fn receipt_covers_stock_issued_beyond_the_layers() {
+        // 5 issued with nothing layered: a receipt of 20 leaves 15 in its layer
+        assert_eq!(receipt_layer_quantity(Decimal::from(-5), Decimal::ZERO, Decimal::from(20)), Decimal::from(15));
+        // A backlog larger than the receipt leaves the layer empty
+        assert_eq!(receipt_layer_quantity(Decimal::from(-30), Decimal::ZERO, Decimal::from(20)), Decimal::ZERO);
+        // Fully layered stock takes the whole receipt
+        assert_eq!(receipt_layer_quantity(Decimal::from(10), Decimal::from(10), Decimal::from(20)), Decimal::from(20));
+    }
+
+    #[test]
+    fn valued_fifo_covers_unlayered_stock_at_the_layer_average() {
+        let partial = line(15, 10, 30).valued(CostingMethod::Fifo);
+        assert_eq!(partial.value, Decimal::from(45));
+        assert_eq!(partial.unit_cost, Decimal::from(3));
+
+        let negative = line(-5, 0, 0).valued(CostingMethod::Fifo);
+        assert_eq!(negative.value, Decimal::new(-125, 1));
+        assert_eq!(negative.unit_cost, Decimal::new(25, 1));
+    }
+
+    #[test]
+    fn valued_moving_average_and_standard() {
+        let average = line(15, 10, 30).valued(CostingMethod::MovingAverage);
+        assert_eq!(average.value, Decimal::new(375, 1));
+        assert_eq!(average.unit_cost, Decimal::new(25, 1));
+
+        let standard = line(15, 10, 30).valued(CostingMethod::Standard);
+        assert_eq!(standard.value, Decimal::from(30));
+        assert_eq!(standard.unit_cost, Decimal::TWO);
+
+        let empty = line(0, 0, 0).valued(CostingMethod::Standard);
+        assert_eq!(empty.value, Decimal::ZERO);
+        assert_eq!(empty.unit_cost, Decimal::ZERO);
+    }
+}
@@ -2,6 +2,9 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 use chrono::{DateTime, Utc, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::models::address::{CreateAddress, CreateContact};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Employee {
@@ -11,14 +14,13 @@ pub struct Employee {
     pub last_name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
-    pub address: Option<String>,
     pub ssn_last4: Option<String>,
     pub hire_date: NaiveDate,
     pub termination_date: Option<NaiveDate>,
     pub department: Option<String>,
     pub job_title: Option<String>,
     pub pay_type: String,
-    pub pay_rate: rust_decimal::Decimal,
+    pub pay_rate: Decimal,
     pub status: String,
     pub notes: Option<String>,
     pub created_at: DateTime<Utc>,
@@ -31,13 +33,16 @@ pub struct CreateEmployee {
     pub last_name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
-    pub address: Option<String>,
     pub ssn_last4: Option<String>,
     pub hire_date: Option<NaiveDate>,
     pub department: Option<String>,
     pub job_title: Option<String>,
     pub pay_type: String,
     pub pay_rate: f64,
+    #[serde(default)]
+    pub addresses: Vec<CreateAddress>,
+    #[serde(default)]
+    pub contacts: Vec<CreateContact>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,7 +51,6 @@ pub struct UpdateEmployee {
     pub last_name: Option<String>,
     pub email: Option<String>,
     pub phone: Option<String>,
-    pub address: Option<String>,
     pub department: Option<String>,
     pub job_title: Option<String>,
     pub pay_type: Option<String>,
@@ -54,3 +58,70 @@ pub struct UpdateEmployee {
     pub status: Option<String>,
     pub notes: Option<String>,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct PayrollHoursQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Hours one employee booked in a week, from the shop floor time entries.
+#[derive(Debug, Clone, FromRow)]
+pub struct WeeklyHours {
+    pub employee_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub pay_type: String,
+    pub week_start: NaiveDate,
+    pub hours: Decimal,
+    pub labor_cost: Decimal,
+}
+
+/// Hours to pay for the period. Hourly employees earn overtime past 40 hours in a week;
+/// weeks cut by the period boundaries only count the days inside it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayrollHours {
+    pub employee_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub pay_type: String,
+    pub regular_hours: Decimal,
+    pub overtime_hours: Decimal,
+    pub total_hours: Decimal,
+    pub labor_cost: Decimal, // Charged to work orders, before overtime premium
+}
+
+pub const OVERTIME_THRESHOLD_HOURS: i64 = 40;
+
+impl PayrollHours {
+    /// Totals the weeks per employee; `weeks` must be ordered by employee.
+    pub fn from_weeks(weeks: Vec<WeeklyHours>) -> Vec<PayrollHours> {
+        let threshold = Decimal::from(OVERTIME_THRESHOLD_HOURS);
+        let mut totals: Vec<PayrollHours> = Vec::new();
+        for week in weeks {
+            let overtime = if week.pay_type == "HOURLY" && week.hours > threshold {
+                week.hours - threshold
+            } else {
+                Decimal::ZERO
+            };
+            if totals.last().is_none_or(|t| t.employee_id != week.employee_id) {
+                totals.push(PayrollHours {
+                    employee_id: week.employee_id,
+                    first_name: week.first_name,
+                    last_name: week.last_name,
+                    pay_type: week.pay_type,
+                    regular_hours: Decimal::ZERO,
+                    overtime_hours: Decimal::ZERO,
+                    total_hours: Decimal::ZERO,
+                    labor_cost: Decimal::ZERO,
+                });
+            }
+            let total = totals.last_mut().expect("pushed above");
+            total.regular_hours += week.hours - overtime;
+            total.overtime_hours += overtime;
+            total.total_hours += week.hours;
+            total.labor_cost += week.labor_cost;
+        }
+        totals
+    }
+}
@@ -18,6 +18,38 @@ pub enum TransactionType {
     ProductionIn,
     #[strum(serialize = "PRODUCTION_OUT")]
     ProductionOut,
+    #[strum(serialize = "RETURN_TO_VENDOR")]
+    ReturnToVendor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AdjustmentReason {
+    #[strum(serialize = "DAMAGE")]
+    Damage,
+    #[strum(serialize = "SHRINKAGE")]
+    Shrinkage,
+    #[strum(serialize = "FOUND")]
+    Found,
+    #[strum(serialize = "COUNT_CORRECTION")]
+    CountCorrection,
+    #[strum(serialize = "QUALITY_REJECT")]
+    QualityReject,
+    #[strum(serialize = "EXPIRED")]
+    Expired,
+    #[strum(serialize = "OTHER")]
+    Other,
+}
+
+impl AdjustmentReason {
+    /// Default reason for a count variance when the counter did not give one.
+    pub fn for_variance(variance: Decimal) -> Self {
+        if variance < Decimal::ZERO {
+            AdjustmentReason::Shrinkage
+        } else {
+            AdjustmentReason::Found
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -29,6 +61,9 @@ pub struct InventoryTransaction {
     pub transaction_type: TransactionType,
     pub reference_id: Option<Uuid>,
     pub notes: Option<String>,
+    pub reason_code: Option<AdjustmentReason>,
+    pub unit_cost: Option<Decimal>,
+    pub total_cost: Option<Decimal>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -39,6 +74,17 @@ pub struct CreateInventoryTransaction {
     pub transaction_type: TransactionType,
     pub reference_id: Option<Uuid>,
     pub notes: Option<String>,
+    pub reason_code: Option<AdjustmentReason>,
+    pub unit_cost: Option<Decimal>, // Receipt cost for the new cost layer; defaults to the current unit cost
+}
+
+/// Manual stock adjustment. Quantity is signed: positive adds stock, negative removes it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateStockAdjustment {
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    pub reason_code: AdjustmentReason,
+    pub notes: Option<String>,
 }
 
 #[async_trait]
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AllocationMethod {
+    #[strum(serialize = "VALUE")]
+    Value,
+    #[strum(serialize = "QUANTITY")]
+    Quantity,
+    #[strum(serialize = "WEIGHT")]
+    Weight,
+    #[strum(serialize = "AREA")]
+    Area,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LandedCostAllocation {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub bill_id: Uuid,
+    pub allocation_method: AllocationMethod,
+    pub amount: Decimal,
+    pub capitalized_amount: Decimal, // Share landed on stock still on hand
+    pub expensed_amount: Decimal,    // Share relating to stock already issued
+    pub journal_entry_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LandedCostAllocationLine {
+    pub id: Uuid,
+    pub allocation_id: Uuid,
+    pub receipt_line_id: Uuid,
+    pub product_id: Uuid,
+    pub basis: Decimal,
+    pub amount: Decimal,
+    pub capitalized_amount: Decimal,
+    pub expensed_amount: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LandedCostAllocationDetail {
+    pub allocation: LandedCostAllocation,
+    pub lines: Vec<LandedCostAllocationLine>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateLandedCostAllocation {
+    pub bill_id: Uuid,
+    pub receipt_ids: Vec<Uuid>,
+    pub allocation_method: AllocationMethod,
+    pub amount: Option<Decimal>, // Defaults to the bill's unallocated balance
+    pub notes: Option<String>,
+}
+
+/// Splits `amount` across lines in proportion to their basis, rounded to cents. The rounding
+/// remainder goes to the line with the largest basis so the shares always sum to `amount`.
+pub fn allocate(amount: Decimal, bases: &[Decimal]) -> Result<Vec<Decimal>, Error> {
+    let total: Decimal = bases.iter().sum();
+    if total <= Decimal::ZERO {
+        return Err(Error::BusinessRule("Selected receipt lines have no basis for this allocation method".to_string()));
+    }
+
+    let mut shares: Vec<Decimal> = bases.iter().map(|b| (amount * b / total).round_dp(2)).collect();
+    let remainder = amount - shares.iter().sum::<Decimal>();
+    if let Some((largest, _)) = bases.iter().enumerate().max_by(|a, b| a.1.cmp(b.1)) {
+        shares[largest] += remainder;
+    }
+    Ok(shares)
+}
@@ -5,6 +5,8 @@ use chrono::{DateTime, NaiveDate, Utc};
 use strum::{Display, EnumString};
 use async_trait::async_trait;
 
+use crate::models::bom::{CostRollup, IndentedBom, WhereUsedLine};
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Recipe {
     pub id: Uuid,
@@ -15,6 +17,35 @@ pub struct Recipe {
     pub description: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub labor_cost: Decimal,    // Per batch
+    pub overhead_cost: Decimal, // Per batch
+    pub standard_unit_cost: Option<Decimal>,
+    pub cost_rolled_up_at: Option<DateTime<Utc>>,
+    pub lead_time_days: i32, // Production lead time, used by MRP to offset planned work orders
+    pub routing_id: Option<Uuid>,
+    pub version: i32,
+    pub status: RecipeStatus,
+    pub effective_from: Option<NaiveDate>, // Effective from the start while None
+    pub effective_to: Option<NaiveDate>,   // Set once a later version takes over
+    pub approved_by: Option<Uuid>,
+    pub approved_at: Option<DateTime<Utc>>,
+}
+
+impl Recipe {
+    pub fn is_effective_on(&self, date: NaiveDate) -> bool {
+        self.status == RecipeStatus::Approved
+            && self.effective_from.is_none_or(|from| from <= date)
+            && self.effective_to.is_none_or(|to| date <= to)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RecipeStatus {
+    #[strum(serialize = "DRAFT")]
+    Draft,
+    #[strum(serialize = "APPROVED")]
+    Approved,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -25,6 +56,33 @@ pub struct RecipeIngredient {
     pub quantity: Decimal,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OutputType {
+    #[strum(serialize = "CO_PRODUCT")]
+    CoProduct,
+    #[strum(serialize = "BY_PRODUCT")]
+    ByProduct,
+}
+
+/// An output made alongside the recipe's primary output.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RecipeOutput {
+    pub id: Uuid,
+    pub recipe_id: Uuid,
+    pub product_id: Uuid,
+    pub output_type: OutputType,
+    pub quantity: Decimal,   // Per batch
+    pub cost_share: Decimal, // Percent of the batch cost
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeDetail {
+    pub recipe: Recipe,
+    pub ingredients: Vec<RecipeIngredient>,
+    pub by_products: Vec<RecipeOutput>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
 #[sqlx(type_name = "work_order_status", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum WorkOrderStatus {
@@ -49,6 +107,18 @@ pub struct WorkOrder {
     pub end_date: Option<NaiveDate>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub produced_quantity: Decimal, // Good output units reported so far
+    pub scrapped_quantity: Decimal, // Output units scrapped
+    pub material_cost: Decimal,     // Value of material issued, net of returns
+    pub output_cost: Decimal,       // Value received into stock as output
+    pub started_at: Option<DateTime<Utc>>,
+    pub labor_hours: Decimal,
+    pub labor_cost: Decimal,
+    pub overhead_cost: Decimal,     // Applied at the tenant's overhead rates
+    pub material_variance: Decimal, // WIP left at close, split by cost element
+    pub labor_variance: Decimal,
+    pub overhead_variance: Decimal,
+    pub scheduled_end: Option<NaiveDate>, // Last day of the last scheduled operation
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,11 +134,73 @@ pub struct CreateRecipe {
     pub output_quantity: Decimal,
     pub description: Option<String>,
     pub ingredients: Vec<CreateRecipeIngredient>,
+    #[serde(default)]
+    pub labor_cost: Decimal,
+    #[serde(default)]
+    pub overhead_cost: Decimal,
+    #[serde(default)]
+    pub lead_time_days: i32,
+    #[serde(default)]
+    pub by_products: Vec<CreateRecipeOutput>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRecipeOutput {
+    pub product_id: Uuid,
+    pub output_type: OutputType,
+    pub quantity: Decimal,
+    #[serde(default)]
+    pub cost_share: Decimal,
+}
+
+/// Checks the secondary outputs of a recipe. The primary output must keep part of the cost.
+pub fn validate_outputs(output_product_id: Uuid, outputs: &[CreateRecipeOutput]) -> Result<(), crate::error::Error> {
+    let mut seen = Vec::with_capacity(outputs.len());
+    for output in outputs {
+        if output.product_id == output_product_id || seen.contains(&output.product_id) {
+            return Err(crate::error::Error::BusinessRule("Each by-product must be a different product from the other outputs".to_string()));
+        }
+        if output.quantity <= Decimal::ZERO || output.cost_share < Decimal::ZERO {
+            return Err(crate::error::Error::BusinessRule("By-product quantities must be positive and cost shares not negative".to_string()));
+        }
+        seen.push(output.product_id);
+    }
+    if outputs.iter().map(|o| o.cost_share).sum::<Decimal>() >= Decimal::ONE_HUNDRED {
+        return Err(crate::error::Error::BusinessRule("By-product cost shares must add up to less than 100%".to_string()));
+    }
+    Ok(())
+}
+
+/// A new draft version of a recipe. Anything left out is copied from the version it is based on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRecipeVersion {
+    pub output_quantity: Option<Decimal>,
+    pub description: Option<String>,
+    pub ingredients: Option<Vec<CreateRecipeIngredient>>,
+    pub by_products: Option<Vec<CreateRecipeOutput>>,
+    pub labor_cost: Option<Decimal>,
+    pub overhead_cost: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApproveRecipe {
+    pub effective_from: Option<NaiveDate>, // Defaults to today
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRecipeCosts {
+    pub labor_cost: Decimal,
+    pub overhead_cost: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRecipeLeadTime {
+    pub lead_time_days: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateWorkOrder {
-    pub recipe_id: Uuid,
+    pub recipe_id: Uuid, // Any version; the one in effect on the start date is used
     pub quantity: Decimal,
     pub start_date: Option<NaiveDate>,
 }
@@ -90,10 +222,19 @@ pub trait ManufacturingService: Send + Sync {
     /// Completes a Work Order.
     /// This must:
     /// 1. Validate status is IN_PROGRESS (or PLANNED).
-    /// 2. Update status to COMPLETED.
-    /// 3. Fetch Recipe Ingredients.
-    /// 4. Create Inventory Transactions (PRODUCTION_OUT) for all ingredients * quantity.
-    /// 5. Create Inventory Transaction (PRODUCTION_IN) for output product * quantity.
-    /// 6. Update Product Stocks.
+    /// 2. If no production has been reported, issue any outstanding material (failing on
+    ///    insufficient stock) and report the full planned output.
+    /// 3. Update status to COMPLETED.
+    /// 4. Post the difference between material issued and output received as production variance.
     async fn complete_work_order(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrder, crate::error::Error>;
+
+    /// Explodes a recipe through every level of sub-assemblies for `quantity` batches.
+    async fn explode_bom(&self, tenant_id: Uuid, recipe_id: Uuid, quantity: Decimal) -> Result<IndentedBom, crate::error::Error>;
+
+    /// Every recipe that consumes the product, directly or through sub-assemblies.
+    async fn where_used(&self, tenant_id: Uuid, product_id: Uuid) -> Result<Vec<WhereUsedLine>, crate::error::Error>;
+
+    /// Rolls component, labor and overhead costs up to a per-unit standard cost and stores it
+    /// on the recipe and every sub-recipe.
+    async fn roll_up_cost(&self, tenant_id: Uuid, recipe_id: Uuid) -> Result<CostRollup, crate::error::Error>;
 }
@@ -0,0 +1,706 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::{Json, Uuid};
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+
+/// Leather grade of an area of the hide, best first. A piece may be cut from its own grade or better.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum QualityGrade {
+    #[strum(serialize = "A")]
+    A,
+    #[strum(serialize = "B")]
+    B,
+    #[strum(serialize = "C")]
+    C,
+}
+
+/// Coordinates are in the unit the hide is measured in (centimetres on our scanners).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Area enclosed by a simple polygon, whichever way it winds.
+pub fn polygon_area(points: &[Point]) -> f64 {
+    let twice: f64 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum();
+    twice.abs() / 2.0
+}
+
+/// Even-odd test; points on an edge may fall either way.
+pub fn polygon_contains(points: &[Point], p: Point) -> bool {
+    let mut inside = false;
+    let mut j = points.len().wrapping_sub(1);
+    for (i, a) in points.iter().enumerate() {
+        let b = points[j];
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn bounds(points: &[Point]) -> (f64, f64, f64, f64) {
+    points.iter().fold((f64::MAX, f64::MAX, f64::MIN, f64::MIN), |(x0, y0, x1, y1), p| {
+        (x0.min(p.x), y0.min(p.y), x1.max(p.x), y1.max(p.y))
+    })
+}
+
+/// Turns the outline by quarter turns and moves it so its bounding box starts at the origin.
+fn rotate(points: &[Point], quarter_turns: u8) -> Vec<Point> {
+    let turned: Vec<Point> = points
+        .iter()
+        .map(|p| match quarter_turns % 4 {
+            0 => *p,
+            1 => Point { x: -p.y, y: p.x },
+            2 => Point { x: -p.x, y: -p.y },
+            _ => Point { x: p.y, y: -p.x },
+        })
+        .collect();
+    let (x0, y0, _, _) = bounds(&turned);
+    turned.iter().map(|p| Point { x: p.x - x0, y: p.y - y0 }).collect()
+}
+
+fn validate_polygon(points: &[Point], what: &str) -> Result<(), Error> {
+    if points.len() < 3 || points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite()) || polygon_area(points) <= 0.0 {
+        return Err(Error::BusinessRule(format!("{} needs at least three points enclosing an area", what)));
+    }
+    Ok(())
+}
+
+fn to_decimal(value: f64) -> Decimal {
+    Decimal::try_from(value).unwrap_or_default().round_dp(2)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityRegion {
+    pub grade: QualityGrade,
+    pub outline: Vec<Point>,
+}
+
+/// A scanned hide. Defects cannot be cut at all; areas outside every quality region are grade A.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hide {
+    pub reference: Option<String>, // Hide or lot number
+    pub outline: Vec<Point>,
+    #[serde(default)]
+    pub defects: Vec<Vec<Point>>,
+    #[serde(default)]
+    pub quality_regions: Vec<QualityRegion>,
+}
+
+impl Hide {
+    pub fn validate(&self) -> Result<(), Error> {
+        validate_polygon(&self.outline, "Hide outline")?;
+        for defect in &self.defects {
+            validate_polygon(defect, "Defect zone")?;
+        }
+        for region in &self.quality_regions {
+            validate_polygon(&region.outline, "Quality region")?;
+        }
+        Ok(())
+    }
+
+    /// Outline less the defect zones, which are assumed to lie inside it without overlapping.
+    pub fn usable_area(&self) -> f64 {
+        polygon_area(&self.outline) - self.defects.iter().map(|d| polygon_area(d)).sum::<f64>()
+    }
+
+    /// Grade at a point, or `None` where it cannot be cut.
+    fn grade_at(&self, p: Point) -> Option<QualityGrade> {
+        if !polygon_contains(&self.outline, p) || self.defects.iter().any(|d| polygon_contains(d, p)) {
+            return None;
+        }
+        Some(
+            self.quality_regions
+                .iter()
+                .filter(|r| polygon_contains(&r.outline, p))
+                .map(|r| r.grade)
+                .max()
+                .unwrap_or(QualityGrade::A),
+        )
+    }
+}
+
+/// A pattern piece cut for each unit of the recipe's output.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PatternPiece {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub recipe_id: Uuid,
+    pub name: String,
+    pub outline: Json<Vec<Point>>,
+    pub quantity: i32,               // Pieces per output unit
+    pub min_grade: QualityGrade,     // Worst grade the piece may be cut from
+    pub allow_rotation: bool,        // False where the grain direction matters
+    pub area: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePatternPiece {
+    pub name: String,
+    pub outline: Vec<Point>,
+    pub quantity: Option<i32>,            // Defaults to 1
+    pub min_grade: Option<QualityGrade>,  // Defaults to C
+    pub allow_rotation: Option<bool>,     // Defaults to true
+}
+
+impl CreatePatternPiece {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.trim().is_empty() || self.quantity.is_some_and(|q| q <= 0) {
+            return Err(Error::BusinessRule("Each pattern piece needs a name and a positive quantity".to_string()));
+        }
+        validate_polygon(&self.outline, &format!("Pattern piece '{}'", self.name))
+    }
+
+    pub fn area(&self) -> Decimal {
+        to_decimal(polygon_area(&self.outline))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetPatternPieces {
+    pub pieces: Vec<CreatePatternPiece>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NestRequest {
+    pub hide: Hide,
+    pub units: Option<i32>,         // Output units to cut; as many as fit when omitted
+    pub resolution: Option<f64>,    // Placement grid step; defaults to 1
+    pub spacing: Option<f64>,       // Knife gap kept between pieces; defaults to 0
+    pub notes: Option<String>,
+}
+
+/// A piece placed on the hide, with its outline in hide coordinates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Placement {
+    pub piece_id: Uuid,
+    pub name: String,
+    pub rotation: i32, // Degrees counter-clockwise
+    pub x: f64,
+    pub y: f64,
+    pub outline: Vec<Point>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PieceSummary {
+    pub piece_id: Uuid,
+    pub name: String,
+    pub area: Decimal, // Of one piece
+    pub requested: i32,
+    pub placed: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NestingResult {
+    pub usable_area: Decimal,
+    pub placed_area: Decimal,
+    pub expected_yield: Decimal, // Percent of the usable area
+    pub requested_units: i32,
+    pub complete_units: i32,
+    pub pieces: Vec<PieceSummary>,
+    pub placements: Vec<Placement>,
+}
+
+/// A nesting worked out but not saved, with its drawing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NestingPreview {
+    #[serde(flatten)]
+    pub result: NestingResult,
+    pub svg: String,
+}
+
+/// Draws the hide, its defects and quality regions and the placed pieces.
+pub fn layout_svg(hide: &Hide, placements: &[Placement]) -> String {
+    fn path(points: &[Point]) -> String {
+        let mut d = String::new();
+        for (i, p) in points.iter().enumerate() {
+            let _ = write!(d, "{}{:.2},{:.2} ", if i == 0 { "M" } else { "L" }, p.x, p.y);
+        }
+        d.push('Z');
+        d
+    }
+
+    let (x0, y0, x1, y1) = bounds(&hide.outline);
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{:.2} {:.2} {:.2} {:.2}">"#,
+        x0, y0, x1 - x0, y1 - y0
+    );
+    let _ = write!(svg, r##"<path d="{}" fill="#e8d5b5" stroke="#8b5a2b" stroke-width="0.5"/>"##, path(&hide.outline));
+    for region in &hide.quality_regions {
+        let fill = if region.grade == QualityGrade::B { "#d9bf8c" } else { "#c9a66b" };
+        let _ = write!(svg, r##"<path d="{}" fill="{}" stroke="#8b5a2b" stroke-width="0.3" stroke-dasharray="2 1"/>"##, path(&region.outline), fill);
+    }
+    for defect in &hide.defects {
+        let _ = write!(svg, r##"<path d="{}" fill="#c0392b" fill-opacity="0.6"/>"##, path(defect));
+    }
+    for placement in placements {
+        let (px0, py0, px1, py1) = bounds(&placement.outline);
+        let _ = write!(
+            svg,
+            r##"<path d="{}" fill="#2e86c1" fill-opacity="0.5" stroke="#1b4f72" stroke-width="0.3"><title>{}</title></path><text x="{:.2}" y="{:.2}" font-size="2" text-anchor="middle">{}</text>"##,
+            path(&placement.outline),
+            xml_escape(&placement.name),
+            (px0 + px1) / 2.0,
+            (py0 + py1) / 2.0,
+            xml_escape(&placement.name)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// The hide rasterised on the placement grid: the grade of each cell, `None` where it cannot be cut.
+struct Grid {
+    x0: f64,
+    y0: f64,
+    step: f64,
+    cols: usize,
+    rows: usize,
+    grades: Vec<Option<QualityGrade>>,
+}
+
+/// Corners and centre of a cell; a cell counts as inside a shape when any of them is.
+fn samples(x: f64, y: f64, step: f64) -> [Point; 5] {
+    [
+        Point { x, y },
+        Point { x: x + step, y },
+        Point { x, y: y + step },
+        Point { x: x + step, y: y + step },
+        Point { x: x + step / 2.0, y: y + step / 2.0 },
+    ]
+}
+
+impl Grid {
+    fn new(hide: &Hide, step: f64) -> Self {
+        let (x0, y0, x1, y1) = bounds(&hide.outline);
+        let cols = ((x1 - x0) / step).ceil() as usize;
+        let rows = ((y1 - y0) / step).ceil() as usize;
+        let mut grades = Vec::with_capacity(cols * rows);
+        for row in 0..rows {
+            for col in 0..cols {
+                // Every sample has to be cuttable; the cell takes the worst grade it touches.
+                let grade = samples(x0 + col as f64 * step, y0 + row as f64 * step, step)
+                    .iter()
+                    .map(|p| hide.grade_at(*p))
+                    .try_fold(QualityGrade::A, |worst, g| g.map(|g| worst.max(g)));
+                grades.push(grade);
+            }
+        }
+        Grid { x0, y0, step, cols, rows, grades }
+    }
+}
+
+/// A piece outline turned one way, with the grid cells it covers relative to its corner.
+struct Orientation {
+    quarter_turns: u8,
+    outline: Vec<Point>,
+    cells: Vec<(usize, usize)>,
+    cols: usize,
+    rows: usize,
+}
+
+impl Orientation {
+    fn new(outline: &[Point], quarter_turns: u8, step: f64) -> Self {
+        let outline = rotate(outline, quarter_turns);
+        let (_, _, x1, y1) = bounds(&outline);
+        let cols = (x1 / step).ceil() as usize;
+        let rows = (y1 / step).ceil() as usize;
+        let mut cells = Vec::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                if samples(col as f64 * step, row as f64 * step, step).iter().any(|p| polygon_contains(&outline, *p)) {
+                    cells.push((col, row));
+                }
+            }
+        }
+        // A sliver smaller than a cell still takes one.
+        if cells.is_empty() {
+            cells.push((0, 0));
+        }
+        Orientation { quarter_turns, outline, cells, cols, rows }
+    }
+}
+
+struct Shape<'a> {
+    piece: &'a PatternPiece,
+    orientations: Vec<Orientation>,
+    area: f64,
+    width: f64,
+    height: f64,
+}
+
+/// A shape placed with one of its orientations at a grid cell.
+#[derive(Clone, Copy)]
+struct Placed {
+    shape: usize,
+    orientation: usize,
+    col: usize,
+    row: usize,
+}
+
+/// Bottom-left fill of one ordering of the pieces.
+fn fill(grid: &Grid, shapes: &[Shape], order: &[usize], gap: usize) -> Vec<Placed> {
+    let mut occupied = vec![false; grid.cols * grid.rows];
+    let mut failed: HashSet<usize> = HashSet::new();
+    let mut placed = Vec::new();
+
+    for &index in order {
+        // The hide only fills up, so a piece that did not fit once never will.
+        if failed.contains(&index) {
+            continue;
+        }
+        let shape = &shapes[index];
+        let fits = |o: &Orientation, col: usize, row: usize| {
+            o.cells.iter().all(|&(c, r)| {
+                let cell = (row + r) * grid.cols + col + c;
+                !occupied[cell] && grid.grades[cell].is_some_and(|g| g <= shape.piece.min_grade)
+            })
+        };
+
+        let best = shape
+            .orientations
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| o.cols <= grid.cols && o.rows <= grid.rows)
+            .filter_map(|(k, o)| {
+                (0..=grid.rows - o.rows)
+                    .flat_map(|row| (0..=grid.cols - o.cols).map(move |col| (row, col)))
+                    .find(|&(row, col)| fits(o, col, row))
+                    .map(|(row, col)| (row, col, k))
+            })
+            .min();
+
+        match best {
+            Some((row, col, k)) => {
+                for &(c, r) in &shape.orientations[k].cells {
+                    let (cc, rr) = (col + c, row + r);
+                    for y in rr.saturating_sub(gap)..=(rr + gap).min(grid.rows - 1) {
+                        for x in cc.saturating_sub(gap)..=(cc + gap).min(grid.cols - 1) {
+                            occupied[y * grid.cols + x] = true;
+                        }
+                    }
+                }
+                placed.push(Placed { shape: index, orientation: k, col, row });
+            }
+            None => {
+                failed.insert(index);
+            }
+        }
+    }
+    placed
+}
+
+/// Nests the recipe's pattern pieces on the hide. Pieces are placed bottom-left on a grid of
+/// `resolution` cells, trying the largest pieces first under a few orderings and keeping the
+/// layout that completes the most units and then covers the most area. Placements are exact to
+/// one grid cell; use a spacing of at least the resolution where pieces must not touch.
+pub fn nest(hide: &Hide, pieces: &[PatternPiece], req: &NestRequest) -> Result<NestingResult, Error> {
+    hide.validate()?;
+    let step = req.resolution.unwrap_or(1.0);
+    let spacing = req.spacing.unwrap_or(0.0);
+    if !step.is_finite() || step <= 0.0 || !spacing.is_finite() || spacing < 0.0 {
+        return Err(Error::BusinessRule("Resolution must be positive and spacing not negative".to_string()));
+    }
+    if pieces.is_empty() {
+        return Err(Error::BusinessRule("Recipe has no pattern pieces".to_string()));
+    }
+    if req.units.is_some_and(|u| u <= 0) {
+        return Err(Error::BusinessRule("Units must be positive".to_string()));
+    }
+
+    let grid = Grid::new(hide, step);
+    if grid.cols * grid.rows > 4_000_000 {
+        return Err(Error::BusinessRule("Hide is too large for this resolution".to_string()));
+    }
+    let usable_area = hide.usable_area();
+    let unit_area: f64 = pieces.iter().map(|p| polygon_area(&p.outline.0) * p.quantity as f64).sum();
+    // Without a target, ask for as many units as could fit by area alone.
+    let requested_units = req.units.unwrap_or_else(|| ((usable_area / unit_area).floor() as i32).max(1));
+
+    let shapes: Vec<Shape> = pieces
+        .iter()
+        .map(|piece| {
+            let turns: &[u8] = if piece.allow_rotation { &[0, 1, 2, 3] } else { &[0] };
+            let (x0, y0, x1, y1) = bounds(&piece.outline.0);
+            Shape {
+                piece,
+                orientations: turns.iter().map(|t| Orientation::new(&piece.outline.0, *t, step)).collect(),
+                area: polygon_area(&piece.outline.0),
+                width: x1 - x0,
+                height: y1 - y0,
+            }
+        })
+        .collect();
+
+    // Largest first by area, height and width, and one unit at a time so units get completed.
+    let copies = |i: usize| std::iter::repeat_n(i, shapes[i].piece.quantity as usize);
+    let instances: Vec<usize> = (0..shapes.len()).flat_map(|i| std::iter::repeat_n(i, (shapes[i].piece.quantity * requested_units) as usize)).collect();
+    let keys: [&dyn Fn(&Shape) -> f64; 3] = [&|s| s.area, &|s| s.height, &|s| s.width];
+    let mut orderings: Vec<Vec<usize>> = keys
+        .iter()
+        .map(|key| {
+            let mut order = instances.clone();
+            order.sort_by(|a, b| key(&shapes[*b]).total_cmp(&key(&shapes[*a])));
+            order
+        })
+        .collect();
+    let mut unit: Vec<usize> = (0..shapes.len()).collect();
+    unit.sort_by(|a, b| shapes[*b].area.total_cmp(&shapes[*a].area));
+    orderings.push((0..requested_units).flat_map(|_| unit.iter().flat_map(|&i| copies(i))).collect());
+    let gap = (spacing / step).ceil() as usize;
+
+    let score = |placed: &[Placed]| {
+        let complete = shapes
+            .iter()
+            .enumerate()
+            .map(|(i, s)| placed.iter().filter(|p| p.shape == i).count() as i32 / s.piece.quantity)
+            .min()
+            .unwrap_or(0);
+        let area: f64 = placed.iter().map(|p| shapes[p.shape].area).sum();
+        (complete, area)
+    };
+
+    let mut best: Option<(Vec<Placed>, (i32, f64))> = None;
+    for order in &orderings {
+        let placed = fill(&grid, &shapes, order, gap);
+        let placed_score = score(&placed);
+        if best.as_ref().is_none_or(|(_, s)| placed_score.0 > s.0 || (placed_score.0 == s.0 && placed_score.1 > s.1)) {
+            best = Some((placed, placed_score));
+        }
+    }
+    let (placed, (complete_units, placed_area)) = best.unwrap_or_default();
+
+    let placements = placed
+        .iter()
+        .map(|p| {
+            let orientation = &shapes[p.shape].orientations[p.orientation];
+            let (x, y) = (grid.x0 + p.col as f64 * grid.step, grid.y0 + p.row as f64 * grid.step);
+            Placement {
+                piece_id: shapes[p.shape].piece.id,
+                name: shapes[p.shape].piece.name.clone(),
+                rotation: orientation.quarter_turns as i32 * 90,
+                x,
+                y,
+                outline: orientation.outline.iter().map(|p| Point { x: p.x + x, y: p.y + y }).collect(),
+            }
+        })
+        .collect();
+    let summaries = shapes
+        .iter()
+        .enumerate()
+        .map(|(i, s)| PieceSummary {
+            piece_id: s.piece.id,
+            name: s.piece.name.clone(),
+            area: s.piece.area,
+            requested: s.piece.quantity * requested_units,
+            placed: placed.iter().filter(|p| p.shape == i).count() as i32,
+        })
+        .collect();
+
+    Ok(NestingResult {
+        usable_area: to_decimal(usable_area),
+        placed_area: to_decimal(placed_area),
+        expected_yield: if usable_area > 0.0 { to_decimal(placed_area / usable_area * 100.0) } else { Decimal::ZERO },
+        requested_units,
+        complete_units,
+        pieces: summaries,
+        placements,
+    })
+}
+
+/// A nesting saved against a work order, with the yield actually cut once recorded.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NestingLayout {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub work_order_id: Uuid,
+    pub recipe_id: Uuid,
+    pub hide_reference: Option<String>,
+    pub hide: Json<Hide>,
+    pub placements: Json<Vec<Placement>>,
+    pub pieces: Json<Vec<PieceSummary>>,
+    pub resolution: Decimal,
+    pub spacing: Decimal,
+    pub usable_area: Decimal,
+    pub placed_area: Decimal,
+    pub expected_yield: Decimal,
+    pub requested_units: i32,
+    pub complete_units: i32,
+    pub actual_cut_area: Option<Decimal>,
+    pub actual_yield: Option<Decimal>,
+    pub recorded_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PieceCount {
+    pub piece_id: Uuid,
+    pub quantity: i32,
+}
+
+/// Good pieces actually cut from the hide.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordCutting {
+    pub pieces_cut: Vec<PieceCount>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkOrderNesting {
+    pub work_order_id: Uuid,
+    pub usable_area: Decimal,
+    pub planned_area: Decimal,
+    pub actual_cut_area: Decimal, // Layouts with a recorded cut only
+    pub expected_yield: Option<Decimal>,
+    pub actual_yield: Option<Decimal>,
+    pub layouts: Vec<NestingLayout>,
+}
+
+impl WorkOrderNesting {
+    pub fn build(work_order_id: Uuid, layouts: Vec<NestingLayout>) -> Self {
+        let percent = |part: Decimal, whole: Decimal| (whole > Decimal::ZERO).then(|| (part / whole * Decimal::ONE_HUNDRED).round_dp(2));
+        let usable_area: Decimal = layouts.iter().map(|l| l.usable_area).sum();
+        let planned_area: Decimal = layouts.iter().map(|l| l.placed_area).sum();
+        let recorded: Vec<&NestingLayout> = layouts.iter().filter(|l| l.actual_cut_area.is_some()).collect();
+        let actual_cut_area: Decimal = recorded.iter().filter_map(|l| l.actual_cut_area).sum();
+        let recorded_usable: Decimal = recorded.iter().map(|l| l.usable_area).sum();
+        WorkOrderNesting {
+            work_order_id,
+            usable_area,
+            planned_area,
+            actual_cut_area,
+            expected_yield: percent(planned_area, usable_area),
+            actual_yield: percent(actual_cut_area, recorded_usable),
+            layouts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> Vec<Point> {
+        vec![Point { x: x0, y: y0 }, Point { x: x1, y: y0 }, Point { x: x1, y: y1 }, Point { x: x0, y: y1 }]
+    }
+
+    fn piece(n: u128, name: &str, outline: Vec<Point>, quantity: i32, min_grade: QualityGrade) -> PatternPiece {
+        PatternPiece {
+            id: Uuid::from_u128(n),
+            tenant_id: Uuid::nil(),
+            recipe_id: Uuid::nil(),
+            name: name.to_string(),
+            area: to_decimal(polygon_area(&outline)),
+            outline: Json(outline),
+            quantity,
+            min_grade,
+            allow_rotation: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn area_of_simple_shapes() {
+        assert_eq!(polygon_area(&rect(0.0, 0.0, 4.0, 2.5)), 10.0);
+        let triangle = [Point { x: 0.0, y: 0.0 }, Point { x: 4.0, y: 0.0 }, Point { x: 0.0, y: 3.0 }];
+        assert_eq!(polygon_area(&triangle), 6.0);
+        // Clockwise winding gives the same area
+        let mut clockwise = rect(1.0, 1.0, 3.0, 4.0);
+        clockwise.reverse();
+        assert_eq!(polygon_area(&clockwise), 6.0);
+        let l_shape = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 4.0, y: 0.0 },
+            Point { x: 4.0, y: 1.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 1.0, y: 3.0 },
+            Point { x: 0.0, y: 3.0 },
+        ];
+        assert_eq!(polygon_area(&l_shape), 6.0);
+    }
+
+    #[test]
+    fn containment_of_simple_shapes() {
+        let square = rect(0.0, 0.0, 2.0, 2.0);
+        assert!(polygon_contains(&square, Point { x: 1.0, y: 1.0 }));
+        assert!(!polygon_contains(&square, Point { x: 3.0, y: 1.0 }));
+        assert!(!polygon_contains(&square, Point { x: 1.0, y: -0.5 }));
+
+        let l_shape = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 4.0, y: 0.0 },
+            Point { x: 4.0, y: 1.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 1.0, y: 3.0 },
+            Point { x: 0.0, y: 3.0 },
+        ];
+        assert!(polygon_contains(&l_shape, Point { x: 3.5, y: 0.5 }));
+        assert!(polygon_contains(&l_shape, Point { x: 0.5, y: 2.5 }));
+        assert!(!polygon_contains(&l_shape, Point { x: 2.5, y: 2.0 }));
+    }
+
+    #[test]
+    fn rotation_keeps_the_outline_at_the_origin() {
+        let turned = rotate(&rect(0.0, 0.0, 4.0, 1.0), 1);
+        assert_eq!(bounds(&turned), (0.0, 0.0, 1.0, 4.0));
+        assert_eq!(polygon_area(&turned), 4.0);
+        assert_eq!(bounds(&rotate(&rect(2.0, 3.0, 6.0, 4.0), 2)), (0.0, 0.0, 4.0, 1.0));
+    }
+
+    #[test]
+    fn placement_avoids_defects_and_worse_grades() {
+        let hide = Hide {
+            reference: None,
+            outline: rect(0.0, 0.0, 30.0, 12.0),
+            defects: vec![rect(8.0, 3.0, 12.0, 7.0)],
+            quality_regions: vec![QualityRegion { grade: QualityGrade::C, outline: rect(0.0, 0.0, 5.0, 12.0) }],
+        };
+        let pieces = [
+            piece(1, "Vamp", rect(0.0, 0.0, 4.0, 4.0), 2, QualityGrade::A),
+            piece(2, "Tongue", rect(0.0, 0.0, 3.0, 2.0), 1, QualityGrade::C),
+        ];
+        let req = NestRequest { hide: hide.clone(), units: Some(2), resolution: None, spacing: None, notes: None };
+
+        let result = nest(&hide, &pieces, &req).unwrap();
+        assert_eq!(result.complete_units, 2);
+        assert_eq!(result.placements.len(), 6);
+        assert_eq!(result.usable_area, Decimal::from(344));
+
+        // Probe off the integer grid so no sample lands on an edge
+        for placement in &result.placements {
+            let min_grade = pieces.iter().find(|p| p.id == placement.piece_id).unwrap().min_grade;
+            for i in 0..120 {
+                for j in 0..48 {
+                    let p = Point { x: i as f64 * 0.25 + 0.125, y: j as f64 * 0.25 + 0.125 };
+                    if !polygon_contains(&placement.outline, p) {
+                        continue;
+                    }
+                    let grade = hide.grade_at(p);
+                    assert!(grade.is_some(), "{} covers a defect at {:?}", placement.name, p);
+                    assert!(grade.unwrap() <= min_grade, "{} is cut from grade {:?} at {:?}", placement.name, grade, p);
+                }
+            }
+        }
+    }
+}
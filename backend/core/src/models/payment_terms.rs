@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PaymentTermsType {
+    #[strum(serialize = "NET")]
+    Net, // Due a number of days after the document date
+    #[strum(serialize = "DAY_OF_MONTH")]
+    DayOfMonth, // Due on a set day of the following month
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PaymentTerms {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub terms_type: PaymentTermsType,
+    pub net_days: i32,
+    pub day_of_month: Option<i32>,
+    pub discount_percent: Decimal, // e.g. 2 for "2/10 Net 30"
+    pub discount_days: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// When a document dated under the terms falls due, and the early-payment discount it carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TermsSchedule {
+    pub due_date: NaiveDate,
+    pub discount_date: Option<NaiveDate>,
+    pub discount_amount: Decimal,
+}
+
+impl PaymentTerms {
+    pub fn schedule(&self, date: NaiveDate, total_amount: Decimal) -> TermsSchedule {
+        let due_date = match (self.terms_type, self.day_of_month) {
+            (PaymentTermsType::DayOfMonth, Some(day)) => day_of_following_month(date, day as u32),
+            _ => date + Duration::days(self.net_days as i64),
+        };
+        let has_discount = self.discount_percent > Decimal::ZERO && total_amount > Decimal::ZERO;
+        TermsSchedule {
+            due_date,
+            discount_date: has_discount.then(|| (date + Duration::days(self.discount_days as i64)).min(due_date)),
+            discount_amount: if has_discount {
+                (total_amount * self.discount_percent / Decimal::ONE_HUNDRED).round_dp(2)
+            } else {
+                Decimal::ZERO
+            },
+        }
+    }
+}
+
+/// The discount a payment can take: all of what is left of the document's discount, provided
+/// the payment arrives in the discount period and settles the balance with it.
+pub fn payment_discount(
+    balance: Decimal,
+    discount_available: Decimal,
+    discount_date: Option<NaiveDate>,
+    paid_on: NaiveDate,
+    amount: Decimal,
+) -> Result<Decimal, Error> {
+    let discount_date = match discount_date {
+        Some(d) if discount_available > Decimal::ZERO => d,
+        _ => return Err(Error::BusinessRule("No early-payment discount is available".to_string())),
+    };
+    if paid_on > discount_date {
+        return Err(Error::BusinessRule(format!("The discount period ended on {}", discount_date)));
+    }
+    if amount + discount_available != balance {
+        return Err(Error::BusinessRule(format!(
+            "Taking the {} discount needs a payment of {} to settle the balance",
+            discount_available,
+            balance - discount_available
+        )));
+    }
+    Ok(discount_available)
+}
+
+/// Day `day` of the month after `date`, or that month's last day if it is shorter.
+fn day_of_following_month(date: NaiveDate, day: u32) -> NaiveDate {
+    let first = date.with_day(1).unwrap_or(date) + Months::new(1);
+    let last = (first + Months::new(1)).pred_opt().unwrap_or(first);
+    first.with_day(day.min(last.day())).unwrap_or(last)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePaymentTerms {
+    pub name: String,
+    pub terms_type: Option<PaymentTermsType>,
+    pub net_days: Option<i32>,
+    pub day_of_month: Option<i32>,
+    pub discount_percent: Option<Decimal>,
+    pub discount_days: Option<i32>,
+}
+
+impl CreatePaymentTerms {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.trim().is_empty() {
+            return Err(Error::BusinessRule("Payment terms need a name".to_string()));
+        }
+        if self.net_days.is_some_and(|d| d < 0) || self.discount_days.is_some_and(|d| d < 0) {
+            return Err(Error::BusinessRule("Days cannot be negative".to_string()));
+        }
+        if self.terms_type == Some(PaymentTermsType::DayOfMonth) && !self.day_of_month.is_some_and(|d| (1..=31).contains(&d)) {
+            return Err(Error::BusinessRule("Day of month terms need a day between 1 and 31".to_string()));
+        }
+        if self.discount_percent.is_some_and(|p| p < Decimal::ZERO || p >= Decimal::ONE_HUNDRED) {
+            return Err(Error::BusinessRule("Discount must be at least 0 and under 100 percent".to_string()));
+        }
+        if self.discount_percent.is_some_and(|p| p > Decimal::ZERO)
+            && self.terms_type != Some(PaymentTermsType::DayOfMonth)
+            && self.discount_days.unwrap_or(0) > self.net_days.unwrap_or(0)
+        {
+            return Err(Error::BusinessRule("The discount period cannot run past the net days".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Renames or retires terms. Documents already dated keep the due date they were given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePaymentTerms {
+    pub name: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetPaymentTerms {
+    pub payment_terms_id: Option<Uuid>, // None clears the default
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn terms(terms_type: PaymentTermsType, net_days: i32, day_of_month: Option<i32>, discount_percent: i64, discount_days: i32) -> PaymentTerms {
+        PaymentTerms {
+            id: Uuid::nil(),
+            tenant_id: Uuid::nil(),
+            name: "Terms".to_string(),
+            terms_type,
+            net_days,
+            day_of_month,
+            discount_percent: Decimal::from(discount_percent),
+            discount_days,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn day_of_month_clamps_to_the_end_of_a_short_month() {
+        assert_eq!(day_of_following_month(date(2024, 1, 31), 31), date(2024, 2, 29));
+        assert_eq!(day_of_following_month(date(2023, 1, 15), 31), date(2023, 2, 28));
+        assert_eq!(day_of_following_month(date(2024, 12, 31), 31), date(2025, 1, 31));
+        assert_eq!(day_of_following_month(date(2024, 3, 1), 10), date(2024, 4, 10));
+    }
+
+    #[test]
+    fn schedule_for_net_and_day_of_month_terms() {
+        // 2/10 Net 30 on 1,000.
+        let schedule = terms(PaymentTermsType::Net, 30, None, 2, 10).schedule(date(2024, 6, 5), Decimal::from(1000));
+        assert_eq!(schedule.due_date, date(2024, 7, 5));
+        assert_eq!(schedule.discount_date, Some(date(2024, 6, 15)));
+        assert_eq!(schedule.discount_amount, Decimal::from(20));
+
+        // Due on the 31st of next month, so the end of February; the discount window can't run past it.
+        let schedule = terms(PaymentTermsType::DayOfMonth, 0, Some(31), 1, 45).schedule(date(2023, 1, 20), Decimal::new(25070, 2));
+        assert_eq!(schedule.due_date, date(2023, 2, 28));
+        assert_eq!(schedule.discount_date, Some(date(2023, 2, 28)));
+        assert_eq!(schedule.discount_amount, Decimal::new(251, 2));
+
+        let schedule = terms(PaymentTermsType::Net, 30, None, 0, 0).schedule(date(2024, 6, 5), Decimal::from(1000));
+        assert_eq!((schedule.discount_date, schedule.discount_amount), (None, Decimal::ZERO));
+    }
+
+    #[test]
+    fn discount_is_taken_up_to_and_including_the_discount_date() {
+        let discount_date = Some(date(2024, 6, 15));
+        let discount = Decimal::from(20);
+        let balance = Decimal::from(1000);
+
+        assert_eq!(payment_discount(balance, discount, discount_date, date(2024, 6, 15), Decimal::from(980)).unwrap(), discount);
+        assert!(matches!(
+            payment_discount(balance, discount, discount_date, date(2024, 6, 16), Decimal::from(980)),
+            Err(Error::BusinessRule(message)) if message == "The discount period ended on 2024-06-15"
+        ));
+        // A part payment inside the window can't take the discount.
+        assert!(payment_discount(balance, discount, discount_date, date(2024, 6, 10), Decimal::from(500)).is_err());
+        assert!(payment_discount(balance, Decimal::ZERO, discount_date, date(2024, 6, 10), balance).is_err());
+    }
+}
@@ -35,6 +35,7 @@ pub struct Product {
     pub price: Decimal,
     pub cost_price: Decimal,
     pub stock_quantity: Decimal,
+    pub category: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -47,6 +48,7 @@ pub struct CreateProduct {
     pub unit_of_measure: UnitOfMeasure,
     pub price: Decimal,
     pub cost_price: Decimal,
+    pub category: Option<String>,
 }
 
 #[async_trait]
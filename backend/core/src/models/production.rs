@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::models::manufacturing::{OutputType, Recipe, RecipeIngredient, WorkOrder, WorkOrderStatus};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScrapReason {
+    #[strum(serialize = "CUTTING_WASTE")]
+    CuttingWaste,
+    #[strum(serialize = "DEFECT")]
+    Defect,
+    #[strum(serialize = "DAMAGE")]
+    Damage,
+    #[strum(serialize = "MACHINE_ERROR")]
+    MachineError,
+    #[strum(serialize = "OTHER")]
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LaborEntryMethod {
+    #[strum(serialize = "MANUAL")]
+    Manual,
+    #[strum(serialize = "CLOCK")]
+    Clock,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OverheadBasis {
+    #[strum(serialize = "LABOR_HOURS")]
+    LaborHours, // Rate per hour logged
+    #[strum(serialize = "LABOR_COST")]
+    LaborCost, // Percentage of labor cost
+    #[strum(serialize = "MATERIAL_COST")]
+    MaterialCost, // Percentage of material issued
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OverheadRate {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub basis: OverheadBasis,
+    pub rate: Decimal,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OverheadRate {
+    /// Overhead applied on `amount` of the rate's basis (hours or cost).
+    pub fn apply(&self, amount: Decimal) -> Decimal {
+        match self.basis {
+            OverheadBasis::LaborHours => (amount * self.rate).round_dp(2),
+            OverheadBasis::LaborCost | OverheadBasis::MaterialCost => (amount * self.rate / Decimal::ONE_HUNDRED).round_dp(2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOverheadRate {
+    pub name: String,
+    pub basis: OverheadBasis,
+    pub rate: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateOverheadRate {
+    pub name: Option<String>,
+    pub rate: Option<Decimal>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LaborTimeEntry {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub work_order_id: Uuid,
+    pub employee_id: Uuid,
+    pub work_order_operation_id: Option<Uuid>,
+    pub work_date: NaiveDate,
+    pub hours: Decimal,
+    pub hourly_rate: Decimal,
+    pub labor_cost: Decimal,
+    pub overhead_cost: Decimal,
+    pub entry_method: LaborEntryMethod,
+    pub clock_in: Option<DateTime<Utc>>,
+    pub clock_out: Option<DateTime<Utc>>,
+    pub journal_entry_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLaborTimeEntry {
+    pub employee_id: Uuid,
+    pub work_order_operation_id: Option<Uuid>,
+    pub hours: Decimal,
+    pub work_date: Option<NaiveDate>,
+    pub hourly_rate: Option<Decimal>, // Defaults to the employee's pay rate
+    pub notes: Option<String>,
+}
+
+/// An employee clocked in on a work order and not yet clocked out.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LaborClock {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub employee_id: Uuid,
+    pub work_order_id: Uuid,
+    pub work_order_operation_id: Option<Uuid>,
+    pub clock_in: DateTime<Utc>,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+}
+
+impl LaborClock {
+    /// Hours worked up to `clock_out`, to the nearest hundredth.
+    pub fn hours_until(&self, clock_out: DateTime<Utc>) -> Decimal {
+        (Decimal::from((clock_out - self.clock_in).num_seconds()) / Decimal::from(3600)).round_dp(2)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockIn {
+    pub employee_id: Uuid,
+    pub work_order_operation_id: Option<Uuid>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockOut {
+    pub employee_id: Uuid,
+    pub notes: Option<String>, // Appended to the clock-in notes
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LaborEfficiencyQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub work_order_id: Option<Uuid>,
+}
+
+/// Labor efficiency: planned (standard) hours earned per actual hour worked, as a percentage.
+pub fn labor_efficiency(planned_hours: Decimal, actual_hours: Decimal) -> Option<Decimal> {
+    (actual_hours > Decimal::ZERO).then(|| (planned_hours / actual_hours * Decimal::ONE_HUNDRED).round_dp(1))
+}
+
+/// Planned against actual hours for one work order operation.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OperationEfficiency {
+    pub work_order_operation_id: Uuid,
+    pub work_order_id: Uuid,
+    pub recipe_name: String,
+    pub sequence: i32,
+    pub operation_name: String,
+    pub work_center_id: Uuid,
+    pub planned_hours: Decimal,
+    pub actual_hours: Decimal,
+    pub labor_cost: Decimal,
+    #[sqlx(skip)]
+    pub efficiency: Option<Decimal>,
+}
+
+/// An employee's hours in the period. Planned hours of each operation are earned in
+/// proportion to the hours the employee booked to it; hours booked to a work order
+/// without an operation count as unplanned and carry no efficiency.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct EmployeeEfficiency {
+    pub employee_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub operation_hours: Decimal,
+    pub unplanned_hours: Decimal,
+    pub earned_hours: Decimal,
+    pub labor_cost: Decimal,
+    #[sqlx(skip)]
+    pub efficiency: Option<Decimal>,
+}
+
+/// Report totals cover the hours booked to operations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaborEfficiencyReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub actual_hours: Decimal,
+    pub earned_hours: Decimal,
+    pub efficiency: Option<Decimal>,
+    pub operations: Vec<OperationEfficiency>,
+    pub employees: Vec<EmployeeEfficiency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkOrderCost {
+    pub work_order_id: Uuid,
+    pub status: WorkOrderStatus,
+    pub produced_quantity: Decimal,
+    pub labor_hours: Decimal,
+    pub material_cost: Decimal,
+    pub labor_cost: Decimal,
+    pub overhead_cost: Decimal,
+    pub total_cost: Decimal,
+    pub output_cost: Decimal, // Relieved from WIP to finished goods
+    pub wip_balance: Decimal,
+    pub actual_unit_cost: Option<Decimal>,
+    pub standard_unit_cost: Option<Decimal>,
+    pub material_variance: Decimal,
+    pub labor_variance: Decimal,
+    pub overhead_variance: Decimal,
+    pub labor_entries: Vec<LaborTimeEntry>,
+}
+
+impl WorkOrderCost {
+    pub fn build(work_order: &WorkOrder, recipe: &Recipe, labor_entries: Vec<LaborTimeEntry>) -> Self {
+        let total_cost = work_order.material_cost + work_order.labor_cost + work_order.overhead_cost;
+        let wip_balance = if work_order.status == WorkOrderStatus::Completed || work_order.status == WorkOrderStatus::Cancelled {
+            Decimal::ZERO
+        } else {
+            total_cost - work_order.output_cost
+        };
+        WorkOrderCost {
+            work_order_id: work_order.id,
+            status: work_order.status.clone(),
+            produced_quantity: work_order.produced_quantity,
+            labor_hours: work_order.labor_hours,
+            material_cost: work_order.material_cost,
+            labor_cost: work_order.labor_cost,
+            overhead_cost: work_order.overhead_cost,
+            total_cost,
+            output_cost: work_order.output_cost,
+            wip_balance,
+            actual_unit_cost: (work_order.produced_quantity > Decimal::ZERO)
+                .then(|| (total_cost / work_order.produced_quantity).round_dp(4)),
+            standard_unit_cost: recipe.standard_unit_cost,
+            material_variance: work_order.material_variance,
+            labor_variance: work_order.labor_variance,
+            overhead_variance: work_order.overhead_variance,
+            labor_entries,
+        }
+    }
+}
+
+/// Splits what is left in WIP at close across material, labor and overhead in proportion to
+/// what each put in. Rounding goes to material.
+pub fn split_variance(variance: Decimal, material: Decimal, labor: Decimal, overhead: Decimal) -> (Decimal, Decimal, Decimal) {
+    let total = material + labor + overhead;
+    if total.is_zero() {
+        return (variance, Decimal::ZERO, Decimal::ZERO);
+    }
+    let labor_share = (variance * labor / total).round_dp(2);
+    let overhead_share = (variance * overhead / total).round_dp(2);
+    (variance - labor_share - overhead_share, labor_share, overhead_share)
+}
+
+/// Splits the value relieved from WIP between the primary output and secondary outputs by
+/// their percentage cost shares. Rounding goes to the primary output.
+pub fn allocate_output_cost(value: Decimal, shares: &[Decimal]) -> (Decimal, Vec<Decimal>) {
+    let allocated: Vec<Decimal> = shares.iter().map(|share| (value * share / Decimal::ONE_HUNDRED).round_dp(2)).collect();
+    (value - allocated.iter().sum::<Decimal>(), allocated)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkOrderMaterial {
+    pub id: Uuid,
+    pub work_order_id: Uuid,
+    pub product_id: Uuid,
+    pub planned_quantity: Decimal,
+    pub issued_quantity: Decimal,
+    pub returned_quantity: Decimal,
+    pub scrapped_quantity: Decimal,
+    pub issued_cost: Decimal, // Net of returns
+}
+
+impl WorkOrderMaterial {
+    /// Quantity actually taken out of stock for the work order.
+    pub fn net_issued(&self) -> Decimal {
+        self.issued_quantity - self.returned_quantity
+    }
+
+    /// Planned quantity not issued yet.
+    pub fn outstanding(&self) -> Decimal {
+        (self.planned_quantity - self.net_issued()).max(Decimal::ZERO)
+    }
+
+    pub fn average_cost(&self) -> Decimal {
+        if self.net_issued() > Decimal::ZERO {
+            (self.issued_cost / self.net_issued()).round_dp(4)
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ProductionReport {
+    pub id: Uuid,
+    pub work_order_id: Uuid,
+    pub quantity: Decimal,
+    pub scrapped_quantity: Decimal,
+    pub unit_cost: Decimal,
+    pub total_cost: Decimal,
+    pub transaction_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub reported_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkOrderScrap {
+    pub id: Uuid,
+    pub work_order_id: Uuid,
+    pub report_id: Option<Uuid>,
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    pub reason: ScrapReason,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A co-product or by-product planned for a work order from its recipe version.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkOrderOutput {
+    pub id: Uuid,
+    pub work_order_id: Uuid,
+    pub product_id: Uuid,
+    pub output_type: OutputType,
+    pub cost_share: Decimal,
+    pub planned_quantity: Decimal,
+    pub produced_quantity: Decimal,
+    pub output_cost: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkOrderDetail {
+    pub work_order: WorkOrder,
+    pub materials: Vec<WorkOrderMaterial>,
+    pub by_products: Vec<WorkOrderOutput>,
+    pub reports: Vec<ProductionReport>,
+    pub scrap: Vec<WorkOrderScrap>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialQuantity {
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    #[serde(default)]
+    pub lot_id: Option<Uuid>, // Chemical lot; earliest expiry first when omitted
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueMaterials {
+    pub lines: Option<Vec<MaterialQuantity>>, // Defaults to everything outstanding
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReturnMaterials {
+    pub lines: Vec<MaterialQuantity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateScrap {
+    pub product_id: Option<Uuid>, // Defaults to the output product
+    pub quantity: Decimal,
+    pub reason: ScrapReason,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportProduction {
+    pub quantity: Decimal, // Good output units
+    #[serde(default)]
+    pub consumption: Vec<MaterialQuantity>, // Material used beyond what was issued, backflushed now
+    #[serde(default)]
+    pub scrap: Vec<CreateScrap>,
+    #[serde(default)]
+    pub by_products: Vec<MaterialQuantity>, // Actual by-product quantities; the recipe ratio when empty
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YieldVarianceQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub recipe_id: Option<Uuid>,
+}
+
+/// Product labels for the yield report.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MaterialLabel {
+    pub id: Uuid,
+    pub sku: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialYield {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub standard_quantity: Decimal, // Recipe quantity for the good output produced
+    pub actual_quantity: Decimal,   // Issued less returned
+    pub scrapped_quantity: Decimal,
+    pub variance_quantity: Decimal, // Positive means more was used than standard
+    pub unit_cost: Decimal,
+    pub variance_value: Decimal,
+    pub yield_percent: Option<Decimal>, // Standard / actual
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkOrderYield {
+    pub work_order_id: Uuid,
+    pub recipe_id: Uuid,
+    pub recipe_name: String,
+    pub status: WorkOrderStatus,
+    pub planned_quantity: Decimal,
+    pub produced_quantity: Decimal,
+    pub scrapped_quantity: Decimal,
+    pub output_yield_percent: Option<Decimal>, // Good output / planned output
+    pub scrap_rate_percent: Option<Decimal>,   // Scrapped / (good + scrapped)
+    pub standard_material_cost: Decimal,
+    pub actual_material_cost: Decimal,
+    pub yield_variance: Decimal, // Sum of material variance values
+    pub materials: Vec<MaterialYield>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YieldVarianceReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub standard_material_cost: Decimal,
+    pub actual_material_cost: Decimal,
+    pub yield_variance: Decimal,
+    pub work_orders: Vec<WorkOrderYield>,
+}
+
+fn percent(part: Decimal, whole: Decimal) -> Option<Decimal> {
+    (whole > Decimal::ZERO).then(|| (part / whole * Decimal::ONE_HUNDRED).round_dp(2))
+}
+
+impl WorkOrderYield {
+    /// Compares what was used against the recipe quantity for the good output actually made.
+    /// Materials issued that are not on the recipe (substitutes) have a standard of zero.
+    pub fn build(
+        work_order: &WorkOrder,
+        recipe: &Recipe,
+        ingredients: &[RecipeIngredient],
+        materials: &[WorkOrderMaterial],
+        labels: &HashMap<Uuid, MaterialLabel>,
+    ) -> Self {
+        let batches_made = if recipe.output_quantity > Decimal::ZERO {
+            work_order.produced_quantity / recipe.output_quantity
+        } else {
+            Decimal::ZERO
+        };
+
+        let mut per_batch: HashMap<Uuid, Decimal> = HashMap::new();
+        for ingredient in ingredients {
+            *per_batch.entry(ingredient.input_product_id).or_default() += ingredient.quantity;
+        }
+
+        let mut product_ids: Vec<Uuid> = materials.iter().map(|m| m.product_id).collect();
+        product_ids.extend(per_batch.keys().filter(|id| !materials.iter().any(|m| m.product_id == **id)));
+
+        let lines: Vec<MaterialYield> = product_ids
+            .into_iter()
+            .map(|product_id| {
+                let material = materials.iter().find(|m| m.product_id == product_id);
+                let standard_quantity = (per_batch.get(&product_id).copied().unwrap_or_default() * batches_made).round_dp(4);
+                let actual_quantity = material.map(|m| m.net_issued()).unwrap_or_default();
+                let unit_cost = material.map(|m| m.average_cost()).unwrap_or_default();
+                let variance_quantity = actual_quantity - standard_quantity;
+                let label = labels.get(&product_id);
+                MaterialYield {
+                    product_id,
+                    sku: label.map(|l| l.sku.clone()).unwrap_or_default(),
+                    name: label.map(|l| l.name.clone()).unwrap_or_default(),
+                    standard_quantity,
+                    actual_quantity,
+                    scrapped_quantity: material.map(|m| m.scrapped_quantity).unwrap_or_default(),
+                    variance_quantity,
+                    unit_cost,
+                    variance_value: (variance_quantity * unit_cost).round_dp(2),
+                    yield_percent: percent(standard_quantity, actual_quantity),
+                }
+            })
+            .collect();
+
+        let actual_material_cost: Decimal = materials.iter().map(|m| m.issued_cost).sum();
+        let yield_variance: Decimal = lines.iter().map(|l| l.variance_value).sum();
+        let planned_quantity = recipe.output_quantity * work_order.quantity;
+
+        WorkOrderYield {
+            work_order_id: work_order.id,
+            recipe_id: recipe.id,
+            recipe_name: recipe.name.clone(),
+            status: work_order.status.clone(),
+            planned_quantity,
+            produced_quantity: work_order.produced_quantity,
+            scrapped_quantity: work_order.scrapped_quantity,
+            output_yield_percent: percent(work_order.produced_quantity, planned_quantity),
+            scrap_rate_percent: percent(work_order.scrapped_quantity, work_order.produced_quantity + work_order.scrapped_quantity),
+            standard_material_cost: actual_material_cost - yield_variance,
+            actual_material_cost,
+            yield_variance,
+            materials: lines,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variance_splits_by_what_each_cost_put_in() {
+        // 100 material, 50 labor, 50 overhead: labor and overhead take a quarter each.
+        let (material, labor, overhead) = split_variance(Decimal::from(10), Decimal::from(100), Decimal::from(50), Decimal::from(50));
+        assert_eq!((material, labor, overhead), (Decimal::from(5), Decimal::new(25, 1), Decimal::new(25, 1)));
+
+        // Thirds don't divide evenly; the rounding lands on material so the parts add back up.
+        let (material, labor, overhead) = split_variance(Decimal::ONE, Decimal::ONE, Decimal::ONE, Decimal::ONE);
+        assert_eq!((labor, overhead), (Decimal::new(33, 2), Decimal::new(33, 2)));
+        assert_eq!(material, Decimal::new(34, 2));
+    }
+
+    #[test]
+    fn variance_without_inputs_goes_to_material() {
+        let variance = Decimal::new(-1250, 2);
+        assert_eq!(split_variance(variance, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO), (variance, Decimal::ZERO, Decimal::ZERO));
+
+        // Labor only, no components issued.
+        assert_eq!(
+            split_variance(Decimal::from(8), Decimal::ZERO, Decimal::from(40), Decimal::ZERO),
+            (Decimal::ZERO, Decimal::from(8), Decimal::ZERO)
+        );
+    }
+
+    #[test]
+    fn output_cost_is_split_by_share_with_rounding_on_the_primary() {
+        // A 15% and a 5% by-product; the primary output keeps the rest.
+        let (primary, secondary) = allocate_output_cost(Decimal::from(200), &[Decimal::from(15), Decimal::from(5)]);
+        assert_eq!(secondary, vec![Decimal::from(30), Decimal::from(10)]);
+        assert_eq!(primary, Decimal::from(160));
+
+        let (primary, secondary) = allocate_output_cost(Decimal::from(100), &[Decimal::new(3333, 2), Decimal::new(3333, 2)]);
+        assert_eq!(secondary, vec![Decimal::new(3333, 2), Decimal::new(3333, 2)]);
+        assert_eq!(primary, Decimal::new(3334, 2));
+    }
+
+    #[test]
+    fn output_cost_without_secondary_outputs_stays_on_the_primary() {
+        assert_eq!(allocate_output_cost(Decimal::new(4250, 2), &[]), (Decimal::new(4250, 2), vec![]));
+        assert_eq!(allocate_output_cost(Decimal::ZERO, &[Decimal::from(20)]), (Decimal::ZERO, vec![Decimal::ZERO]));
+    }
+}
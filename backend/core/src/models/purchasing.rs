@@ -5,6 +5,8 @@ use chrono::{DateTime, NaiveDate, Utc};
 use strum::{Display, EnumString};
 use async_trait::async_trait;
 
+use crate::models::address::{CreateAddress, CreateContact};
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Supplier {
     pub id: Uuid,
@@ -12,10 +14,16 @@ pub struct Supplier {
     pub name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
-    pub address: Option<String>,
     pub contact_person: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub tax_id: Option<String>,
+    pub terms: Option<String>,
+    pub account_number: Option<String>, // Our account number with the supplier
+    pub balance: Decimal,               // Open bills less payments
+    pub notes: Option<String>,
+    pub is_active: bool,                // Inactive suppliers cannot be ordered from or billed
+    pub payment_terms_id: Option<Uuid>, // Default for new bills
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,8 +31,80 @@ pub struct CreateSupplier {
     pub name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
-    pub address: Option<String>,
     pub contact_person: Option<String>,
+    pub tax_id: Option<String>,
+    pub terms: Option<String>,
+    pub account_number: Option<String>,
+    pub notes: Option<String>,
+    pub payment_terms_id: Option<Uuid>,
+    #[serde(default)]
+    pub addresses: Vec<CreateAddress>,
+    #[serde(default)]
+    pub contacts: Vec<CreateContact>,
+}
+
+/// Fields left out are kept as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSupplier {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub contact_person: Option<String>,
+    pub tax_id: Option<String>,
+    pub terms: Option<String>,
+    pub account_number: Option<String>,
+    pub notes: Option<String>,
+    pub payment_terms_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SupplierQuery {
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SupplierTransactionType {
+    #[strum(serialize = "PURCHASE_ORDER")]
+    PurchaseOrder,
+    #[strum(serialize = "BILL")]
+    Bill,
+    #[strum(serialize = "BILL_PAYMENT")]
+    BillPayment,
+    #[strum(serialize = "VENDOR_CREDIT")]
+    VendorCredit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SupplierTransaction {
+    pub transaction_type: SupplierTransactionType,
+    pub document_id: Uuid,
+    pub reference: String,
+    pub date: NaiveDate,
+    pub status: String,
+    pub amount: Decimal,
+    pub balance_change: Decimal, // Orders don't change what is owed; bills held on match exceptions don't yet either
+    #[sqlx(skip)]
+    pub balance: Decimal,        // Running balance after this transaction
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupplierHistory {
+    pub supplier: Supplier,
+    pub transactions: Vec<SupplierTransaction>,
+}
+
+impl SupplierHistory {
+    /// Fills in the running balance; transactions must be in date order.
+    pub fn build(supplier: Supplier, mut transactions: Vec<SupplierTransaction>) -> Self {
+        let mut balance = Decimal::ZERO;
+        for transaction in &mut transactions {
+            balance += transaction.balance_change;
+            transaction.balance = balance;
+        }
+        SupplierHistory { supplier, transactions }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
@@ -47,10 +127,13 @@ pub struct PurchaseOrder {
     pub supplier_id: Uuid,
     pub order_number: String,
     pub date: NaiveDate,
+    pub expected_date: Option<NaiveDate>, // Promised delivery
     pub status: PurchaseOrderStatus,
     pub total_amount: Decimal,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub remit_to_address_id: Option<Uuid>,
+    pub remit_to_address: Option<String>, // Copied when the order is raised
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -61,13 +144,17 @@ pub struct PurchaseOrderLine {
     pub quantity: Decimal,
     pub unit_price: Decimal,
     pub total_price: Decimal, // Generated in DB, but good to have in struct
+    pub weight: Option<Decimal>, // Line totals, used to allocate landed costs
+    pub area: Option<Decimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreatePurchaseOrderLine {
     pub product_id: Uuid,
     pub quantity: Decimal,
-    pub unit_price: Decimal,
+    pub unit_price: Option<Decimal>, // Defaults to the supplier's latest price, then the product's cost
+    pub weight: Option<Decimal>,
+    pub area: Option<Decimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,9 +162,161 @@ pub struct CreatePurchaseOrder {
     pub supplier_id: Uuid,
     pub order_number: String,
     pub date: NaiveDate,
+    pub expected_date: Option<NaiveDate>, // Defaults to the longest supplier or product lead time
+    pub remit_to_address_id: Option<Uuid>, // Defaults to the supplier's remit-to, then billing address
     pub lines: Vec<CreatePurchaseOrderLine>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PriceSource {
+    #[strum(serialize = "QUOTE")]
+    Quote,
+    #[strum(serialize = "PURCHASE_ORDER")]
+    PurchaseOrder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SupplierPrice {
+    pub id: Uuid,
+    pub supplier_id: Uuid,
+    pub supplier_name: String,
+    pub product_id: Uuid,
+    pub sku: String,
+    pub unit_price: Decimal,
+    pub min_order_quantity: Option<Decimal>,
+    pub lead_time_days: Option<i32>,
+    pub source: PriceSource,
+    pub source_id: Option<Uuid>,
+    pub source_number: Option<String>,
+    pub effective_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceHistoryQuery {
+    pub supplier_id: Option<Uuid>,
+    pub product_id: Option<Uuid>,
+}
+
+// --- Scorecards ---
+#[derive(Debug, Deserialize)]
+pub struct SupplierScorecardQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub supplier_id: Option<Uuid>,
+}
+
+/// Weights of the overall score. Metrics a supplier has no data for are left out and the
+/// remaining weights scaled up.
+pub const ON_TIME_WEIGHT: i64 = 35;
+pub const FILL_RATE_WEIGHT: i64 = 25;
+pub const QUALITY_WEIGHT: i64 = 25;
+pub const PRICE_WEIGHT: i64 = 15;
+
+fn percent(part: Decimal, whole: Decimal) -> Option<Decimal> {
+    (whole > Decimal::ZERO).then(|| (part / whole * Decimal::ONE_HUNDRED).round_dp(1))
+}
+
+/// One supplier's performance on purchase orders placed in the period. Deliveries count once
+/// they are received or past their expected date, so overdue orders count as late and unfilled.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SupplierScorecard {
+    pub supplier_id: Uuid,
+    pub supplier_name: String,
+    pub orders_placed: i64,
+    pub deliveries_due: i64,
+    pub on_time_deliveries: i64,
+    pub overdue_orders: i64, // Past the expected date and still not received
+    pub avg_days_late: Decimal, // Over late deliveries only
+    pub ordered_quantity: Decimal,
+    pub filled_quantity: Decimal, // Received less returned to the supplier
+    pub inspected_quantity: Decimal,
+    pub rejected_quantity: Decimal,
+    pub billed_value: Decimal, // Billed quantity at the ordered price
+    pub invoice_variance: Decimal, // Absolute gap between billed and ordered prices
+    pub price_changes: i64, // Ordered prices compared with the previous order of the product
+    pub avg_price_change_percent: Option<Decimal>,
+    #[sqlx(skip)]
+    pub on_time_rate: Option<Decimal>,
+    #[sqlx(skip)]
+    pub fill_rate: Option<Decimal>,
+    #[sqlx(skip)]
+    pub defect_rate: Option<Decimal>,
+    #[sqlx(skip)]
+    pub invoice_variance_percent: Option<Decimal>,
+    #[sqlx(skip)]
+    pub price_stability: Option<Decimal>, // 100 less the average price change and invoice variance
+    #[sqlx(skip)]
+    pub score: Option<Decimal>,
+}
+
+impl SupplierScorecard {
+    pub fn compute(&mut self) {
+        self.on_time_rate = percent(Decimal::from(self.on_time_deliveries), Decimal::from(self.deliveries_due));
+        self.fill_rate = percent(self.filled_quantity, self.ordered_quantity);
+        self.defect_rate = percent(self.rejected_quantity, self.inspected_quantity);
+        self.invoice_variance_percent = percent(self.invoice_variance, self.billed_value);
+        self.price_stability = match (self.avg_price_change_percent, self.invoice_variance_percent) {
+            (None, None) => None,
+            (change, variance) => Some(
+                (Decimal::ONE_HUNDRED - change.unwrap_or_default() - variance.unwrap_or_default())
+                    .max(Decimal::ZERO)
+                    .round_dp(1),
+            ),
+        };
+
+        let metrics = [
+            (self.on_time_rate, ON_TIME_WEIGHT),
+            (self.fill_rate, FILL_RATE_WEIGHT),
+            (self.defect_rate.map(|d| Decimal::ONE_HUNDRED - d), QUALITY_WEIGHT),
+            (self.price_stability, PRICE_WEIGHT),
+        ];
+        let weight: Decimal = metrics.iter().filter(|(m, _)| m.is_some()).map(|(_, w)| Decimal::from(*w)).sum();
+        let weighted: Decimal = metrics.iter().filter_map(|(m, w)| m.map(|m| m * Decimal::from(*w))).sum();
+        self.score = (weight > Decimal::ZERO).then(|| (weighted / weight).round_dp(1));
+    }
+}
+
+/// Suppliers ranked by overall score, best first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupplierScorecardReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub suppliers: Vec<SupplierScorecard>,
+}
+
+// --- Receipts ---
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PurchaseReceipt {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub order_id: Uuid,
+    pub receipt_number: String,
+    pub date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PurchaseReceiptLine {
+    pub id: Uuid,
+    pub receipt_id: Uuid,
+    pub order_line_id: Uuid,
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    pub unit_cost: Decimal,
+    pub weight: Option<Decimal>,
+    pub area: Option<Decimal>,
+    pub landed_cost: Decimal, // Freight, duty etc. allocated to this line so far
+    pub transaction_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurchaseReceiptDetail {
+    pub receipt: PurchaseReceipt,
+    pub lines: Vec<PurchaseReceiptLine>,
+}
+
 #[async_trait]
 pub trait PurchasingService: Send + Sync {
     async fn create_supplier(&self, tenant_id: Uuid, supplier: CreateSupplier) -> Result<Supplier, crate::error::Error>;
@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InspectionType {
+    #[strum(serialize = "RECEIPT")]
+    Receipt,
+    #[strum(serialize = "PRODUCTION")]
+    Production,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InspectionSource {
+    #[strum(serialize = "RECEIPT")]
+    Receipt,
+    #[strum(serialize = "PRODUCTION")]
+    Production,
+    #[strum(serialize = "REWORK")]
+    Rework,
+    #[strum(serialize = "MANUAL")]
+    Manual,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InspectionStatus {
+    #[strum(serialize = "PENDING")]
+    Pending,
+    #[strum(serialize = "PASSED")]
+    Passed,
+    #[strum(serialize = "FAILED")]
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DispositionAction {
+    #[strum(serialize = "RELEASE")]
+    Release,
+    #[strum(serialize = "REWORK")]
+    Rework,
+    #[strum(serialize = "SCRAP")]
+    Scrap,
+    #[strum(serialize = "RETURN_TO_VENDOR")]
+    ReturnToVendor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct InspectionPlan {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub product_id: Uuid,
+    pub name: String,
+    pub inspection_type: InspectionType,
+    pub routing_operation_id: Option<Uuid>, // In-process check; not triggered by stock movements
+    pub sample_size: Option<Decimal>,
+    pub is_active: bool,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct InspectionCharacteristic {
+    pub id: Uuid,
+    pub plan_id: Uuid,
+    pub sequence: i32,
+    pub name: String,
+    pub unit: Option<String>,
+    pub lower_limit: Option<Decimal>,
+    pub upper_limit: Option<Decimal>,
+}
+
+impl InspectionCharacteristic {
+    pub fn is_measured(&self) -> bool {
+        self.lower_limit.is_some() || self.upper_limit.is_some()
+    }
+
+    /// A measured value has to sit within the limits; the inspector can still fail it.
+    pub fn evaluate(&self, result: &RecordResult) -> Result<bool, Error> {
+        if !self.is_measured() {
+            return result.passed.ok_or(Error::BusinessRule(format!("Record a pass or fail for '{}'", self.name)));
+        }
+        let value = result.measured_value.ok_or(Error::BusinessRule(format!("Record a measured value for '{}'", self.name)))?;
+        let within = self.lower_limit.is_none_or(|l| value >= l) && self.upper_limit.is_none_or(|u| value <= u);
+        Ok(within && result.passed != Some(false))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectionPlanDetail {
+    pub plan: InspectionPlan,
+    pub characteristics: Vec<InspectionCharacteristic>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateInspectionCharacteristic {
+    pub name: String,
+    pub unit: Option<String>,
+    pub lower_limit: Option<Decimal>,
+    pub upper_limit: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateInspectionPlan {
+    pub product_id: Uuid,
+    pub name: String,
+    pub inspection_type: InspectionType,
+    pub routing_operation_id: Option<Uuid>,
+    pub sample_size: Option<Decimal>,
+    pub notes: Option<String>,
+    pub characteristics: Vec<CreateInspectionCharacteristic>, // In inspection order
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInspectionPlan {
+    pub name: Option<String>,
+    pub sample_size: Option<Decimal>,
+    pub is_active: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QualityInspection {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub inspection_number: String,
+    pub plan_id: Option<Uuid>,
+    pub product_id: Uuid,
+    pub source_type: InspectionSource,
+    pub receipt_line_id: Option<Uuid>,
+    pub work_order_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>, // Inspection the reworked stock came from
+    pub quantity: Decimal,
+    pub quarantined: bool, // False for in-process checks on stock not made yet
+    pub status: InspectionStatus,
+    pub accepted_quantity: Decimal,
+    pub rejected_quantity: Decimal,
+    pub disposed_quantity: Decimal,
+    pub inspector_id: Option<Uuid>,
+    pub inspected_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QualityInspection {
+    /// Stock the inspection still holds in quarantine.
+    pub fn quarantined_quantity(&self) -> Decimal {
+        if !self.quarantined {
+            return Decimal::ZERO;
+        }
+        match self.status {
+            InspectionStatus::Pending => self.quantity,
+            _ => self.rejected_quantity - self.disposed_quantity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct InspectionResult {
+    pub id: Uuid,
+    pub inspection_id: Uuid,
+    pub characteristic_id: Option<Uuid>,
+    pub name: String,
+    pub measured_value: Option<Decimal>,
+    pub passed: bool,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QualityDisposition {
+    pub id: Uuid,
+    pub inspection_id: Uuid,
+    pub action: DispositionAction,
+    pub quantity: Decimal,
+    pub transaction_id: Option<Uuid>,
+    pub rework_inspection_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectionDetail {
+    pub inspection: QualityInspection,
+    pub characteristics: Vec<InspectionCharacteristic>,
+    pub results: Vec<InspectionResult>,
+    pub dispositions: Vec<QualityDisposition>,
+}
+
+/// Raises an inspection by hand: stock already on hand is put in quarantine, while an
+/// inspection against a work order is an in-process check that holds no stock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateInspection {
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    pub plan_id: Option<Uuid>,
+    pub work_order_id: Option<Uuid>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordResult {
+    pub characteristic_id: Option<Uuid>, // None for a check outside the plan
+    pub name: Option<String>,
+    pub measured_value: Option<Decimal>,
+    pub passed: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordInspection {
+    pub inspector_id: Uuid, // Employee
+    pub results: Vec<RecordResult>,
+    pub rejected_quantity: Option<Decimal>, // On failure; defaults to the whole quantity
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDisposition {
+    pub action: DispositionAction,
+    pub quantity: Decimal,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InspectionQuery {
+    pub status: Option<InspectionStatus>,
+    pub product_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QuarantineLine {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub stock_quantity: Decimal,
+    pub quarantined_quantity: Decimal,
+    pub available_quantity: Decimal,
+}
+
+/// A recorded result ready to store.
+pub struct EvaluatedResult {
+    pub characteristic_id: Option<Uuid>,
+    pub name: String,
+    pub measured_value: Option<Decimal>,
+    pub passed: bool,
+    pub notes: Option<String>,
+}
+
+/// Checks the results against the plan. Every characteristic of the plan needs a result;
+/// extra checks outside the plan need a name and an explicit pass or fail.
+pub fn evaluate_results(characteristics: &[InspectionCharacteristic], results: Vec<RecordResult>) -> Result<Vec<EvaluatedResult>, Error> {
+    if let Some(missing) = characteristics.iter().find(|c| !results.iter().any(|r| r.characteristic_id == Some(c.id))) {
+        return Err(Error::BusinessRule(format!("No result recorded for '{}'", missing.name)));
+    }
+
+    results
+        .into_iter()
+        .map(|result| match result.characteristic_id {
+            Some(id) => {
+                let characteristic = characteristics
+                    .iter()
+                    .find(|c| c.id == id)
+                    .ok_or(Error::BusinessRule("Result is for a characteristic outside the inspection plan".to_string()))?;
+                Ok(EvaluatedResult {
+                    characteristic_id: Some(id),
+                    name: characteristic.name.clone(),
+                    passed: characteristic.evaluate(&result)?,
+                    measured_value: result.measured_value,
+                    notes: result.notes,
+                })
+            }
+            None => {
+                let name = result.name.filter(|n| !n.trim().is_empty())
+                    .ok_or(Error::BusinessRule("Name each check outside the inspection plan".to_string()))?;
+                let passed = result.passed.ok_or(Error::BusinessRule(format!("Record a pass or fail for '{}'", name)))?;
+                Ok(EvaluatedResult { characteristic_id: None, name, measured_value: result.measured_value, passed, notes: result.notes })
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReorderSettings {
+    pub product_id: Uuid,
+    pub reorder_point: Decimal,
+    pub reorder_quantity: Decimal,
+    pub min_order_qty: Decimal,
+    pub lead_time_days: i32,
+    pub preferred_vendor_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateReorderSettings {
+    pub reorder_point: Option<Decimal>,
+    pub reorder_quantity: Option<Decimal>,
+    pub min_order_qty: Option<Decimal>,
+    pub lead_time_days: Option<i32>,
+    pub preferred_vendor_id: Option<Uuid>,
+    /// Removes the preferred vendor; takes precedence over `preferred_vendor_id`.
+    #[serde(default)]
+    pub clear_preferred_vendor: bool,
+}
+
+/// Current stock position of a product as seen by the replenishment engine.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StockPosition {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub on_hand: Decimal,
+    pub committed: Decimal, // Confirmed sales orders + open work order requirements
+    pub on_order: Decimal,  // Open purchase order lines
+    pub reorder_point: Decimal,
+    pub reorder_quantity: Decimal,
+    pub min_order_qty: Decimal,
+    pub lead_time_days: i32,
+    pub avg_daily_usage: Decimal,
+    pub unit_cost: Decimal,
+    pub preferred_vendor_id: Option<Uuid>,
+    pub supplier_name: Option<String>,
+}
+
+impl StockPosition {
+    pub fn available(&self) -> Decimal {
+        self.on_hand - self.committed
+    }
+
+    pub fn projected(&self) -> Decimal {
+        self.available() + self.on_order
+    }
+
+    pub fn needs_reorder(&self) -> bool {
+        self.reorder_point > Decimal::ZERO && self.projected() <= self.reorder_point
+    }
+
+    /// Quantity to order so the projected position rises above the reorder point.
+    /// Logic:
+    /// 1. Shortfall = reorder_point - projected, plus expected usage over the lead time.
+    /// 2. If a reorder quantity is set, order whole lots of it covering the shortfall.
+    /// 3. Never order less than the supplier minimum (min_order_qty).
+    pub fn suggested_order_quantity(&self) -> Decimal {
+        if !self.needs_reorder() {
+            return Decimal::ZERO;
+        }
+
+        let lead_time_demand = self.avg_daily_usage * Decimal::from(self.lead_time_days.max(0));
+        let shortfall = self.reorder_point - self.projected() + lead_time_demand;
+
+        let mut quantity = if self.reorder_quantity > Decimal::ZERO {
+            let lots = (shortfall / self.reorder_quantity).ceil().max(Decimal::ONE);
+            lots * self.reorder_quantity
+        } else {
+            shortfall
+        };
+
+        if quantity < self.min_order_qty {
+            quantity = self.min_order_qty;
+        }
+
+        quantity.round_dp(2)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplenishmentSuggestion {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub on_hand: Decimal,
+    pub available: Decimal,
+    pub on_order: Decimal,
+    pub reorder_point: Decimal,
+    pub suggested_quantity: Decimal,
+    pub unit_cost: Decimal,
+    pub line_total: Decimal,
+    pub lead_time_days: i32,
+    pub expected_date: NaiveDate,
+}
+
+impl ReplenishmentSuggestion {
+    pub fn from_position(position: &StockPosition, today: NaiveDate) -> Self {
+        let suggested_quantity = position.suggested_order_quantity();
+        Self {
+            product_id: position.product_id,
+            sku: position.sku.clone(),
+            name: position.name.clone(),
+            on_hand: position.on_hand,
+            available: position.available(),
+            on_order: position.on_order,
+            reorder_point: position.reorder_point,
+            suggested_quantity,
+            unit_cost: position.unit_cost,
+            line_total: suggested_quantity * position.unit_cost,
+            lead_time_days: position.lead_time_days,
+            expected_date: today + chrono::Duration::days(position.lead_time_days.max(0) as i64),
+        }
+    }
+}
+
+/// Suggestions grouped by preferred supplier. `supplier_id` is None for products without one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupplierReplenishment {
+    pub supplier_id: Option<Uuid>,
+    pub supplier_name: Option<String>,
+    pub lines: Vec<ReplenishmentSuggestion>,
+    pub total_amount: Decimal,
+}
+
+/// Groups reorder suggestions by preferred supplier, skipping products that need nothing.
+pub fn group_by_supplier(positions: &[StockPosition], today: NaiveDate) -> Vec<SupplierReplenishment> {
+    let mut groups: Vec<SupplierReplenishment> = Vec::new();
+
+    for position in positions.iter().filter(|p| p.needs_reorder()) {
+        let suggestion = ReplenishmentSuggestion::from_position(position, today);
+        if suggestion.suggested_quantity <= Decimal::ZERO {
+            continue;
+        }
+
+        let group = match groups.iter_mut().find(|g| g.supplier_id == position.preferred_vendor_id) {
+            Some(g) => g,
+            None => {
+                groups.push(SupplierReplenishment {
+                    supplier_id: position.preferred_vendor_id,
+                    supplier_name: position.supplier_name.clone(),
+                    lines: Vec::new(),
+                    total_amount: Decimal::ZERO,
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+        group.total_amount += suggestion.line_total;
+        group.lines.push(suggestion);
+    }
+
+    groups
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenerateReplenishmentOrders {
+    pub supplier_ids: Option<Vec<Uuid>>, // Restrict to these suppliers (all when omitted)
+    pub date: Option<NaiveDate>,
+}
+
+// --- Low Stock Report ---
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LowStockReport {
+    pub as_of: NaiveDate,
+    pub lines: Vec<LowStockLine>,
+    pub total_shortfall_value: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LowStockLine {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub on_hand: Decimal,
+    pub available: Decimal,
+    pub on_order: Decimal,
+    pub reorder_point: Decimal,
+    pub shortfall: Decimal,
+    pub suggested_quantity: Decimal,
+    pub preferred_vendor: Option<String>,
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+use crate::models::auth::Role;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RequisitionStatus {
+    #[strum(serialize = "DRAFT")]
+    Draft,
+    #[strum(serialize = "PENDING")]
+    Pending, // Submitted, waiting on approvals
+    #[strum(serialize = "APPROVED")]
+    Approved,
+    #[strum(serialize = "REJECTED")]
+    Rejected,
+    #[strum(serialize = "CONVERTED")]
+    Converted, // Every line is on a purchase order
+    #[strum(serialize = "CANCELLED")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApprovalStatus {
+    #[strum(serialize = "PENDING")]
+    Pending,
+    #[strum(serialize = "APPROVED")]
+    Approved,
+    #[strum(serialize = "REJECTED")]
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RequisitionAction {
+    #[strum(serialize = "CREATED")]
+    Created,
+    #[strum(serialize = "SUBMITTED")]
+    Submitted,
+    #[strum(serialize = "APPROVED")]
+    Approved,
+    #[strum(serialize = "REJECTED")]
+    Rejected,
+    #[strum(serialize = "COMMENTED")]
+    Commented,
+    #[strum(serialize = "CONVERTED")]
+    Converted,
+    #[strum(serialize = "CANCELLED")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RequisitionApprovalRule {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub min_amount: Decimal,
+    pub department: Option<String>,
+    pub category: Option<String>,
+    pub approver_role: Role,
+    pub sequence: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RequisitionApprovalRule {
+    /// Whether the rule covers a requisition of this total, department and product categories.
+    pub fn applies_to(&self, total_amount: Decimal, department: Option<&str>, categories: &[String]) -> bool {
+        self.is_active
+            && total_amount >= self.min_amount
+            && self.department.as_deref().is_none_or(|d| department.is_some_and(|r| r.eq_ignore_ascii_case(d)))
+            && self.category.as_deref().is_none_or(|c| categories.iter().any(|p| p.eq_ignore_ascii_case(c)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRequisitionApprovalRule {
+    pub name: String,
+    pub min_amount: Option<Decimal>,
+    pub department: Option<String>,
+    pub category: Option<String>,
+    pub approver_role: Role,
+    pub sequence: Option<i32>,
+}
+
+/// Fields left out are kept as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRequisitionApprovalRule {
+    pub name: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub department: Option<String>,
+    pub category: Option<String>,
+    pub approver_role: Option<Role>,
+    pub sequence: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PurchaseRequisition {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub requisition_number: String,
+    pub requested_by: Uuid,
+    pub department: Option<String>,
+    pub needed_by: Option<NaiveDate>,
+    pub status: RequisitionStatus,
+    pub total_amount: Decimal,
+    pub notes: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PurchaseRequisitionLine {
+    pub id: Uuid,
+    pub requisition_id: Uuid,
+    pub product_id: Uuid,
+    pub supplier_id: Option<Uuid>,
+    pub quantity: Decimal,
+    pub estimated_price: Decimal,
+    pub amount: Decimal,
+    pub purchase_order_id: Option<Uuid>,
+    pub sort_order: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RequisitionApproval {
+    pub id: Uuid,
+    pub requisition_id: Uuid,
+    pub rule_id: Option<Uuid>,
+    pub rule_name: String,
+    pub sequence: i32,
+    pub approver_role: Role,
+    pub status: ApprovalStatus,
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RequisitionEvent {
+    pub id: Uuid,
+    pub requisition_id: Uuid,
+    pub action: RequisitionAction,
+    pub user_id: Option<Uuid>,
+    pub username: Option<String>,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequisitionDetail {
+    pub requisition: PurchaseRequisition,
+    pub lines: Vec<PurchaseRequisitionLine>,
+    pub approvals: Vec<RequisitionApproval>,
+    pub history: Vec<RequisitionEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRequisitionLine {
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    pub estimated_price: Option<Decimal>, // Defaults to the product's cost
+    pub supplier_id: Option<Uuid>,        // Defaults to the preferred vendor
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRequisition {
+    pub department: Option<String>,
+    pub needed_by: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub lines: Vec<CreateRequisitionLine>,
+    #[serde(default)]
+    pub submit: bool, // Submit for approval straight away
+}
+
+impl CreateRequisition {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.lines.is_empty() {
+            return Err(Error::BusinessRule("A requisition needs at least one line".to_string()));
+        }
+        if self.lines.iter().any(|l| l.quantity <= Decimal::ZERO || l.estimated_price.is_some_and(|p| p < Decimal::ZERO)) {
+            return Err(Error::BusinessRule("Lines need a positive quantity and a non-negative price".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequisitionQuery {
+    pub status: Option<RequisitionStatus>,
+    #[serde(default)]
+    pub awaiting_me: bool, // Only requisitions whose current step the caller's role can approve
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequisitionDecision {
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertRequisitions {
+    pub requisition_ids: Vec<Uuid>,
+    pub date: Option<NaiveDate>,
+}
+
+/// The approval steps that are up next: all pending steps sharing the lowest sequence.
+pub fn current_steps(approvals: &[RequisitionApproval]) -> Vec<&RequisitionApproval> {
+    let next = approvals
+        .iter()
+        .filter(|a| a.status == ApprovalStatus::Pending)
+        .map(|a| a.sequence)
+        .min();
+    approvals
+        .iter()
+        .filter(|a| a.status == ApprovalStatus::Pending && Some(a.sequence) == next)
+        .collect()
+}
+
+/// Admins can act on any step; everyone else needs the step's role.
+pub fn can_approve(role: &str, approver_role: &Role) -> bool {
+    role == Role::Admin.to_string() || role == approver_role.to_string()
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RfqStatus {
+    #[strum(serialize = "OPEN")]
+    Open, // Collecting quotes
+    #[strum(serialize = "AWARDED")]
+    Awarded, // Converted into a purchase order
+    #[strum(serialize = "CANCELLED")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RfqSupplierStatus {
+    #[strum(serialize = "INVITED")]
+    Invited,
+    #[strum(serialize = "RESPONDED")]
+    Responded,
+    #[strum(serialize = "DECLINED")]
+    Declined,
+    #[strum(serialize = "AWARDED")]
+    Awarded,
+    #[strum(serialize = "LOST")]
+    Lost,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Rfq {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub rfq_number: String,
+    pub title: Option<String>,
+    pub issue_date: NaiveDate,
+    pub response_due: Option<NaiveDate>,
+    pub status: RfqStatus,
+    pub awarded_supplier_id: Option<Uuid>,
+    pub purchase_order_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RfqLine {
+    pub id: Uuid,
+    pub rfq_id: Uuid,
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub quantity: Decimal,
+    pub sort_order: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RfqSupplier {
+    pub id: Uuid,
+    pub rfq_id: Uuid,
+    pub supplier_id: Uuid,
+    pub supplier_name: String,
+    pub status: RfqSupplierStatus,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub valid_until: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RfqQuote {
+    pub id: Uuid,
+    pub rfq_supplier_id: Uuid,
+    pub supplier_id: Uuid,
+    pub rfq_line_id: Uuid,
+    pub unit_price: Decimal,
+    pub lead_time_days: Option<i32>,
+    pub min_order_quantity: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+impl RfqQuote {
+    /// Quantity the supplier would actually ship: the requested quantity raised to their minimum.
+    pub fn order_quantity(&self, requested: Decimal) -> Decimal {
+        self.min_order_quantity.map_or(requested, |moq| requested.max(moq))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RfqDetail {
+    pub rfq: Rfq,
+    pub lines: Vec<RfqLine>,
+    pub suppliers: Vec<RfqSupplier>,
+    pub quotes: Vec<RfqQuote>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRfqLine {
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+}
+
+/// Asks the listed suppliers to quote on the products.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRfq {
+    pub title: Option<String>,
+    pub issue_date: Option<NaiveDate>,
+    pub response_due: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub lines: Vec<CreateRfqLine>,
+    pub supplier_ids: Vec<Uuid>,
+}
+
+impl CreateRfq {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.lines.is_empty() || self.supplier_ids.is_empty() {
+            return Err(Error::BusinessRule("An RFQ needs at least one product and one supplier".to_string()));
+        }
+        if self.lines.iter().any(|l| l.quantity <= Decimal::ZERO) {
+            return Err(Error::BusinessRule("Quantities must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteLine {
+    pub rfq_line_id: Uuid,
+    pub unit_price: Decimal,
+    pub lead_time_days: Option<i32>,
+    pub min_order_quantity: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+/// A supplier's response. Resubmitting replaces the earlier quote; lines can be left out
+/// for products the supplier does not offer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordQuote {
+    pub supplier_id: Uuid,
+    pub valid_until: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub lines: Vec<QuoteLine>,
+}
+
+impl RecordQuote {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.lines.is_empty() {
+            return Err(Error::BusinessRule("A quote needs at least one line".to_string()));
+        }
+        if self.lines.iter().any(|l| {
+            l.unit_price < Decimal::ZERO
+                || l.lead_time_days.is_some_and(|d| d < 0)
+                || l.min_order_quantity.is_some_and(|q| q < Decimal::ZERO)
+        }) {
+            return Err(Error::BusinessRule("Prices, lead times and minimum quantities cannot be negative".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclineRfq {
+    pub supplier_id: Uuid,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AwardRfq {
+    pub supplier_id: Uuid,
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RfqQuery {
+    pub status: Option<RfqStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteOffer {
+    pub supplier_id: Uuid,
+    pub supplier_name: String,
+    pub unit_price: Decimal,
+    pub lead_time_days: Option<i32>,
+    pub min_order_quantity: Option<Decimal>,
+    pub order_quantity: Decimal,
+    pub line_total: Decimal,
+    pub is_lowest: bool, // Lowest line total for the line
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonLine {
+    pub rfq_line_id: Uuid,
+    pub product_id: Uuid,
+    pub sku: String,
+    pub quantity: Decimal,
+    pub offers: Vec<QuoteOffer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupplierQuoteTotal {
+    pub supplier_id: Uuid,
+    pub supplier_name: String,
+    pub status: RfqSupplierStatus,
+    pub lines_quoted: usize,
+    pub complete: bool, // Quoted every line
+    pub total: Decimal,
+    pub max_lead_time_days: Option<i32>,
+    pub valid_until: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteComparison {
+    pub rfq: Rfq,
+    pub lines: Vec<ComparisonLine>,
+    pub suppliers: Vec<SupplierQuoteTotal>,
+    pub best_supplier_id: Option<Uuid>, // Lowest total among complete quotes
+}
+
+impl QuoteComparison {
+    pub fn build(detail: RfqDetail) -> Self {
+        let lines: Vec<ComparisonLine> = detail
+            .lines
+            .iter()
+            .map(|line| {
+                let mut offers: Vec<QuoteOffer> = detail
+                    .quotes
+                    .iter()
+                    .filter(|q| q.rfq_line_id == line.id)
+                    .filter_map(|q| {
+                        let supplier = detail.suppliers.iter().find(|s| s.id == q.rfq_supplier_id)?;
+                        let order_quantity = q.order_quantity(line.quantity);
+                        Some(QuoteOffer {
+                            supplier_id: supplier.supplier_id,
+                            supplier_name: supplier.supplier_name.clone(),
+                            unit_price: q.unit_price,
+                            lead_time_days: q.lead_time_days,
+                            min_order_quantity: q.min_order_quantity,
+                            order_quantity,
+                            line_total: (order_quantity * q.unit_price).round_dp(2),
+                            is_lowest: false,
+                        })
+                    })
+                    .collect();
+                offers.sort_by_key(|o| o.line_total);
+                if let Some(lowest) = offers.first().map(|o| o.line_total) {
+                    offers.iter_mut().filter(|o| o.line_total == lowest).for_each(|o| o.is_lowest = true);
+                }
+                ComparisonLine {
+                    rfq_line_id: line.id,
+                    product_id: line.product_id,
+                    sku: line.sku.clone(),
+                    quantity: line.quantity,
+                    offers,
+                }
+            })
+            .collect();
+
+        let suppliers: Vec<SupplierQuoteTotal> = detail
+            .suppliers
+            .iter()
+            .map(|supplier| {
+                let offers: Vec<&QuoteOffer> = lines
+                    .iter()
+                    .filter_map(|l| l.offers.iter().find(|o| o.supplier_id == supplier.supplier_id))
+                    .collect();
+                SupplierQuoteTotal {
+                    supplier_id: supplier.supplier_id,
+                    supplier_name: supplier.supplier_name.clone(),
+                    status: supplier.status,
+                    lines_quoted: offers.len(),
+                    complete: offers.len() == lines.len(),
+                    total: offers.iter().map(|o| o.line_total).sum(),
+                    max_lead_time_days: offers.iter().filter_map(|o| o.lead_time_days).max(),
+                    valid_until: supplier.valid_until,
+                }
+            })
+            .collect();
+
+        let best_supplier_id = suppliers
+            .iter()
+            .filter(|s| s.complete && s.lines_quoted > 0)
+            .min_by(|a, b| a.total.cmp(&b.total))
+            .map(|s| s.supplier_id);
+
+        QuoteComparison { rfq: detail.rfq, lines, suppliers, best_supplier_id }
+    }
+}
@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+
+use crate::error::Error;
+use crate::models::manufacturing::WorkOrder;
+
+/// How far the scheduler looks for free capacity before giving up.
+pub const SCHEDULING_HORIZON_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkCenter {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub hours_per_day: Decimal,
+    pub capacity_units: i32,    // Parallel machines or crews
+    pub working_days: Vec<i32>, // ISO weekdays, Monday = 1
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkCenter {
+    /// Normal capacity for the day, before calendar exceptions.
+    pub fn regular_hours(&self, date: NaiveDate) -> Decimal {
+        if self.working_days.contains(&(date.weekday().number_from_monday() as i32)) {
+            self.hours_per_day * Decimal::from(self.capacity_units)
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWorkCenter {
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub hours_per_day: Option<Decimal>, // Defaults to 8
+    pub capacity_units: Option<i32>,    // Defaults to 1
+    pub working_days: Option<Vec<i32>>, // Defaults to Monday to Friday
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateWorkCenter {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub hours_per_day: Option<Decimal>,
+    pub capacity_units: Option<i32>,
+    pub working_days: Option<Vec<i32>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CalendarException {
+    pub id: Uuid,
+    pub work_center_id: Uuid,
+    pub date: NaiveDate,
+    pub available_hours: Decimal, // Replaces the regular hours; zero closes the work center
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCalendarException {
+    pub date: NaiveDate,
+    pub available_hours: Decimal,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Routing {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RoutingOperation {
+    pub id: Uuid,
+    pub routing_id: Uuid,
+    pub sequence: i32,
+    pub name: String,
+    pub work_center_id: Uuid,
+    pub setup_hours: Decimal,         // Once per work order
+    pub run_hours_per_batch: Decimal, // Per recipe batch
+    pub wait_days: i32,               // Before the next operation can start
+    pub description: Option<String>,
+}
+
+impl RoutingOperation {
+    pub fn hours_for(&self, batches: Decimal) -> Decimal {
+        (self.setup_hours + self.run_hours_per_batch * batches).round_dp(2)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingDetail {
+    pub routing: Routing,
+    pub operations: Vec<RoutingOperation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRoutingOperation {
+    pub name: String,
+    pub work_center_id: Uuid,
+    #[serde(default)]
+    pub setup_hours: Decimal,
+    pub run_hours_per_batch: Decimal,
+    #[serde(default)]
+    pub wait_days: i32,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRouting {
+    pub name: String,
+    pub description: Option<String>,
+    pub operations: Vec<CreateRoutingOperation>, // In execution order
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetRecipeRouting {
+    pub routing_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScheduleDirection {
+    Forward,  // As soon as possible from the start date
+    Backward, // As late as possible to finish by the due date
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleWorkOrder {
+    pub direction: ScheduleDirection,
+    pub start_date: Option<NaiveDate>, // Forward; defaults to the work order start date or today
+    pub due_date: Option<NaiveDate>,   // Backward; required
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkOrderOperation {
+    pub id: Uuid,
+    pub work_order_id: Uuid,
+    pub routing_operation_id: Option<Uuid>,
+    pub sequence: i32,
+    pub name: String,
+    pub work_center_id: Uuid,
+    pub planned_hours: Decimal,
+    pub scheduled_start: NaiveDate,
+    pub scheduled_end: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkOrderSchedule {
+    pub work_order: WorkOrder,
+    pub operations: Vec<WorkOrderOperation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkCenterLoadQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LoadAllocation {
+    pub load_date: NaiveDate,
+    pub work_order_id: Uuid,
+    pub operation_name: String,
+    pub hours: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkCenterDay {
+    pub date: NaiveDate,
+    pub available_hours: Decimal,
+    pub loaded_hours: Decimal,
+    pub utilization_percent: Option<Decimal>,
+    pub allocations: Vec<LoadAllocation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkCenterLoad {
+    pub work_center: WorkCenter,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub available_hours: Decimal,
+    pub loaded_hours: Decimal,
+    pub days: Vec<WorkCenterDay>,
+}
+
+impl WorkCenterLoad {
+    pub fn build(work_center: WorkCenter, from: NaiveDate, to: NaiveDate, exceptions: &[CalendarException], allocations: Vec<LoadAllocation>) -> Self {
+        let calendar = CapacityCalendar::new(work_center, exceptions, &[]);
+        let mut by_day: HashMap<NaiveDate, Vec<LoadAllocation>> = HashMap::new();
+        for allocation in allocations {
+            by_day.entry(allocation.load_date).or_default().push(allocation);
+        }
+
+        let mut days = Vec::new();
+        let mut date = from;
+        while date <= to {
+            let allocations = by_day.remove(&date).unwrap_or_default();
+            let available_hours = calendar.available(date);
+            let loaded_hours: Decimal = allocations.iter().map(|a| a.hours).sum();
+            days.push(WorkCenterDay {
+                date,
+                available_hours,
+                loaded_hours,
+                utilization_percent: (available_hours > Decimal::ZERO)
+                    .then(|| (loaded_hours / available_hours * Decimal::ONE_HUNDRED).round_dp(2)),
+                allocations,
+            });
+            date += Duration::days(1);
+        }
+
+        WorkCenterLoad {
+            available_hours: days.iter().map(|d| d.available_hours).sum(),
+            loaded_hours: days.iter().map(|d| d.loaded_hours).sum(),
+            work_center: calendar.work_center,
+            from,
+            to,
+            days,
+        }
+    }
+}
+
+// --- Scheduling ---
+
+/// Capacity of one work center: regular hours, calendar exceptions and hours already booked.
+pub struct CapacityCalendar {
+    pub work_center: WorkCenter,
+    exceptions: HashMap<NaiveDate, Decimal>,
+    loaded: HashMap<NaiveDate, Decimal>,
+}
+
+impl CapacityCalendar {
+    pub fn new(work_center: WorkCenter, exceptions: &[CalendarException], loads: &[(NaiveDate, Decimal)]) -> Self {
+        let mut loaded: HashMap<NaiveDate, Decimal> = HashMap::new();
+        for (date, hours) in loads {
+            *loaded.entry(*date).or_default() += *hours;
+        }
+        CapacityCalendar {
+            exceptions: exceptions.iter().map(|e| (e.date, e.available_hours)).collect(),
+            work_center,
+            loaded,
+        }
+    }
+
+    pub fn available(&self, date: NaiveDate) -> Decimal {
+        self.exceptions.get(&date).copied().unwrap_or_else(|| self.work_center.regular_hours(date))
+    }
+
+    pub fn free(&self, date: NaiveDate) -> Decimal {
+        (self.available(date) - self.loaded.get(&date).copied().unwrap_or_default()).max(Decimal::ZERO)
+    }
+
+    fn book(&mut self, date: NaiveDate, hours: Decimal) {
+        *self.loaded.entry(date).or_default() += hours;
+    }
+}
+
+pub struct OperationToSchedule {
+    pub routing_operation_id: Option<Uuid>,
+    pub sequence: i32,
+    pub name: String,
+    pub work_center_id: Uuid,
+    pub hours: Decimal,
+    pub wait_days: i32,
+}
+
+pub struct ScheduledOperation {
+    pub routing_operation_id: Option<Uuid>,
+    pub sequence: i32,
+    pub name: String,
+    pub work_center_id: Uuid,
+    pub hours: Decimal,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub allocations: Vec<(NaiveDate, Decimal)>,
+}
+
+/// Books operations day by day against finite capacity, in sequence, each one starting no
+/// earlier than the day the previous one ends plus its wait. An operation may share a day
+/// with the one before it if hours are left.
+/// - Forward: from `anchor` as early as capacity allows.
+/// - Backward: finishing on or before `anchor`, as late as capacity allows; fails if that
+///   would need a day before `earliest`.
+pub fn schedule(
+    calendars: &mut HashMap<Uuid, CapacityCalendar>,
+    operations: &[OperationToSchedule],
+    direction: ScheduleDirection,
+    anchor: NaiveDate,
+    earliest: NaiveDate,
+) -> Result<Vec<ScheduledOperation>, Error> {
+    let step = match direction {
+        ScheduleDirection::Forward => Duration::days(1),
+        ScheduleDirection::Backward => Duration::days(-1),
+    };
+    let ordered: Vec<&OperationToSchedule> = match direction {
+        ScheduleDirection::Forward => operations.iter().collect(),
+        ScheduleDirection::Backward => operations.iter().rev().collect(),
+    };
+
+    let mut scheduled = Vec::with_capacity(operations.len());
+    let mut cursor = anchor;
+    for (index, operation) in ordered.into_iter().enumerate() {
+        // Backward, an operation's wait sits between its end and the start of the one after it.
+        if direction == ScheduleDirection::Backward && index > 0 {
+            cursor -= Duration::days(operation.wait_days.max(0) as i64);
+        }
+
+        let calendar = calendars
+            .get_mut(&operation.work_center_id)
+            .ok_or(Error::BusinessRule(format!("Work center for operation '{}' is missing or inactive", operation.name)))?;
+
+        let mut allocations = Vec::new();
+        let mut remaining = operation.hours;
+        let mut day = cursor;
+        while remaining > Decimal::ZERO {
+            if (day - anchor).num_days().abs() > SCHEDULING_HORIZON_DAYS {
+                return Err(Error::BusinessRule(format!(
+                    "No capacity for operation '{}' at {} within a year",
+                    operation.name, calendar.work_center.code
+                )));
+            }
+            if day < earliest {
+                return Err(Error::BusinessRule(format!(
+                    "Cannot finish by {}: operation '{}' would have to start before {}. Schedule forward instead.",
+                    anchor, operation.name, earliest
+                )));
+            }
+            let take = calendar.free(day).min(remaining);
+            if take > Decimal::ZERO {
+                calendar.book(day, take);
+                allocations.push((day, take));
+                remaining -= take;
+            }
+            if remaining > Decimal::ZERO {
+                day += step;
+            }
+        }
+
+        let (start, end) = match (allocations.first(), allocations.last()) {
+            (Some(first), Some(last)) if direction == ScheduleDirection::Forward => (first.0, last.0),
+            (Some(first), Some(last)) => (last.0, first.0),
+            _ => (cursor, cursor),
+        };
+        cursor = match direction {
+            ScheduleDirection::Forward => end + Duration::days(operation.wait_days.max(0) as i64),
+            ScheduleDirection::Backward => start,
+        };
+
+        scheduled.push(ScheduledOperation {
+            routing_operation_id: operation.routing_operation_id,
+            sequence: operation.sequence,
+            name: operation.name.clone(),
+            work_center_id: operation.work_center_id,
+            hours: operation.hours,
+            start,
+            end,
+            allocations,
+        });
+    }
+
+    if direction == ScheduleDirection::Backward {
+        scheduled.reverse();
+    }
+    Ok(scheduled)
+}
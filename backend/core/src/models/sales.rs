@@ -12,9 +12,12 @@ pub struct Customer {
     pub name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
-    pub address: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub payment_terms_id: Option<Uuid>, // Default for new invoices
+    pub terms: Option<String>,
+    pub credit_limit: Option<Decimal>, // No credit check when empty
+    pub credit_hold: bool, // Every new order is held until a manager releases it
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,7 +25,54 @@ pub struct CreateCustomer {
     pub name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
-    pub address: Option<String>,
+    pub payment_terms_id: Option<Uuid>,
+    pub credit_limit: Option<Decimal>,
+    #[serde(default)]
+    pub addresses: Vec<CreateAddress>,
+    #[serde(default)]
+    pub contacts: Vec<CreateContact>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCreditLimit {
+    pub credit_limit: Option<Decimal>,
+    #[serde(default)]
+    pub credit_hold: bool,
+}
+
+/// What a customer owes and has on order against their credit limit.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CustomerCredit {
+    pub credit_limit: Option<Decimal>,
+    pub credit_hold: bool,
+    pub open_receivables: Decimal, // Unpaid invoices less open credit memos
+    pub open_orders: Decimal, // Orders not yet invoiced, excluding held orders
+}
+
+impl CustomerCredit {
+    pub fn exposure(&self) -> Decimal {
+        self.open_receivables + self.open_orders
+    }
+
+    pub fn available(&self) -> Option<Decimal> {
+        self.credit_limit.map(|limit| limit - self.exposure())
+    }
+
+    /// Why an order of `amount` must be held, if it must.
+    pub fn hold_reason(&self, amount: Decimal) -> Option<String> {
+        if self.credit_hold {
+            return Some("Customer is on credit hold".to_string());
+        }
+        match self.credit_limit {
+            Some(limit) if self.exposure() + amount > limit => Some(format!(
+                "Order of {} takes exposure to {} over the credit limit of {}",
+                amount,
+                self.exposure() + amount,
+                limit
+            )),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
@@ -30,6 +80,8 @@ pub struct CreateCustomer {
 pub enum SalesOrderStatus {
     #[strum(serialize = "DRAFT")]
     Draft,
+    #[strum(serialize = "ON_HOLD")]
+    OnHold,
     #[strum(serialize = "CONFIRMED")]
     Confirmed,
     #[strum(serialize = "SHIPPED")]
@@ -49,6 +101,14 @@ pub struct SalesOrder {
     pub total_amount: Decimal,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub hold_reason: Option<String>,
+    pub credit_override_by: Option<Uuid>,
+    pub credit_override_at: Option<DateTime<Utc>>,
+    pub credit_override_reason: Option<String>,
+    pub billing_address_id: Option<Uuid>,
+    pub billing_address: Option<String>, // Copied when the order is entered
+    pub shipping_address_id: Option<Uuid>,
+    pub shipping_address: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -73,10 +133,35 @@ pub struct CreateSalesOrder {
     pub customer_id: Uuid,
     pub order_number: String,
     pub date: NaiveDate,
+    pub billing_address_id: Option<Uuid>, // Defaults to the customer's default billing address
+    pub shipping_address_id: Option<Uuid>, // Defaults to the default shipping, then billing address
     pub lines: Vec<CreateSalesOrderLine>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseCreditHold {
+    pub reason: String,
+}
+
+/// Everything about one customer's account on a single page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomerCenter {
+    pub customer: Customer,
+    pub credit: CustomerCredit,
+    pub addresses: Vec<PartyAddress>,
+    pub contacts: Vec<PartyContact>,
+    pub balance: Decimal, // Open receivables
+    pub available_credit: Option<Decimal>,
+    pub open_invoices: Vec<Invoice>,
+    pub orders: Vec<SalesOrder>,
+    pub payments: Vec<Payment>,
+    pub credit_memos: Vec<CreditMemo>,
+}
+
+use crate::models::accounting::{Invoice, Payment};
+use crate::models::address::{CreateAddress, CreateContact, PartyAddress, PartyContact};
 use crate::models::analytics::SalesTrend;
+use crate::models::transactions::CreditMemo;
 
 #[async_trait]
 pub trait SalesService: Send + Sync {
@@ -92,6 +177,10 @@ pub trait SalesService: Send + Sync {
         order: CreateSalesOrder,
     ) -> Result<SalesOrder, crate::error::Error>;
 
+    /// Confirms a draft order after checking the customer's credit.
+    /// An order that fails the check is put ON_HOLD instead.
+    async fn confirm_order(&self, tenant_id: Uuid, order_id: Uuid) -> Result<SalesOrder, crate::error::Error>;
+
     /// Ships a Sales Order.
     /// This must:
     /// 1. Validate status is CONFIRMED.
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::models::inventory::AdjustmentReason;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CountType {
+    #[strum(serialize = "FULL")]
+    Full,
+    #[strum(serialize = "CYCLE")]
+    Cycle,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CountSessionStatus {
+    #[strum(serialize = "OPEN")]
+    Open,
+    #[strum(serialize = "SUBMITTED")]
+    Submitted,
+    #[strum(serialize = "APPROVED")]
+    Approved,
+    #[strum(serialize = "CANCELLED")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CountSession {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub session_number: String,
+    pub count_type: CountType,
+    pub status: CountSessionStatus,
+    pub is_blind: bool,
+    pub location: Option<String>,
+    pub schedule_id: Option<Uuid>,
+    pub snapshot_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub approved_by: Option<Uuid>,
+    pub journal_entry_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CountLine {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub product_id: Uuid,
+    pub location: Option<String>,
+    pub snapshot_quantity: Decimal, // Frozen on-hand when the session opened (0 for extra locations)
+    pub counted_quantity: Option<Decimal>,
+    pub unit_cost: Decimal,
+    pub reason_code: Option<AdjustmentReason>,
+    pub notes: Option<String>,
+    pub counted_at: Option<DateTime<Utc>>,
+}
+
+/// Count sheet line as shown to counters. Snapshot and variance are hidden on blind counts
+/// until the session is submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountSheetLine {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub sku: String,
+    pub product_name: String,
+    pub location: Option<String>,
+    pub snapshot_quantity: Option<Decimal>,
+    pub counted_quantity: Option<Decimal>,
+    pub variance: Option<Decimal>,
+    pub reason_code: Option<AdjustmentReason>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountSessionDetail {
+    pub session: CountSession,
+    pub lines: Vec<CountSheetLine>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCountSession {
+    pub count_type: CountType,
+    pub is_blind: bool,
+    pub location: Option<String>,
+    pub product_ids: Option<Vec<Uuid>>, // Defaults to every active inventory item
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordCountLine {
+    pub product_id: Uuid,
+    pub location: Option<String>,
+    pub counted_quantity: Decimal,
+    pub reason_code: Option<AdjustmentReason>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordCounts {
+    pub lines: Vec<RecordCountLine>,
+}
+
+// --- Variance Review ---
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountVarianceReport {
+    pub session_id: Uuid,
+    pub lines: Vec<CountVarianceLine>,
+    pub total_gain_value: Decimal,
+    pub total_loss_value: Decimal,
+    pub net_value: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountVarianceLine {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub product_name: String,
+    pub snapshot_quantity: Decimal,
+    pub counted_quantity: Decimal,
+    pub variance: Decimal,
+    pub unit_cost: Decimal,
+    pub variance_value: Decimal,
+    pub reason_code: AdjustmentReason,
+}
+
+impl CountVarianceReport {
+    /// Aggregates count lines per product (a product may be counted in several locations).
+    /// Lines without a reason code default to SHRINKAGE for losses and FOUND for gains.
+    pub fn from_lines(session_id: Uuid, lines: &[(CountLine, String, String)]) -> Self {
+        let mut totals: Vec<(CountVarianceLine, Option<AdjustmentReason>)> = Vec::new();
+
+        for (line, sku, name) in lines {
+            let (entry, reason) = match totals.iter_mut().find(|(l, _)| l.product_id == line.product_id) {
+                Some(t) => (&mut t.0, &mut t.1),
+                None => {
+                    totals.push((
+                        CountVarianceLine {
+                            product_id: line.product_id,
+                            sku: sku.clone(),
+                            product_name: name.clone(),
+                            snapshot_quantity: Decimal::ZERO,
+                            counted_quantity: Decimal::ZERO,
+                            variance: Decimal::ZERO,
+                            unit_cost: line.unit_cost,
+                            variance_value: Decimal::ZERO,
+                            reason_code: AdjustmentReason::Other,
+                        },
+                        None,
+                    ));
+                    let t = totals.last_mut().unwrap();
+                    (&mut t.0, &mut t.1)
+                }
+            };
+            entry.snapshot_quantity += line.snapshot_quantity;
+            entry.counted_quantity += line.counted_quantity.unwrap_or_default();
+            if line.reason_code.is_some() {
+                *reason = line.reason_code.clone();
+            }
+        }
+
+        let (mut gains, mut losses) = (Decimal::ZERO, Decimal::ZERO);
+        let mut out = Vec::new();
+        for (mut l, reason) in totals {
+            l.variance = l.counted_quantity - l.snapshot_quantity;
+            if l.variance == Decimal::ZERO {
+                continue;
+            }
+            l.variance_value = l.variance * l.unit_cost;
+            l.reason_code = reason.unwrap_or_else(|| AdjustmentReason::for_variance(l.variance));
+            if l.variance_value > Decimal::ZERO {
+                gains += l.variance_value;
+            } else {
+                losses -= l.variance_value;
+            }
+            out.push(l);
+        }
+
+        CountVarianceReport { session_id, lines: out, total_gain_value: gains, total_loss_value: losses, net_value: gains - losses }
+    }
+}
+
+// --- Cycle Count Scheduling ---
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CycleCountSchedule {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub frequency_days: i32,
+    pub items_per_count: i32,
+    pub is_blind: bool,
+    pub location: Option<String>,
+    pub next_count_date: NaiveDate,
+    pub last_run_date: Option<NaiveDate>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCycleCountSchedule {
+    pub name: String,
+    pub frequency_days: i32,
+    pub items_per_count: i32,
+    pub is_blind: bool,
+    pub location: Option<String>,
+    pub next_count_date: Option<NaiveDate>,
+}
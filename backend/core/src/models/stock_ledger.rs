@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::models::inventory::{AdjustmentReason, TransactionType};
+
+/// One inventory movement with the product's on-hand balance right after it.
+/// Quantity is signed: receipts are positive, issues negative.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StockMovement {
+    pub transaction_id: Uuid,
+    pub product_id: Uuid,
+    pub sku: String,
+    pub product_name: String,
+    pub created_at: DateTime<Utc>,
+    pub transaction_type: TransactionType,
+    pub quantity: Decimal,
+    pub running_balance: Decimal,
+    pub reference_type: Option<String>, // PURCHASE_ORDER, SALES_ORDER, WORK_ORDER, COUNT_SESSION, VENDOR_CREDIT
+    pub reference_id: Option<Uuid>,
+    pub reference_number: Option<String>,
+    pub reason_code: Option<AdjustmentReason>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockCard {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub opening_balance: Decimal,
+    pub total_in: Decimal,
+    pub total_out: Decimal,
+    pub closing_balance: Decimal,
+    pub movements: Vec<StockMovement>,
+}
+
+// --- Stock on Hand as of a Date ---
+#[derive(Debug, Deserialize)]
+pub struct StockAsOfQuery {
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StockOnHandLine {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub quantity: Decimal,
+    pub unit_cost: Decimal,
+    pub value: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockOnHandReport {
+    pub as_of: NaiveDate,
+    pub lines: Vec<StockOnHandLine>,
+    pub total_value: Decimal,
+}
+
+// --- Movement Summary ---
+#[derive(Debug, Deserialize)]
+pub struct MovementQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub product_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MovementSummaryLine {
+    pub transaction_type: TransactionType,
+    pub transaction_count: i64,
+    pub quantity_in: Decimal,
+    pub quantity_out: Decimal,
+    pub net_quantity: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovementSummary {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub product_id: Option<Uuid>,
+    pub lines: Vec<MovementSummaryLine>,
+    pub total_in: Decimal,
+    pub total_out: Decimal,
+}
@@ -59,6 +59,16 @@ pub struct Bill {
     pub notes: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub purchase_order_id: Option<Uuid>,
+    pub receipt_id: Option<Uuid>,
+    pub match_status: crate::models::bill_matching::MatchStatus,
+    pub match_approved_by: Option<Uuid>,
+    pub match_approved_at: Option<DateTime<Utc>>,
+    pub journal_entry_id: Option<Uuid>,
+    pub payment_terms_id: Option<Uuid>,
+    pub discount_date: Option<NaiveDate>,
+    pub discount_amount: rust_decimal::Decimal,
+    pub discount_taken: rust_decimal::Decimal,
 }
 
 #[derive(Debug, Deserialize)]
@@ -66,9 +76,10 @@ pub struct CreateBill {
     pub supplier_id: Uuid,
     pub bill_number: String,
     pub date: Option<NaiveDate>,
-    pub due_date: NaiveDate,
+    pub due_date: Option<NaiveDate>, // Worked out from the payment terms when omitted
     pub total_amount: f64,
     pub terms: Option<String>,
+    pub payment_terms_id: Option<Uuid>, // Defaults to the supplier's terms
     pub notes: Option<String>,
 }
 
@@ -128,6 +139,7 @@ pub struct JournalEntry {
     pub date: NaiveDate,
     pub memo: Option<String>,
     pub is_adjusting: bool,
+    pub status: String, // PENDING_APPROVAL until approved when an approval rule covers it
     pub created_at: DateTime<Utc>,
 }
 
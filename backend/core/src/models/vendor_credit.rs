@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use strum::{Display, EnumString};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, EnumString, Display, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VendorCreditStatus {
+    #[strum(serialize = "OPEN")]
+    Open,
+    #[strum(serialize = "PARTIALLY_APPLIED")]
+    PartiallyApplied,
+    #[strum(serialize = "APPLIED")]
+    Applied,
+}
+
+/// A credit note from a supplier. It reduces what is owed as soon as it is recorded and is
+/// used up by applying it to the supplier's bills when they are paid.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct VendorCredit {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub supplier_id: Uuid,
+    pub credit_number: String,
+    pub supplier_reference: Option<String>, // The supplier's credit note number
+    pub bill_id: Option<Uuid>,
+    pub date: NaiveDate,
+    pub status: VendorCreditStatus,
+    pub total_amount: Decimal,
+    pub amount_applied: Decimal,
+    pub notes: Option<String>,
+    pub journal_entry_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl VendorCredit {
+    pub fn remaining(&self) -> Decimal {
+        self.total_amount - self.amount_applied
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct VendorCreditLine {
+    pub id: Uuid,
+    pub credit_id: Uuid,
+    pub product_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+    pub receipt_line_id: Option<Uuid>,
+    pub inspection_id: Option<Uuid>,
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit_price: Decimal,
+    pub amount: Decimal,
+    pub returned: bool, // Stock was shipped back to the supplier
+    pub transaction_id: Option<Uuid>,
+    pub sort_order: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct VendorCreditApplication {
+    pub id: Uuid,
+    pub credit_id: Uuid,
+    pub bill_id: Uuid,
+    pub bill_number: String,
+    pub bill_payment_id: Option<Uuid>,
+    pub amount: Decimal,
+    pub date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VendorCreditDetail {
+    pub credit: VendorCredit,
+    pub lines: Vec<VendorCreditLine>,
+    pub applications: Vec<VendorCreditApplication>,
+}
+
+/// Either a product or an account line. Product lines with `return_stock` ship the quantity
+/// back out of stock, taking it from quarantine when an inspection is given; without it they
+/// are a price allowance on goods kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateVendorCreditLine {
+    pub product_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+    pub receipt_line_id: Option<Uuid>,
+    pub inspection_id: Option<Uuid>,
+    pub description: Option<String>,
+    pub quantity: Decimal,
+    pub unit_price: Decimal,
+    #[serde(default)]
+    pub return_stock: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateVendorCredit {
+    pub supplier_id: Uuid,
+    pub supplier_reference: Option<String>,
+    pub bill_id: Option<Uuid>,
+    pub date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub lines: Vec<CreateVendorCreditLine>,
+}
+
+impl CreateVendorCredit {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.lines.is_empty() {
+            return Err(Error::BusinessRule("A vendor credit needs at least one line".to_string()));
+        }
+        for line in &self.lines {
+            if line.quantity <= Decimal::ZERO || line.unit_price < Decimal::ZERO {
+                return Err(Error::BusinessRule("Quantities must be positive and prices cannot be negative".to_string()));
+            }
+            if line.product_id.is_some() == line.account_id.is_some() {
+                return Err(Error::BusinessRule("Each line credits either a product or an account".to_string()));
+            }
+            if !line.return_stock && (line.receipt_line_id.is_some() || line.inspection_id.is_some()) {
+                return Err(Error::BusinessRule("Receipts and inspections only apply to returned stock".to_string()));
+            }
+            if line.return_stock && line.product_id.is_none() {
+                return Err(Error::BusinessRule("Only product lines can return stock".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn total(&self) -> Decimal {
+        self.lines.iter().map(|l| (l.quantity * l.unit_price).round_dp(2)).sum()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VendorCreditQuery {
+    pub supplier_id: Option<Uuid>,
+    pub status: Option<VendorCreditStatus>,
+}
+
+/// Part of a vendor credit used to settle a bill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditApplication {
+    pub credit_id: Uuid,
+    pub amount: Decimal,
+}
@@ -2,10 +2,20 @@ use async_trait::async_trait;
 use smart_erp_core::models::accounting::{
     AccountingService, CreateInvoice, CreatePayment, Invoice, InvoiceStatus, Payment,
 };
+use smart_erp_core::models::address::AddressType;
+use smart_erp_core::models::payment_terms::payment_discount;
 use smart_erp_core::error::Error;
-use sqlx::{PgPool, Postgres, Row, Transaction};
+use crate::db::address::{document_address, Party};
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use crate::db::payment_terms::{document_terms, TermsParty};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
+const INVOICE_COLUMNS: &str = "id, tenant_id, customer_id, sales_order_id, invoice_number, date, due_date, status, total_amount, amount_paid, created_at, updated_at, payment_terms_id, discount_date, discount_amount, discount_taken, billing_address_id, billing_address, shipping_address_id, shipping_address";
+const PAYMENT_COLUMNS: &str = "id, tenant_id, invoice_id, amount, date, method, reference, created_at, updated_at, discount_amount";
+
 pub struct PostgresAccountingRepository {
     pool: PgPool,
 }
@@ -16,9 +26,10 @@ impl PostgresAccountingRepository {
     }
 
     pub async fn list_invoices(&self, tenant_id: Uuid) -> Result<Vec<Invoice>, Error> {
-        let rows = sqlx::query_as::<_, Invoice>(
-            "SELECT id, tenant_id, customer_id, sales_order_id, invoice_number, date, due_date, status, total_amount, amount_paid, created_at, updated_at FROM invoices WHERE tenant_id = $1 ORDER BY created_at DESC"
-        )
+        let rows = sqlx::query_as::<_, Invoice>(&format!(
+            "SELECT {} FROM invoices WHERE tenant_id = $1 ORDER BY created_at DESC",
+            INVOICE_COLUMNS
+        ))
         .bind(tenant_id)
         .fetch_all(&self.pool)
         .await
@@ -27,9 +38,10 @@ impl PostgresAccountingRepository {
     }
 
     pub async fn list_payments(&self, tenant_id: Uuid) -> Result<Vec<Payment>, Error> {
-        let rows = sqlx::query_as::<_, Payment>(
-            "SELECT id, tenant_id, invoice_id, amount, date, method, reference, created_at, updated_at FROM payments WHERE tenant_id = $1 ORDER BY created_at DESC"
-        )
+        let rows = sqlx::query_as::<_, Payment>(&format!(
+            "SELECT {} FROM payments WHERE tenant_id = $1 ORDER BY created_at DESC",
+            PAYMENT_COLUMNS
+        ))
         .bind(tenant_id)
         .fetch_all(&self.pool)
         .await
@@ -45,24 +57,69 @@ impl AccountingService for PostgresAccountingRepository {
         tenant_id: Uuid,
         invoice: CreateInvoice,
     ) -> Result<Invoice, Error> {
-        let record = sqlx::query_as::<_, Invoice>(
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let (terms, schedule) = document_terms(
+            &mut tx,
+            tenant_id,
+            invoice.payment_terms_id,
+            TermsParty::Customer(invoice.customer_id),
+            invoice.date,
+            invoice.due_date,
+            invoice.total_amount,
+        ).await?;
+
+        // Invoices for an order bill and ship to where the order said
+        let order_addresses = match invoice.sales_order_id {
+            Some(id) => sqlx::query_as::<_, (Option<Uuid>, Option<String>, Option<Uuid>, Option<String>)>(
+                "SELECT billing_address_id, billing_address, shipping_address_id, shipping_address FROM sales_orders WHERE id = $1 AND tenant_id = $2"
+            )
+            .bind(id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?,
+            None => None,
+        };
+        let (billing_id, billing) = invoice_address(
+            &mut tx, tenant_id, invoice.customer_id, AddressType::Billing, invoice.billing_address_id,
+            order_addresses.as_ref().map(|(id, text, _, _)| (*id, text.clone())),
+        ).await?;
+        let (shipping_id, shipping) = invoice_address(
+            &mut tx, tenant_id, invoice.customer_id, AddressType::Shipping, invoice.shipping_address_id,
+            order_addresses.map(|(_, _, id, text)| (id, text)),
+        ).await?;
+
+        let record = sqlx::query_as::<_, Invoice>(&format!(
             r#"
-            INSERT INTO invoices (tenant_id, customer_id, sales_order_id, invoice_number, date, due_date, total_amount, status)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, 'DRAFT')
-            RETURNING id, tenant_id, customer_id, sales_order_id, invoice_number, date, due_date, status, total_amount, amount_paid, created_at, updated_at
-            "#
-        )
+            INSERT INTO invoices (tenant_id, customer_id, sales_order_id, invoice_number, date, due_date, total_amount, status,
+                                  payment_terms_id, discount_date, discount_amount,
+                                  billing_address_id, billing_address, shipping_address_id, shipping_address)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, 'DRAFT', $8, $9, $10, $11, $12, $13, $14)
+            RETURNING {}
+            "#,
+            INVOICE_COLUMNS
+        ))
         .bind(tenant_id)
         .bind(invoice.customer_id)
         .bind(invoice.sales_order_id)
         .bind(invoice.invoice_number)
         .bind(invoice.date)
-        .bind(invoice.due_date)
+        .bind(schedule.due_date)
         .bind(invoice.total_amount)
-        .fetch_one(&self.pool)
+        .bind(terms.map(|t| t.id))
+        .bind(schedule.discount_date)
+        .bind(schedule.discount_amount)
+        .bind(billing_id)
+        .bind(billing)
+        .bind(shipping_id)
+        .bind(shipping)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
         Ok(record)
     }
 
@@ -73,38 +130,50 @@ impl AccountingService for PostgresAccountingRepository {
     ) -> Result<Payment, Error> {
         let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
 
-        let created_payment = sqlx::query_as::<_, Payment>(
+        let invoice = sqlx::query_as::<_, Invoice>(&format!(
+            "SELECT {} FROM invoices WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+            INVOICE_COLUMNS
+        ))
+        .bind(payment.invoice_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Invoice not found".to_string()))?;
+
+        let discount = if payment.take_discount {
+            payment_discount(
+                invoice.total_amount - invoice.amount_paid,
+                invoice.discount_amount - invoice.discount_taken,
+                invoice.discount_date,
+                payment.date,
+                payment.amount,
+            )?
+        } else {
+            Decimal::ZERO
+        };
+
+        let created_payment = sqlx::query_as::<_, Payment>(&format!(
             r#"
-            INSERT INTO payments (tenant_id, invoice_id, amount, date, method, reference)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, tenant_id, invoice_id, amount, date, method, reference, created_at, updated_at
-            "#
-        )
+            INSERT INTO payments (tenant_id, invoice_id, amount, date, method, reference, discount_amount)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            PAYMENT_COLUMNS
+        ))
         .bind(tenant_id)
         .bind(payment.invoice_id)
         .bind(payment.amount)
         .bind(payment.date)
         .bind(payment.method)
         .bind(payment.reference)
+        .bind(discount)
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
-        let invoice_row = sqlx::query(
-            r#"
-            SELECT total_amount, amount_paid FROM invoices WHERE id = $1 FOR UPDATE
-            "#
-        )
-        .bind(payment.invoice_id)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(|e| Error::Database(e.to_string()))?;
-
-        let total_amount: rust_decimal::Decimal = invoice_row.try_get("total_amount").unwrap_or_default();
-        let amount_paid: rust_decimal::Decimal = invoice_row.try_get("amount_paid").unwrap_or_default();
-
-        let new_amount_paid = amount_paid + payment.amount;
-        let new_status = if new_amount_paid >= total_amount {
+        let new_amount_paid = invoice.amount_paid + payment.amount + discount;
+        let new_status = if new_amount_paid >= invoice.total_amount {
             InvoiceStatus::Paid
         } else {
             InvoiceStatus::PartiallyPaid
@@ -113,19 +182,45 @@ impl AccountingService for PostgresAccountingRepository {
         sqlx::query(
             r#"
             UPDATE invoices
-            SET amount_paid = $1, status = $2, updated_at = NOW()
-            WHERE id = $3
+            SET amount_paid = $1, status = $2, discount_taken = discount_taken + $3, updated_at = NOW()
+            WHERE id = $4
             "#
         )
         .bind(new_amount_paid)
         .bind(new_status)
+        .bind(discount)
         .bind(payment.invoice_id)
         .execute(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
+        if discount > Decimal::ZERO {
+            let memo = format!("Early payment discount on invoice {}", invoice.invoice_number);
+            let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "DSC", payment.date).await?;
+            post_journal_entry(&mut tx, tenant_id, &entry_number, payment.date, &memo, &[
+                LedgerLine::debit("4200", discount, &memo),
+                LedgerLine::credit("1200", discount, &memo),
+            ]).await?;
+        }
+
         tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
 
         Ok(created_payment)
     }
 }
+
+/// An address chosen on the invoice wins, then the sales order's copy, then the customer's default.
+async fn invoice_address(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    customer_id: Uuid,
+    address_type: AddressType,
+    chosen: Option<Uuid>,
+    from_order: Option<(Option<Uuid>, Option<String>)>,
+) -> Result<(Option<Uuid>, Option<String>), Error> {
+    if let (None, Some((id, Some(text)))) = (chosen, &from_order) {
+        return Ok((*id, Some(text.clone())));
+    }
+    let address = document_address(tx, tenant_id, Party::Customer(customer_id), address_type, chosen).await?;
+    Ok((address.as_ref().map(|a| a.id), address.map(|a| a.formatted())))
+}
@@ -0,0 +1,413 @@
+use smart_erp_core::models::address::{
+    AddressType, ContactRole, CreateAddress, CreateContact, PartyAddress, PartyContact, UpdateAddress,
+    UpdateContact,
+};
+use smart_erp_core::error::Error;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Who an address or contact belongs to.
+#[derive(Debug, Clone, Copy)]
+pub enum Party {
+    Customer(Uuid),
+    Supplier(Uuid),
+    Employee(Uuid),
+}
+
+impl Party {
+    fn column(&self) -> &'static str {
+        match self {
+            Party::Customer(_) => "customer_id",
+            Party::Supplier(_) => "supplier_id",
+            Party::Employee(_) => "employee_id",
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        match self {
+            Party::Customer(id) | Party::Supplier(id) | Party::Employee(id) => *id,
+        }
+    }
+
+    fn owner(customer_id: Option<Uuid>, supplier_id: Option<Uuid>, employee_id: Option<Uuid>) -> Party {
+        match (customer_id, supplier_id, employee_id) {
+            (Some(id), _, _) => Party::Customer(id),
+            (_, Some(id), _) => Party::Supplier(id),
+            (_, _, Some(id)) => Party::Employee(id),
+            _ => unreachable!("party_addresses and party_contacts always have an owner"),
+        }
+    }
+
+    fn check_address_type(&self, address_type: AddressType) -> Result<(), Error> {
+        let (allowed, message) = match self {
+            Party::Customer(_) => (
+                matches!(address_type, AddressType::Billing | AddressType::Shipping),
+                "Customers have billing and shipping addresses",
+            ),
+            Party::Supplier(_) => (
+                matches!(address_type, AddressType::Billing | AddressType::Shipping | AddressType::RemitTo),
+                "Suppliers have billing, shipping and remit-to addresses",
+            ),
+            Party::Employee(_) => (address_type == AddressType::Home, "Employees have home addresses"),
+        };
+        if !allowed {
+            return Err(Error::BusinessRule(message.to_string()));
+        }
+        Ok(())
+    }
+}
+
+pub struct PostgresAddressRepository {
+    pool: PgPool,
+}
+
+impl PostgresAddressRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_addresses(&self, tenant_id: Uuid, party: Party) -> Result<Vec<PartyAddress>, Error> {
+        sqlx::query_as::<_, PartyAddress>(&format!(
+            "SELECT * FROM party_addresses WHERE {} = $1 AND tenant_id = $2 ORDER BY address_type, is_default DESC, created_at",
+            party.column()
+        ))
+        .bind(party.id())
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_address(&self, tenant_id: Uuid, party: Party, req: CreateAddress) -> Result<PartyAddress, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        check_party(&mut tx, tenant_id, party).await?;
+        let address = insert_address(&mut tx, tenant_id, party, req).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(address)
+    }
+
+    /// Documents already issued keep their copy of the old address.
+    pub async fn update_address(&self, tenant_id: Uuid, address_id: Uuid, req: UpdateAddress) -> Result<PartyAddress, Error> {
+        if req.line1.as_deref().is_some_and(|l| l.trim().is_empty()) {
+            return Err(Error::BusinessRule("Address line 1 cannot be blank".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let address = lock_address(&mut tx, tenant_id, address_id).await?;
+        if req.is_default == Some(true) {
+            let party = Party::owner(address.customer_id, address.supplier_id, address.employee_id);
+            clear_default_address(&mut tx, party, address.address_type).await?;
+        }
+
+        let address = sqlx::query_as::<_, PartyAddress>(
+            r#"
+            UPDATE party_addresses
+            SET label = COALESCE($1, label),
+                line1 = COALESCE($2, line1),
+                line2 = COALESCE($3, line2),
+                city = COALESCE($4, city),
+                region = COALESCE($5, region),
+                postal_code = COALESCE($6, postal_code),
+                country = COALESCE($7, country),
+                is_default = COALESCE($8, is_default),
+                updated_at = NOW()
+            WHERE id = $9
+            RETURNING *
+            "#
+        )
+        .bind(req.label)
+        .bind(req.line1.as_deref().map(str::trim))
+        .bind(req.line2)
+        .bind(req.city)
+        .bind(req.region)
+        .bind(req.postal_code)
+        .bind(req.country)
+        .bind(req.is_default)
+        .bind(address.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(address)
+    }
+
+    /// Removing a default address makes the oldest remaining one of the same type the default.
+    pub async fn delete_address(&self, tenant_id: Uuid, address_id: Uuid) -> Result<(), Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let address = lock_address(&mut tx, tenant_id, address_id).await?;
+
+        sqlx::query("DELETE FROM party_addresses WHERE id = $1")
+            .bind(address.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        if address.is_default {
+            let party = Party::owner(address.customer_id, address.supplier_id, address.employee_id);
+            sqlx::query(&format!(
+                r#"
+                UPDATE party_addresses SET is_default = TRUE, updated_at = NOW()
+                WHERE id = (SELECT id FROM party_addresses WHERE {} = $1 AND address_type = $2 ORDER BY created_at LIMIT 1)
+                "#,
+                party.column()
+            ))
+            .bind(party.id())
+            .bind(address.address_type)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    pub async fn list_contacts(&self, tenant_id: Uuid, party: Party) -> Result<Vec<PartyContact>, Error> {
+        sqlx::query_as::<_, PartyContact>(&format!(
+            "SELECT * FROM party_contacts WHERE {} = $1 AND tenant_id = $2 ORDER BY is_primary DESC, name",
+            party.column()
+        ))
+        .bind(party.id())
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_contact(&self, tenant_id: Uuid, party: Party, req: CreateContact) -> Result<PartyContact, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        check_party(&mut tx, tenant_id, party).await?;
+        let contact = insert_contact(&mut tx, tenant_id, party, req).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(contact)
+    }
+
+    pub async fn update_contact(&self, tenant_id: Uuid, contact_id: Uuid, req: UpdateContact) -> Result<PartyContact, Error> {
+        if req.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(Error::BusinessRule("Contact name cannot be blank".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let contact = sqlx::query_as::<_, PartyContact>("SELECT * FROM party_contacts WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+            .bind(contact_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Contact not found".to_string()))?;
+        if req.is_primary == Some(true) {
+            clear_primary_contact(&mut tx, Party::owner(contact.customer_id, contact.supplier_id, contact.employee_id)).await?;
+        }
+
+        let contact = sqlx::query_as::<_, PartyContact>(
+            r#"
+            UPDATE party_contacts
+            SET name = COALESCE($1, name),
+                role = COALESCE($2, role),
+                title = COALESCE($3, title),
+                email = COALESCE($4, email),
+                phone = COALESCE($5, phone),
+                is_primary = COALESCE($6, is_primary),
+                notes = COALESCE($7, notes),
+                updated_at = NOW()
+            WHERE id = $8
+            RETURNING *
+            "#
+        )
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(req.role)
+        .bind(req.title)
+        .bind(req.email)
+        .bind(req.phone)
+        .bind(req.is_primary)
+        .bind(req.notes)
+        .bind(contact.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(contact)
+    }
+
+    pub async fn delete_contact(&self, tenant_id: Uuid, contact_id: Uuid) -> Result<(), Error> {
+        let result = sqlx::query("DELETE FROM party_contacts WHERE id = $1 AND tenant_id = $2")
+            .bind(contact_id)
+            .bind(tenant_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound("Contact not found".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// The first address of each type becomes the default.
+pub async fn insert_address(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, party: Party, req: CreateAddress) -> Result<PartyAddress, Error> {
+    req.validate()?;
+    party.check_address_type(req.address_type)?;
+
+    let has_default = sqlx::query_scalar::<_, bool>(&format!(
+        "SELECT EXISTS (SELECT 1 FROM party_addresses WHERE {} = $1 AND address_type = $2 AND is_default)",
+        party.column()
+    ))
+    .bind(party.id())
+    .bind(req.address_type)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    if req.is_default && has_default {
+        clear_default_address(tx, party, req.address_type).await?;
+    }
+
+    sqlx::query_as::<_, PartyAddress>(&format!(
+        r#"
+        INSERT INTO party_addresses (tenant_id, {}, address_type, label, line1, line2, city, region, postal_code, country, is_default)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+        party.column()
+    ))
+    .bind(tenant_id)
+    .bind(party.id())
+    .bind(req.address_type)
+    .bind(req.label)
+    .bind(req.line1.trim())
+    .bind(req.line2)
+    .bind(req.city)
+    .bind(req.region)
+    .bind(req.postal_code)
+    .bind(req.country)
+    .bind(req.is_default || !has_default)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+pub async fn insert_contact(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, party: Party, req: CreateContact) -> Result<PartyContact, Error> {
+    req.validate()?;
+    if req.is_primary {
+        clear_primary_contact(tx, party).await?;
+    }
+
+    sqlx::query_as::<_, PartyContact>(&format!(
+        r#"
+        INSERT INTO party_contacts (tenant_id, {}, name, role, title, email, phone, is_primary, notes)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+        party.column()
+    ))
+    .bind(tenant_id)
+    .bind(party.id())
+    .bind(req.name.trim())
+    .bind(req.role.unwrap_or(ContactRole::Other))
+    .bind(req.title)
+    .bind(req.email)
+    .bind(req.phone)
+    .bind(req.is_primary)
+    .bind(req.notes)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+/// The address to copy onto a new document: the one chosen, else the party's default of that
+/// type. Shipping and remit-to fall back to the default billing address.
+pub async fn document_address(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    party: Party,
+    address_type: AddressType,
+    chosen: Option<Uuid>,
+) -> Result<Option<PartyAddress>, Error> {
+    if let Some(id) = chosen {
+        let address = sqlx::query_as::<_, PartyAddress>(&format!(
+            "SELECT * FROM party_addresses WHERE id = $1 AND tenant_id = $2 AND {} = $3",
+            party.column()
+        ))
+        .bind(id)
+        .bind(tenant_id)
+        .bind(party.id())
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Address not found for this party".to_string()))?;
+        if address.address_type != address_type && address.address_type != AddressType::Billing {
+            return Err(Error::BusinessRule(format!("Expected a {:?} address, got {:?}", address_type, address.address_type)));
+        }
+        return Ok(Some(address));
+    }
+
+    sqlx::query_as::<_, PartyAddress>(&format!(
+        r#"
+        SELECT * FROM party_addresses
+        WHERE {} = $1 AND tenant_id = $2 AND is_default AND address_type IN ($3, 'BILLING')
+        ORDER BY address_type = $3 DESC
+        LIMIT 1
+        "#,
+        party.column()
+    ))
+    .bind(party.id())
+    .bind(tenant_id)
+    .bind(address_type)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+async fn check_party(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, party: Party) -> Result<(), Error> {
+    let (sql, name) = match party {
+        Party::Customer(_) => ("SELECT EXISTS (SELECT 1 FROM customers WHERE id = $1 AND tenant_id = $2)", "Customer"),
+        Party::Supplier(_) => ("SELECT EXISTS (SELECT 1 FROM suppliers WHERE id = $1 AND tenant_id = $2)", "Supplier"),
+        Party::Employee(_) => ("SELECT EXISTS (SELECT 1 FROM employees WHERE id = $1 AND tenant_id = $2)", "Employee"),
+    };
+    let exists = sqlx::query_scalar::<_, bool>(sql)
+        .bind(party.id())
+        .bind(tenant_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    if !exists {
+        return Err(Error::NotFound(format!("{} not found", name)));
+    }
+    Ok(())
+}
+
+async fn lock_address(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, address_id: Uuid) -> Result<PartyAddress, Error> {
+    sqlx::query_as::<_, PartyAddress>("SELECT * FROM party_addresses WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+        .bind(address_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Address not found".to_string()))
+}
+
+async fn clear_default_address(tx: &mut Transaction<'_, Postgres>, party: Party, address_type: AddressType) -> Result<(), Error> {
+    sqlx::query(&format!(
+        "UPDATE party_addresses SET is_default = FALSE, updated_at = NOW() WHERE {} = $1 AND address_type = $2 AND is_default",
+        party.column()
+    ))
+    .bind(party.id())
+    .bind(address_type)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+async fn clear_primary_contact(tx: &mut Transaction<'_, Postgres>, party: Party) -> Result<(), Error> {
+    sqlx::query(&format!(
+        "UPDATE party_contacts SET is_primary = FALSE, updated_at = NOW() WHERE {} = $1 AND is_primary",
+        party.column()
+    ))
+    .bind(party.id())
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
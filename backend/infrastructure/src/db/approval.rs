@@ -0,0 +1,641 @@
+use smart_erp_core::models::approval::*;
+use smart_erp_core::models::auth::Role;
+use smart_erp_core::models::requisition::ApprovalStatus;
+use smart_erp_core::error::Error;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresApprovalRepository {
+    pool: PgPool,
+}
+
+impl PostgresApprovalRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // --- Rules ---
+    pub async fn list_rules(&self, tenant_id: Uuid) -> Result<Vec<ApprovalRule>, Error> {
+        sqlx::query_as::<_, ApprovalRule>(
+            "SELECT * FROM approval_rules WHERE tenant_id = $1 ORDER BY document_type, sequence, min_amount, name"
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_rule(&self, tenant_id: Uuid, req: CreateApprovalRule) -> Result<ApprovalRule, Error> {
+        if req.name.trim().is_empty() {
+            return Err(Error::BusinessRule("Rule name is required".to_string()));
+        }
+        if req.min_amount.is_some_and(|a| a < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Minimum amount cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, ApprovalRule>(
+            r#"
+            INSERT INTO approval_rules (tenant_id, document_type, name, min_amount, approver_role, sequence)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.document_type)
+        .bind(req.name.trim())
+        .bind(req.min_amount.unwrap_or(Decimal::ZERO))
+        .bind(req.approver_role)
+        .bind(req.sequence.unwrap_or(1))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Changes apply to requests raised from now on; steps already raised are kept.
+    pub async fn update_rule(&self, tenant_id: Uuid, rule_id: Uuid, req: UpdateApprovalRule) -> Result<ApprovalRule, Error> {
+        if req.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(Error::BusinessRule("Rule name is required".to_string()));
+        }
+        if req.min_amount.is_some_and(|a| a < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Minimum amount cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, ApprovalRule>(
+            r#"
+            UPDATE approval_rules
+            SET name = COALESCE($1, name),
+                min_amount = COALESCE($2, min_amount),
+                approver_role = COALESCE($3, approver_role),
+                sequence = COALESCE($4, sequence),
+                is_active = COALESCE($5, is_active),
+                updated_at = NOW()
+            WHERE id = $6 AND tenant_id = $7
+            RETURNING *
+            "#
+        )
+        .bind(req.name.map(|n| n.trim().to_string()))
+        .bind(req.min_amount)
+        .bind(req.approver_role)
+        .bind(req.sequence)
+        .bind(req.is_active)
+        .bind(rule_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Approval rule not found".to_string()))
+    }
+
+    // --- Requests ---
+    pub async fn list_requests(&self, tenant_id: Uuid, query: ApprovalRequestQuery, user_id: Uuid, role: &str) -> Result<Vec<ApprovalRequest>, Error> {
+        let requests = sqlx::query_as::<_, ApprovalRequest>(
+            r#"
+            SELECT * FROM approval_requests
+            WHERE tenant_id = $1
+              AND ($2::varchar IS NULL OR document_type = $2)
+              AND ($3::uuid IS NULL OR document_id = $3)
+              AND ($4::varchar IS NULL OR status = $4)
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.document_type)
+        .bind(query.document_id)
+        .bind(if query.awaiting_me { Some(ApprovalRequestStatus::Pending) } else { query.status })
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        if !query.awaiting_me {
+            return Ok(requests);
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let mut awaiting = Vec::new();
+        for request in requests {
+            if request.requested_by == Some(user_id) {
+                continue;
+            }
+            let roles = acting_roles(&mut tx, tenant_id, user_id, role, request.document_type).await?;
+            let detail = load_detail(&mut tx, request).await?;
+            if detail.step_for(&roles).is_some() {
+                awaiting.push(detail.request);
+            }
+        }
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(awaiting)
+    }
+
+    pub async fn get_request(&self, tenant_id: Uuid, request_id: Uuid) -> Result<ApprovalRequestDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let request = sqlx::query_as::<_, ApprovalRequest>("SELECT * FROM approval_requests WHERE id = $1 AND tenant_id = $2")
+            .bind(request_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Approval request not found".to_string()))?;
+        let detail = load_detail(&mut tx, request).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    /// Sends a purchase order or bill for approval, or resubmits a held journal entry or
+    /// credit memo after a rejection.
+    pub async fn request_approval(&self, tenant_id: Uuid, req: RequestApproval, user_id: Uuid) -> Result<ApprovalRequestDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let document = document_info(&mut tx, tenant_id, req.document_type, req.document_id).await?;
+        if !document.open {
+            return Err(Error::BusinessRule(format!("{} no longer needs approval", document.number)));
+        }
+        if !approval_required(&mut tx, tenant_id, req.document_type, document.amount).await? {
+            return Err(Error::BusinessRule(format!("No approval rule applies to {}", document.number)));
+        }
+        let approved: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM approval_requests
+                WHERE tenant_id = $1 AND document_type = $2 AND document_id = $3 AND status = 'APPROVED' AND amount >= $4
+            )
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.document_type)
+        .bind(req.document_id)
+        .bind(document.amount)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+        if approved {
+            return Err(Error::BusinessRule(format!("{} is already approved", document.number)));
+        }
+
+        if matches!(req.document_type, DocumentType::JournalEntry | DocumentType::CreditMemo) {
+            set_document_status(&mut tx, req.document_type, req.document_id, "PENDING_APPROVAL").await?;
+        }
+        let request_id = open_request(&mut tx, tenant_id, req.document_type, req.document_id, &document.number, document.amount, user_id).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_request(tenant_id, request_id).await
+    }
+
+    /// Approves the current step the caller can decide on, directly or through a delegation.
+    /// The request is approved once every step is. Requesters cannot decide on their own requests.
+    pub async fn approve(&self, tenant_id: Uuid, request_id: Uuid, user_id: Uuid, role: &str, req: ApprovalDecision) -> Result<ApprovalRequestDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let detail = lock_pending(&mut tx, tenant_id, request_id, user_id).await?;
+        let (step_id, delegator) = decide_step(&mut tx, &detail, user_id, role).await?;
+        set_step_status(&mut tx, step_id, ApprovalStatus::Approved, user_id, delegator, req.comment.as_deref()).await?;
+        record_event(&mut tx, request_id, ApprovalAction::Approved, user_id, delegator, req.comment.as_deref()).await?;
+
+        let remaining = detail.steps.iter().filter(|s| s.status == ApprovalStatus::Pending && s.id != step_id).count();
+        if remaining == 0 {
+            set_request_status(&mut tx, request_id, ApprovalRequestStatus::Approved).await?;
+            complete_document(&mut tx, detail.request.document_type, detail.request.document_id, true).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_request(tenant_id, request_id).await
+    }
+
+    /// A single rejection rejects the whole request.
+    pub async fn reject(&self, tenant_id: Uuid, request_id: Uuid, user_id: Uuid, role: &str, req: ApprovalDecision) -> Result<ApprovalRequestDetail, Error> {
+        let comment = required_comment(req.comment, "Give a reason for the rejection")?;
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let detail = lock_pending(&mut tx, tenant_id, request_id, user_id).await?;
+        let (step_id, delegator) = decide_step(&mut tx, &detail, user_id, role).await?;
+        set_step_status(&mut tx, step_id, ApprovalStatus::Rejected, user_id, delegator, Some(&comment)).await?;
+        record_event(&mut tx, request_id, ApprovalAction::Rejected, user_id, delegator, Some(&comment)).await?;
+        set_request_status(&mut tx, request_id, ApprovalRequestStatus::Rejected).await?;
+        complete_document(&mut tx, detail.request.document_type, detail.request.document_id, false).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_request(tenant_id, request_id).await
+    }
+
+    pub async fn comment(&self, tenant_id: Uuid, request_id: Uuid, user_id: Uuid, req: ApprovalDecision) -> Result<ApprovalRequestDetail, Error> {
+        let comment = required_comment(req.comment, "Comment cannot be empty")?;
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM approval_requests WHERE id = $1 AND tenant_id = $2)")
+            .bind(request_id)
+            .bind(tenant_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if !exists {
+            return Err(Error::NotFound("Approval request not found".to_string()));
+        }
+        record_event(&mut tx, request_id, ApprovalAction::Commented, user_id, None, Some(&comment)).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_request(tenant_id, request_id).await
+    }
+
+    /// Withdraws a pending request. Held journal entries and credit memos stay held.
+    pub async fn cancel(&self, tenant_id: Uuid, request_id: Uuid, user_id: Uuid, role: &str, req: ApprovalDecision) -> Result<ApprovalRequestDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let request = lock_request(&mut tx, tenant_id, request_id).await?;
+        if request.requested_by != Some(user_id) && role != Role::Admin.to_string() {
+            return Err(Error::BusinessRule("Only the requester or an administrator can cancel an approval request".to_string()));
+        }
+        if request.status != ApprovalRequestStatus::Pending {
+            return Err(Error::BusinessRule(format!("Approval request is already {}", request.status)));
+        }
+        set_request_status(&mut tx, request_id, ApprovalRequestStatus::Cancelled).await?;
+        record_event(&mut tx, request_id, ApprovalAction::Cancelled, user_id, None, req.comment.as_deref()).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_request(tenant_id, request_id).await
+    }
+
+    // --- Delegations ---
+    pub async fn list_delegations(&self, tenant_id: Uuid) -> Result<Vec<ApprovalDelegation>, Error> {
+        sqlx::query_as::<_, ApprovalDelegation>(
+            "SELECT * FROM approval_delegations WHERE tenant_id = $1 ORDER BY starts_on DESC, created_at DESC"
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_delegation(&self, tenant_id: Uuid, req: CreateApprovalDelegation, delegator_id: Uuid) -> Result<ApprovalDelegation, Error> {
+        let today = Utc::now().date_naive();
+        req.validate(delegator_id, today)?;
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND tenant_id = $2)")
+            .bind(req.delegate_id)
+            .bind(tenant_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if !exists {
+            return Err(Error::NotFound("User not found".to_string()));
+        }
+
+        sqlx::query_as::<_, ApprovalDelegation>(
+            r#"
+            INSERT INTO approval_delegations (tenant_id, delegator_id, delegate_id, document_type, starts_on, ends_on)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(delegator_id)
+        .bind(req.delegate_id)
+        .bind(req.document_type)
+        .bind(req.starts_on.unwrap_or(today))
+        .bind(req.ends_on)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn revoke_delegation(&self, tenant_id: Uuid, delegation_id: Uuid, user_id: Uuid, role: &str) -> Result<ApprovalDelegation, Error> {
+        sqlx::query_as::<_, ApprovalDelegation>(
+            r#"
+            UPDATE approval_delegations
+            SET is_active = FALSE
+            WHERE id = $1 AND tenant_id = $2 AND (delegator_id = $3 OR $4)
+            RETURNING *
+            "#
+        )
+        .bind(delegation_id)
+        .bind(tenant_id)
+        .bind(user_id)
+        .bind(role == Role::Admin.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Delegation not found".to_string()))
+    }
+}
+
+/// Whether any active rule covers a document of this type and amount.
+pub async fn approval_required(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, document_type: DocumentType, amount: Decimal) -> Result<bool, Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM approval_rules WHERE tenant_id = $1 AND document_type = $2 AND is_active = TRUE AND min_amount <= $3)"
+    )
+    .bind(tenant_id)
+    .bind(document_type)
+    .bind(amount)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+/// Gate for repositories about to move a document on: passes when no rule covers it or an
+/// approval for at least its current amount is on file.
+pub async fn require_approval(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    document_type: DocumentType,
+    document_id: Uuid,
+    document_number: &str,
+    amount: Decimal,
+    action: &str,
+) -> Result<(), Error> {
+    if !approval_required(tx, tenant_id, document_type, amount).await? {
+        return Ok(());
+    }
+    let approved: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM approval_requests
+            WHERE tenant_id = $1 AND document_type = $2 AND document_id = $3 AND status = 'APPROVED' AND amount >= $4
+        )
+        "#
+    )
+    .bind(tenant_id)
+    .bind(document_type)
+    .bind(document_id)
+    .bind(amount)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    if !approved {
+        return Err(Error::BusinessRule(format!("{} needs approval before it can be {}", document_number, action)));
+    }
+    Ok(())
+}
+
+/// Raises a request with one step per rule covering the amount.
+pub async fn open_request(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    document_type: DocumentType,
+    document_id: Uuid,
+    document_number: &str,
+    amount: Decimal,
+    user_id: Uuid,
+) -> Result<Uuid, Error> {
+    let pending: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM approval_requests WHERE tenant_id = $1 AND document_type = $2 AND document_id = $3 AND status = 'PENDING')"
+    )
+    .bind(tenant_id)
+    .bind(document_type)
+    .bind(document_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    if pending {
+        return Err(Error::BusinessRule(format!("{} is already waiting on approval", document_number)));
+    }
+
+    let request_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO approval_requests (tenant_id, document_type, document_id, document_number, amount, requested_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#
+    )
+    .bind(tenant_id)
+    .bind(document_type)
+    .bind(document_id)
+    .bind(document_number)
+    .bind(amount)
+    .bind(user_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO approval_steps (request_id, rule_id, rule_name, sequence, approver_role)
+        SELECT $1, id, name, sequence, approver_role
+        FROM approval_rules
+        WHERE tenant_id = $2 AND document_type = $3 AND is_active = TRUE AND min_amount <= $4
+        "#
+    )
+    .bind(request_id)
+    .bind(tenant_id)
+    .bind(document_type)
+    .bind(amount)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    record_event(tx, request_id, ApprovalAction::Requested, user_id, None, None).await?;
+    Ok(request_id)
+}
+
+struct DocumentInfo {
+    number: String,
+    amount: Decimal,
+    open: bool, // Still at a stage where approval matters
+}
+
+async fn document_info(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, document_type: DocumentType, document_id: Uuid) -> Result<DocumentInfo, Error> {
+    let (sql, label) = match document_type {
+        DocumentType::PurchaseOrder => (
+            "SELECT order_number, total_amount, status NOT IN ('RECEIVED', 'CANCELLED') FROM purchase_orders WHERE id = $1 AND tenant_id = $2",
+            "Purchase Order",
+        ),
+        DocumentType::Bill => (
+            "SELECT bill_number, total_amount, amount_paid < total_amount FROM bills WHERE id = $1 AND tenant_id = $2",
+            "Bill",
+        ),
+        DocumentType::JournalEntry => (
+            r#"
+            SELECT je.entry_number, COALESCE((SELECT SUM(debit) FROM journal_entry_lines WHERE entry_id = je.id), 0), je.status <> 'POSTED'
+            FROM journal_entries je WHERE je.id = $1 AND je.tenant_id = $2
+            "#,
+            "Journal entry",
+        ),
+        DocumentType::CreditMemo => (
+            "SELECT memo_number, total_amount, status IN ('PENDING_APPROVAL', 'REJECTED') FROM credit_memos WHERE id = $1 AND tenant_id = $2",
+            "Credit memo",
+        ),
+    };
+
+    let (number, amount, open): (String, Decimal, bool) = sqlx::query_as(sql)
+        .bind(document_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound(format!("{} not found", label)))?;
+    Ok(DocumentInfo { number, amount, open })
+}
+
+/// Releases or turns down a held journal entry or credit memo once its request is decided.
+async fn complete_document(tx: &mut Transaction<'_, Postgres>, document_type: DocumentType, document_id: Uuid, approved: bool) -> Result<(), Error> {
+    match (document_type, approved) {
+        (DocumentType::JournalEntry, true) => set_document_status(tx, document_type, document_id, "POSTED").await,
+        (DocumentType::CreditMemo, true) => set_document_status(tx, document_type, document_id, "OPEN").await,
+        (DocumentType::JournalEntry | DocumentType::CreditMemo, false) => set_document_status(tx, document_type, document_id, "REJECTED").await,
+        _ => Ok(()),
+    }
+}
+
+async fn set_document_status(tx: &mut Transaction<'_, Postgres>, document_type: DocumentType, document_id: Uuid, status: &str) -> Result<(), Error> {
+    let table = match document_type {
+        DocumentType::JournalEntry => "journal_entries",
+        DocumentType::CreditMemo => "credit_memos",
+        _ => return Ok(()),
+    };
+    sqlx::query(&format!(
+        "UPDATE {} SET status = $1 WHERE id = $2 AND status IN ('PENDING_APPROVAL', 'REJECTED')",
+        table
+    ))
+    .bind(status)
+    .bind(document_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+async fn lock_request(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, request_id: Uuid) -> Result<ApprovalRequest, Error> {
+    sqlx::query_as::<_, ApprovalRequest>("SELECT * FROM approval_requests WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+        .bind(request_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Approval request not found".to_string()))
+}
+
+async fn lock_pending(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, request_id: Uuid, user_id: Uuid) -> Result<ApprovalRequestDetail, Error> {
+    let request = lock_request(tx, tenant_id, request_id).await?;
+    if request.status != ApprovalRequestStatus::Pending {
+        return Err(Error::BusinessRule(format!("Approval request is {}, not waiting on approval", request.status)));
+    }
+    if request.requested_by == Some(user_id) {
+        return Err(Error::BusinessRule("You cannot decide on your own approval request".to_string()));
+    }
+    load_detail(tx, request).await
+}
+
+async fn load_detail(tx: &mut Transaction<'_, Postgres>, request: ApprovalRequest) -> Result<ApprovalRequestDetail, Error> {
+    let steps = sqlx::query_as::<_, ApprovalStep>("SELECT * FROM approval_steps WHERE request_id = $1 ORDER BY sequence, rule_name")
+        .bind(request.id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    let history = sqlx::query_as::<_, ApprovalEvent>(
+        r#"
+        SELECT e.id, e.request_id, e.action, e.user_id, u.username, e.on_behalf_of, e.comment, e.created_at
+        FROM approval_events e
+        LEFT JOIN users u ON u.id = e.user_id
+        WHERE e.request_id = $1
+        ORDER BY e.created_at, e.id
+        "#
+    )
+    .bind(request.id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(ApprovalRequestDetail { request, steps, history })
+}
+
+/// The user's own role plus the roles of everyone who delegated this document type to them today.
+async fn acting_roles(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, user_id: Uuid, role: &str, document_type: DocumentType) -> Result<Vec<(String, Option<Uuid>)>, Error> {
+    let delegated: Vec<(String, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT u.role, d.delegator_id
+        FROM approval_delegations d
+        JOIN users u ON u.id = d.delegator_id
+        WHERE d.tenant_id = $1 AND d.delegate_id = $2 AND d.is_active = TRUE
+          AND $3 BETWEEN d.starts_on AND d.ends_on
+          AND (d.document_type IS NULL OR d.document_type = $4)
+        ORDER BY d.created_at
+        "#
+    )
+    .bind(tenant_id)
+    .bind(user_id)
+    .bind(Utc::now().date_naive())
+    .bind(document_type)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let mut roles = vec![(role.to_string(), None)];
+    roles.extend(delegated.into_iter().map(|(role, delegator)| (role, Some(delegator))));
+    Ok(roles)
+}
+
+async fn decide_step(tx: &mut Transaction<'_, Postgres>, detail: &ApprovalRequestDetail, user_id: Uuid, role: &str) -> Result<(Uuid, Option<Uuid>), Error> {
+    let roles = acting_roles(tx, detail.request.tenant_id, user_id, role, detail.request.document_type).await?;
+    detail
+        .step_for(&roles)
+        .map(|(step, delegator)| (step.id, delegator))
+        .ok_or_else(|| {
+            let waiting: Vec<String> = detail.current_steps().iter().map(|s| s.approver_role.to_string()).collect();
+            Error::BusinessRule(format!("Waiting on approval from {}", waiting.join(", ")))
+        })
+}
+
+async fn set_step_status(
+    tx: &mut Transaction<'_, Postgres>,
+    step_id: Uuid,
+    status: ApprovalStatus,
+    user_id: Uuid,
+    on_behalf_of: Option<Uuid>,
+    comment: Option<&str>,
+) -> Result<(), Error> {
+    sqlx::query(
+        "UPDATE approval_steps SET status = $1, decided_by = $2, on_behalf_of = $3, decided_at = NOW(), comment = $4 WHERE id = $5"
+    )
+    .bind(status)
+    .bind(user_id)
+    .bind(on_behalf_of)
+    .bind(comment)
+    .bind(step_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+async fn set_request_status(tx: &mut Transaction<'_, Postgres>, request_id: Uuid, status: ApprovalRequestStatus) -> Result<(), Error> {
+    sqlx::query("UPDATE approval_requests SET status = $1, decided_at = NOW(), updated_at = NOW() WHERE id = $2")
+        .bind(status)
+        .bind(request_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Stamped with the clock rather than the transaction start so events from one request stay in order.
+async fn record_event(
+    tx: &mut Transaction<'_, Postgres>,
+    request_id: Uuid,
+    action: ApprovalAction,
+    user_id: Uuid,
+    on_behalf_of: Option<Uuid>,
+    comment: Option<&str>,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO approval_events (request_id, action, user_id, on_behalf_of, comment, created_at)
+        VALUES ($1, $2, $3, $4, $5, clock_timestamp())
+        "#
+    )
+    .bind(request_id)
+    .bind(action)
+    .bind(user_id)
+    .bind(on_behalf_of)
+    .bind(comment)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+fn required_comment(comment: Option<String>, message: &str) -> Result<String, Error> {
+    comment
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .ok_or(Error::BusinessRule(message.to_string()))
+}
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use smart_erp_core::models::auth::{
-    AuthResponse, AuthService, Claims, LoginRequest, RegisterRequest, User, Role,
+    AuthResponse, AuthService, Claims, LoginRequest, RegisterRequest, User,
 };
 use smart_erp_core::error::Error;
 use sqlx::PgPool;
@@ -0,0 +1,465 @@
+use smart_erp_core::models::bill_matching::*;
+use smart_erp_core::models::transactions::Bill;
+use smart_erp_core::models::payment_terms::payment_discount;
+use smart_erp_core::models::approval::DocumentType;
+use smart_erp_core::error::Error;
+use crate::db::approval::require_approval;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use crate::db::payment_terms::{document_terms, TermsParty};
+use crate::db::purchasing::{adjust_supplier_balance, check_supplier_active};
+use crate::db::vendor_credit::apply_vendor_credits;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// A receipt line with what is left to bill and the PO price it was ordered at.
+#[derive(sqlx::FromRow)]
+struct BillableLine {
+    receipt_line_id: Uuid,
+    product_id: Uuid,
+    product_name: String,
+    unbilled_quantity: Decimal,
+    po_price: Decimal,
+}
+
+pub struct PostgresBillMatchingRepository {
+    pool: PgPool,
+}
+
+impl PostgresBillMatchingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_settings(&self, tenant_id: Uuid) -> Result<PurchaseMatchSettings, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let settings = load_settings(&mut tx, tenant_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(settings)
+    }
+
+    pub async fn update_settings(&self, tenant_id: Uuid, req: UpdatePurchaseMatchSettings) -> Result<PurchaseMatchSettings, Error> {
+        if req.quantity_tolerance_percent < Decimal::ZERO || req.price_tolerance_percent < Decimal::ZERO {
+            return Err(Error::BusinessRule("Tolerances cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, PurchaseMatchSettings>(
+            r#"
+            INSERT INTO purchase_match_settings (tenant_id, quantity_tolerance_percent, price_tolerance_percent)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (tenant_id) DO UPDATE
+            SET quantity_tolerance_percent = EXCLUDED.quantity_tolerance_percent,
+                price_tolerance_percent = EXCLUDED.price_tolerance_percent,
+                updated_at = NOW()
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.quantity_tolerance_percent)
+        .bind(req.price_tolerance_percent)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_match(&self, tenant_id: Uuid, bill_id: Uuid) -> Result<BillMatchDetail, Error> {
+        let bill = sqlx::query_as::<_, Bill>("SELECT * FROM bills WHERE id = $1 AND tenant_id = $2")
+            .bind(bill_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Bill not found".to_string()))?;
+
+        let lines = sqlx::query_as::<_, BillLine>("SELECT * FROM bill_lines WHERE bill_id = $1 ORDER BY sort_order")
+            .bind(bill_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let exceptions = sqlx::query_as::<_, BillMatchException>(
+            "SELECT * FROM bill_match_exceptions WHERE bill_id = $1 ORDER BY created_at, exception_type"
+        )
+        .bind(bill_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(BillMatchDetail { bill, lines, exceptions })
+    }
+
+    /// Creates a bill from a PO's receipts and matches it. Clean matches post straight away;
+    /// exceptions hold the bill (and its posting) until approved.
+    pub async fn create_bill(&self, tenant_id: Uuid, req: CreateBillFromPurchase) -> Result<BillMatchDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let (supplier_id, order_number): (Uuid, String) = sqlx::query_as(
+            "SELECT supplier_id, order_number FROM purchase_orders WHERE id = $1 AND tenant_id = $2"
+        )
+        .bind(req.purchase_order_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Purchase Order not found".to_string()))?;
+
+        check_supplier_active(&mut tx, tenant_id, supplier_id).await?;
+
+        let billable = sqlx::query_as::<_, BillableLine>(
+            r#"
+            SELECT rl.id AS receipt_line_id, rl.product_id, p.name AS product_name,
+                   rl.quantity - rl.billed_quantity - rl.returned_quantity AS unbilled_quantity,
+                   ol.unit_price AS po_price
+            FROM purchase_receipt_lines rl
+            JOIN purchase_receipts r ON r.id = rl.receipt_id
+            JOIN purchase_order_lines ol ON ol.id = rl.order_line_id
+            JOIN products p ON p.id = rl.product_id
+            WHERE r.tenant_id = $1 AND r.order_id = $2 AND ($3::uuid IS NULL OR r.id = $3)
+            ORDER BY r.created_at, rl.id
+            FOR UPDATE OF rl
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.purchase_order_id)
+        .bind(req.receipt_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let requested: Vec<(&BillableLine, Decimal, Decimal)> = match &req.lines {
+            Some(lines) => lines.iter().map(|l| {
+                if l.quantity <= Decimal::ZERO || l.unit_price < Decimal::ZERO {
+                    return Err(Error::BusinessRule("Bill lines need a positive quantity and a non-negative price".to_string()));
+                }
+                billable.iter()
+                    .find(|b| b.receipt_line_id == l.receipt_line_id)
+                    .map(|b| (b, l.quantity, l.unit_price))
+                    .ok_or(Error::BusinessRule(format!("Receipt line {} is not on PO {}", l.receipt_line_id, order_number)))
+            }).collect::<Result<_, _>>()?,
+            None => billable.iter()
+                .filter(|b| b.unbilled_quantity > Decimal::ZERO)
+                .map(|b| (b, b.unbilled_quantity, b.po_price))
+                .collect(),
+        };
+        if requested.is_empty() {
+            return Err(Error::BusinessRule(format!("Nothing received on PO {} is left to bill", order_number)));
+        }
+
+        let total_amount: Decimal = requested.iter().map(|(_, qty, price)| (qty * price).round_dp(2)).sum();
+        let date = req.date.unwrap_or_else(|| Utc::now().date_naive());
+        let (terms, schedule) = document_terms(&mut tx, tenant_id, req.payment_terms_id, TermsParty::Supplier(supplier_id), date, req.due_date, total_amount).await?;
+
+        let bill_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO bills (tenant_id, supplier_id, bill_number, date, due_date, total_amount, terms, notes, purchase_order_id, receipt_id,
+                               payment_terms_id, discount_date, discount_amount)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING id
+            "#
+        )
+        .bind(tenant_id)
+        .bind(supplier_id)
+        .bind(&req.bill_number)
+        .bind(date)
+        .bind(schedule.due_date)
+        .bind(total_amount)
+        .bind(req.terms.clone().or(terms.as_ref().map(|t| t.name.clone())))
+        .bind(&req.notes)
+        .bind(req.purchase_order_id)
+        .bind(req.receipt_id)
+        .bind(terms.map(|t| t.id))
+        .bind(schedule.discount_date)
+        .bind(schedule.discount_amount)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let settings = load_settings(&mut tx, tenant_id).await?;
+        let mut has_exceptions = false;
+
+        for (i, (line, quantity, unit_price)) in requested.iter().enumerate() {
+            // Reserve the received quantity now so a bill waiting on approval cannot be billed twice.
+            let matched_quantity = (*quantity).min(line.unbilled_quantity).max(Decimal::ZERO);
+            sqlx::query("UPDATE purchase_receipt_lines SET billed_quantity = billed_quantity + $1 WHERE id = $2")
+                .bind(matched_quantity)
+                .bind(line.receipt_line_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+
+            let bill_line_id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO bill_lines (bill_id, product_id, description, quantity, unit_price, amount, sort_order, receipt_line_id, matched_quantity)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING id
+                "#
+            )
+            .bind(bill_id)
+            .bind(line.product_id)
+            .bind(&line.product_name)
+            .bind(quantity)
+            .bind(unit_price)
+            .bind((*quantity * *unit_price).round_dp(2))
+            .bind(i as i32)
+            .bind(line.receipt_line_id)
+            .bind(matched_quantity)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            let mut exceptions = Vec::new();
+            if quantity_exceeds_tolerance(line.unbilled_quantity, *quantity, settings.quantity_tolerance_percent) {
+                exceptions.push((MatchExceptionType::Quantity, line.unbilled_quantity, *quantity));
+            }
+            if price_exceeds_tolerance(line.po_price, *unit_price, settings.price_tolerance_percent) {
+                exceptions.push((MatchExceptionType::Price, line.po_price, *unit_price));
+            }
+
+            for (exception_type, expected, actual) in exceptions {
+                has_exceptions = true;
+                sqlx::query(
+                    r#"
+                    INSERT INTO bill_match_exceptions (bill_id, bill_line_id, exception_type, expected, actual, variance_percent)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    "#
+                )
+                .bind(bill_id)
+                .bind(bill_line_id)
+                .bind(exception_type)
+                .bind(expected)
+                .bind(actual)
+                .bind(variance_percent(expected, actual))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            }
+        }
+
+        if has_exceptions {
+            sqlx::query("UPDATE bills SET match_status = 'EXCEPTION' WHERE id = $1")
+                .bind(bill_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+        } else {
+            post_matched_bill(&mut tx, tenant_id, bill_id, MatchStatus::Matched, None).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_match(tenant_id, bill_id).await
+    }
+
+    /// Accepts a bill's match exceptions, releasing it for posting and payment.
+    pub async fn approve_match(&self, tenant_id: Uuid, bill_id: Uuid, approved_by: Uuid) -> Result<BillMatchDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let status: MatchStatus = sqlx::query_scalar("SELECT match_status FROM bills WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+            .bind(bill_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Bill not found".to_string()))?;
+
+        if status != MatchStatus::Exception {
+            return Err(Error::BusinessRule(format!("Bill has no match exceptions to approve (status {})", status)));
+        }
+
+        post_matched_bill(&mut tx, tenant_id, bill_id, MatchStatus::Approved, Some(approved_by)).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_match(tenant_id, bill_id).await
+    }
+
+    pub async fn list_payments(&self, tenant_id: Uuid, bill_id: Uuid) -> Result<Vec<BillPayment>, Error> {
+        sqlx::query_as::<_, BillPayment>(
+            "SELECT * FROM bill_payments WHERE tenant_id = $1 AND bill_id = $2 ORDER BY date, created_at"
+        )
+        .bind(tenant_id)
+        .bind(bill_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Pays a bill from a bank account, after any vendor credits applied with the payment. Bills
+    /// with unapproved match exceptions cannot be paid.
+    pub async fn pay_bill(&self, tenant_id: Uuid, bill_id: Uuid, req: CreateBillPayment) -> Result<BillPayment, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let bill = sqlx::query_as::<_, Bill>("SELECT * FROM bills WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+            .bind(bill_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Bill not found".to_string()))?;
+
+        if bill.match_status == MatchStatus::Exception {
+            return Err(Error::BusinessRule(format!(
+                "Bill {} has unapproved match exceptions and cannot be paid",
+                bill.bill_number
+            )));
+        }
+        require_approval(&mut tx, tenant_id, DocumentType::Bill, bill.id, &bill.bill_number, bill.total_amount, "paid").await?;
+
+        let balance = bill.total_amount - bill.amount_paid;
+        let credit_amount: Decimal = req.credits.iter().map(|c| c.amount).sum();
+        if req.amount < Decimal::ZERO || req.amount + credit_amount <= Decimal::ZERO || req.amount + credit_amount > balance {
+            return Err(Error::BusinessRule(format!("Payment and credits must be between 0 and the open balance {}", balance)));
+        }
+        let date = req.date.unwrap_or_else(|| Utc::now().date_naive());
+        let discount = if req.take_discount {
+            payment_discount(balance, bill.discount_amount - bill.discount_taken, bill.discount_date, date, req.amount + credit_amount)?
+        } else {
+            Decimal::ZERO
+        };
+        let settled = req.amount + credit_amount + discount;
+
+        let bank_account = match req.account_id {
+            Some(account_id) => sqlx::query_scalar::<_, String>(
+                "SELECT account_number FROM accounts WHERE id = $1 AND tenant_id = $2 AND account_type = 'BANK'"
+            )
+            .bind(account_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::BusinessRule("Payment account must be a bank account".to_string()))?,
+            None => "1000".to_string(),
+        };
+
+        let payment = sqlx::query_as::<_, BillPayment>(
+            r#"
+            INSERT INTO bill_payments (tenant_id, bill_id, amount, date, method, reference, account_id, discount_amount, credit_amount)
+            VALUES ($1, $2, $3, $4, COALESCE($5, $6), $7, $8, $9, $10)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(bill_id)
+        .bind(req.amount)
+        .bind(date)
+        .bind(&req.method)
+        .bind(if req.amount.is_zero() { "VENDOR_CREDIT" } else { "BANK_TRANSFER" })
+        .bind(&req.reference)
+        .bind(req.account_id)
+        .bind(discount)
+        .bind(credit_amount)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        apply_vendor_credits(&mut tx, tenant_id, &bill, payment.id, date, &req.credits).await?;
+
+        let status = if settled == balance { "PAID" } else { "PARTIALLY_PAID" };
+        sqlx::query("UPDATE bills SET amount_paid = amount_paid + $1, discount_taken = discount_taken + $2, status = $3, updated_at = NOW() WHERE id = $4")
+            .bind(settled)
+            .bind(discount)
+            .bind(status)
+            .bind(bill_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        // Credits came off the balance and AP when they were recorded.
+        let paid = req.amount + discount;
+        adjust_supplier_balance(&mut tx, bill.supplier_id, -paid).await?;
+
+        if paid > Decimal::ZERO {
+            let memo = format!("Payment of bill {}", bill.bill_number);
+            let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "PAY", payment.date).await?;
+            post_journal_entry(&mut tx, tenant_id, &entry_number, payment.date, &memo, &[
+                LedgerLine::debit("2000", paid, &memo),
+                LedgerLine::credit(&bank_account, req.amount, &memo),
+                LedgerLine::credit("4950", discount, &memo),
+            ]).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(payment)
+    }
+}
+
+/// Tenants without a settings row allow no quantity overage and 2% on price.
+async fn load_settings(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid) -> Result<PurchaseMatchSettings, Error> {
+    let settings = sqlx::query_as::<_, PurchaseMatchSettings>("SELECT * FROM purchase_match_settings WHERE tenant_id = $1")
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(settings.unwrap_or(PurchaseMatchSettings {
+        tenant_id,
+        quantity_tolerance_percent: Decimal::ZERO,
+        price_tolerance_percent: Decimal::TWO,
+        updated_at: Utc::now(),
+    }))
+}
+
+/// Moves the billed lines out of GRNI into AP. Receipts accrued GRNI at receipt cost, so any
+/// difference to the billed amount is purchase price variance. Quantity billed beyond what
+/// was received has no accrual to clear and lands in PPV as well.
+async fn post_matched_bill(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    bill_id: Uuid,
+    status: MatchStatus,
+    approved_by: Option<Uuid>,
+) -> Result<(), Error> {
+    let accrued: Decimal = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(ROUND(bl.matched_quantity * rl.unit_cost, 2)), 0)
+        FROM bill_lines bl
+        JOIN purchase_receipt_lines rl ON rl.id = bl.receipt_line_id
+        WHERE bl.bill_id = $1
+        "#
+    )
+    .bind(bill_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let (bill_number, total_amount, date, supplier_id): (String, Decimal, chrono::NaiveDate, Uuid) = sqlx::query_as(
+        "SELECT bill_number, total_amount, date, supplier_id FROM bills WHERE id = $1"
+    )
+    .bind(bill_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let memo = format!("Bill {}", bill_number);
+    let variance = total_amount - accrued;
+    let entry_number = next_document_number(tx, tenant_id, "journal_entries", "entry_number", "BIL", date).await?;
+    let journal_entry_id = post_journal_entry(tx, tenant_id, &entry_number, date, &memo, &[
+        LedgerLine::debit("2050", accrued, &memo),
+        LedgerLine::debit("5400", variance.max(Decimal::ZERO), &memo),
+        LedgerLine::credit("5400", (-variance).max(Decimal::ZERO), &memo),
+        LedgerLine::credit("2000", total_amount, &memo),
+    ]).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE bills SET match_status = $1, match_approved_by = $2,
+            match_approved_at = CASE WHEN $2::uuid IS NULL THEN NULL ELSE NOW() END,
+            journal_entry_id = $3, updated_at = NOW()
+        WHERE id = $4
+        "#
+    )
+    .bind(status)
+    .bind(approved_by)
+    .bind(journal_entry_id)
+    .bind(bill_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    adjust_supplier_balance(tx, supplier_id, total_amount).await?;
+
+    Ok(())
+}
@@ -0,0 +1,623 @@
+use smart_erp_core::models::compliance::*;
+use smart_erp_core::models::inventory::{AdjustmentReason, CreateInventoryTransaction, TransactionType};
+use smart_erp_core::error::Error;
+use crate::db::inventory::insert_transaction;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresComplianceRepository {
+    pool: PgPool,
+}
+
+const CONSUMPTION_QUERY: &str = r#"
+    SELECT c.id, c.lot_id, l.lot_number, c.product_id, p.sku, c.work_order_id, c.quantity, c.consumed_date,
+           l.sds_reference, l.hazard_class
+    FROM chemical_consumptions c
+    JOIN chemical_lots l ON l.id = c.lot_id
+    JOIN products p ON p.id = c.product_id
+"#;
+
+impl PostgresComplianceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_substances(&self, tenant_id: Uuid) -> Result<Vec<RestrictedSubstance>, Error> {
+        sqlx::query_as::<_, RestrictedSubstance>("SELECT * FROM restricted_substances WHERE tenant_id = $1 ORDER BY name")
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_substance(&self, tenant_id: Uuid, req: CreateRestrictedSubstance) -> Result<RestrictedSubstance, Error> {
+        if req.name.trim().is_empty() || req.reporting_threshold.is_some_and(|t| t < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Name the substance; the reporting threshold cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, RestrictedSubstance>(
+            r#"
+            INSERT INTO restricted_substances (tenant_id, name, cas_number, regulation, reporting_threshold)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.name.trim())
+        .bind(req.cas_number)
+        .bind(req.regulation)
+        .bind(req.reporting_threshold)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn list_chemicals(&self, tenant_id: Uuid) -> Result<Vec<Chemical>, Error> {
+        let mut chemicals = sqlx::query_as::<_, Chemical>(
+            r#"
+            SELECT p.id AS product_id, p.sku, p.name, p.stock_quantity,
+                   COALESCE(SUM(l.remaining_quantity) FILTER (WHERE l.expiry_date IS NULL OR l.expiry_date >= CURRENT_DATE), 0) AS lot_quantity,
+                   COALESCE(SUM(l.remaining_quantity) FILTER (WHERE l.expiry_date < CURRENT_DATE), 0) AS expired_quantity
+            FROM products p
+            LEFT JOIN chemical_lots l ON l.product_id = p.id
+            WHERE p.tenant_id = $1 AND p.is_chemical
+            GROUP BY p.id
+            ORDER BY p.sku
+            "#
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for chemical in &mut chemicals {
+            chemical.substances = load_substances(&self.pool, chemical.product_id).await?;
+        }
+        Ok(chemicals)
+    }
+
+    /// Makes the product a lot-tracked chemical. From then on it can only be consumed from
+    /// registered lots that have not expired.
+    pub async fn set_chemical(&self, tenant_id: Uuid, product_id: Uuid, req: SetChemical) -> Result<Vec<ChemicalSubstance>, Error> {
+        req.validate()?;
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let updated = sqlx::query("UPDATE products SET is_chemical = TRUE, updated_at = NOW() WHERE id = $1 AND tenant_id = $2")
+            .bind(product_id)
+            .bind(tenant_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if updated.rows_affected() == 0 {
+            return Err(Error::NotFound("Product not found".to_string()));
+        }
+
+        sqlx::query("DELETE FROM chemical_substances WHERE product_id = $1")
+            .bind(product_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        for share in &req.substances {
+            let inserted = sqlx::query(
+                r#"
+                INSERT INTO chemical_substances (product_id, substance_id, concentration)
+                SELECT $1, id, $3 FROM restricted_substances WHERE id = $2 AND tenant_id = $4
+                "#
+            )
+            .bind(product_id)
+            .bind(share.substance_id)
+            .bind(share.concentration)
+            .bind(tenant_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+            if inserted.rows_affected() == 0 {
+                return Err(Error::NotFound("Restricted substance not found".to_string()));
+            }
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        load_substances(&self.pool, product_id).await
+    }
+
+    pub async fn list_lots(&self, tenant_id: Uuid, query: ChemicalLotQuery) -> Result<Vec<ChemicalLot>, Error> {
+        sqlx::query_as::<_, ChemicalLot>(
+            r#"
+            SELECT * FROM chemical_lots
+            WHERE tenant_id = $1
+              AND ($2::uuid IS NULL OR product_id = $2)
+              AND ($3::boolean IS NULL OR (expiry_date IS NOT NULL AND expiry_date < CURRENT_DATE) = $3)
+              AND ($4 OR remaining_quantity > 0)
+            ORDER BY expiry_date NULLS LAST, received_date, lot_number
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.product_id)
+        .bind(query.expired)
+        .bind(query.include_empty)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_lot(&self, tenant_id: Uuid, lot_id: Uuid) -> Result<ChemicalLotDetail, Error> {
+        let lot = sqlx::query_as::<_, ChemicalLot>("SELECT * FROM chemical_lots WHERE id = $1 AND tenant_id = $2")
+            .bind(lot_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Chemical lot not found".to_string()))?;
+
+        let consumptions = sqlx::query_as::<_, ChemicalConsumption>(&format!(
+            "{} WHERE c.lot_id = $1 ORDER BY c.created_at",
+            CONSUMPTION_QUERY
+        ))
+        .bind(lot_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(ChemicalLotDetail { lot, consumptions })
+    }
+
+    pub async fn create_lot(&self, tenant_id: Uuid, req: CreateChemicalLot) -> Result<ChemicalLot, Error> {
+        if req.quantity <= Decimal::ZERO {
+            return Err(Error::BusinessRule("Lot quantity must be positive".to_string()));
+        }
+        if req.lot_number.trim().is_empty() || req.sds_reference.trim().is_empty() || req.hazard_class.trim().is_empty() {
+            return Err(Error::BusinessRule("Lot number, SDS reference and hazard class are required".to_string()));
+        }
+        let received_date = req.received_date.unwrap_or_else(|| Utc::now().date_naive());
+        if req.expiry_date.is_some_and(|d| d < received_date) {
+            return Err(Error::BusinessRule("Lot expires before it was received".to_string()));
+        }
+
+        let is_chemical: bool = sqlx::query_scalar("SELECT is_chemical FROM products WHERE id = $1 AND tenant_id = $2")
+            .bind(req.product_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Product not found".to_string()))?;
+        if !is_chemical {
+            return Err(Error::BusinessRule("Product is not set up as a chemical".to_string()));
+        }
+
+        sqlx::query_as::<_, ChemicalLot>(
+            r#"
+            INSERT INTO chemical_lots (tenant_id, product_id, lot_number, supplier_id, sds_reference, hazard_class,
+                                       received_date, expiry_date, quantity, remaining_quantity, notes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9, $10)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.product_id)
+        .bind(req.lot_number.trim())
+        .bind(req.supplier_id)
+        .bind(req.sds_reference.trim())
+        .bind(req.hazard_class.trim())
+        .bind(received_date)
+        .bind(req.expiry_date)
+        .bind(req.quantity)
+        .bind(req.notes)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn work_order_chemicals(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<Vec<ChemicalConsumption>, Error> {
+        sqlx::query_as::<_, ChemicalConsumption>(&format!(
+            "{} WHERE c.tenant_id = $1 AND c.work_order_id = $2 ORDER BY c.created_at",
+            CONSUMPTION_QUERY
+        ))
+        .bind(tenant_id)
+        .bind(work_order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn list_disposals(&self, tenant_id: Uuid, query: WasteDisposalQuery) -> Result<Vec<WasteDisposal>, Error> {
+        sqlx::query_as::<_, WasteDisposal>(
+            r#"
+            SELECT * FROM waste_disposals
+            WHERE tenant_id = $1
+              AND ($2::date IS NULL OR disposal_date >= $2)
+              AND ($3::date IS NULL OR disposal_date <= $3)
+              AND ($4::varchar IS NULL OR waste_type = $4)
+            ORDER BY disposal_date DESC, disposal_number DESC
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.waste_type)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_disposal(&self, tenant_id: Uuid, disposal_id: Uuid) -> Result<WasteDisposalDetail, Error> {
+        let disposal = sqlx::query_as::<_, WasteDisposal>("SELECT * FROM waste_disposals WHERE id = $1 AND tenant_id = $2")
+            .bind(disposal_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Waste disposal not found".to_string()))?;
+
+        let substances = sqlx::query_as::<_, DisposedSubstance>(
+            r#"
+            SELECT d.substance_id, s.name, d.quantity
+            FROM waste_disposal_substances d
+            JOIN restricted_substances s ON s.id = d.substance_id
+            WHERE d.disposal_id = $1
+            ORDER BY s.name
+            "#
+        )
+        .bind(disposal_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(WasteDisposalDetail { disposal, substances })
+    }
+
+    /// Records waste leaving site under its manifest. A chemical lot disposed of is written off
+    /// stock at cost to chemical disposal (7600).
+    pub async fn create_disposal(&self, tenant_id: Uuid, req: CreateWasteDisposal, created_by: Uuid) -> Result<WasteDisposalDetail, Error> {
+        req.validate()?;
+        let disposal_date = req.disposal_date.unwrap_or_else(|| Utc::now().date_naive());
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let disposal_number = next_document_number(&mut tx, tenant_id, "waste_disposals", "disposal_number", "WD", disposal_date).await?;
+
+        let mut unit = req.unit.clone();
+        let mut substances = req.substances.clone();
+        let mut transaction_id = None;
+        if let Some(lot_id) = req.lot_id {
+            let lot = lock_lot(&mut tx, tenant_id, lot_id).await?;
+            if req.quantity > lot.remaining_quantity {
+                return Err(Error::BusinessRule(format!(
+                    "Lot {} only has {} remaining",
+                    lot.lot_number, lot.remaining_quantity
+                )));
+            }
+            draw_lot(&mut tx, lot.id, req.quantity).await?;
+
+            let uom: String = sqlx::query_scalar("SELECT unit_of_measure FROM products WHERE id = $1")
+                .bind(lot.product_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            unit = unit.or(Some(uom));
+            if substances.is_empty() {
+                substances = load_substances(&mut *tx, lot.product_id)
+                    .await?
+                    .into_iter()
+                    .map(|s| SubstanceQuantity {
+                        substance_id: s.substance_id,
+                        quantity: (req.quantity * s.concentration / Decimal::ONE_HUNDRED).round_dp(4),
+                    })
+                    .collect();
+            }
+
+            let record = insert_transaction(&mut tx, tenant_id, CreateInventoryTransaction {
+                product_id: lot.product_id,
+                quantity: -req.quantity,
+                transaction_type: TransactionType::Adjustment,
+                reference_id: Some(lot.id),
+                notes: Some(format!("Lot {} disposed on {}", lot.lot_number, disposal_number)),
+                reason_code: Some(if lot.is_expired(disposal_date) { AdjustmentReason::Expired } else { AdjustmentReason::Other }),
+                unit_cost: None,
+            }).await?;
+            transaction_id = Some(record.id);
+
+            let value = record.total_cost.unwrap_or_default();
+            let memo = format!("Chemical disposal {} lot {}", disposal_number, lot.lot_number);
+            let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "WST", disposal_date).await?;
+            post_journal_entry(&mut tx, tenant_id, &entry_number, disposal_date, &memo, &[
+                LedgerLine::debit("7600", value, &memo),
+                LedgerLine::credit("1300", value, &memo),
+            ]).await?;
+        }
+
+        let disposal = sqlx::query_as::<_, WasteDisposal>(
+            r#"
+            INSERT INTO waste_disposals (tenant_id, disposal_number, waste_type, description, quantity, unit, disposal_date, manifest_number,
+                                         carrier, facility, disposal_cost, work_order_id, lot_id, transaction_id, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(&disposal_number)
+        .bind(req.waste_type)
+        .bind(req.description.trim())
+        .bind(req.quantity)
+        .bind(unit.unwrap_or_else(|| "KG".to_string()))
+        .bind(disposal_date)
+        .bind(req.manifest_number)
+        .bind(req.carrier)
+        .bind(req.facility)
+        .bind(req.disposal_cost)
+        .bind(req.work_order_id)
+        .bind(req.lot_id)
+        .bind(transaction_id)
+        .bind(req.notes)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for substance in substances {
+            let inserted = sqlx::query(
+                r#"
+                INSERT INTO waste_disposal_substances (disposal_id, substance_id, quantity)
+                SELECT $1, id, $3 FROM restricted_substances WHERE id = $2 AND tenant_id = $4
+                "#
+            )
+            .bind(disposal.id)
+            .bind(substance.substance_id)
+            .bind(substance.quantity)
+            .bind(tenant_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+            if inserted.rows_affected() == 0 {
+                return Err(Error::NotFound("Restricted substance not found".to_string()));
+            }
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_disposal(tenant_id, disposal.id).await
+    }
+
+    /// Restricted substances used and disposed of in the period, waste by type and expired
+    /// lots still in stock.
+    pub async fn compliance_report(&self, tenant_id: Uuid, query: ComplianceReportQuery) -> Result<ComplianceReport, Error> {
+        if query.from > query.to {
+            return Err(Error::BusinessRule("'from' date must not be after 'to' date".to_string()));
+        }
+
+        let mut substances = sqlx::query_as::<_, SubstanceReportLine>(
+            r#"
+            SELECT s.id AS substance_id, s.name, s.cas_number, s.regulation, s.reporting_threshold,
+                   COALESCE((
+                       SELECT SUM(c.quantity * cs.concentration / 100)
+                       FROM chemical_consumptions c
+                       JOIN chemical_substances cs ON cs.product_id = c.product_id AND cs.substance_id = s.id
+                       WHERE c.tenant_id = s.tenant_id AND c.consumed_date BETWEEN $2 AND $3
+                   ), 0)::DECIMAL(14, 4) AS used_quantity,
+                   COALESCE((
+                       SELECT SUM(ds.quantity)
+                       FROM waste_disposal_substances ds
+                       JOIN waste_disposals d ON d.id = ds.disposal_id
+                       WHERE ds.substance_id = s.id AND d.disposal_date BETWEEN $2 AND $3
+                   ), 0)::DECIMAL(14, 4) AS disposed_quantity
+            FROM restricted_substances s
+            WHERE s.tenant_id = $1
+            ORDER BY s.name
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.from)
+        .bind(query.to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+        for line in &mut substances {
+            line.over_threshold = line.reporting_threshold.is_some_and(|t| line.used_quantity > t);
+        }
+
+        let waste = sqlx::query_as::<_, WasteReportLine>(
+            r#"
+            SELECT waste_type, unit, COUNT(*) AS disposals, SUM(quantity) AS quantity, COALESCE(SUM(disposal_cost), 0) AS disposal_cost
+            FROM waste_disposals
+            WHERE tenant_id = $1 AND disposal_date BETWEEN $2 AND $3
+            GROUP BY waste_type, unit
+            ORDER BY waste_type, unit
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.from)
+        .bind(query.to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let expired_lots = sqlx::query_as::<_, ChemicalLot>(
+            r#"
+            SELECT * FROM chemical_lots
+            WHERE tenant_id = $1 AND expiry_date < $2 AND remaining_quantity > 0
+            ORDER BY expiry_date, lot_number
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(ComplianceReport { from: query.from, to: query.to, substances, waste, expired_lots })
+    }
+}
+
+async fn load_substances<'e, E>(executor: E, product_id: Uuid) -> Result<Vec<ChemicalSubstance>, Error>
+where
+    E: sqlx::Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, ChemicalSubstance>(
+        r#"
+        SELECT cs.substance_id, s.name, s.cas_number, cs.concentration
+        FROM chemical_substances cs
+        JOIN restricted_substances s ON s.id = cs.substance_id
+        WHERE cs.product_id = $1
+        ORDER BY s.name
+        "#
+    )
+    .bind(product_id)
+    .fetch_all(executor)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+async fn lock_lot(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, lot_id: Uuid) -> Result<ChemicalLot, Error> {
+    sqlx::query_as::<_, ChemicalLot>("SELECT * FROM chemical_lots WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+        .bind(lot_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Chemical lot not found".to_string()))
+}
+
+async fn draw_lot(tx: &mut Transaction<'_, Postgres>, lot_id: Uuid, quantity: Decimal) -> Result<(), Error> {
+    sqlx::query("UPDATE chemical_lots SET remaining_quantity = remaining_quantity - $1 WHERE id = $2")
+        .bind(quantity)
+        .bind(lot_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+async fn record_consumption(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, lot: &ChemicalLot, work_order_id: Uuid, quantity: Decimal, date: NaiveDate) -> Result<(), Error> {
+    draw_lot(tx, lot.id, quantity).await?;
+    sqlx::query(
+        r#"
+        INSERT INTO chemical_consumptions (tenant_id, lot_id, product_id, work_order_id, quantity, consumed_date)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#
+    )
+    .bind(tenant_id)
+    .bind(lot.id)
+    .bind(lot.product_id)
+    .bind(work_order_id)
+    .bind(quantity)
+    .bind(date)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+async fn is_chemical(tx: &mut Transaction<'_, Postgres>, product_id: Uuid) -> Result<bool, Error> {
+    sqlx::query_scalar("SELECT is_chemical FROM products WHERE id = $1")
+        .bind(product_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+}
+
+/// Draws chemical issued to a work order from its lots: the given lot, or unexpired lots
+/// earliest expiry first. Expired lots are never consumed. Products that are not chemicals
+/// are left alone.
+pub async fn consume_lots(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order_id: Uuid, product_id: Uuid, quantity: Decimal, lot_id: Option<Uuid>) -> Result<(), Error> {
+    if !is_chemical(tx, product_id).await? {
+        return Ok(());
+    }
+    let today = Utc::now().date_naive();
+
+    if let Some(lot_id) = lot_id {
+        let lot = lock_lot(tx, tenant_id, lot_id).await?;
+        if lot.product_id != product_id {
+            return Err(Error::BusinessRule(format!("Lot {} is a different chemical", lot.lot_number)));
+        }
+        if let Some(expiry) = lot.expiry_date.filter(|_| lot.is_expired(today)) {
+            return Err(Error::BusinessRule(format!("Lot {} expired on {}", lot.lot_number, expiry)));
+        }
+        if lot.remaining_quantity < quantity {
+            return Err(Error::BusinessRule(format!("Lot {} only has {} remaining", lot.lot_number, lot.remaining_quantity)));
+        }
+        return record_consumption(tx, tenant_id, &lot, work_order_id, quantity, today).await;
+    }
+
+    let lots = sqlx::query_as::<_, ChemicalLot>(
+        r#"
+        SELECT * FROM chemical_lots
+        WHERE tenant_id = $1 AND product_id = $2 AND remaining_quantity > 0
+          AND (expiry_date IS NULL OR expiry_date >= $3)
+        ORDER BY expiry_date NULLS LAST, received_date, created_at
+        FOR UPDATE
+        "#
+    )
+    .bind(tenant_id)
+    .bind(product_id)
+    .bind(today)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let available: Decimal = lots.iter().map(|l| l.remaining_quantity).sum();
+    if available < quantity {
+        return Err(Error::BusinessRule(format!(
+            "Only {} left in unexpired lots, {} required; expired lots cannot be consumed",
+            available, quantity
+        )));
+    }
+
+    let mut outstanding = quantity;
+    for lot in lots {
+        if outstanding <= Decimal::ZERO {
+            break;
+        }
+        let take = outstanding.min(lot.remaining_quantity);
+        record_consumption(tx, tenant_id, &lot, work_order_id, take, today).await?;
+        outstanding -= take;
+    }
+    Ok(())
+}
+
+/// Puts chemical returned from a work order back into the lots it came from, the given lot
+/// or the most recently drawn first. Anything issued before the product was lot-tracked goes
+/// back as untracked stock.
+pub async fn return_lots(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order_id: Uuid, product_id: Uuid, quantity: Decimal, lot_id: Option<Uuid>) -> Result<(), Error> {
+    if !is_chemical(tx, product_id).await? {
+        return Ok(());
+    }
+
+    let drawn: Vec<(Uuid, Decimal)> = sqlx::query_as(
+        r#"
+        SELECT lot_id, SUM(quantity)
+        FROM chemical_consumptions
+        WHERE work_order_id = $1 AND product_id = $2 AND ($3::uuid IS NULL OR lot_id = $3)
+        GROUP BY lot_id
+        HAVING SUM(quantity) > 0
+        ORDER BY MAX(created_at) DESC
+        "#
+    )
+    .bind(work_order_id)
+    .bind(product_id)
+    .bind(lot_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let net: Decimal = drawn.iter().map(|(_, q)| *q).sum();
+    if lot_id.is_some() && net < quantity {
+        return Err(Error::BusinessRule(format!("Only {} was drawn from the lot for this work order", net)));
+    }
+
+    let today = Utc::now().date_naive();
+    let mut outstanding = quantity;
+    for (lot_id, consumed) in drawn {
+        if outstanding <= Decimal::ZERO {
+            break;
+        }
+        let lot = lock_lot(tx, tenant_id, lot_id).await?;
+        let back = outstanding.min(consumed);
+        record_consumption(tx, tenant_id, &lot, work_order_id, -back, today).await?;
+        outstanding -= back;
+    }
+    Ok(())
+}
@@ -0,0 +1,397 @@
+use smart_erp_core::models::costing::*;
+use smart_erp_core::models::inventory::InventoryTransaction;
+use smart_erp_core::error::Error;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+const LAYER_COLUMNS: &str = "id, tenant_id, product_id, transaction_id, received_at, original_quantity, remaining_quantity, unit_cost";
+
+pub struct PostgresCostingRepository {
+    pool: PgPool,
+}
+
+impl PostgresCostingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_settings(&self, tenant_id: Uuid) -> Result<InventorySettings, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let settings = load_settings(&mut tx, tenant_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(settings)
+    }
+
+    /// Switches the tenant's costing method. Average and standard costs are seeded from the
+    /// current layers so inventory value carries over unchanged where possible.
+    pub async fn update_settings(&self, tenant_id: Uuid, req: UpdateInventorySettings) -> Result<InventorySettings, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let settings = sqlx::query_as::<_, InventorySettings>(
+            r#"
+            INSERT INTO inventory_settings (tenant_id, costing_method) VALUES ($1, $2)
+            ON CONFLICT (tenant_id) DO UPDATE SET costing_method = EXCLUDED.costing_method, updated_at = NOW()
+            RETURNING tenant_id, costing_method, updated_at
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.costing_method)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        match req.costing_method {
+            CostingMethod::MovingAverage => {
+                sqlx::query(
+                    r#"
+                    UPDATE products p SET average_cost = l.value / l.qty, updated_at = NOW()
+                    FROM (
+                        SELECT product_id, SUM(remaining_quantity * unit_cost) AS value, SUM(remaining_quantity) AS qty
+                        FROM cost_layers WHERE tenant_id = $1 AND remaining_quantity > 0 GROUP BY product_id
+                    ) l
+                    WHERE p.id = l.product_id
+                    "#
+                )
+                .bind(tenant_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            }
+            CostingMethod::Standard => {
+                sqlx::query("UPDATE products SET standard_cost = cost_price WHERE tenant_id = $1 AND standard_cost = 0")
+                    .bind(tenant_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| Error::Database(e.to_string()))?;
+            }
+            CostingMethod::Fifo => {}
+        }
+
+        let product_ids: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM products WHERE tenant_id = $1")
+            .bind(tenant_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        for product_id in product_ids {
+            refresh_cost_price(&mut tx, product_id, req.costing_method).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(settings)
+    }
+
+    /// Sets a product's standard cost. Under standard costing the on-hand quantity is
+    /// revalued and the difference posted to Purchase Price Variance.
+    pub async fn update_standard_cost(&self, tenant_id: Uuid, product_id: Uuid, req: UpdateStandardCost) -> Result<ValuationLine, Error> {
+        if req.standard_cost < Decimal::ZERO {
+            return Err(Error::BusinessRule("Standard cost cannot be negative".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let method = load_settings(&mut tx, tenant_id).await?.costing_method;
+
+        let (sku, stock, old_cost): (String, Decimal, Decimal) = sqlx::query_as(
+            "SELECT sku, stock_quantity, standard_cost FROM products WHERE id = $1 AND tenant_id = $2 FOR UPDATE"
+        )
+        .bind(product_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+        sqlx::query("UPDATE products SET standard_cost = $1, updated_at = NOW() WHERE id = $2")
+            .bind(req.standard_cost)
+            .bind(product_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        if method == CostingMethod::Standard {
+            refresh_cost_price(&mut tx, product_id, method).await?;
+
+            let revaluation = (stock.max(Decimal::ZERO) * (req.standard_cost - old_cost)).round_dp(2);
+            let memo = format!("Standard cost revaluation {}", sku);
+            let lines = if revaluation > Decimal::ZERO {
+                vec![LedgerLine::debit("1300", revaluation, &memo), LedgerLine::credit("5400", revaluation, &memo)]
+            } else {
+                vec![LedgerLine::debit("5400", -revaluation, &memo), LedgerLine::credit("1300", -revaluation, &memo)]
+            };
+            let date = Utc::now().date_naive();
+            let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "REV", date).await?;
+            post_journal_entry(&mut tx, tenant_id, &entry_number, date, &memo, &lines).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let valuation = self.valuation_lines(tenant_id, Some(product_id), method).await?;
+        valuation.into_iter().next().ok_or(Error::NotFound("Product not found".to_string()))
+    }
+
+    pub async fn list_layers(&self, tenant_id: Uuid, product_id: Uuid) -> Result<Vec<CostLayer>, Error> {
+        sqlx::query_as::<_, CostLayer>(&format!(
+            "SELECT {} FROM cost_layers WHERE tenant_id = $1 AND product_id = $2 ORDER BY received_at, id",
+            LAYER_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(product_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn valuation(&self, tenant_id: Uuid) -> Result<InventoryValuation, Error> {
+        let method = self.get_settings(tenant_id).await?.costing_method;
+        let lines = self.valuation_lines(tenant_id, None, method).await?;
+        let total_value = lines.iter().map(|l| l.value).sum();
+
+        Ok(InventoryValuation { costing_method: method, lines, total_value })
+    }
+
+    async fn valuation_lines(&self, tenant_id: Uuid, product_id: Option<Uuid>, method: CostingMethod) -> Result<Vec<ValuationLine>, Error> {
+        let lines = sqlx::query_as::<_, ValuationLine>(
+            r#"
+            SELECT p.id AS product_id, p.sku, p.name, p.stock_quantity AS quantity,
+                   COALESCE(l.qty, 0) AS layer_quantity, COALESCE(l.value, 0) AS layer_value,
+                   COALESCE(NULLIF(p.average_cost, 0), p.cost_price) AS average_cost,
+                   COALESCE(NULLIF(p.standard_cost, 0), p.cost_price) AS standard_cost
+            FROM products p
+            LEFT JOIN LATERAL (
+                SELECT SUM(remaining_quantity) AS qty, SUM(remaining_quantity * unit_cost) AS value
+                FROM cost_layers WHERE product_id = p.id AND remaining_quantity > 0
+            ) l ON TRUE
+            WHERE p.tenant_id = $1 AND p.item_type IN ('INVENTORY', 'ASSEMBLY') AND ($2::uuid IS NULL OR p.id = $2)
+            ORDER BY p.name
+            "#
+        )
+        .bind(tenant_id)
+        .bind(product_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(lines.into_iter().map(|l| l.valued(method)).collect())
+    }
+}
+
+/// Tenants without a settings row cost with FIFO.
+pub async fn load_settings(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid) -> Result<InventorySettings, Error> {
+    let settings = sqlx::query_as::<_, InventorySettings>(
+        "SELECT tenant_id, costing_method, updated_at FROM inventory_settings WHERE tenant_id = $1"
+    )
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(settings.unwrap_or(InventorySettings { tenant_id, costing_method: CostingMethod::Fifo, updated_at: Utc::now() }))
+}
+
+/// Values an inventory transaction that has just been inserted: receipts open a cost layer,
+/// less any stock already issued beyond the layers, and issues draw layers down. Must run before the product's stock quantity is updated.
+/// Returns the transaction with `unit_cost` and `total_cost` filled in.
+pub async fn apply_cost(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    record: &InventoryTransaction,
+    receipt_cost: Option<Decimal>,
+) -> Result<InventoryTransaction, Error> {
+    let method = load_settings(tx, tenant_id).await?.costing_method;
+
+    let (stock, average_cost, standard_cost, cost_price): (Decimal, Decimal, Decimal, Decimal) = sqlx::query_as(
+        r#"
+        SELECT stock_quantity, COALESCE(NULLIF(average_cost, 0), cost_price), COALESCE(NULLIF(standard_cost, 0), cost_price), cost_price
+        FROM products WHERE id = $1 AND tenant_id = $2 FOR UPDATE
+        "#
+    )
+    .bind(record.product_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+    // Stock that predates costing (seeded or imported) gets an opening layer at cost price
+    // so it is issued before anything received later.
+    sqlx::query(
+        r#"
+        INSERT INTO cost_layers (tenant_id, product_id, received_at, original_quantity, remaining_quantity, unit_cost)
+        SELECT $1, $2, TIMESTAMPTZ 'epoch', $3 - l.qty, $3 - l.qty, $4
+        FROM (SELECT COALESCE(SUM(remaining_quantity), 0) AS qty FROM cost_layers WHERE product_id = $2 AND remaining_quantity > 0) l
+        WHERE $3 > l.qty
+        "#
+    )
+    .bind(tenant_id)
+    .bind(record.product_id)
+    .bind(stock)
+    .bind(cost_price)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let (unit_cost, total_cost) = if record.quantity > Decimal::ZERO {
+        let actual = receipt_cost.unwrap_or(cost_price);
+        let unit_cost = receipt_unit_cost(method, actual, standard_cost);
+
+        let layered: Decimal = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(remaining_quantity), 0) FROM cost_layers WHERE product_id = $1 AND remaining_quantity > 0"
+        )
+        .bind(record.product_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO cost_layers (tenant_id, product_id, transaction_id, original_quantity, remaining_quantity, unit_cost)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(tenant_id)
+        .bind(record.product_id)
+        .bind(record.id)
+        .bind(record.quantity)
+        .bind(receipt_layer_quantity(stock, layered, record.quantity))
+        .bind(unit_cost)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        sqlx::query("UPDATE products SET average_cost = $1 WHERE id = $2")
+            .bind(moving_average(stock, average_cost, record.quantity, unit_cost))
+            .bind(record.product_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        (unit_cost, (record.quantity * unit_cost).round_dp(2))
+    } else {
+        let layers = sqlx::query_as::<_, CostLayer>(&format!(
+            "SELECT {} FROM cost_layers WHERE product_id = $1 AND remaining_quantity > 0 ORDER BY received_at, id FOR UPDATE",
+            LAYER_COLUMNS
+        ))
+        .bind(record.product_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let issue = cost_issue(method, &layers, -record.quantity, average_cost, standard_cost);
+
+        for c in &issue.consumptions {
+            sqlx::query("UPDATE cost_layers SET remaining_quantity = remaining_quantity - $1 WHERE id = $2")
+                .bind(c.quantity)
+                .bind(c.layer_id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+
+            sqlx::query("INSERT INTO cost_layer_consumptions (layer_id, transaction_id, quantity, unit_cost) VALUES ($1, $2, $3, $4)")
+                .bind(c.layer_id)
+                .bind(record.id)
+                .bind(c.quantity)
+                .bind(c.unit_cost)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        (issue.unit_cost(), issue.total_cost)
+    };
+
+    refresh_cost_price(tx, record.product_id, method).await?;
+
+    sqlx::query_as::<_, InventoryTransaction>(
+        r#"
+        UPDATE inventory_transactions SET unit_cost = $2, total_cost = $3 WHERE id = $1
+        RETURNING id, tenant_id, product_id, quantity, transaction_type, reference_id, notes, reason_code, unit_cost, total_cost, created_at
+        "#
+    )
+    .bind(record.id)
+    .bind(unit_cost)
+    .bind(total_cost)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+/// Adds a cost (e.g. freight or duty) to the layer opened by a receipt. Only the share still
+/// on hand is capitalized; the rest relates to stock already issued and is returned as
+/// expensed. Standard costing never changes layer cost, so everything is expensed.
+/// Returns `(capitalized, expensed)`.
+pub async fn capitalize_to_layer(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    transaction_id: Uuid,
+    amount: Decimal,
+) -> Result<(Decimal, Decimal), Error> {
+    let method = load_settings(tx, tenant_id).await?.costing_method;
+
+    let layer = sqlx::query_as::<_, CostLayer>(&format!(
+        "SELECT {} FROM cost_layers WHERE tenant_id = $1 AND transaction_id = $2 FOR UPDATE",
+        LAYER_COLUMNS
+    ))
+    .bind(tenant_id)
+    .bind(transaction_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let layer = match layer {
+        Some(l) if method != CostingMethod::Standard && l.original_quantity > Decimal::ZERO && l.remaining_quantity > Decimal::ZERO => l,
+        _ => return Ok((Decimal::ZERO, amount)),
+    };
+
+    let capitalized = (amount * layer.remaining_quantity / layer.original_quantity).round_dp(2);
+
+    sqlx::query("UPDATE cost_layers SET unit_cost = unit_cost + $1 WHERE id = $2")
+        .bind((capitalized / layer.remaining_quantity).round_dp(4))
+        .bind(layer.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        UPDATE products SET average_cost = COALESCE(NULLIF(average_cost, 0), cost_price) + $1 / stock_quantity, updated_at = NOW()
+        WHERE id = $2 AND stock_quantity > 0
+        "#
+    )
+    .bind(capitalized)
+    .bind(layer.product_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    refresh_cost_price(tx, layer.product_id, method).await?;
+
+    Ok((capitalized, amount - capitalized))
+}
+
+/// Keeps `products.cost_price` equal to the current unit cost under the tenant's method.
+async fn refresh_cost_price(tx: &mut Transaction<'_, Postgres>, product_id: Uuid, method: CostingMethod) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        UPDATE products p SET cost_price = ROUND(CASE $2
+            WHEN 'MOVING_AVERAGE' THEN COALESCE(NULLIF(p.average_cost, 0), p.cost_price)
+            WHEN 'STANDARD' THEN COALESCE(NULLIF(p.standard_cost, 0), p.cost_price)
+            ELSE COALESCE((
+                SELECT SUM(l.remaining_quantity * l.unit_cost) / NULLIF(SUM(l.remaining_quantity), 0)
+                FROM cost_layers l WHERE l.product_id = p.id AND l.remaining_quantity > 0
+            ), p.cost_price)
+        END, 2)
+        WHERE p.id = $1
+        "#
+    )
+    .bind(product_id)
+    .bind(method.to_string())
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(())
+}
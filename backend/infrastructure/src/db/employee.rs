@@ -1,6 +1,7 @@
-use smart_erp_core::models::employee::{Employee, CreateEmployee};
+use smart_erp_core::models::employee::{Employee, CreateEmployee, PayrollHours, PayrollHoursQuery, WeeklyHours};
 use smart_erp_core::error::Error;
-use sqlx::PgPool;
+use crate::db::address::{insert_address, insert_contact, Party};
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 pub struct PostgresEmployeeRepository {
@@ -41,10 +42,11 @@ impl PostgresEmployeeRepository {
     }
 
     pub async fn create_employee(&self, tenant_id: Uuid, employee: CreateEmployee) -> Result<Employee, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
         let record = sqlx::query_as::<_, Employee>(
             r#"
-            INSERT INTO employees (tenant_id, first_name, last_name, email, phone, address, ssn_last4, hire_date, department, job_title, pay_type, pay_rate)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, COALESCE($8, CURRENT_DATE), $9, $10, $11, $12)
+            INSERT INTO employees (tenant_id, first_name, last_name, email, phone, ssn_last4, hire_date, department, job_title, pay_type, pay_rate)
+            VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, CURRENT_DATE), $8, $9, $10, $11)
             RETURNING *
             "#
         )
@@ -53,19 +55,56 @@ impl PostgresEmployeeRepository {
         .bind(&employee.last_name)
         .bind(&employee.email)
         .bind(&employee.phone)
-        .bind(&employee.address)
         .bind(&employee.ssn_last4)
         .bind(employee.hire_date)
         .bind(&employee.department)
         .bind(&employee.job_title)
         .bind(&employee.pay_type)
         .bind(employee.pay_rate)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
+
+        let party = Party::Employee(record.id);
+        for address in employee.addresses {
+            insert_address(&mut tx, tenant_id, party, address).await?;
+        }
+        for contact in employee.contacts {
+            insert_contact(&mut tx, tenant_id, party, contact).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
         Ok(record)
     }
 
+    /// Hours booked on the shop floor in the period, split into regular and overtime for payroll.
+    pub async fn payroll_hours(&self, tenant_id: Uuid, query: PayrollHoursQuery) -> Result<Vec<PayrollHours>, Error> {
+        if query.from > query.to {
+            return Err(Error::BusinessRule("'from' date must not be after 'to' date".to_string()));
+        }
+
+        let weeks = sqlx::query_as::<_, WeeklyHours>(
+            r#"
+            SELECT e.id AS employee_id, e.first_name, e.last_name, e.pay_type,
+                   date_trunc('week', l.work_date)::date AS week_start,
+                   SUM(l.hours) AS hours, SUM(l.labor_cost) AS labor_cost
+            FROM labor_time_entries l
+            JOIN employees e ON e.id = l.employee_id
+            WHERE l.tenant_id = $1 AND l.work_date BETWEEN $2 AND $3
+            GROUP BY e.id, week_start
+            ORDER BY e.last_name, e.first_name, e.id, week_start
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.from)
+        .bind(query.to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(PayrollHours::from_weeks(weeks))
+    }
+
     pub async fn delete_employee(&self, tenant_id: Uuid, id: Uuid) -> Result<(), Error> {
         let result = sqlx::query(
             "DELETE FROM employees WHERE id = $1 AND tenant_id = $2"
@@ -1,8 +1,12 @@
 use async_trait::async_trait;
 use smart_erp_core::models::inventory::{
-    CreateInventoryTransaction, InventoryService, InventoryTransaction,
+    CreateInventoryTransaction, CreateStockAdjustment, InventoryService, InventoryTransaction, TransactionType,
 };
 use smart_erp_core::error::Error;
+use crate::db::costing::apply_cost;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use chrono::Utc;
 use rust_decimal::Decimal;
 use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
@@ -15,6 +19,105 @@ impl PostgresInventoryRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
+
+    /// Posts a reason-coded ADJUSTMENT and the matching Inventory Asset / Inventory Adjustments entry.
+    pub async fn adjust_stock(&self, tenant_id: Uuid, adjustment: CreateStockAdjustment) -> Result<InventoryTransaction, Error> {
+        if adjustment.quantity == Decimal::ZERO {
+            return Err(Error::BusinessRule("Adjustment quantity cannot be zero".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let sku: String = sqlx::query_scalar(
+            "SELECT sku FROM products WHERE id = $1 AND tenant_id = $2"
+        )
+        .bind(adjustment.product_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+        let record = insert_transaction(&mut tx, tenant_id, CreateInventoryTransaction {
+            product_id: adjustment.product_id,
+            quantity: adjustment.quantity,
+            transaction_type: TransactionType::Adjustment,
+            reference_id: None,
+            notes: adjustment.notes,
+            reason_code: Some(adjustment.reason_code.clone()),
+            unit_cost: None,
+        }).await?;
+
+        let value = record.total_cost.unwrap_or_default();
+        let memo = format!("Stock adjustment {} ({})", sku, adjustment.reason_code);
+        let lines = if adjustment.quantity > Decimal::ZERO {
+            vec![LedgerLine::debit("1300", value, &memo), LedgerLine::credit("5900", value, &memo)]
+        } else {
+            vec![LedgerLine::debit("5900", value, &memo), LedgerLine::credit("1300", value, &memo)]
+        };
+        let date = Utc::now().date_naive();
+        let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "ADJ", date).await?;
+        post_journal_entry(&mut tx, tenant_id, &entry_number, date, &memo, &lines).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(record)
+    }
+}
+
+/// Inserts an inventory transaction, values it against the cost layers and applies its
+/// signed quantity to on-hand stock.
+pub async fn insert_transaction(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    transaction: CreateInventoryTransaction,
+) -> Result<InventoryTransaction, Error> {
+    // Lock the product before the insert takes a key-share lock on it; upgrading that lock in
+    // `apply_cost` deadlocks concurrent movements of the same product.
+    sqlx::query("SELECT id FROM products WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+        .bind(transaction.product_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+    let record = sqlx::query_as::<_, InventoryTransaction>(
+        r#"
+        INSERT INTO inventory_transactions 
+        (tenant_id, product_id, quantity, transaction_type, reference_id, notes, reason_code)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, tenant_id, product_id, quantity, transaction_type, reference_id, notes, reason_code, unit_cost, total_cost, created_at
+        "#
+    )
+    .bind(tenant_id)
+    .bind(transaction.product_id)
+    .bind(transaction.quantity)
+    .bind(transaction.transaction_type)
+    .bind(transaction.reference_id)
+    .bind(transaction.notes)
+    .bind(transaction.reason_code)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let record = apply_cost(tx, tenant_id, &record, transaction.unit_cost).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE products 
+        SET stock_quantity = stock_quantity + $1, updated_at = NOW()
+        WHERE id = $2 AND tenant_id = $3
+        "#
+    )
+    .bind(transaction.quantity)
+    .bind(transaction.product_id)
+    .bind(tenant_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(record)
 }
 
 #[async_trait]
@@ -26,37 +129,7 @@ impl InventoryService for PostgresInventoryRepository {
     ) -> Result<InventoryTransaction, Error> {
         let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
 
-        let record = sqlx::query_as::<_, InventoryTransaction>(
-            r#"
-            INSERT INTO inventory_transactions 
-            (tenant_id, product_id, quantity, transaction_type, reference_id, notes)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, tenant_id, product_id, quantity, transaction_type, reference_id, notes, created_at
-            "#
-        )
-        .bind(tenant_id)
-        .bind(transaction.product_id)
-        .bind(transaction.quantity)
-        .bind(transaction.transaction_type)
-        .bind(transaction.reference_id)
-        .bind(transaction.notes)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(|e| Error::Database(e.to_string()))?;
-
-        sqlx::query(
-            r#"
-            UPDATE products 
-            SET stock_quantity = stock_quantity + $1, updated_at = NOW()
-            WHERE id = $2 AND tenant_id = $3
-            "#
-        )
-        .bind(transaction.quantity)
-        .bind(transaction.product_id)
-        .bind(tenant_id)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| Error::Database(e.to_string()))?;
+        let record = insert_transaction(&mut tx, tenant_id, transaction).await?;
 
         tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
 
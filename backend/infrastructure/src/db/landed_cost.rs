@@ -0,0 +1,216 @@
+use smart_erp_core::models::landed_cost::*;
+use smart_erp_core::models::costing::CostingMethod;
+use smart_erp_core::models::purchasing::PurchaseReceiptLine;
+use smart_erp_core::error::Error;
+use crate::db::costing::{capitalize_to_layer, load_settings};
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresLandedCostRepository {
+    pool: PgPool,
+}
+
+impl PostgresLandedCostRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_allocations(&self, tenant_id: Uuid) -> Result<Vec<LandedCostAllocation>, Error> {
+        sqlx::query_as::<_, LandedCostAllocation>(
+            "SELECT * FROM landed_cost_allocations WHERE tenant_id = $1 ORDER BY created_at DESC"
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_allocation(&self, tenant_id: Uuid, allocation_id: Uuid) -> Result<LandedCostAllocationDetail, Error> {
+        let allocation = sqlx::query_as::<_, LandedCostAllocation>(
+            "SELECT * FROM landed_cost_allocations WHERE id = $1 AND tenant_id = $2"
+        )
+        .bind(allocation_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Landed cost allocation not found".to_string()))?;
+
+        let lines = sqlx::query_as::<_, LandedCostAllocationLine>(
+            "SELECT * FROM landed_cost_allocation_lines WHERE allocation_id = $1 ORDER BY amount DESC"
+        )
+        .bind(allocation_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(LandedCostAllocationDetail { allocation, lines })
+    }
+
+    /// Spreads a freight/duty bill across the lines of one or more receipts. The share of stock
+    /// still on hand is capitalized into its cost layer; the rest goes straight to cost of sales.
+    /// The bill already credited AP when it was entered, so the allocation draws on purchases clearing.
+    pub async fn allocate(&self, tenant_id: Uuid, req: CreateLandedCostAllocation) -> Result<LandedCostAllocationDetail, Error> {
+        if req.receipt_ids.is_empty() {
+            return Err(Error::BusinessRule("Select at least one receipt to allocate to".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let (bill_number, bill_total, purchase_order_id): (String, Decimal, Option<Uuid>) = sqlx::query_as(
+            "SELECT bill_number, total_amount, purchase_order_id FROM bills WHERE id = $1 AND tenant_id = $2 FOR UPDATE"
+        )
+        .bind(req.bill_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Bill not found".to_string()))?;
+
+        // Matched bills clear GRNI into AP; their cost is already on the receipt.
+        if purchase_order_id.is_some() {
+            return Err(Error::BusinessRule(format!(
+                "Bill {} is matched to a purchase order and cannot be allocated as landed cost",
+                bill_number
+            )));
+        }
+
+        let already_allocated: Decimal = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(amount), 0) FROM landed_cost_allocations WHERE bill_id = $1"
+        )
+        .bind(req.bill_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let available = bill_total - already_allocated;
+        let amount = req.amount.unwrap_or(available);
+        if amount <= Decimal::ZERO {
+            return Err(Error::BusinessRule(format!("Bill {} has nothing left to allocate", bill_number)));
+        }
+        if amount > available {
+            return Err(Error::BusinessRule(format!(
+                "Allocation of {} exceeds the unallocated balance {} on bill {}",
+                amount, available, bill_number
+            )));
+        }
+
+        let receipt_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM purchase_receipts WHERE tenant_id = $1 AND id = ANY($2)"
+        )
+        .bind(tenant_id)
+        .bind(&req.receipt_ids)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+        if receipt_count as usize != req.receipt_ids.len() {
+            return Err(Error::NotFound("One or more receipts not found".to_string()));
+        }
+
+        let lines = sqlx::query_as::<_, PurchaseReceiptLine>(
+            "SELECT * FROM purchase_receipt_lines WHERE receipt_id = ANY($1) ORDER BY id FOR UPDATE"
+        )
+        .bind(&req.receipt_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let bases: Vec<Decimal> = lines.iter().map(|l| match req.allocation_method {
+            AllocationMethod::Value => l.quantity * l.unit_cost,
+            AllocationMethod::Quantity => l.quantity,
+            AllocationMethod::Weight => l.weight.unwrap_or_default(),
+            AllocationMethod::Area => l.area.unwrap_or_default(),
+        }).collect();
+        let shares = allocate(amount, &bases)?;
+
+        let allocation = sqlx::query_as::<_, LandedCostAllocation>(
+            r#"
+            INSERT INTO landed_cost_allocations (tenant_id, bill_id, allocation_method, amount, notes)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.bill_id)
+        .bind(req.allocation_method)
+        .bind(amount)
+        .bind(&req.notes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let (mut capitalized_total, mut expensed_total) = (Decimal::ZERO, Decimal::ZERO);
+        for ((line, basis), share) in lines.iter().zip(bases).zip(shares) {
+            if share == Decimal::ZERO {
+                continue;
+            }
+            let (capitalized, expensed) = match line.transaction_id {
+                Some(transaction_id) => capitalize_to_layer(&mut tx, tenant_id, transaction_id, share).await?,
+                None => (Decimal::ZERO, share),
+            };
+            capitalized_total += capitalized;
+            expensed_total += expensed;
+
+            sqlx::query("UPDATE purchase_receipt_lines SET landed_cost = landed_cost + $1 WHERE id = $2")
+                .bind(share)
+                .bind(line.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO landed_cost_allocation_lines (allocation_id, receipt_line_id, product_id, basis, amount, capitalized_amount, expensed_amount)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#
+            )
+            .bind(allocation.id)
+            .bind(line.id)
+            .bind(line.product_id)
+            .bind(basis)
+            .bind(share)
+            .bind(capitalized)
+            .bind(expensed)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        // Standard costing keeps layers at standard, so the whole landed cost is a purchase variance.
+        let expense_account = match load_settings(&mut tx, tenant_id).await?.costing_method {
+            CostingMethod::Standard => "5400",
+            CostingMethod::Fifo | CostingMethod::MovingAverage => "5000",
+        };
+        let memo = format!("Landed cost from bill {}", bill_number);
+        let date = Utc::now().date_naive();
+        let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "LCA", date).await?;
+        let journal_entry_id = post_journal_entry(&mut tx, tenant_id, &entry_number, date, &memo, &[
+            LedgerLine::debit("1300", capitalized_total, &memo),
+            LedgerLine::debit(expense_account, expensed_total, &memo),
+            LedgerLine::credit("1450", amount, &memo),
+        ]).await?;
+
+        let allocation = sqlx::query_as::<_, LandedCostAllocation>(
+            r#"
+            UPDATE landed_cost_allocations SET capitalized_amount = $1, expensed_amount = $2, journal_entry_id = $3
+            WHERE id = $4
+            RETURNING *
+            "#
+        )
+        .bind(capitalized_total)
+        .bind(expensed_total)
+        .bind(journal_entry_id)
+        .bind(allocation.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_allocation(tenant_id, allocation.id).await
+    }
+}
@@ -0,0 +1,102 @@
+use smart_erp_core::error::Error;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{Postgres, Row, Transaction};
+use uuid::Uuid;
+
+/// One side of an automatic posting, addressed by chart-of-accounts number (e.g. "1300").
+#[derive(Debug, Clone)]
+pub struct LedgerLine {
+    pub account_number: String,
+    pub debit: Decimal,
+    pub credit: Decimal,
+    pub memo: Option<String>,
+}
+
+impl LedgerLine {
+    pub fn debit(account_number: &str, amount: Decimal, memo: &str) -> Self {
+        Self { account_number: account_number.to_string(), debit: amount, credit: Decimal::ZERO, memo: Some(memo.to_string()) }
+    }
+
+    pub fn credit(account_number: &str, amount: Decimal, memo: &str) -> Self {
+        Self { account_number: account_number.to_string(), debit: Decimal::ZERO, credit: amount, memo: Some(memo.to_string()) }
+    }
+}
+
+pub fn is_debit_normal(account_type: &str) -> bool {
+    matches!(account_type, "BANK" | "ACCOUNTS_RECEIVABLE" | "OTHER_CURRENT_ASSET" | "FIXED_ASSET" | "OTHER_ASSET" | "COST_OF_GOODS_SOLD" | "EXPENSE" | "OTHER_EXPENSE")
+}
+
+/// Posts a balanced journal entry inside the caller's transaction and updates account balances.
+/// Zero lines are dropped; returns None when nothing is left to post.
+pub async fn post_journal_entry(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    entry_number: &str,
+    date: NaiveDate,
+    memo: &str,
+    lines: &[LedgerLine],
+) -> Result<Option<Uuid>, Error> {
+    let lines: Vec<&LedgerLine> = lines.iter()
+        .filter(|l| l.debit != Decimal::ZERO || l.credit != Decimal::ZERO)
+        .collect();
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
+    let total_debits: Decimal = lines.iter().map(|l| l.debit).sum();
+    let total_credits: Decimal = lines.iter().map(|l| l.credit).sum();
+    if total_debits != total_credits {
+        return Err(Error::BusinessRule(format!(
+            "Journal entry {} is unbalanced: debits {} != credits {}",
+            entry_number, total_debits, total_credits
+        )));
+    }
+
+    let entry_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO journal_entries (tenant_id, entry_number, date, memo) VALUES ($1, $2, $3, $4) RETURNING id"
+    )
+    .bind(tenant_id)
+    .bind(entry_number)
+    .bind(date)
+    .bind(memo)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    for (i, line) in lines.iter().enumerate() {
+        let account = sqlx::query(
+            "SELECT id, account_type FROM accounts WHERE tenant_id = $1 AND account_number = $2"
+        )
+        .bind(tenant_id)
+        .bind(&line.account_number)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::BusinessRule(format!("Account {} is not set up in the chart of accounts", line.account_number)))?;
+
+        let account_id: Uuid = account.try_get("id").map_err(|e| Error::Database(e.to_string()))?;
+        let account_type: String = account.try_get("account_type").map_err(|e| Error::Database(e.to_string()))?;
+
+        sqlx::query("INSERT INTO journal_entry_lines (entry_id, account_id, debit, credit, memo, sort_order) VALUES ($1, $2, $3, $4, $5, $6)")
+            .bind(entry_id)
+            .bind(account_id)
+            .bind(line.debit)
+            .bind(line.credit)
+            .bind(&line.memo)
+            .bind(i as i32)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let delta = if is_debit_normal(&account_type) { line.debit - line.credit } else { line.credit - line.debit };
+        sqlx::query("UPDATE accounts SET balance = balance + $1, updated_at = NOW() WHERE id = $2")
+            .bind(delta)
+            .bind(account_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+    }
+
+    Ok(Some(entry_id))
+}
@@ -1,13 +1,19 @@
 use async_trait::async_trait;
 use smart_erp_core::models::manufacturing::{
-    CreateRecipe, CreateWorkOrder, ManufacturingService, Recipe, RecipeIngredient, WorkOrder,
-    WorkOrderStatus,
+    validate_outputs, ApproveRecipe, CreateRecipe, CreateRecipeIngredient, CreateRecipeOutput, CreateRecipeVersion,
+    CreateWorkOrder, ManufacturingService, Recipe, RecipeDetail, RecipeIngredient, RecipeOutput, RecipeStatus,
+    UpdateRecipeCosts, UpdateRecipeLeadTime, WorkOrder,
 };
-use smart_erp_core::models::inventory::TransactionType;
+use smart_erp_core::models::bom::{BomGraph, BomProduct, CostRollup, IndentedBom, WhereUsedLine};
 use smart_erp_core::error::Error;
+use crate::db::production::{complete, create_materials};
+use chrono::{Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
+pub const WORK_ORDER_COLUMNS: &str = "id, tenant_id, recipe_id, quantity, status, start_date, end_date, created_at, updated_at, produced_quantity, scrapped_quantity, material_cost, output_cost, started_at, labor_hours, labor_cost, overhead_cost, material_variance, labor_variance, overhead_variance, scheduled_end";
+
 pub struct PostgresManufacturingRepository {
     pool: PgPool,
 }
@@ -19,7 +25,7 @@ impl PostgresManufacturingRepository {
 
     pub async fn list_recipes(&self, tenant_id: Uuid) -> Result<Vec<Recipe>, Error> {
         let rows = sqlx::query_as::<_, Recipe>(
-            "SELECT * FROM recipes WHERE tenant_id = $1 ORDER BY name"
+            "SELECT * FROM recipes WHERE tenant_id = $1 ORDER BY name, version"
         )
         .bind(tenant_id)
         .fetch_all(&self.pool)
@@ -29,217 +35,508 @@ impl PostgresManufacturingRepository {
     }
 
     pub async fn list_work_orders(&self, tenant_id: Uuid) -> Result<Vec<WorkOrder>, Error> {
-        let rows = sqlx::query_as::<_, WorkOrder>(
-            "SELECT id, tenant_id, recipe_id, quantity, status, start_date, end_date, created_at, updated_at FROM work_orders WHERE tenant_id = $1 ORDER BY created_at DESC"
-        )
+        let rows = sqlx::query_as::<_, WorkOrder>(&format!(
+            "SELECT {} FROM work_orders WHERE tenant_id = $1 ORDER BY created_at DESC",
+            WORK_ORDER_COLUMNS
+        ))
         .bind(tenant_id)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
         Ok(rows)
     }
-}
 
-#[async_trait]
-impl ManufacturingService for PostgresManufacturingRepository {
-    async fn create_recipe(
-        &self,
-        tenant_id: Uuid,
-        recipe: CreateRecipe,
-    ) -> Result<Recipe, Error> {
-        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+    pub async fn update_recipe_costs(&self, tenant_id: Uuid, recipe_id: Uuid, costs: UpdateRecipeCosts) -> Result<Recipe, Error> {
+        if costs.labor_cost < Decimal::ZERO || costs.overhead_cost < Decimal::ZERO {
+            return Err(Error::BusinessRule("Labor and overhead costs cannot be negative".to_string()));
+        }
 
-        let created_recipe = sqlx::query_as::<_, Recipe>(
+        sqlx::query_as::<_, Recipe>(
             r#"
-            INSERT INTO recipes (tenant_id, name, output_product_id, output_quantity, description)
-            VALUES ($1, $2, $3, $4, $5)
+            UPDATE recipes SET labor_cost = $1, overhead_cost = $2, updated_at = NOW()
+            WHERE id = $3 AND tenant_id = $4
             RETURNING *
             "#
         )
+        .bind(costs.labor_cost)
+        .bind(costs.overhead_cost)
+        .bind(recipe_id)
         .bind(tenant_id)
-        .bind(recipe.name)
-        .bind(recipe.output_product_id)
-        .bind(recipe.output_quantity)
-        .bind(recipe.description)
-        .fetch_one(&mut *tx)
+        .fetch_optional(&self.pool)
         .await
-        .map_err(|e| Error::Database(e.to_string()))?;
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Recipe not found".to_string()))
+    }
 
-        for ingredient in recipe.ingredients {
-            sqlx::query(
-                r#"
-                INSERT INTO recipe_ingredients (recipe_id, input_product_id, quantity)
-                VALUES ($1, $2, $3)
-                "#
-            )
-            .bind(created_recipe.id)
-            .bind(ingredient.input_product_id)
-            .bind(ingredient.quantity)
-            .execute(&mut *tx)
+    pub async fn update_recipe_lead_time(&self, tenant_id: Uuid, recipe_id: Uuid, req: UpdateRecipeLeadTime) -> Result<Recipe, Error> {
+        if req.lead_time_days < 0 {
+            return Err(Error::BusinessRule("Lead time cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, Recipe>(
+            "UPDATE recipes SET lead_time_days = $1, updated_at = NOW() WHERE id = $2 AND tenant_id = $3 RETURNING *"
+        )
+        .bind(req.lead_time_days)
+        .bind(recipe_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Recipe not found".to_string()))
+    }
+
+    pub async fn get_recipe(&self, tenant_id: Uuid, recipe_id: Uuid) -> Result<RecipeDetail, Error> {
+        let recipe = sqlx::query_as::<_, Recipe>("SELECT * FROM recipes WHERE id = $1 AND tenant_id = $2")
+            .bind(recipe_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Recipe not found".to_string()))?;
+
+        let ingredients = sqlx::query_as::<_, RecipeIngredient>("SELECT * FROM recipe_ingredients WHERE recipe_id = $1")
+            .bind(recipe_id)
+            .fetch_all(&self.pool)
             .await
             .map_err(|e| Error::Database(e.to_string()))?;
-        }
 
-        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        let by_products = sqlx::query_as::<_, RecipeOutput>("SELECT * FROM recipe_outputs WHERE recipe_id = $1")
+            .bind(recipe_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
 
-        Ok(created_recipe)
+        Ok(RecipeDetail { recipe, ingredients, by_products })
     }
 
-    async fn create_work_order(
-        &self,
-        tenant_id: Uuid,
-        work_order: CreateWorkOrder,
-    ) -> Result<WorkOrder, Error> {
-        let record = sqlx::query_as::<_, WorkOrder>(
+    /// Every version of the recipe, newest first.
+    pub async fn list_recipe_versions(&self, tenant_id: Uuid, recipe_id: Uuid) -> Result<Vec<Recipe>, Error> {
+        let versions = sqlx::query_as::<_, Recipe>(
             r#"
-            INSERT INTO work_orders (tenant_id, recipe_id, quantity, start_date, status)
-            VALUES ($1, $2, $3, $4, 'PLANNED')
-            RETURNING id, tenant_id, recipe_id, quantity, status, start_date, end_date, created_at, updated_at
+            SELECT * FROM recipes
+            WHERE tenant_id = $1 AND name = (SELECT name FROM recipes WHERE id = $2 AND tenant_id = $1)
+            ORDER BY version DESC
             "#
         )
         .bind(tenant_id)
-        .bind(work_order.recipe_id)
-        .bind(work_order.quantity)
-        .bind(work_order.start_date)
-        .fetch_one(&self.pool)
+        .bind(recipe_id)
+        .fetch_all(&self.pool)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
-        Ok(record)
+        if versions.is_empty() {
+            return Err(Error::NotFound("Recipe not found".to_string()));
+        }
+        Ok(versions)
     }
 
-    async fn complete_work_order(
-        &self,
-        tenant_id: Uuid,
-        work_order_id: Uuid,
-    ) -> Result<WorkOrder, Error> {
+    /// Starts a draft version from an existing one. Work orders keep the version they were made
+    /// from; the draft only takes effect once approved.
+    pub async fn create_recipe_version(&self, tenant_id: Uuid, recipe_id: Uuid, req: CreateRecipeVersion) -> Result<RecipeDetail, Error> {
         let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
 
-        let work_order = sqlx::query_as::<_, WorkOrder>(
+        let base = sqlx::query_as::<_, Recipe>("SELECT * FROM recipes WHERE id = $1 AND tenant_id = $2")
+            .bind(recipe_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Recipe not found".to_string()))?;
+
+        let output_quantity = req.output_quantity.unwrap_or(base.output_quantity);
+        let labor_cost = req.labor_cost.unwrap_or(base.labor_cost);
+        let overhead_cost = req.overhead_cost.unwrap_or(base.overhead_cost);
+        if output_quantity <= Decimal::ZERO || labor_cost < Decimal::ZERO || overhead_cost < Decimal::ZERO {
+            return Err(Error::BusinessRule("Output quantity must be positive and costs not negative".to_string()));
+        }
+
+        // Versions of one recipe share its name; lock them so two drafts cannot take the same number.
+        let version: i32 = sqlx::query_scalar(
+            "SELECT MAX(version) FROM (SELECT version FROM recipes WHERE tenant_id = $1 AND name = $2 FOR UPDATE) v"
+        )
+        .bind(tenant_id)
+        .bind(&base.name)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let draft = sqlx::query_as::<_, Recipe>(
             r#"
-            SELECT id, tenant_id, recipe_id, quantity, status, start_date, end_date, created_at, updated_at
-            FROM work_orders
-            WHERE id = $1 AND tenant_id = $2
+            INSERT INTO recipes (tenant_id, name, output_product_id, output_quantity, description, labor_cost, overhead_cost,
+                                 lead_time_days, routing_id, version, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(&base.name)
+        .bind(base.output_product_id)
+        .bind(output_quantity)
+        .bind(req.description.or(base.description))
+        .bind(labor_cost)
+        .bind(overhead_cost)
+        .bind(base.lead_time_days)
+        .bind(base.routing_id)
+        .bind(version + 1)
+        .bind(RecipeStatus::Draft)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        match req.ingredients {
+            Some(ingredients) => insert_ingredients(&mut tx, draft.id, ingredients).await?,
+            None => {
+                sqlx::query(
+                    "INSERT INTO recipe_ingredients (recipe_id, input_product_id, quantity) SELECT $1, input_product_id, quantity FROM recipe_ingredients WHERE recipe_id = $2"
+                )
+                .bind(draft.id)
+                .bind(base.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            }
+        }
+
+        match req.by_products {
+            Some(outputs) => insert_outputs(&mut tx, &draft, outputs).await?,
+            None => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO recipe_outputs (recipe_id, product_id, output_type, quantity, cost_share)
+                    SELECT $1, product_id, output_type, quantity, cost_share FROM recipe_outputs WHERE recipe_id = $2
+                    "#
+                )
+                .bind(draft.id)
+                .bind(base.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            }
+        }
+
+        load_bom_graph(&mut tx, tenant_id).await?.check_cycles(draft.id)?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_recipe(tenant_id, draft.id).await
+    }
+
+    /// Approves a draft version from its effective date. The version in effect until then is
+    /// closed the day before; a version already due to take effect on or after that date blocks it.
+    pub async fn approve_recipe(&self, tenant_id: Uuid, recipe_id: Uuid, req: ApproveRecipe, approved_by: Uuid) -> Result<Recipe, Error> {
+        let today = Utc::now().date_naive();
+        let effective_from = req.effective_from.unwrap_or(today);
+        if effective_from < today {
+            return Err(Error::BusinessRule("A recipe version cannot take effect in the past".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let draft = sqlx::query_as::<_, Recipe>("SELECT * FROM recipes WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+            .bind(recipe_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Recipe not found".to_string()))?;
+
+        if draft.status != RecipeStatus::Draft {
+            return Err(Error::BusinessRule(format!("Version {} of '{}' is already approved", draft.version, draft.name)));
+        }
+
+        let current = sqlx::query_as::<_, Recipe>(
+            r#"
+            SELECT * FROM recipes
+            WHERE tenant_id = $1 AND name = $2 AND status = 'APPROVED' AND (effective_to IS NULL OR effective_to >= $3)
             FOR UPDATE
             "#
         )
-        .bind(work_order_id)
         .bind(tenant_id)
-        .fetch_optional(&mut *tx)
+        .bind(&draft.name)
+        .bind(effective_from)
+        .fetch_all(&mut *tx)
         .await
-        .map_err(|e| Error::Database(e.to_string()))?
-        .ok_or(Error::NotFound("Work Order not found".to_string()))?;
+        .map_err(|e| Error::Database(e.to_string()))?;
 
-        if work_order.status == WorkOrderStatus::Completed || work_order.status == WorkOrderStatus::Cancelled {
+        if let Some(later) = current.iter().find(|r| r.effective_from.is_some_and(|from| from >= effective_from)) {
             return Err(Error::BusinessRule(format!(
-                "Cannot complete work order with status {:?}.",
-                work_order.status
+                "Version {} already takes effect on {}; choose a later date",
+                later.version,
+                later.effective_from.unwrap_or(effective_from)
             )));
         }
 
-        let updated_work_order = sqlx::query_as::<_, WorkOrder>(
+        for recipe in &current {
+            sqlx::query("UPDATE recipes SET effective_to = $1, updated_at = NOW() WHERE id = $2")
+                .bind(effective_from - Duration::days(1))
+                .bind(recipe.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        let approved = sqlx::query_as::<_, Recipe>(
             r#"
-            UPDATE work_orders
-            SET status = 'COMPLETED', end_date = CURRENT_DATE, updated_at = NOW()
-            WHERE id = $1
-            RETURNING id, tenant_id, recipe_id, quantity, status, start_date, end_date, created_at, updated_at
+            UPDATE recipes
+            SET status = $1, effective_from = $2, effective_to = NULL, approved_by = $3, approved_at = NOW(), updated_at = NOW()
+            WHERE id = $4
+            RETURNING *
             "#
         )
-        .bind(work_order_id)
+        .bind(RecipeStatus::Approved)
+        .bind(effective_from)
+        .bind(approved_by)
+        .bind(recipe_id)
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
-        let recipe = sqlx::query_as::<_, Recipe>(
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(approved)
+    }
+
+    /// Rolls up every recipe, deepest sub-assemblies first as they are reached.
+    pub async fn roll_up_all_costs(&self, tenant_id: Uuid) -> Result<Vec<CostRollup>, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let graph = load_bom_graph(&mut tx, tenant_id).await?;
+        let recipes = self.list_recipes(tenant_id).await?;
+
+        let mut rollups = Vec::with_capacity(recipes.len());
+        for recipe in recipes {
+            store_unit_costs(&mut tx, &graph, recipe.id).await?;
+            rollups.push(graph.roll_up(recipe.id)?);
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(rollups)
+    }
+}
+
+/// Loads the tenant's recipes as a graph. Where several recipes make the same product, one
+/// with a version in effect today is used for explosion and costing, the oldest first.
+pub async fn load_bom_graph(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid) -> Result<BomGraph, Error> {
+    let recipes = sqlx::query_as::<_, Recipe>(
+        r#"
+        SELECT * FROM recipes WHERE tenant_id = $1
+        ORDER BY (status = 'APPROVED' AND COALESCE(effective_from, CURRENT_DATE) <= CURRENT_DATE
+                  AND COALESCE(effective_to, CURRENT_DATE) >= CURRENT_DATE) DESC, created_at, id
+        "#
+    )
+    .bind(tenant_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let ingredients = sqlx::query_as::<_, RecipeIngredient>(
+        r#"
+        SELECT i.* FROM recipe_ingredients i
+        JOIN recipes r ON r.id = i.recipe_id
+        WHERE r.tenant_id = $1
+        "#
+    )
+    .bind(tenant_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let products = sqlx::query_as::<_, BomProduct>(
+        "SELECT id, sku, name, COALESCE(NULLIF(standard_cost, 0), cost_price) AS unit_cost FROM products WHERE tenant_id = $1"
+    )
+    .bind(tenant_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(BomGraph::new(recipes, ingredients, products))
+}
+
+/// The version of a recipe in effect on the date, given any version of it.
+pub async fn effective_recipe(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, recipe_id: Uuid, date: NaiveDate) -> Result<Recipe, Error> {
+    let versions = sqlx::query_as::<_, Recipe>(
+        "SELECT * FROM recipes WHERE tenant_id = $1 AND name = (SELECT name FROM recipes WHERE id = $2 AND tenant_id = $1)"
+    )
+    .bind(tenant_id)
+    .bind(recipe_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let name = versions.first().map(|r| r.name.clone()).ok_or(Error::NotFound("Recipe not found".to_string()))?;
+    versions
+        .into_iter()
+        .filter(|r| r.is_effective_on(date))
+        .max_by_key(|r| r.version)
+        .ok_or(Error::BusinessRule(format!("No approved version of recipe '{}' is in effect on {}", name, date)))
+}
+
+async fn insert_ingredients(tx: &mut Transaction<'_, Postgres>, recipe_id: Uuid, ingredients: Vec<CreateRecipeIngredient>) -> Result<(), Error> {
+    for ingredient in ingredients {
+        sqlx::query(
             r#"
-            SELECT * FROM recipes WHERE id = $1
+            INSERT INTO recipe_ingredients (recipe_id, input_product_id, quantity)
+            VALUES ($1, $2, $3)
             "#
         )
-        .bind(work_order.recipe_id)
-        .fetch_one(&mut *tx)
+        .bind(recipe_id)
+        .bind(ingredient.input_product_id)
+        .bind(ingredient.quantity)
+        .execute(&mut **tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
+    }
+    Ok(())
+}
 
-        let ingredients = sqlx::query_as::<_, RecipeIngredient>(
+async fn insert_outputs(tx: &mut Transaction<'_, Postgres>, recipe: &Recipe, outputs: Vec<CreateRecipeOutput>) -> Result<(), Error> {
+    validate_outputs(recipe.output_product_id, &outputs)?;
+    for output in outputs {
+        sqlx::query(
             r#"
-            SELECT * FROM recipe_ingredients WHERE recipe_id = $1
+            INSERT INTO recipe_outputs (recipe_id, product_id, output_type, quantity, cost_share)
+            VALUES ($1, $2, $3, $4, $5)
             "#
         )
-        .bind(work_order.recipe_id)
-        .fetch_all(&mut *tx)
+        .bind(recipe.id)
+        .bind(output.product_id)
+        .bind(output.output_type)
+        .bind(output.quantity)
+        .bind(output.cost_share)
+        .execute(&mut **tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
+    }
+    Ok(())
+}
 
-        for ingredient in ingredients {
-            let amount_needed = ingredient.quantity * work_order.quantity;
-
-            sqlx::query(
-                r#"
-                INSERT INTO inventory_transactions 
-                (tenant_id, product_id, quantity, transaction_type, reference_id, notes)
-                VALUES ($1, $2, $3, $4, $5, $6)
-                "#
-            )
-            .bind(tenant_id)
-            .bind(ingredient.input_product_id)
-            .bind(amount_needed)
-            .bind(TransactionType::ProductionOut)
-            .bind(work_order_id)
-            .bind(format!("Used for WO {}", work_order_id))
-            .execute(&mut *tx)
+async fn store_unit_costs(tx: &mut Transaction<'_, Postgres>, graph: &BomGraph, recipe_id: Uuid) -> Result<(), Error> {
+    for (id, unit_cost) in graph.unit_costs(recipe_id)? {
+        sqlx::query("UPDATE recipes SET standard_unit_cost = $1, cost_rolled_up_at = NOW() WHERE id = $2")
+            .bind(unit_cost)
+            .bind(id)
+            .execute(&mut **tx)
             .await
             .map_err(|e| Error::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ManufacturingService for PostgresManufacturingRepository {
+    async fn create_recipe(
+        &self,
+        tenant_id: Uuid,
+        recipe: CreateRecipe,
+    ) -> Result<Recipe, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
 
-            sqlx::query(
-                r#"
-                UPDATE products
-                SET stock_quantity = stock_quantity - $1, updated_at = NOW()
-                WHERE id = $2 AND tenant_id = $3
-                "#
-            )
-            .bind(amount_needed)
-            .bind(ingredient.input_product_id)
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM recipes WHERE tenant_id = $1 AND name = $2)")
             .bind(tenant_id)
-            .execute(&mut *tx)
+            .bind(&recipe.name)
+            .fetch_one(&mut *tx)
             .await
             .map_err(|e| Error::Database(e.to_string()))?;
+        if exists {
+            return Err(Error::BusinessRule(format!("Recipe '{}' already exists; create a new version of it instead", recipe.name)));
         }
 
-        let amount_produced = recipe.output_quantity * work_order.quantity;
-
-        sqlx::query(
+        // The first version has nothing in flight to protect, so it is approved as created.
+        let created_recipe = sqlx::query_as::<_, Recipe>(
             r#"
-            INSERT INTO inventory_transactions 
-            (tenant_id, product_id, quantity, transaction_type, reference_id, notes)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO recipes (tenant_id, name, output_product_id, output_quantity, description, labor_cost, overhead_cost, lead_time_days)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
             "#
         )
         .bind(tenant_id)
+        .bind(recipe.name)
         .bind(recipe.output_product_id)
-        .bind(amount_produced)
-        .bind(TransactionType::ProductionIn)
-        .bind(work_order_id)
-        .bind(format!("Produced from WO {}", work_order_id))
-        .execute(&mut *tx)
+        .bind(recipe.output_quantity)
+        .bind(recipe.description)
+        .bind(recipe.labor_cost)
+        .bind(recipe.overhead_cost)
+        .bind(recipe.lead_time_days)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
-        sqlx::query(
+        insert_ingredients(&mut tx, created_recipe.id, recipe.ingredients).await?;
+        insert_outputs(&mut tx, &created_recipe, recipe.by_products).await?;
+
+        // A recipe that needs its own output through any chain of sub-recipes can never be made.
+        load_bom_graph(&mut tx, tenant_id).await?.check_cycles(created_recipe.id)?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(created_recipe)
+    }
+
+    async fn create_work_order(
+        &self,
+        tenant_id: Uuid,
+        work_order: CreateWorkOrder,
+    ) -> Result<WorkOrder, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let start_date = work_order.start_date.unwrap_or_else(|| Utc::now().date_naive());
+        let recipe = effective_recipe(&mut tx, tenant_id, work_order.recipe_id, start_date).await?;
+
+        let record = sqlx::query_as::<_, WorkOrder>(&format!(
             r#"
-            UPDATE products
-            SET stock_quantity = stock_quantity + $1, updated_at = NOW()
-            WHERE id = $2 AND tenant_id = $3
-            "#
-        )
-        .bind(amount_produced)
-        .bind(recipe.output_product_id)
+            INSERT INTO work_orders (tenant_id, recipe_id, quantity, start_date, status)
+            VALUES ($1, $2, $3, $4, 'PLANNED')
+            RETURNING {}
+            "#,
+            WORK_ORDER_COLUMNS
+        ))
         .bind(tenant_id)
-        .execute(&mut *tx)
+        .bind(recipe.id)
+        .bind(work_order.quantity)
+        .bind(work_order.start_date)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
+        create_materials(&mut tx, &record).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(record)
+    }
+
+    async fn complete_work_order(
+        &self,
+        tenant_id: Uuid,
+        work_order_id: Uuid,
+    ) -> Result<WorkOrder, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let work_order = complete(&mut tx, tenant_id, work_order_id).await?;
         tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(work_order)
+    }
+
+    async fn explode_bom(&self, tenant_id: Uuid, recipe_id: Uuid, quantity: Decimal) -> Result<IndentedBom, Error> {
+        if quantity <= Decimal::ZERO {
+            return Err(Error::BusinessRule("Quantity must be positive".to_string()));
+        }
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let graph = load_bom_graph(&mut tx, tenant_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        graph.explode(recipe_id, quantity)
+    }
 
-        Ok(updated_work_order)
+    async fn where_used(&self, tenant_id: Uuid, product_id: Uuid) -> Result<Vec<WhereUsedLine>, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let graph = load_bom_graph(&mut tx, tenant_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(graph.where_used(product_id))
+    }
+
+    async fn roll_up_cost(&self, tenant_id: Uuid, recipe_id: Uuid) -> Result<CostRollup, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let graph = load_bom_graph(&mut tx, tenant_id).await?;
+        let rollup = graph.roll_up(recipe_id)?;
+        store_unit_costs(&mut tx, &graph, recipe_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(rollup)
     }
 }
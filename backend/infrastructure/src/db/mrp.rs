@@ -0,0 +1,329 @@
+use smart_erp_core::models::mrp::*;
+use smart_erp_core::models::manufacturing::WorkOrder;
+use smart_erp_core::models::purchasing::PurchaseOrder;
+use smart_erp_core::models::address::AddressType;
+use smart_erp_core::error::Error;
+use crate::db::manufacturing::{effective_recipe, load_bom_graph, WORK_ORDER_COLUMNS};
+use crate::db::production::create_materials;
+use crate::db::address::{document_address, Party};
+use crate::db::numbering::next_document_number;
+use crate::db::purchasing::check_supplier_active;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Independent demand: confirmed sales orders, plus material not yet issued to open work
+/// orders on their start date.
+const DEMAND_QUERY: &str = r#"
+    SELECT l.product_id, o.date, l.quantity
+    FROM sales_order_lines l JOIN sales_orders o ON o.id = l.order_id
+    WHERE o.tenant_id = $1 AND o.status = 'CONFIRMED'
+    UNION ALL
+    SELECT m.product_id, COALESCE(w.start_date, CURRENT_DATE),
+           GREATEST(m.planned_quantity - (m.issued_quantity - m.returned_quantity), 0)
+    FROM work_orders w JOIN work_order_materials m ON m.work_order_id = w.id
+    WHERE w.tenant_id = $1 AND w.status IN ('PLANNED', 'IN_PROGRESS')
+"#;
+
+/// Scheduled receipts: open purchase orders due on their expected date (one lead time after
+/// ordering when none was promised), output and by-products still to come from open work
+/// orders one production lead time after they start.
+const RECEIPT_QUERY: &str = r#"
+    SELECT l.product_id, COALESCE(o.expected_date, o.date + p.lead_time_days) AS date, l.quantity
+    FROM purchase_order_lines l
+    JOIN purchase_orders o ON o.id = l.order_id
+    JOIN products p ON p.id = l.product_id
+    WHERE o.tenant_id = $1 AND o.status IN ('DRAFT', 'ORDERED')
+    UNION ALL
+    SELECT r.output_product_id, COALESCE(w.start_date, CURRENT_DATE) + r.lead_time_days,
+           GREATEST(r.output_quantity * w.quantity - w.produced_quantity, 0)
+    FROM work_orders w JOIN recipes r ON r.id = w.recipe_id
+    WHERE w.tenant_id = $1 AND w.status IN ('PLANNED', 'IN_PROGRESS')
+    UNION ALL
+    SELECT o.product_id, COALESCE(w.start_date, CURRENT_DATE) + r.lead_time_days,
+           GREATEST(o.planned_quantity - o.produced_quantity, 0)
+    FROM work_orders w
+    JOIN recipes r ON r.id = w.recipe_id
+    JOIN work_order_outputs o ON o.work_order_id = w.id
+    WHERE w.tenant_id = $1 AND w.status IN ('PLANNED', 'IN_PROGRESS')
+"#;
+
+pub struct PostgresMrpRepository {
+    pool: PgPool,
+}
+
+impl PostgresMrpRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Plans the horizon from today. Unfirmed planned orders from earlier runs are replaced.
+    pub async fn run(&self, tenant_id: Uuid, req: RunMrp, created_by: Uuid) -> Result<MrpRunDetail, Error> {
+        let weeks = req.horizon_weeks.unwrap_or(12);
+        if weeks == 0 || weeks > 104 {
+            return Err(Error::BusinessRule("Horizon must be between 1 and 104 weeks".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let graph = load_bom_graph(&mut tx, tenant_id).await?;
+
+        let items = sqlx::query_as::<_, MrpItem>(
+            r#"
+            SELECT id AS product_id, stock_quantity - quarantined_quantity AS on_hand, lead_time_days, min_order_qty, preferred_vendor_id
+            FROM products
+            WHERE tenant_id = $1 AND is_active = true AND item_type IN ('INVENTORY', 'ASSEMBLY')
+            "#
+        )
+        .bind(tenant_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let demands = sqlx::query_as::<_, MrpFlow>(DEMAND_QUERY)
+            .bind(tenant_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let receipts = sqlx::query_as::<_, MrpFlow>(RECEIPT_QUERY)
+            .bind(tenant_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let start = Utc::now().date_naive();
+        let period_days = 7;
+        let plan = plan(&graph, MrpInput { start, periods: weeks, period_days, items, demands, receipts })?;
+
+        sqlx::query("DELETE FROM mrp_planned_orders WHERE tenant_id = $1 AND status = 'PLANNED'")
+            .bind(tenant_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let run_number = next_document_number(&mut tx, tenant_id, "mrp_runs", "run_number", "MRP", start).await?;
+        let run = sqlx::query_as::<_, MrpRun>(
+            r#"
+            INSERT INTO mrp_runs (tenant_id, run_number, horizon_start, horizon_end, period_days, planned_order_count, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(&run_number)
+        .bind(start)
+        .bind(start + chrono::Duration::days(weeks as i64 * period_days - 1))
+        .bind(period_days as i32)
+        .bind(plan.planned_orders.len() as i32)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for requirement in &plan.requirements {
+            sqlx::query(
+                r#"
+                INSERT INTO mrp_requirements (run_id, product_id, period_start, gross_requirement, scheduled_receipts, planned_receipts, projected_on_hand, net_requirement)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#
+            )
+            .bind(run.id)
+            .bind(requirement.product_id)
+            .bind(requirement.period_start)
+            .bind(requirement.gross_requirement)
+            .bind(requirement.scheduled_receipts)
+            .bind(requirement.planned_receipts)
+            .bind(requirement.projected_on_hand)
+            .bind(requirement.net_requirement)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        for order in &plan.planned_orders {
+            sqlx::query(
+                r#"
+                INSERT INTO mrp_planned_orders (tenant_id, run_id, product_id, order_type, recipe_id, supplier_id, quantity, batches, release_date, due_date)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                "#
+            )
+            .bind(tenant_id)
+            .bind(run.id)
+            .bind(order.product_id)
+            .bind(order.order_type)
+            .bind(order.recipe_id)
+            .bind(order.supplier_id)
+            .bind(order.quantity)
+            .bind(order.batches)
+            .bind(order.release_date)
+            .bind(order.due_date)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_run(tenant_id, run.id).await
+    }
+
+    pub async fn list_runs(&self, tenant_id: Uuid) -> Result<Vec<MrpRun>, Error> {
+        sqlx::query_as::<_, MrpRun>("SELECT * FROM mrp_runs WHERE tenant_id = $1 ORDER BY created_at DESC")
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_run(&self, tenant_id: Uuid, run_id: Uuid) -> Result<MrpRunDetail, Error> {
+        let run = sqlx::query_as::<_, MrpRun>("SELECT * FROM mrp_runs WHERE id = $1 AND tenant_id = $2")
+            .bind(run_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("MRP run not found".to_string()))?;
+
+        let requirements = sqlx::query_as::<_, MrpRequirement>(
+            "SELECT * FROM mrp_requirements WHERE run_id = $1 ORDER BY product_id, period_start"
+        )
+        .bind(run_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let planned_orders = sqlx::query_as::<_, PlannedOrder>(
+            "SELECT * FROM mrp_planned_orders WHERE run_id = $1 ORDER BY release_date, product_id"
+        )
+        .bind(run_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(MrpRunDetail { run, requirements, planned_orders })
+    }
+
+    /// Planned orders from the latest run that have not been firmed yet.
+    pub async fn list_planned_orders(&self, tenant_id: Uuid) -> Result<Vec<PlannedOrder>, Error> {
+        sqlx::query_as::<_, PlannedOrder>(
+            "SELECT * FROM mrp_planned_orders WHERE tenant_id = $1 AND status = 'PLANNED' ORDER BY release_date, product_id"
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Turns a planned order into a PLANNED work order or a DRAFT purchase order.
+    pub async fn firm(&self, tenant_id: Uuid, planned_order_id: Uuid, req: FirmPlannedOrder) -> Result<FirmedPlannedOrder, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let planned = sqlx::query_as::<_, PlannedOrder>(
+            "SELECT * FROM mrp_planned_orders WHERE id = $1 AND tenant_id = $2 FOR UPDATE"
+        )
+        .bind(planned_order_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Planned order not found".to_string()))?;
+
+        if planned.status != PlannedOrderStatus::Planned {
+            return Err(Error::BusinessRule("Planned order has already been firmed".to_string()));
+        }
+
+        let today = Utc::now().date_naive();
+        let (mut work_order, mut purchase_order) = (None, None);
+
+        match planned.order_type {
+            PlannedOrderType::WorkOrder => {
+                let recipe_id = planned.recipe_id.ok_or(Error::BusinessRule("Planned work order has no recipe".to_string()))?;
+                let start_date = planned.release_date.max(today);
+                let recipe = effective_recipe(&mut tx, tenant_id, recipe_id, start_date).await?;
+                let record = sqlx::query_as::<_, WorkOrder>(&format!(
+                    r#"
+                    INSERT INTO work_orders (tenant_id, recipe_id, quantity, start_date, status)
+                    VALUES ($1, $2, $3, $4, 'PLANNED')
+                    RETURNING {}
+                    "#,
+                    WORK_ORDER_COLUMNS
+                ))
+                .bind(tenant_id)
+                .bind(recipe.id)
+                .bind(planned.batches.unwrap_or(planned.quantity))
+                .bind(start_date)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+
+                create_materials(&mut tx, &record).await?;
+                work_order = Some(record);
+            }
+            PlannedOrderType::PurchaseOrder => {
+                let supplier_id = req.supplier_id.or(planned.supplier_id)
+                    .ok_or(Error::BusinessRule("Choose a supplier: the product has no preferred supplier".to_string()))?;
+                check_supplier_active(&mut tx, tenant_id, supplier_id).await?;
+
+                let unit_price: Decimal = sqlx::query_scalar("SELECT cost_price FROM products WHERE id = $1")
+                    .bind(planned.product_id)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(|e| Error::Database(e.to_string()))?;
+
+                let order_number = next_document_number(&mut tx, tenant_id, "purchase_orders", "order_number", "MRP", today).await?;
+                let remit_to = document_address(&mut tx, tenant_id, Party::Supplier(supplier_id), AddressType::RemitTo, None).await?;
+                let po = sqlx::query_as::<_, PurchaseOrder>(
+                    r#"
+                    INSERT INTO purchase_orders (tenant_id, supplier_id, order_number, date, expected_date, total_amount, status,
+                                                 remit_to_address_id, remit_to_address)
+                    VALUES ($1, $2, $3, $4, $5, $6, 'DRAFT', $7, $8)
+                    RETURNING id, tenant_id, supplier_id, order_number, date, expected_date, status, total_amount, created_at, updated_at, remit_to_address_id, remit_to_address
+                    "#
+                )
+                .bind(tenant_id)
+                .bind(supplier_id)
+                .bind(order_number)
+                .bind(today)
+                .bind(planned.due_date)
+                .bind((planned.quantity * unit_price).round_dp(2))
+                .bind(remit_to.as_ref().map(|a| a.id))
+                .bind(remit_to.as_ref().map(|a| a.formatted()))
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+
+                sqlx::query("INSERT INTO purchase_order_lines (order_id, product_id, quantity, unit_price) VALUES ($1, $2, $3, $4)")
+                    .bind(po.id)
+                    .bind(planned.product_id)
+                    .bind(planned.quantity)
+                    .bind(unit_price)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| Error::Database(e.to_string()))?;
+
+                purchase_order = Some(po);
+            }
+        }
+
+        let planned_order = sqlx::query_as::<_, PlannedOrder>(
+            r#"
+            UPDATE mrp_planned_orders
+            SET status = 'FIRMED', firmed_at = NOW(), work_order_id = $1, purchase_order_id = $2
+            WHERE id = $3
+            RETURNING *
+            "#
+        )
+        .bind(work_order.as_ref().map(|w| w.id))
+        .bind(purchase_order.as_ref().map(|p| p.id))
+        .bind(planned_order_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(FirmedPlannedOrder { planned_order, work_order, purchase_order })
+    }
+}
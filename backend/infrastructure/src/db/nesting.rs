@@ -0,0 +1,212 @@
+use smart_erp_core::models::nesting::*;
+use smart_erp_core::models::manufacturing::{WorkOrder, WorkOrderStatus};
+use smart_erp_core::error::Error;
+use crate::db::manufacturing::WORK_ORDER_COLUMNS;
+use rust_decimal::Decimal;
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresNestingRepository {
+    pool: PgPool,
+}
+
+impl PostgresNestingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_pattern_pieces(&self, tenant_id: Uuid, recipe_id: Uuid) -> Result<Vec<PatternPiece>, Error> {
+        sqlx::query_as::<_, PatternPiece>(
+            "SELECT * FROM pattern_pieces WHERE tenant_id = $1 AND recipe_id = $2 ORDER BY created_at, name"
+        )
+        .bind(tenant_id)
+        .bind(recipe_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Replaces the recipe's pattern pieces.
+    pub async fn set_pattern_pieces(&self, tenant_id: Uuid, recipe_id: Uuid, req: SetPatternPieces) -> Result<Vec<PatternPiece>, Error> {
+        for piece in &req.pieces {
+            piece.validate()?;
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM recipes WHERE id = $1 AND tenant_id = $2)")
+            .bind(recipe_id)
+            .bind(tenant_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if !exists {
+            return Err(Error::NotFound("Recipe not found".to_string()));
+        }
+
+        sqlx::query("DELETE FROM pattern_pieces WHERE recipe_id = $1")
+            .bind(recipe_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut pieces = Vec::with_capacity(req.pieces.len());
+        for piece in req.pieces {
+            let area = piece.area();
+            let record = sqlx::query_as::<_, PatternPiece>(
+                r#"
+                INSERT INTO pattern_pieces (tenant_id, recipe_id, name, outline, quantity, min_grade, allow_rotation, area)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING *
+                "#
+            )
+            .bind(tenant_id)
+            .bind(recipe_id)
+            .bind(piece.name)
+            .bind(Json(piece.outline))
+            .bind(piece.quantity.unwrap_or(1))
+            .bind(piece.min_grade.unwrap_or(QualityGrade::C))
+            .bind(piece.allow_rotation.unwrap_or(true))
+            .bind(area)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+            pieces.push(record);
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(pieces)
+    }
+
+    /// Works out a layout for the recipe on a hide without saving it.
+    pub async fn preview(&self, tenant_id: Uuid, recipe_id: Uuid, req: NestRequest) -> Result<NestingPreview, Error> {
+        let pieces = self.list_pattern_pieces(tenant_id, recipe_id).await?;
+        let (req, result) = run_nest(pieces, req).await?;
+        let svg = layout_svg(&req.hide, &result.placements);
+        Ok(NestingPreview { result, svg })
+    }
+
+    /// Nests the work order's recipe on a hide and saves the layout for the cutting table.
+    pub async fn create_layout(&self, tenant_id: Uuid, work_order_id: Uuid, req: NestRequest, created_by: Uuid) -> Result<NestingLayout, Error> {
+        let work_order = sqlx::query_as::<_, WorkOrder>(&format!(
+            "SELECT {} FROM work_orders WHERE id = $1 AND tenant_id = $2",
+            WORK_ORDER_COLUMNS
+        ))
+        .bind(work_order_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Work Order not found".to_string()))?;
+        if work_order.status == WorkOrderStatus::Completed || work_order.status == WorkOrderStatus::Cancelled {
+            return Err(Error::BusinessRule(format!("Work order is {:?}", work_order.status)));
+        }
+
+        let pieces = self.list_pattern_pieces(tenant_id, work_order.recipe_id).await?;
+        let (req, result) = run_nest(pieces, req).await?;
+
+        sqlx::query_as::<_, NestingLayout>(
+            r#"
+            INSERT INTO nesting_layouts (tenant_id, work_order_id, recipe_id, hide_reference, hide, placements, pieces, resolution, spacing,
+                                         usable_area, placed_area, expected_yield, requested_units, complete_units, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(work_order.id)
+        .bind(work_order.recipe_id)
+        .bind(req.hide.reference.clone())
+        .bind(Json(&req.hide))
+        .bind(Json(&result.placements))
+        .bind(Json(&result.pieces))
+        .bind(Decimal::try_from(req.resolution.unwrap_or(1.0)).unwrap_or(Decimal::ONE))
+        .bind(Decimal::try_from(req.spacing.unwrap_or(0.0)).unwrap_or_default())
+        .bind(result.usable_area)
+        .bind(result.placed_area)
+        .bind(result.expected_yield)
+        .bind(result.requested_units)
+        .bind(result.complete_units)
+        .bind(req.notes)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_layout(&self, tenant_id: Uuid, layout_id: Uuid) -> Result<NestingLayout, Error> {
+        sqlx::query_as::<_, NestingLayout>("SELECT * FROM nesting_layouts WHERE id = $1 AND tenant_id = $2")
+            .bind(layout_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Nesting layout not found".to_string()))
+    }
+
+    pub async fn layout_svg(&self, tenant_id: Uuid, layout_id: Uuid) -> Result<String, Error> {
+        let layout = self.get_layout(tenant_id, layout_id).await?;
+        Ok(layout_svg(&layout.hide.0, &layout.placements.0))
+    }
+
+    /// Records the good pieces cut from the hide; the actual yield is their pattern area over
+    /// the usable area of the hide.
+    pub async fn record_cutting(&self, tenant_id: Uuid, layout_id: Uuid, req: RecordCutting) -> Result<NestingLayout, Error> {
+        let layout = self.get_layout(tenant_id, layout_id).await?;
+
+        let mut cut_area = Decimal::ZERO;
+        for count in &req.pieces_cut {
+            if count.quantity < 0 {
+                return Err(Error::BusinessRule("Piece quantities cannot be negative".to_string()));
+            }
+            let piece = layout
+                .pieces
+                .0
+                .iter()
+                .find(|p| p.piece_id == count.piece_id)
+                .ok_or(Error::BusinessRule("Piece is not part of the layout".to_string()))?;
+            cut_area += piece.area * Decimal::from(count.quantity);
+        }
+        let actual_yield = if layout.usable_area > Decimal::ZERO {
+            (cut_area / layout.usable_area * Decimal::ONE_HUNDRED).round_dp(2)
+        } else {
+            Decimal::ZERO
+        };
+
+        sqlx::query_as::<_, NestingLayout>(
+            r#"
+            UPDATE nesting_layouts
+            SET actual_cut_area = $1, actual_yield = $2, recorded_at = NOW(), notes = COALESCE($3, notes)
+            WHERE id = $4
+            RETURNING *
+            "#
+        )
+        .bind(cut_area)
+        .bind(actual_yield)
+        .bind(req.notes)
+        .bind(layout.id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn work_order_nesting(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrderNesting, Error> {
+        let layouts = sqlx::query_as::<_, NestingLayout>(
+            "SELECT * FROM nesting_layouts WHERE tenant_id = $1 AND work_order_id = $2 ORDER BY created_at"
+        )
+        .bind(tenant_id)
+        .bind(work_order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+        Ok(WorkOrderNesting::build(work_order_id, layouts))
+    }
+}
+
+/// Nesting is CPU bound, so it runs off the async workers.
+async fn run_nest(pieces: Vec<PatternPiece>, req: NestRequest) -> Result<(NestRequest, NestingResult), Error> {
+    tokio::task::spawn_blocking(move || nest(&req.hide, &pieces, &req).map(|result| (req, result)))
+        .await
+        .map_err(|e| Error::BusinessRule(format!("Nesting failed: {}", e)))?
+}
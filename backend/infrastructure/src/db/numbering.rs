@@ -0,0 +1,58 @@
+use smart_erp_core::error::Error;
+use chrono::NaiveDate;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Next document number of the form PREFIX-YYYYMMDD-NNN, sequenced per tenant and day.
+/// The counter row stays locked until the caller's transaction ends, so concurrent callers
+/// queue behind it; numbers already taken under the stem (including hand-entered ones) are skipped.
+/// `table` and `column` are trusted identifiers from the calling repository, never user input.
+pub async fn next_document_number(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    table: &str,
+    column: &str,
+    prefix: &str,
+    date: NaiveDate,
+) -> Result<String, Error> {
+    let stem = format!("{}-{}-", prefix, date.format("%Y%m%d"));
+    let last: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO document_sequences (tenant_id, table_name, stem)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (tenant_id, table_name, stem) DO UPDATE SET last_value = document_sequences.last_value
+        RETURNING last_value
+        "#
+    )
+    .bind(tenant_id)
+    .bind(table)
+    .bind(&stem)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let taken: Option<i32> = sqlx::query_scalar(&format!(
+        r#"
+        SELECT MAX(SUBSTRING({column} FROM LENGTH($2) + 1)::INTEGER) FROM {table}
+        WHERE tenant_id = $1 AND LEFT({column}, LENGTH($2)) = $2
+          AND SUBSTRING({column} FROM LENGTH($2) + 1) ~ '^[0-9]{{1,9}}$'
+        "#
+    ))
+    .bind(tenant_id)
+    .bind(&stem)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let next = last.max(taken.unwrap_or(0)) + 1;
+    sqlx::query("UPDATE document_sequences SET last_value = $1 WHERE tenant_id = $2 AND table_name = $3 AND stem = $4")
+        .bind(next)
+        .bind(tenant_id)
+        .bind(table)
+        .bind(&stem)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(format!("{}{:03}", stem, next))
+}
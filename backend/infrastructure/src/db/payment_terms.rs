@@ -0,0 +1,182 @@
+use smart_erp_core::models::payment_terms::*;
+use smart_erp_core::models::sales::Customer;
+use smart_erp_core::error::Error;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Whose default terms apply when a document does not name any.
+pub enum TermsParty {
+    Customer(Uuid),
+    Supplier(Uuid),
+}
+
+pub struct PostgresPaymentTermsRepository {
+    pool: PgPool,
+}
+
+impl PostgresPaymentTermsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_terms(&self, tenant_id: Uuid) -> Result<Vec<PaymentTerms>, Error> {
+        sqlx::query_as::<_, PaymentTerms>(
+            "SELECT * FROM payment_terms WHERE tenant_id = $1 ORDER BY is_active DESC, name"
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_terms(&self, tenant_id: Uuid, req: CreatePaymentTerms) -> Result<PaymentTerms, Error> {
+        req.validate()?;
+        let terms_type = req.terms_type.unwrap_or(PaymentTermsType::Net);
+
+        sqlx::query_as::<_, PaymentTerms>(
+            r#"
+            INSERT INTO payment_terms (tenant_id, name, terms_type, net_days, day_of_month, discount_percent, discount_days)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.name.trim())
+        .bind(terms_type)
+        .bind(req.net_days.unwrap_or(0))
+        .bind(if terms_type == PaymentTermsType::DayOfMonth { req.day_of_month } else { None })
+        .bind(req.discount_percent.unwrap_or(Decimal::ZERO))
+        .bind(req.discount_days.unwrap_or(0))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn update_terms(&self, tenant_id: Uuid, terms_id: Uuid, req: UpdatePaymentTerms) -> Result<PaymentTerms, Error> {
+        if req.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(Error::BusinessRule("Payment terms need a name".to_string()));
+        }
+
+        sqlx::query_as::<_, PaymentTerms>(
+            r#"
+            UPDATE payment_terms
+            SET name = COALESCE($1, name), is_active = COALESCE($2, is_active), updated_at = NOW()
+            WHERE id = $3 AND tenant_id = $4
+            RETURNING *
+            "#
+        )
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(req.is_active)
+        .bind(terms_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Payment terms not found".to_string()))
+    }
+
+    /// Sets the terms new invoices for the customer default to.
+    pub async fn set_customer_terms(&self, tenant_id: Uuid, customer_id: Uuid, req: SetPaymentTerms) -> Result<Customer, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let name = match req.payment_terms_id {
+            Some(id) => Some(active_terms(&mut tx, tenant_id, id).await?.name),
+            None => None,
+        };
+
+        let customer = sqlx::query_as::<_, Customer>(
+            r#"
+            UPDATE customers SET payment_terms_id = $1, terms = COALESCE($2, terms), updated_at = NOW()
+            WHERE id = $3 AND tenant_id = $4
+            RETURNING *
+            "#
+        )
+        .bind(req.payment_terms_id)
+        .bind(name)
+        .bind(customer_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Customer not found".to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(customer)
+    }
+}
+
+pub async fn active_terms(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, terms_id: Uuid) -> Result<PaymentTerms, Error> {
+    let terms = sqlx::query_as::<_, PaymentTerms>("SELECT * FROM payment_terms WHERE id = $1 AND tenant_id = $2")
+        .bind(terms_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Payment terms not found".to_string()))?;
+
+    if !terms.is_active {
+        return Err(Error::BusinessRule(format!("Payment terms {} are no longer in use", terms.name)));
+    }
+    Ok(terms)
+}
+
+/// Picks the document's terms (its own, else the party's default) and works out when it is due.
+/// A due date given on the document wins over the one from the terms.
+pub async fn document_terms(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    terms_id: Option<Uuid>,
+    party: TermsParty,
+    date: NaiveDate,
+    due_date: Option<NaiveDate>,
+    total_amount: Decimal,
+) -> Result<(Option<PaymentTerms>, TermsSchedule), Error> {
+    let terms_id = match terms_id {
+        Some(id) => Some(id),
+        None => {
+            let (table, party_id) = match party {
+                TermsParty::Customer(id) => ("customers", id),
+                TermsParty::Supplier(id) => ("suppliers", id),
+            };
+            // Parties loaded without structured terms still carry the free-text name
+            let sql = format!(
+                r#"
+                SELECT COALESCE(p.payment_terms_id,
+                    (SELECT pt.id FROM payment_terms pt WHERE pt.tenant_id = p.tenant_id AND pt.name = p.terms AND pt.is_active))
+                FROM {} p WHERE p.id = $1 AND p.tenant_id = $2
+                "#,
+                table
+            );
+            sqlx::query_scalar::<_, Option<Uuid>>(&sql)
+                .bind(party_id)
+                .bind(tenant_id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?
+                .flatten()
+        }
+    };
+
+    let terms = match terms_id {
+        Some(id) => Some(active_terms(tx, tenant_id, id).await?),
+        None => None,
+    };
+
+    let schedule = match (&terms, due_date) {
+        (Some(terms), due_date) => {
+            let mut schedule = terms.schedule(date, total_amount);
+            if let Some(due_date) = due_date {
+                schedule.due_date = due_date;
+                schedule.discount_date = schedule.discount_date.map(|d| d.min(due_date));
+            }
+            schedule
+        }
+        (None, Some(due_date)) => TermsSchedule { due_date, discount_date: None, discount_amount: Decimal::ZERO },
+        (None, None) => return Err(Error::BusinessRule("Give a due date or payment terms".to_string())),
+    };
+    if schedule.due_date < date {
+        return Err(Error::BusinessRule("Due date is before the document date".to_string()));
+    }
+    Ok((terms, schedule))
+}
@@ -23,9 +23,9 @@ impl ProductService for PostgresProductRepository {
     ) -> Result<Product, Error> {
         let record = sqlx::query_as::<_, Product>(
             r#"
-            INSERT INTO products (tenant_id, name, sku, description, unit_of_measure, price, cost_price)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, tenant_id, name, sku, description, unit_of_measure, price, cost_price, stock_quantity, created_at, updated_at
+            INSERT INTO products (tenant_id, name, sku, description, unit_of_measure, price, cost_price, average_cost, standard_cost, category)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $7, $7, $8)
+            RETURNING id, tenant_id, name, sku, description, unit_of_measure, price, cost_price, stock_quantity, category, created_at, updated_at
             "#
         )
         .bind(tenant_id)
@@ -35,6 +35,7 @@ impl ProductService for PostgresProductRepository {
         .bind(product.unit_of_measure)
         .bind(product.price)
         .bind(product.cost_price)
+        .bind(product.category)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
@@ -45,7 +46,7 @@ impl ProductService for PostgresProductRepository {
     async fn list_products(&self, tenant_id: Uuid) -> Result<Vec<Product>, Error> {
         let records = sqlx::query_as::<_, Product>(
             r#"
-            SELECT id, tenant_id, name, sku, description, unit_of_measure, price, cost_price, stock_quantity, created_at, updated_at
+            SELECT id, tenant_id, name, sku, description, unit_of_measure, price, cost_price, stock_quantity, category, created_at, updated_at
             FROM products
             WHERE tenant_id = $1
             ORDER BY name
@@ -62,7 +63,7 @@ impl ProductService for PostgresProductRepository {
     async fn get_product(&self, tenant_id: Uuid, product_id: Uuid) -> Result<Product, Error> {
         let record = sqlx::query_as::<_, Product>(
             r#"
-            SELECT id, tenant_id, name, sku, description, unit_of_measure, price, cost_price, stock_quantity, created_at, updated_at
+            SELECT id, tenant_id, name, sku, description, unit_of_measure, price, cost_price, stock_quantity, category, created_at, updated_at
             FROM products
             WHERE id = $1 AND tenant_id = $2
             "#
@@ -0,0 +1,1225 @@
+use std::collections::HashMap;
+
+use smart_erp_core::models::production::*;
+use smart_erp_core::models::manufacturing::{Recipe, RecipeIngredient, WorkOrder, WorkOrderStatus};
+use smart_erp_core::models::inventory::{CreateInventoryTransaction, TransactionType};
+use smart_erp_core::models::quality::InspectionType;
+use smart_erp_core::error::Error;
+use crate::db::compliance::{consume_lots, return_lots};
+use crate::db::inventory::insert_transaction;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::manufacturing::WORK_ORDER_COLUMNS;
+use crate::db::numbering::next_document_number;
+use crate::db::quality::hold_for_inspection;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresProductionRepository {
+    pool: PgPool,
+}
+
+impl PostgresProductionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_work_order(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrderDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let detail = load_detail(&mut tx, tenant_id, work_order_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    pub async fn start(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrder, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = lock_work_order(&mut tx, tenant_id, work_order_id).await?;
+        if work_order.status != WorkOrderStatus::Planned {
+            return Err(Error::BusinessRule(format!("Cannot start work order with status {:?}.", work_order.status)));
+        }
+        let work_order = start_work_order(&mut tx, work_order).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(work_order)
+    }
+
+    /// Issues material to the work order, starting it if it is still planned.
+    pub async fn issue_materials(&self, tenant_id: Uuid, work_order_id: Uuid, req: IssueMaterials) -> Result<WorkOrderDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = open_work_order(&mut tx, tenant_id, work_order_id).await?;
+        let work_order = start_work_order(&mut tx, work_order).await?;
+
+        let lines = match req.lines {
+            Some(lines) => lines,
+            None => load_materials(&mut tx, work_order_id)
+                .await?
+                .into_iter()
+                .filter(|m| m.outstanding() > Decimal::ZERO)
+                .map(|m| MaterialQuantity { product_id: m.product_id, quantity: m.outstanding(), lot_id: None })
+                .collect(),
+        };
+        if lines.is_empty() {
+            return Err(Error::BusinessRule("Nothing left to issue".to_string()));
+        }
+        for line in lines {
+            issue_material(&mut tx, tenant_id, &work_order, line.product_id, line.quantity, line.lot_id).await?;
+        }
+
+        let detail = load_detail(&mut tx, tenant_id, work_order_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    /// Puts unused material (e.g. offcuts) back into stock at its issue cost.
+    pub async fn return_materials(&self, tenant_id: Uuid, work_order_id: Uuid, req: ReturnMaterials) -> Result<WorkOrderDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = open_work_order(&mut tx, tenant_id, work_order_id).await?;
+        if req.lines.is_empty() {
+            return Err(Error::BusinessRule("Nothing to return".to_string()));
+        }
+        for line in req.lines {
+            return_material(&mut tx, tenant_id, &work_order, line.product_id, line.quantity, line.lot_id).await?;
+        }
+
+        let detail = load_detail(&mut tx, tenant_id, work_order_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    pub async fn report_production(&self, tenant_id: Uuid, work_order_id: Uuid, req: ReportProduction, reported_by: Uuid) -> Result<WorkOrderDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = open_work_order(&mut tx, tenant_id, work_order_id).await?;
+        report(&mut tx, tenant_id, work_order, req, Some(reported_by)).await?;
+
+        let detail = load_detail(&mut tx, tenant_id, work_order_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    /// Cancels the work order and returns material not consumed by the output already
+    /// reported. Output already in stock stays there.
+    pub async fn cancel(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrderDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = open_work_order(&mut tx, tenant_id, work_order_id).await?;
+        let (recipe, ingredients) = load_recipe(&mut tx, work_order.recipe_id).await?;
+        let batches_used = batches_for(&recipe, work_order.produced_quantity + work_order.scrapped_quantity);
+
+        for material in load_materials(&mut tx, work_order_id).await? {
+            let standard: Decimal = ingredients
+                .iter()
+                .filter(|i| i.input_product_id == material.product_id)
+                .map(|i| i.quantity * batches_used)
+                .sum();
+            let unused = material.net_issued() - standard - material.scrapped_quantity;
+            if unused > Decimal::ZERO {
+                return_material(&mut tx, tenant_id, &work_order, material.product_id, unused.round_dp(4), None).await?;
+            }
+        }
+
+        let work_order = lock_work_order(&mut tx, tenant_id, work_order_id).await?;
+        close_work_order(&mut tx, tenant_id, work_order, WorkOrderStatus::Cancelled).await?;
+
+        let detail = load_detail(&mut tx, tenant_id, work_order_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    pub async fn work_order_yield(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrderYield, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let work_order = fetch_work_order(&mut tx, tenant_id, work_order_id).await?;
+        let labels = load_labels(&mut tx, tenant_id).await?;
+        let report = build_yield(&mut tx, &work_order, &labels).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(report)
+    }
+
+    /// Yield of work orders completed in the period (last 30 days by default).
+    pub async fn yield_variance(&self, tenant_id: Uuid, query: YieldVarianceQuery) -> Result<YieldVarianceReport, Error> {
+        let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = query.from.unwrap_or(to - chrono::Duration::days(30));
+        if from > to {
+            return Err(Error::BusinessRule("'from' date must not be after 'to' date".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_orders = sqlx::query_as::<_, WorkOrder>(&format!(
+            r#"
+            SELECT {} FROM work_orders
+            WHERE tenant_id = $1 AND status = 'COMPLETED' AND end_date BETWEEN $2 AND $3
+              AND ($4::uuid IS NULL OR recipe_id = $4)
+            ORDER BY end_date, created_at
+            "#,
+            WORK_ORDER_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(from)
+        .bind(to)
+        .bind(query.recipe_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let labels = load_labels(&mut tx, tenant_id).await?;
+        let mut yields = Vec::with_capacity(work_orders.len());
+        for work_order in &work_orders {
+            yields.push(build_yield(&mut tx, work_order, &labels).await?);
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(YieldVarianceReport {
+            from,
+            to,
+            standard_material_cost: yields.iter().map(|y| y.standard_material_cost).sum(),
+            actual_material_cost: yields.iter().map(|y| y.actual_material_cost).sum(),
+            yield_variance: yields.iter().map(|y| y.yield_variance).sum(),
+            work_orders: yields,
+        })
+    }
+
+    /// Logs direct labor against the work order at the employee's rate and applies
+    /// labor-based overhead.
+    pub async fn log_labor(&self, tenant_id: Uuid, work_order_id: Uuid, req: CreateLaborTimeEntry, created_by: Uuid) -> Result<LaborTimeEntry, Error> {
+        if req.hours <= Decimal::ZERO || req.hourly_rate.is_some_and(|r| r < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Hours must be positive and the rate not negative".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = open_work_order(&mut tx, tenant_id, work_order_id).await?;
+        let work_order = start_work_order(&mut tx, work_order).await?;
+        check_operation(&mut tx, work_order.id, req.work_order_operation_id).await?;
+
+        let entry = post_labor(&mut tx, tenant_id, &work_order, LaborPosting {
+            employee_id: req.employee_id,
+            work_order_operation_id: req.work_order_operation_id,
+            work_date: req.work_date.unwrap_or_else(|| Utc::now().date_naive()),
+            hours: req.hours,
+            hourly_rate: req.hourly_rate,
+            entry_method: LaborEntryMethod::Manual,
+            clock_in: None,
+            clock_out: None,
+            notes: req.notes,
+        }, created_by).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(entry)
+    }
+
+    pub async fn clock_in(&self, tenant_id: Uuid, work_order_id: Uuid, req: ClockIn, created_by: Uuid) -> Result<LaborClock, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = open_work_order(&mut tx, tenant_id, work_order_id).await?;
+        let work_order = start_work_order(&mut tx, work_order).await?;
+        check_operation(&mut tx, work_order.id, req.work_order_operation_id).await?;
+        active_employee(&mut tx, tenant_id, req.employee_id).await?;
+
+        if let Some(open) = open_clock(&mut tx, tenant_id, req.employee_id).await? {
+            return Err(Error::BusinessRule(format!("Employee is already clocked in on work order {}", open.work_order_id)));
+        }
+
+        let clock = sqlx::query_as::<_, LaborClock>(
+            r#"
+            INSERT INTO labor_clocks (tenant_id, employee_id, work_order_id, work_order_operation_id, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.employee_id)
+        .bind(work_order.id)
+        .bind(req.work_order_operation_id)
+        .bind(req.notes)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(clock)
+    }
+
+    /// Closes the employee's open clock and posts the hours worked as a labor time entry.
+    pub async fn clock_out(&self, tenant_id: Uuid, req: ClockOut, created_by: Uuid) -> Result<LaborTimeEntry, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let clock = open_clock(&mut tx, tenant_id, req.employee_id)
+            .await?
+            .ok_or(Error::BusinessRule("Employee is not clocked in".to_string()))?;
+        let clock_out = Utc::now();
+        let hours = clock.hours_until(clock_out);
+        if hours <= Decimal::ZERO {
+            return Err(Error::BusinessRule("Clocked in for under a minute; cancel the clock-in instead".to_string()));
+        }
+
+        sqlx::query("DELETE FROM labor_clocks WHERE id = $1")
+            .bind(clock.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = open_work_order(&mut tx, tenant_id, clock.work_order_id).await?;
+        let notes = match (clock.notes, req.notes) {
+            (Some(a), Some(b)) => Some(format!("{}; {}", a, b)),
+            (a, b) => a.or(b),
+        };
+        let entry = post_labor(&mut tx, tenant_id, &work_order, LaborPosting {
+            employee_id: clock.employee_id,
+            work_order_operation_id: clock.work_order_operation_id,
+            work_date: clock.clock_in.date_naive(),
+            hours,
+            hourly_rate: None,
+            entry_method: LaborEntryMethod::Clock,
+            clock_in: Some(clock.clock_in),
+            clock_out: Some(clock_out),
+            notes,
+        }, created_by).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(entry)
+    }
+
+    /// Drops a clock-in made by mistake without posting any labor.
+    pub async fn cancel_clock(&self, tenant_id: Uuid, clock_id: Uuid) -> Result<(), Error> {
+        let result = sqlx::query("DELETE FROM labor_clocks WHERE id = $1 AND tenant_id = $2")
+            .bind(clock_id)
+            .bind(tenant_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound("Clock-in not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn list_clocks(&self, tenant_id: Uuid) -> Result<Vec<LaborClock>, Error> {
+        sqlx::query_as::<_, LaborClock>("SELECT * FROM labor_clocks WHERE tenant_id = $1 ORDER BY clock_in")
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn list_labor(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<Vec<LaborTimeEntry>, Error> {
+        sqlx::query_as::<_, LaborTimeEntry>(
+            "SELECT * FROM labor_time_entries WHERE tenant_id = $1 AND work_order_id = $2 ORDER BY work_date, created_at"
+        )
+        .bind(tenant_id)
+        .bind(work_order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Actual cost to date: material, labor and overhead in, output relieved and, once closed,
+    /// the variances written off.
+    pub async fn work_order_cost(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrderCost, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let work_order = fetch_work_order(&mut tx, tenant_id, work_order_id).await?;
+        let (recipe, _) = load_recipe(&mut tx, work_order.recipe_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let labor_entries = self.list_labor(tenant_id, work_order_id).await?;
+        Ok(WorkOrderCost::build(&work_order, &recipe, labor_entries))
+    }
+
+    /// Planned against actual hours for labor booked in the period, by operation and by employee.
+    pub async fn labor_efficiency(&self, tenant_id: Uuid, query: LaborEfficiencyQuery) -> Result<LaborEfficiencyReport, Error> {
+        let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = query.from.unwrap_or(to - chrono::Duration::days(30));
+        if from > to {
+            return Err(Error::BusinessRule("'from' date must not be after 'to' date".to_string()));
+        }
+
+        let mut operations = sqlx::query_as::<_, OperationEfficiency>(
+            r#"
+            SELECT o.id AS work_order_operation_id, o.work_order_id, r.name AS recipe_name,
+                   o.sequence, o.name AS operation_name, o.work_center_id, o.planned_hours,
+                   SUM(l.hours) AS actual_hours, SUM(l.labor_cost) AS labor_cost
+            FROM labor_time_entries l
+            JOIN work_order_operations o ON o.id = l.work_order_operation_id
+            JOIN work_orders w ON w.id = o.work_order_id
+            JOIN recipes r ON r.id = w.recipe_id
+            WHERE l.tenant_id = $1 AND l.work_date BETWEEN $2 AND $3
+              AND ($4::uuid IS NULL OR l.work_order_id = $4)
+            GROUP BY o.id, r.name, w.created_at
+            ORDER BY w.created_at, o.sequence
+            "#
+        )
+        .bind(tenant_id)
+        .bind(from)
+        .bind(to)
+        .bind(query.work_order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        // Each employee earns the operation's planned hours in proportion to the hours they booked.
+        let mut employees = sqlx::query_as::<_, EmployeeEfficiency>(
+            r#"
+            WITH booked AS (
+                SELECT l.work_order_operation_id, SUM(l.hours) AS hours
+                FROM labor_time_entries l
+                WHERE l.tenant_id = $1 AND l.work_date BETWEEN $2 AND $3 AND l.work_order_operation_id IS NOT NULL
+                GROUP BY l.work_order_operation_id
+            )
+            SELECT e.id AS employee_id, e.first_name, e.last_name,
+                   COALESCE(SUM(l.hours) FILTER (WHERE l.work_order_operation_id IS NOT NULL), 0) AS operation_hours,
+                   COALESCE(SUM(l.hours) FILTER (WHERE l.work_order_operation_id IS NULL), 0) AS unplanned_hours,
+                   ROUND(COALESCE(SUM(o.planned_hours * l.hours / b.hours), 0), 2) AS earned_hours,
+                   SUM(l.labor_cost) AS labor_cost
+            FROM labor_time_entries l
+            JOIN employees e ON e.id = l.employee_id
+            LEFT JOIN work_order_operations o ON o.id = l.work_order_operation_id
+            LEFT JOIN booked b ON b.work_order_operation_id = l.work_order_operation_id
+            WHERE l.tenant_id = $1 AND l.work_date BETWEEN $2 AND $3
+              AND ($4::uuid IS NULL OR l.work_order_id = $4)
+            GROUP BY e.id
+            ORDER BY e.last_name, e.first_name
+            "#
+        )
+        .bind(tenant_id)
+        .bind(from)
+        .bind(to)
+        .bind(query.work_order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for operation in &mut operations {
+            operation.efficiency = labor_efficiency(operation.planned_hours, operation.actual_hours);
+        }
+        for employee in &mut employees {
+            employee.efficiency = labor_efficiency(employee.earned_hours, employee.operation_hours);
+        }
+        let actual_hours: Decimal = operations.iter().map(|o| o.actual_hours).sum();
+        let earned_hours: Decimal = operations.iter().map(|o| o.planned_hours).sum();
+
+        Ok(LaborEfficiencyReport {
+            from,
+            to,
+            actual_hours,
+            earned_hours,
+            efficiency: labor_efficiency(earned_hours, actual_hours),
+            operations,
+            employees,
+        })
+    }
+
+    pub async fn list_overhead_rates(&self, tenant_id: Uuid) -> Result<Vec<OverheadRate>, Error> {
+        sqlx::query_as::<_, OverheadRate>("SELECT * FROM overhead_rates WHERE tenant_id = $1 ORDER BY name")
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_overhead_rate(&self, tenant_id: Uuid, req: CreateOverheadRate) -> Result<OverheadRate, Error> {
+        if req.name.trim().is_empty() || req.rate < Decimal::ZERO {
+            return Err(Error::BusinessRule("Overhead rate needs a name and a rate of zero or more".to_string()));
+        }
+
+        sqlx::query_as::<_, OverheadRate>(
+            "INSERT INTO overhead_rates (tenant_id, name, basis, rate) VALUES ($1, $2, $3, $4) RETURNING *"
+        )
+        .bind(tenant_id)
+        .bind(req.name.trim())
+        .bind(req.basis)
+        .bind(req.rate)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn update_overhead_rate(&self, tenant_id: Uuid, rate_id: Uuid, req: UpdateOverheadRate) -> Result<OverheadRate, Error> {
+        if req.rate.is_some_and(|r| r < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Rate cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, OverheadRate>(
+            r#"
+            UPDATE overhead_rates
+            SET name = COALESCE($1, name), rate = COALESCE($2, rate), is_active = COALESCE($3, is_active), updated_at = NOW()
+            WHERE id = $4 AND tenant_id = $5
+            RETURNING *
+            "#
+        )
+        .bind(req.name)
+        .bind(req.rate)
+        .bind(req.is_active)
+        .bind(rate_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Overhead rate not found".to_string()))
+    }
+}
+
+/// Plans the recipe quantities for a new work order, by-products included.
+pub async fn create_materials(tx: &mut Transaction<'_, Postgres>, work_order: &WorkOrder) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO work_order_materials (work_order_id, product_id, planned_quantity)
+        SELECT $1, input_product_id, SUM(quantity) * $2 FROM recipe_ingredients WHERE recipe_id = $3
+        GROUP BY input_product_id
+        "#
+    )
+    .bind(work_order.id)
+    .bind(work_order.quantity)
+    .bind(work_order.recipe_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO work_order_outputs (work_order_id, product_id, output_type, cost_share, planned_quantity)
+        SELECT $1, product_id, output_type, cost_share, quantity * $2 FROM recipe_outputs WHERE recipe_id = $3
+        "#
+    )
+    .bind(work_order.id)
+    .bind(work_order.quantity)
+    .bind(work_order.recipe_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// One-step completion: a work order with nothing reported gets its outstanding material
+/// issued and its full planned output reported before it is closed.
+pub async fn complete(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrder, Error> {
+    let work_order = lock_work_order(tx, tenant_id, work_order_id).await?;
+    if work_order.status == WorkOrderStatus::Completed || work_order.status == WorkOrderStatus::Cancelled {
+        return Err(Error::BusinessRule(format!(
+            "Cannot complete work order with status {:?}.",
+            work_order.status
+        )));
+    }
+
+    let has_reports: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM work_order_reports WHERE work_order_id = $1)")
+        .bind(work_order_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    let work_order = if has_reports {
+        work_order
+    } else {
+        let work_order = start_work_order(tx, work_order).await?;
+        for material in load_materials(tx, work_order_id).await? {
+            if material.outstanding() > Decimal::ZERO {
+                issue_material(tx, tenant_id, &work_order, material.product_id, material.outstanding(), None).await?;
+            }
+        }
+        let (recipe, _) = load_recipe(tx, work_order.recipe_id).await?;
+        let planned_output = recipe.output_quantity * work_order.quantity;
+        let work_order = lock_work_order(tx, tenant_id, work_order_id).await?;
+        report(tx, tenant_id, work_order, ReportProduction {
+            quantity: planned_output,
+            consumption: Vec::new(),
+            scrap: Vec::new(),
+            by_products: Vec::new(),
+            notes: None,
+        }, None).await?;
+        lock_work_order(tx, tenant_id, work_order_id).await?
+    };
+
+    close_work_order(tx, tenant_id, work_order, WorkOrderStatus::Completed).await
+}
+
+async fn fetch_work_order(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrder, Error> {
+    sqlx::query_as::<_, WorkOrder>(&format!("SELECT {} FROM work_orders WHERE id = $1 AND tenant_id = $2", WORK_ORDER_COLUMNS))
+        .bind(work_order_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Work Order not found".to_string()))
+}
+
+async fn lock_work_order(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrder, Error> {
+    sqlx::query_as::<_, WorkOrder>(&format!("SELECT {} FROM work_orders WHERE id = $1 AND tenant_id = $2 FOR UPDATE", WORK_ORDER_COLUMNS))
+        .bind(work_order_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Work Order not found".to_string()))
+}
+
+/// Locks a work order that is still planned or in progress.
+async fn open_work_order(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrder, Error> {
+    let work_order = lock_work_order(tx, tenant_id, work_order_id).await?;
+    if work_order.status == WorkOrderStatus::Completed || work_order.status == WorkOrderStatus::Cancelled {
+        return Err(Error::BusinessRule(format!("Work order is {:?}", work_order.status)));
+    }
+    Ok(work_order)
+}
+
+async fn start_work_order(tx: &mut Transaction<'_, Postgres>, work_order: WorkOrder) -> Result<WorkOrder, Error> {
+    if work_order.status != WorkOrderStatus::Planned {
+        return Ok(work_order);
+    }
+
+    sqlx::query_as::<_, WorkOrder>(&format!(
+        r#"
+        UPDATE work_orders
+        SET status = 'IN_PROGRESS', started_at = NOW(), start_date = COALESCE(start_date, CURRENT_DATE), updated_at = NOW()
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        WORK_ORDER_COLUMNS
+    ))
+    .bind(work_order.id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+struct LaborPosting {
+    employee_id: Uuid,
+    work_order_operation_id: Option<Uuid>,
+    work_date: chrono::NaiveDate,
+    hours: Decimal,
+    hourly_rate: Option<Decimal>, // Defaults to the employee's pay rate
+    entry_method: LaborEntryMethod,
+    clock_in: Option<chrono::DateTime<Utc>>,
+    clock_out: Option<chrono::DateTime<Utc>>,
+    notes: Option<String>,
+}
+
+/// Costs the hours into WIP with labor-based overhead and adds them to the work order.
+async fn post_labor(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order: &WorkOrder, labor: LaborPosting, created_by: Uuid) -> Result<LaborTimeEntry, Error> {
+    let (pay_type, pay_rate) = active_employee(tx, tenant_id, labor.employee_id).await?;
+
+    // Salaried pay rates are annual; spread them over a standard 2,080-hour year.
+    let hourly_rate = labor.hourly_rate.unwrap_or(if pay_type == "SALARY" {
+        (pay_rate / Decimal::from(2080)).round_dp(2)
+    } else {
+        pay_rate
+    });
+    let labor_cost = (labor.hours * hourly_rate).round_dp(2);
+    let overhead_cost: Decimal = load_overhead_rates(tx, tenant_id)
+        .await?
+        .iter()
+        .map(|r| match r.basis {
+            OverheadBasis::LaborHours => r.apply(labor.hours),
+            OverheadBasis::LaborCost => r.apply(labor_cost),
+            OverheadBasis::MaterialCost => Decimal::ZERO,
+        })
+        .sum();
+
+    let journal_entry_id = post_wip(tx, tenant_id, "LAB", &format!("Labor WO {}", work_order.id), &[
+        ("5200", labor_cost),
+        ("5300", overhead_cost),
+    ]).await?;
+
+    let entry = sqlx::query_as::<_, LaborTimeEntry>(
+        r#"
+        INSERT INTO labor_time_entries (tenant_id, work_order_id, employee_id, work_order_operation_id, work_date, hours, hourly_rate,
+                                        labor_cost, overhead_cost, entry_method, clock_in, clock_out, journal_entry_id, notes, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        RETURNING *
+        "#
+    )
+    .bind(tenant_id)
+    .bind(work_order.id)
+    .bind(labor.employee_id)
+    .bind(labor.work_order_operation_id)
+    .bind(labor.work_date)
+    .bind(labor.hours)
+    .bind(hourly_rate)
+    .bind(labor_cost)
+    .bind(overhead_cost)
+    .bind(labor.entry_method)
+    .bind(labor.clock_in)
+    .bind(labor.clock_out)
+    .bind(journal_entry_id)
+    .bind(labor.notes)
+    .bind(created_by)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET labor_hours = labor_hours + $1, labor_cost = labor_cost + $2, overhead_cost = overhead_cost + $3, updated_at = NOW()
+        WHERE id = $4
+        "#
+    )
+    .bind(labor.hours)
+    .bind(labor_cost)
+    .bind(overhead_cost)
+    .bind(work_order.id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(entry)
+}
+
+/// Pay type and rate of an active employee.
+async fn active_employee(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, employee_id: Uuid) -> Result<(String, Decimal), Error> {
+    sqlx::query_as(
+        "SELECT pay_type, pay_rate FROM employees WHERE id = $1 AND tenant_id = $2 AND status = 'ACTIVE'"
+    )
+    .bind(employee_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Active employee not found".to_string()))
+}
+
+async fn check_operation(tx: &mut Transaction<'_, Postgres>, work_order_id: Uuid, operation_id: Option<Uuid>) -> Result<(), Error> {
+    let Some(operation_id) = operation_id else {
+        return Ok(());
+    };
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM work_order_operations WHERE id = $1 AND work_order_id = $2)")
+        .bind(operation_id)
+        .bind(work_order_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    if !exists {
+        return Err(Error::BusinessRule("Operation is not on this work order".to_string()));
+    }
+    Ok(())
+}
+
+async fn open_clock(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, employee_id: Uuid) -> Result<Option<LaborClock>, Error> {
+    sqlx::query_as::<_, LaborClock>("SELECT * FROM labor_clocks WHERE tenant_id = $1 AND employee_id = $2 FOR UPDATE")
+        .bind(tenant_id)
+        .bind(employee_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+}
+
+async fn load_recipe(tx: &mut Transaction<'_, Postgres>, recipe_id: Uuid) -> Result<(Recipe, Vec<RecipeIngredient>), Error> {
+    let recipe = sqlx::query_as::<_, Recipe>("SELECT * FROM recipes WHERE id = $1")
+        .bind(recipe_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    let ingredients = sqlx::query_as::<_, RecipeIngredient>("SELECT * FROM recipe_ingredients WHERE recipe_id = $1")
+        .bind(recipe_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok((recipe, ingredients))
+}
+
+async fn load_materials(tx: &mut Transaction<'_, Postgres>, work_order_id: Uuid) -> Result<Vec<WorkOrderMaterial>, Error> {
+    sqlx::query_as::<_, WorkOrderMaterial>(
+        "SELECT * FROM work_order_materials WHERE work_order_id = $1 ORDER BY planned_quantity DESC, product_id"
+    )
+    .bind(work_order_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+async fn load_outputs(tx: &mut Transaction<'_, Postgres>, work_order_id: Uuid) -> Result<Vec<WorkOrderOutput>, Error> {
+    sqlx::query_as::<_, WorkOrderOutput>(
+        "SELECT * FROM work_order_outputs WHERE work_order_id = $1 ORDER BY cost_share DESC, product_id"
+    )
+    .bind(work_order_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+async fn load_detail(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrderDetail, Error> {
+    let work_order = fetch_work_order(tx, tenant_id, work_order_id).await?;
+    let materials = load_materials(tx, work_order_id).await?;
+    let by_products = load_outputs(tx, work_order_id).await?;
+
+    let reports = sqlx::query_as::<_, ProductionReport>(
+        "SELECT * FROM work_order_reports WHERE work_order_id = $1 ORDER BY created_at"
+    )
+    .bind(work_order_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let scrap = sqlx::query_as::<_, WorkOrderScrap>(
+        "SELECT * FROM work_order_scrap WHERE work_order_id = $1 ORDER BY created_at"
+    )
+    .bind(work_order_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(WorkOrderDetail { work_order, materials, by_products, reports, scrap })
+}
+
+async fn load_labels(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid) -> Result<HashMap<Uuid, MaterialLabel>, Error> {
+    let labels = sqlx::query_as::<_, MaterialLabel>("SELECT id, sku, name FROM products WHERE tenant_id = $1")
+        .bind(tenant_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(labels.into_iter().map(|l| (l.id, l)).collect())
+}
+
+async fn build_yield(tx: &mut Transaction<'_, Postgres>, work_order: &WorkOrder, labels: &HashMap<Uuid, MaterialLabel>) -> Result<WorkOrderYield, Error> {
+    let (recipe, ingredients) = load_recipe(tx, work_order.recipe_id).await?;
+    let materials = load_materials(tx, work_order.id).await?;
+    Ok(WorkOrderYield::build(work_order, &recipe, &ingredients, &materials, labels))
+}
+
+fn batches_for(recipe: &Recipe, output_quantity: Decimal) -> Decimal {
+    if recipe.output_quantity > Decimal::ZERO {
+        output_quantity / recipe.output_quantity
+    } else {
+        Decimal::ZERO
+    }
+}
+
+/// Pairs each planned by-product with the quantity made alongside `batches` of the primary output.
+fn by_product_quantities(outputs: &[WorkOrderOutput], reported: &[MaterialQuantity], batches: Decimal, work_order_batches: Decimal) -> Vec<(WorkOrderOutput, Decimal)> {
+    outputs
+        .iter()
+        .map(|output| {
+            let quantity = match reported.iter().find(|r| r.product_id == output.product_id) {
+                Some(line) => line.quantity,
+                None if work_order_batches > Decimal::ZERO => (output.planned_quantity / work_order_batches * batches).round_dp(4),
+                None => Decimal::ZERO,
+            };
+            (output.clone(), quantity)
+        })
+        .collect()
+}
+
+/// Takes material out of stock into the work order. Fails rather than driving stock negative
+/// or consuming stock held in quarantine.
+async fn issue_material(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order: &WorkOrder, product_id: Uuid, quantity: Decimal, lot_id: Option<Uuid>) -> Result<Decimal, Error> {
+    if quantity <= Decimal::ZERO {
+        return Err(Error::BusinessRule("Issue quantity must be positive".to_string()));
+    }
+
+    let (sku, stock, quarantined): (String, Decimal, Decimal) = sqlx::query_as(
+        "SELECT sku, stock_quantity, quarantined_quantity FROM products WHERE id = $1 AND tenant_id = $2 FOR UPDATE"
+    )
+    .bind(product_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+    if stock - quarantined < quantity {
+        return Err(Error::BusinessRule(format!(
+            "Insufficient stock of {}: {} on hand ({} quarantined), {} required",
+            sku, stock, quarantined, quantity
+        )));
+    }
+    consume_lots(tx, tenant_id, work_order.id, product_id, quantity, lot_id).await?;
+
+    let record = insert_transaction(tx, tenant_id, CreateInventoryTransaction {
+        product_id,
+        quantity: -quantity,
+        transaction_type: TransactionType::ProductionOut,
+        reference_id: Some(work_order.id),
+        notes: Some(format!("Issued to WO {}", work_order.id)),
+        reason_code: None,
+        unit_cost: None,
+    }).await?;
+    let value = record.total_cost.unwrap_or_default();
+
+    sqlx::query(
+        r#"
+        INSERT INTO work_order_materials (work_order_id, product_id, issued_quantity, issued_cost)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (work_order_id, product_id) DO UPDATE
+        SET issued_quantity = work_order_materials.issued_quantity + EXCLUDED.issued_quantity,
+            issued_cost = work_order_materials.issued_cost + EXCLUDED.issued_cost
+        "#
+    )
+    .bind(work_order.id)
+    .bind(product_id)
+    .bind(quantity)
+    .bind(value)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    absorb_material(tx, tenant_id, work_order.id, value, &format!("Material issue WO {}", work_order.id)).await?;
+    Ok(value)
+}
+
+async fn return_material(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order: &WorkOrder, product_id: Uuid, quantity: Decimal, lot_id: Option<Uuid>) -> Result<(), Error> {
+    if quantity <= Decimal::ZERO {
+        return Err(Error::BusinessRule("Return quantity must be positive".to_string()));
+    }
+
+    let material = sqlx::query_as::<_, WorkOrderMaterial>(
+        "SELECT * FROM work_order_materials WHERE work_order_id = $1 AND product_id = $2 FOR UPDATE"
+    )
+    .bind(work_order.id)
+    .bind(product_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::BusinessRule("Product was not issued to this work order".to_string()))?;
+
+    if quantity > material.net_issued() {
+        return Err(Error::BusinessRule(format!(
+            "Cannot return {}: only {} issued",
+            quantity,
+            material.net_issued()
+        )));
+    }
+    return_lots(tx, tenant_id, work_order.id, product_id, quantity, lot_id).await?;
+
+    let record = insert_transaction(tx, tenant_id, CreateInventoryTransaction {
+        product_id,
+        quantity,
+        transaction_type: TransactionType::ProductionIn,
+        reference_id: Some(work_order.id),
+        notes: Some(format!("Returned from WO {}", work_order.id)),
+        reason_code: None,
+        unit_cost: Some(material.average_cost()),
+    }).await?;
+    let value = record.total_cost.unwrap_or_default();
+
+    sqlx::query(
+        "UPDATE work_order_materials SET returned_quantity = returned_quantity + $1, issued_cost = issued_cost - $2 WHERE id = $3"
+    )
+    .bind(quantity)
+    .bind(value)
+    .bind(material.id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    absorb_material(tx, tenant_id, work_order.id, -value, &format!("Material return WO {}", work_order.id)).await
+}
+
+/// Moves material value between inventory and WIP (negative for returns) and applies
+/// material-based overhead on it.
+async fn absorb_material(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order_id: Uuid, value: Decimal, memo: &str) -> Result<(), Error> {
+    let overhead: Decimal = load_overhead_rates(tx, tenant_id)
+        .await?
+        .iter()
+        .filter(|r| r.basis == OverheadBasis::MaterialCost)
+        .map(|r| r.apply(value))
+        .sum();
+
+    sqlx::query("UPDATE work_orders SET material_cost = material_cost + $1, overhead_cost = overhead_cost + $2, updated_at = NOW() WHERE id = $3")
+        .bind(value)
+        .bind(overhead)
+        .bind(work_order_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    post_wip(tx, tenant_id, "WIP", memo, &[("1300", value), ("5300", overhead)]).await?;
+    Ok(())
+}
+
+async fn load_overhead_rates(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid) -> Result<Vec<OverheadRate>, Error> {
+    sqlx::query_as::<_, OverheadRate>("SELECT * FROM overhead_rates WHERE tenant_id = $1 AND is_active = true")
+        .bind(tenant_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+}
+
+/// Posts value moving into WIP (1310) from each of `sources`; a negative amount moves value
+/// out of WIP to that account instead.
+async fn post_wip(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, prefix: &str, memo: &str, sources: &[(&str, Decimal)]) -> Result<Option<Uuid>, Error> {
+    let total: Decimal = sources.iter().map(|(_, amount)| *amount).sum();
+    let mut lines = vec![
+        LedgerLine::debit("1310", total.max(Decimal::ZERO), memo),
+        LedgerLine::credit("1310", (-total).max(Decimal::ZERO), memo),
+    ];
+    for &(account, amount) in sources {
+        lines.push(LedgerLine::credit(account, amount.max(Decimal::ZERO), memo));
+        lines.push(LedgerLine::debit(account, (-amount).max(Decimal::ZERO), memo));
+    }
+    if lines.iter().all(|l| l.debit.is_zero() && l.credit.is_zero()) {
+        return Ok(None);
+    }
+
+    let date = Utc::now().date_naive();
+    let entry_number = next_document_number(tx, tenant_id, "journal_entries", "entry_number", prefix, date).await?;
+    post_journal_entry(tx, tenant_id, &entry_number, date, memo, &lines).await
+}
+
+/// Records good output and scrap. Consumption lines are issued first; a work order with no
+/// material issued at all backflushes the recipe quantities for the units reported.
+/// Output is valued at the material not yet relieved spread over the output still expected,
+/// so scrapped units leave their cost behind as variance when the order is closed.
+async fn report(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order: WorkOrder, req: ReportProduction, reported_by: Option<Uuid>) -> Result<ProductionReport, Error> {
+    if req.quantity < Decimal::ZERO || req.scrap.iter().any(|s| s.quantity <= Decimal::ZERO) {
+        return Err(Error::BusinessRule("Quantities must be positive".to_string()));
+    }
+    if req.quantity.is_zero() && req.scrap.is_empty() {
+        return Err(Error::BusinessRule("Report good output or scrap".to_string()));
+    }
+
+    let work_order = start_work_order(tx, work_order).await?;
+    let (recipe, ingredients) = load_recipe(tx, work_order.recipe_id).await?;
+    let materials = load_materials(tx, work_order.id).await?;
+    let outputs = load_outputs(tx, work_order.id).await?;
+
+    if req.by_products.iter().any(|b| b.quantity < Decimal::ZERO || !outputs.iter().any(|o| o.product_id == b.product_id)) {
+        return Err(Error::BusinessRule("By-products must be outputs of the work order's recipe, in quantities not negative".to_string()));
+    }
+    if req.quantity.is_zero() && !req.by_products.is_empty() {
+        return Err(Error::BusinessRule("By-products are reported together with good output".to_string()));
+    }
+
+    let output_scrap: Decimal = req.scrap
+        .iter()
+        .filter(|s| s.product_id.is_none_or(|id| id == recipe.output_product_id))
+        .map(|s| s.quantity)
+        .sum();
+
+    if !req.consumption.is_empty() {
+        for line in &req.consumption {
+            issue_material(tx, tenant_id, &work_order, line.product_id, line.quantity, line.lot_id).await?;
+        }
+    } else if materials.iter().all(|m| m.issued_quantity.is_zero()) {
+        let batches = batches_for(&recipe, req.quantity + output_scrap);
+        for ingredient in &ingredients {
+            let quantity = (ingredient.quantity * batches).round_dp(4);
+            if quantity > Decimal::ZERO {
+                issue_material(tx, tenant_id, &work_order, ingredient.input_product_id, quantity, None).await?;
+            }
+        }
+    }
+
+    let work_order = lock_work_order(tx, tenant_id, work_order.id).await?;
+
+    let (mut unit_cost, mut total_cost, mut transaction_id) = (Decimal::ZERO, Decimal::ZERO, None);
+    let mut relieved = Decimal::ZERO;
+    if req.quantity > Decimal::ZERO {
+        let planned_output = recipe.output_quantity * work_order.quantity;
+        let expected = (planned_output - work_order.produced_quantity).max(req.quantity);
+        let unrelieved = (work_order.material_cost + work_order.labor_cost + work_order.overhead_cost - work_order.output_cost).max(Decimal::ZERO);
+        let value = (unrelieved * req.quantity / expected).round_dp(2);
+
+        // By-products come out in the recipe's proportion unless their actual quantities are given.
+        let by_products: Vec<(WorkOrderOutput, Decimal)> = by_product_quantities(&outputs, &req.by_products, batches_for(&recipe, req.quantity), work_order.quantity)
+            .into_iter()
+            .filter(|(_, quantity)| *quantity > Decimal::ZERO)
+            .collect();
+        let shares: Vec<Decimal> = by_products.iter().map(|(o, _)| o.cost_share).collect();
+        let (primary_value, allocated) = allocate_output_cost(value, &shares);
+
+        let output = insert_transaction(tx, tenant_id, CreateInventoryTransaction {
+            product_id: recipe.output_product_id,
+            quantity: req.quantity,
+            transaction_type: TransactionType::ProductionIn,
+            reference_id: Some(work_order.id),
+            notes: Some(format!("Produced from WO {}", work_order.id)),
+            reason_code: None,
+            unit_cost: Some((primary_value / req.quantity).round_dp(4)),
+        }).await?;
+        unit_cost = output.unit_cost.unwrap_or_default();
+        total_cost = output.total_cost.unwrap_or_default();
+        transaction_id = Some(output.id);
+        relieved += total_cost;
+        hold_for_inspection(tx, tenant_id, InspectionType::Production, recipe.output_product_id, req.quantity, None, Some(work_order.id)).await?;
+
+        for ((by_product, quantity), cost) in by_products.into_iter().zip(allocated) {
+            let receipt = insert_transaction(tx, tenant_id, CreateInventoryTransaction {
+                product_id: by_product.product_id,
+                quantity,
+                transaction_type: TransactionType::ProductionIn,
+                reference_id: Some(work_order.id),
+                notes: Some(format!("By-product of WO {}", work_order.id)),
+                reason_code: None,
+                unit_cost: Some((cost / quantity).round_dp(4)),
+            }).await?;
+            let receipt_cost = receipt.total_cost.unwrap_or_default();
+            relieved += receipt_cost;
+            hold_for_inspection(tx, tenant_id, InspectionType::Production, by_product.product_id, quantity, None, Some(work_order.id)).await?;
+
+            sqlx::query(
+                "UPDATE work_order_outputs SET produced_quantity = produced_quantity + $1, output_cost = output_cost + $2 WHERE id = $3"
+            )
+            .bind(quantity)
+            .bind(receipt_cost)
+            .bind(by_product.id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        post_wip(tx, tenant_id, "PRD", &format!("Production WO {}", work_order.id), &[("1300", -relieved)]).await?;
+    }
+
+    let production_report = sqlx::query_as::<_, ProductionReport>(
+        r#"
+        INSERT INTO work_order_reports (work_order_id, quantity, scrapped_quantity, unit_cost, total_cost, transaction_id, notes, reported_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#
+    )
+    .bind(work_order.id)
+    .bind(req.quantity)
+    .bind(output_scrap)
+    .bind(unit_cost)
+    .bind(total_cost)
+    .bind(transaction_id)
+    .bind(req.notes)
+    .bind(reported_by)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    for scrap in req.scrap {
+        let product_id = scrap.product_id.unwrap_or(recipe.output_product_id);
+        if product_id != recipe.output_product_id {
+            let updated = sqlx::query(
+                "UPDATE work_order_materials SET scrapped_quantity = scrapped_quantity + $1 WHERE work_order_id = $2 AND product_id = $3"
+            )
+            .bind(scrap.quantity)
+            .bind(work_order.id)
+            .bind(product_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+            if updated.rows_affected() == 0 {
+                return Err(Error::BusinessRule("Scrapped product is neither the output nor a material of this work order".to_string()));
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO work_order_scrap (work_order_id, report_id, product_id, quantity, reason, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(work_order.id)
+        .bind(production_report.id)
+        .bind(product_id)
+        .bind(scrap.quantity)
+        .bind(scrap.reason)
+        .bind(scrap.notes)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET produced_quantity = produced_quantity + $1, scrapped_quantity = scrapped_quantity + $2,
+            output_cost = output_cost + $3, updated_at = NOW()
+        WHERE id = $4
+        "#
+    )
+    .bind(req.quantity)
+    .bind(output_scrap)
+    .bind(relieved)
+    .bind(work_order.id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(production_report)
+}
+
+/// Closes the work order. Whatever is left in WIP once output has been relieved (scrap, yield
+/// loss, labor inefficiency, standard cost differences) is written off to the COGS
+/// sub-accounts for material, labor and overhead.
+async fn close_work_order(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, work_order: WorkOrder, status: WorkOrderStatus) -> Result<WorkOrder, Error> {
+    let clocked_in: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM labor_clocks WHERE work_order_id = $1")
+        .bind(work_order.id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    if clocked_in > 0 {
+        return Err(Error::BusinessRule(format!("{} employee(s) are still clocked in on the work order", clocked_in)));
+    }
+
+    let variance = work_order.material_cost + work_order.labor_cost + work_order.overhead_cost - work_order.output_cost;
+    let (material, labor, overhead) = split_variance(variance, work_order.material_cost, work_order.labor_cost, work_order.overhead_cost);
+
+    post_wip(tx, tenant_id, "PRD", &format!("Production variance WO {}", work_order.id), &[
+        ("5100", -material),
+        ("5200", -labor),
+        ("5300", -overhead),
+    ]).await?;
+
+    // Free the capacity the order no longer needs.
+    sqlx::query("DELETE FROM work_center_loads WHERE work_order_id = $1 AND load_date > CURRENT_DATE")
+        .bind(work_order.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    sqlx::query_as::<_, WorkOrder>(&format!(
+        r#"
+        UPDATE work_orders
+        SET status = $1, end_date = CURRENT_DATE, material_variance = $2, labor_variance = $3, overhead_variance = $4, updated_at = NOW()
+        WHERE id = $5
+        RETURNING {}
+        "#,
+        WORK_ORDER_COLUMNS
+    ))
+    .bind(status)
+    .bind(material)
+    .bind(labor)
+    .bind(overhead)
+    .bind(work_order.id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smart_erp_core::models::manufacturing::OutputType;
+
+    fn output(product: u128, planned_quantity: i64) -> WorkOrderOutput {
+        WorkOrderOutput {
+            id: Uuid::new_v4(),
+            work_order_id: Uuid::nil(),
+            product_id: Uuid::from_u128(product),
+            output_type: OutputType::ByProduct,
+            cost_share: Decimal::from(10),
+            planned_quantity: Decimal::from(planned_quantity),
+            produced_quantity: Decimal::ZERO,
+            output_cost: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn by_products_follow_the_batches_reported_unless_given() {
+        let outputs = [output(1, 12), output(2, 5)];
+        let reported = [MaterialQuantity { product_id: Uuid::from_u128(2), quantity: Decimal::from(7), lot_id: None }];
+
+        // 1.5 of the work order's 4 batches: 12 planned scales to 4.5; the reported 7 is kept as is.
+        let quantities: Vec<Decimal> = by_product_quantities(&outputs, &reported, Decimal::new(15, 1), Decimal::from(4))
+            .into_iter()
+            .map(|(_, quantity)| quantity)
+            .collect();
+        assert_eq!(quantities, vec![Decimal::new(45, 1), Decimal::from(7)]);
+    }
+
+    #[test]
+    fn by_products_of_a_work_order_without_batches_are_zero() {
+        let quantities = by_product_quantities(&[output(1, 12)], &[], Decimal::ONE, Decimal::ZERO);
+        assert_eq!(quantities[0].1, Decimal::ZERO);
+    }
+}
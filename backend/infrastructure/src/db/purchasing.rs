@@ -1,10 +1,24 @@
 use async_trait::async_trait;
 use smart_erp_core::models::purchasing::{
-    CreatePurchaseOrder, CreateSupplier, PurchaseOrder, PurchaseOrderLine, PurchaseOrderStatus,
-    PurchasingService, Supplier,
+    CreatePurchaseOrder, CreateSupplier, PriceHistoryQuery, PriceSource, PurchaseOrder, PurchaseOrderLine,
+    PurchaseOrderStatus, PurchaseReceipt, PurchaseReceiptDetail, PurchaseReceiptLine, PurchasingService,
+    Supplier, SupplierHistory, SupplierPrice, SupplierQuery, SupplierScorecard, SupplierScorecardQuery,
+    SupplierScorecardReport, SupplierTransaction, UpdateSupplier,
 };
-use smart_erp_core::models::inventory::TransactionType;
+use smart_erp_core::models::inventory::{CreateInventoryTransaction, TransactionType};
+use smart_erp_core::models::quality::InspectionType;
+use smart_erp_core::models::approval::DocumentType;
+use smart_erp_core::models::address::AddressType;
 use smart_erp_core::error::Error;
+use crate::db::address::{document_address, insert_address, insert_contact, Party};
+use crate::db::approval::require_approval;
+use crate::db::inventory::insert_transaction;
+use crate::db::quality::hold_for_inspection;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use crate::db::payment_terms::active_terms;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
 use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
@@ -17,20 +31,283 @@ impl PostgresPurchasingRepository {
         Self { pool }
     }
 
-    pub async fn list_suppliers(&self, tenant_id: Uuid) -> Result<Vec<Supplier>, Error> {
+    pub async fn list_suppliers(&self, tenant_id: Uuid, query: SupplierQuery) -> Result<Vec<Supplier>, Error> {
         let rows = sqlx::query_as::<_, Supplier>(
-            "SELECT * FROM suppliers WHERE tenant_id = $1 ORDER BY name"
+            "SELECT * FROM suppliers WHERE tenant_id = $1 AND ($2::boolean IS NULL OR is_active = $2) ORDER BY name"
         )
         .bind(tenant_id)
+        .bind(query.is_active)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
         Ok(rows)
     }
 
+    pub async fn get_supplier(&self, tenant_id: Uuid, supplier_id: Uuid) -> Result<Supplier, Error> {
+        sqlx::query_as::<_, Supplier>("SELECT * FROM suppliers WHERE id = $1 AND tenant_id = $2")
+            .bind(supplier_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Supplier not found".to_string()))
+    }
+
+    pub async fn update_supplier(&self, tenant_id: Uuid, supplier_id: Uuid, req: UpdateSupplier) -> Result<Supplier, Error> {
+        if req.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(Error::BusinessRule("Supplier name cannot be blank".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let terms = match req.payment_terms_id {
+            Some(id) => Some(active_terms(&mut tx, tenant_id, id).await?.name),
+            None => req.terms,
+        };
+
+        // Free-text terms that name structured terms pick those up as the default
+        let supplier = sqlx::query_as::<_, Supplier>(
+            r#"
+            UPDATE suppliers
+            SET name = COALESCE($1, name),
+                email = COALESCE($2, email),
+                phone = COALESCE($3, phone),
+                contact_person = COALESCE($4, contact_person),
+                tax_id = COALESCE($5, tax_id),
+                terms = COALESCE($6, terms),
+                payment_terms_id = CASE WHEN $6 IS NULL THEN payment_terms_id
+                    ELSE (SELECT id FROM payment_terms WHERE tenant_id = $10 AND name = $6 AND is_active) END,
+                account_number = COALESCE($7, account_number),
+                notes = COALESCE($8, notes),
+                updated_at = NOW()
+            WHERE id = $9 AND tenant_id = $10
+            RETURNING *
+            "#
+        )
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(req.email)
+        .bind(req.phone)
+        .bind(req.contact_person)
+        .bind(req.tax_id)
+        .bind(terms)
+        .bind(req.account_number)
+        .bind(req.notes)
+        .bind(supplier_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Supplier not found".to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(supplier)
+    }
+
+    /// Deactivating keeps the supplier's history but stops new orders and bills.
+    pub async fn set_supplier_active(&self, tenant_id: Uuid, supplier_id: Uuid, is_active: bool) -> Result<Supplier, Error> {
+        sqlx::query_as::<_, Supplier>(
+            "UPDATE suppliers SET is_active = $1, updated_at = NOW() WHERE id = $2 AND tenant_id = $3 RETURNING *"
+        )
+        .bind(is_active)
+        .bind(supplier_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Supplier not found".to_string()))
+    }
+
+    /// Orders, bills and bill payments with the supplier, oldest first, with the running balance owed.
+    pub async fn supplier_history(&self, tenant_id: Uuid, supplier_id: Uuid) -> Result<SupplierHistory, Error> {
+        let supplier = self.get_supplier(tenant_id, supplier_id).await?;
+
+        let transactions = sqlx::query_as::<_, SupplierTransaction>(
+            r#"
+            SELECT * FROM (
+                SELECT 'PURCHASE_ORDER'::varchar AS transaction_type, o.id AS document_id, o.order_number AS reference, o.date,
+                       o.status::text AS status, o.total_amount AS amount, 0::numeric AS balance_change, o.created_at
+                FROM purchase_orders o
+                WHERE o.tenant_id = $1 AND o.supplier_id = $2
+                UNION ALL
+                SELECT 'BILL', b.id, b.bill_number, b.date, b.status, b.total_amount,
+                       CASE WHEN b.match_status = 'EXCEPTION' THEN 0 ELSE b.total_amount END, b.created_at
+                FROM bills b
+                WHERE b.tenant_id = $1 AND b.supplier_id = $2
+                UNION ALL
+                SELECT 'BILL_PAYMENT', p.id, b.bill_number || COALESCE(' ' || p.reference, ''), p.date, p.method, p.amount,
+                       -(p.amount + p.discount_amount), p.created_at
+                FROM bill_payments p
+                JOIN bills b ON b.id = p.bill_id
+                WHERE p.tenant_id = $1 AND b.supplier_id = $2
+                UNION ALL
+                SELECT 'VENDOR_CREDIT', c.id, c.credit_number || COALESCE(' ' || c.supplier_reference, ''), c.date, c.status,
+                       c.total_amount, -c.total_amount, c.created_at
+                FROM vendor_credits c
+                WHERE c.tenant_id = $1 AND c.supplier_id = $2
+            ) t
+            ORDER BY date, created_at
+            "#
+        )
+        .bind(tenant_id)
+        .bind(supplier_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(SupplierHistory::build(supplier, transactions))
+    }
+
+    /// Quoted and ordered prices, newest first.
+    pub async fn price_history(&self, tenant_id: Uuid, query: PriceHistoryQuery) -> Result<Vec<SupplierPrice>, Error> {
+        sqlx::query_as::<_, SupplierPrice>(
+            r#"
+            SELECT h.id, h.supplier_id, s.name AS supplier_name, h.product_id, p.sku, h.unit_price, h.min_order_quantity,
+                   h.lead_time_days, h.source, h.source_id, h.source_number, h.effective_date, h.created_at
+            FROM supplier_price_history h
+            JOIN suppliers s ON s.id = h.supplier_id
+            JOIN products p ON p.id = h.product_id
+            WHERE h.tenant_id = $1
+              AND ($2::uuid IS NULL OR h.supplier_id = $2)
+              AND ($3::uuid IS NULL OR h.product_id = $3)
+            ORDER BY h.effective_date DESC, h.created_at DESC
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.supplier_id)
+        .bind(query.product_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Delivery, fill, quality and price performance on orders placed in the period, best score first.
+    pub async fn supplier_scorecards(&self, tenant_id: Uuid, query: SupplierScorecardQuery) -> Result<SupplierScorecardReport, Error> {
+        let today = Utc::now().date_naive();
+        let to = query.to.unwrap_or(today);
+        let from = query.from.unwrap_or(to - chrono::Duration::days(365));
+        if from > to {
+            return Err(Error::BusinessRule("'from' date must not be after 'to' date".to_string()));
+        }
+
+        let mut suppliers = sqlx::query_as::<_, SupplierScorecard>(
+            r#"
+            WITH orders AS (
+                SELECT po.id, po.supplier_id,
+                       COALESCE(po.expected_date, po.date + COALESCE((
+                           SELECT MAX(p.lead_time_days)
+                           FROM purchase_order_lines l JOIN products p ON p.id = l.product_id
+                           WHERE l.order_id = po.id
+                       ), 0)) AS expected_date,
+                       (SELECT MIN(r.date) FROM purchase_receipts r WHERE r.order_id = po.id) AS received_date
+                FROM purchase_orders po
+                WHERE po.tenant_id = $1 AND po.date BETWEEN $2 AND $3
+                  AND po.status IN ('ORDERED', 'RECEIVED')
+                  AND ($5::uuid IS NULL OR po.supplier_id = $5)
+            ),
+            due AS (
+                SELECT * FROM orders WHERE received_date IS NOT NULL OR expected_date < $4
+            ),
+            delivery AS (
+                SELECT supplier_id,
+                       COUNT(*) AS deliveries_due,
+                       COUNT(*) FILTER (WHERE received_date <= expected_date) AS on_time_deliveries,
+                       COUNT(*) FILTER (WHERE received_date IS NULL) AS overdue_orders,
+                       ROUND(AVG(COALESCE(received_date, $4) - expected_date)
+                             FILTER (WHERE COALESCE(received_date, $4) > expected_date), 1) AS avg_days_late
+                FROM due
+                GROUP BY supplier_id
+            ),
+            fill AS (
+                SELECT d.supplier_id, SUM(l.quantity) AS ordered_quantity,
+                       SUM(LEAST(l.quantity, COALESCE(rl.received, 0))) AS filled_quantity
+                FROM due d
+                JOIN purchase_order_lines l ON l.order_id = d.id
+                LEFT JOIN (
+                    SELECT order_line_id, SUM(quantity - returned_quantity) AS received
+                    FROM purchase_receipt_lines
+                    GROUP BY order_line_id
+                ) rl ON rl.order_line_id = l.id
+                GROUP BY d.supplier_id
+            ),
+            receipt_lines AS (
+                SELECT o.supplier_id, rl.id, rl.unit_cost
+                FROM orders o
+                JOIN purchase_receipts r ON r.order_id = o.id
+                JOIN purchase_receipt_lines rl ON rl.receipt_id = r.id
+            ),
+            quality AS (
+                SELECT rl.supplier_id,
+                       SUM(q.accepted_quantity + q.rejected_quantity) AS inspected_quantity,
+                       SUM(q.rejected_quantity) AS rejected_quantity
+                FROM receipt_lines rl
+                JOIN quality_inspections q ON q.receipt_line_id = rl.id
+                WHERE q.status <> 'PENDING' AND q.parent_id IS NULL
+                GROUP BY rl.supplier_id
+            ),
+            billing AS (
+                SELECT rl.supplier_id,
+                       ROUND(SUM(rl.unit_cost * bl.matched_quantity), 2) AS billed_value,
+                       ROUND(SUM(ABS(bl.unit_price - rl.unit_cost) * bl.matched_quantity), 2) AS invoice_variance
+                FROM receipt_lines rl
+                JOIN bill_lines bl ON bl.receipt_line_id = rl.id
+                GROUP BY rl.supplier_id
+            ),
+            prices AS (
+                SELECT h.supplier_id, h.unit_price, h.effective_date,
+                       LAG(h.unit_price) OVER (PARTITION BY h.supplier_id, h.product_id ORDER BY h.effective_date, h.created_at) AS previous_price
+                FROM supplier_price_history h
+                WHERE h.tenant_id = $1 AND h.source = 'PURCHASE_ORDER' AND h.effective_date <= $3
+            ),
+            price_changes AS (
+                SELECT supplier_id, COUNT(*) AS price_changes,
+                       ROUND(AVG(ABS(unit_price - previous_price) / previous_price * 100), 1) AS avg_price_change_percent
+                FROM prices
+                WHERE effective_date BETWEEN $2 AND $3 AND previous_price > 0
+                GROUP BY supplier_id
+            )
+            SELECT s.id AS supplier_id, s.name AS supplier_name,
+                   (SELECT COUNT(*) FROM orders o WHERE o.supplier_id = s.id) AS orders_placed,
+                   COALESCE(d.deliveries_due, 0) AS deliveries_due,
+                   COALESCE(d.on_time_deliveries, 0) AS on_time_deliveries,
+                   COALESCE(d.overdue_orders, 0) AS overdue_orders,
+                   COALESCE(d.avg_days_late, 0) AS avg_days_late,
+                   COALESCE(f.ordered_quantity, 0) AS ordered_quantity,
+                   COALESCE(f.filled_quantity, 0) AS filled_quantity,
+                   COALESCE(q.inspected_quantity, 0) AS inspected_quantity,
+                   COALESCE(q.rejected_quantity, 0) AS rejected_quantity,
+                   COALESCE(b.billed_value, 0) AS billed_value,
+                   COALESCE(b.invoice_variance, 0) AS invoice_variance,
+                   COALESCE(pc.price_changes, 0) AS price_changes,
+                   pc.avg_price_change_percent
+            FROM suppliers s
+            LEFT JOIN delivery d ON d.supplier_id = s.id
+            LEFT JOIN fill f ON f.supplier_id = s.id
+            LEFT JOIN quality q ON q.supplier_id = s.id
+            LEFT JOIN billing b ON b.supplier_id = s.id
+            LEFT JOIN price_changes pc ON pc.supplier_id = s.id
+            WHERE s.tenant_id = $1 AND EXISTS (SELECT 1 FROM orders o WHERE o.supplier_id = s.id)
+            ORDER BY s.name
+            "#
+        )
+        .bind(tenant_id)
+        .bind(from)
+        .bind(to)
+        .bind(today)
+        .bind(query.supplier_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for supplier in &mut suppliers {
+            supplier.compute();
+        }
+        suppliers.sort_by_key(|s| std::cmp::Reverse(s.score));
+
+        Ok(SupplierScorecardReport { from, to, suppliers })
+    }
+
     pub async fn list_purchase_orders(&self, tenant_id: Uuid) -> Result<Vec<PurchaseOrder>, Error> {
         let rows = sqlx::query_as::<_, PurchaseOrder>(
-            "SELECT id, tenant_id, supplier_id, order_number, date, status, total_amount, created_at, updated_at FROM purchase_orders WHERE tenant_id = $1 ORDER BY created_at DESC"
+            "SELECT id, tenant_id, supplier_id, order_number, date, expected_date, status, total_amount, created_at, updated_at, remit_to_address_id, remit_to_address FROM purchase_orders WHERE tenant_id = $1 ORDER BY created_at DESC"
         )
         .bind(tenant_id)
         .fetch_all(&self.pool)
@@ -38,6 +315,38 @@ impl PostgresPurchasingRepository {
         .map_err(|e| Error::Database(e.to_string()))?;
         Ok(rows)
     }
+
+    pub async fn list_receipts(&self, tenant_id: Uuid) -> Result<Vec<PurchaseReceipt>, Error> {
+        sqlx::query_as::<_, PurchaseReceipt>(
+            "SELECT * FROM purchase_receipts WHERE tenant_id = $1 ORDER BY created_at DESC"
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_receipt(&self, tenant_id: Uuid, receipt_id: Uuid) -> Result<PurchaseReceiptDetail, Error> {
+        let receipt = sqlx::query_as::<_, PurchaseReceipt>(
+            "SELECT * FROM purchase_receipts WHERE id = $1 AND tenant_id = $2"
+        )
+        .bind(receipt_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Purchase receipt not found".to_string()))?;
+
+        let lines = sqlx::query_as::<_, PurchaseReceiptLine>(
+            "SELECT * FROM purchase_receipt_lines WHERE receipt_id = $1 ORDER BY id"
+        )
+        .bind(receipt_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(PurchaseReceiptDetail { receipt, lines })
+    }
 }
 
 #[async_trait]
@@ -47,10 +356,17 @@ impl PurchasingService for PostgresPurchasingRepository {
         tenant_id: Uuid,
         supplier: CreateSupplier,
     ) -> Result<Supplier, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let terms = match supplier.payment_terms_id {
+            Some(id) => Some(active_terms(&mut tx, tenant_id, id).await?.name),
+            None => supplier.terms,
+        };
+
         let record = sqlx::query_as::<_, Supplier>(
             r#"
-            INSERT INTO suppliers (tenant_id, name, email, phone, address, contact_person)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO suppliers (tenant_id, name, email, phone, contact_person, tax_id, terms, account_number, notes, payment_terms_id)
+            VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, 'Net 30'), $8, $9,
+                    (SELECT id FROM payment_terms WHERE tenant_id = $1 AND name = COALESCE($7, 'Net 30') AND is_active))
             RETURNING *
             "#
         )
@@ -58,12 +374,25 @@ impl PurchasingService for PostgresPurchasingRepository {
         .bind(supplier.name)
         .bind(supplier.email)
         .bind(supplier.phone)
-        .bind(supplier.address)
         .bind(supplier.contact_person)
-        .fetch_one(&self.pool)
+        .bind(supplier.tax_id)
+        .bind(terms)
+        .bind(supplier.account_number)
+        .bind(supplier.notes)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
+        let party = Party::Supplier(record.id);
+        for address in supplier.addresses {
+            insert_address(&mut tx, tenant_id, party, address).await?;
+        }
+        for contact in supplier.contacts {
+            insert_contact(&mut tx, tenant_id, party, contact).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
         Ok(record)
     }
 
@@ -74,40 +403,84 @@ impl PurchasingService for PostgresPurchasingRepository {
     ) -> Result<PurchaseOrder, Error> {
         let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
 
+        check_supplier_active(&mut tx, tenant_id, order.supplier_id).await?;
+
+        let mut prices = Vec::with_capacity(order.lines.len());
+        for line in &order.lines {
+            let price = match line.unit_price {
+                Some(price) => price,
+                None => default_price(&mut tx, tenant_id, order.supplier_id, line.product_id).await?,
+            };
+            prices.push(price);
+        }
+        if order.expected_date.is_some_and(|d| d < order.date) {
+            return Err(Error::BusinessRule("Expected delivery cannot be before the order date".to_string()));
+        }
+        let expected_date = match order.expected_date {
+            Some(date) => date,
+            None => {
+                let mut lead_time_days = 0;
+                for line in &order.lines {
+                    lead_time_days = lead_time_days.max(default_lead_time(&mut tx, tenant_id, order.supplier_id, line.product_id).await?);
+                }
+                order.date + chrono::Duration::days(lead_time_days.into())
+            }
+        };
+
         let total_amount: rust_decimal::Decimal = order.lines.iter()
-            .map(|line| line.quantity * line.unit_price)
+            .zip(&prices)
+            .map(|(line, price)| line.quantity * price)
             .sum();
+        let remit_to = document_address(&mut tx, tenant_id, Party::Supplier(order.supplier_id), AddressType::RemitTo, order.remit_to_address_id).await?;
 
         let po = sqlx::query_as::<_, PurchaseOrder>(
             r#"
-            INSERT INTO purchase_orders (tenant_id, supplier_id, order_number, date, total_amount, status)
-            VALUES ($1, $2, $3, $4, $5, 'DRAFT')
-            RETURNING id, tenant_id, supplier_id, order_number, date, status, total_amount, created_at, updated_at
+            INSERT INTO purchase_orders (tenant_id, supplier_id, order_number, date, expected_date, total_amount, status,
+                                         remit_to_address_id, remit_to_address)
+            VALUES ($1, $2, $3, $4, $5, $6, 'DRAFT', $7, $8)
+            RETURNING id, tenant_id, supplier_id, order_number, date, expected_date, status, total_amount, created_at, updated_at, remit_to_address_id, remit_to_address
             "#
         )
         .bind(tenant_id)
         .bind(order.supplier_id)
         .bind(order.order_number)
         .bind(order.date)
+        .bind(expected_date)
         .bind(total_amount)
+        .bind(remit_to.as_ref().map(|a| a.id))
+        .bind(remit_to.as_ref().map(|a| a.formatted()))
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
-        for line in order.lines {
+        for (line, price) in order.lines.into_iter().zip(prices) {
             sqlx::query(
                 r#"
-                INSERT INTO purchase_order_lines (order_id, product_id, quantity, unit_price)
-                VALUES ($1, $2, $3, $4)
+                INSERT INTO purchase_order_lines (order_id, product_id, quantity, unit_price, weight, area)
+                VALUES ($1, $2, $3, $4, $5, $6)
                 "#
             )
             .bind(po.id)
             .bind(line.product_id)
             .bind(line.quantity)
-            .bind(line.unit_price)
+            .bind(price)
+            .bind(line.weight)
+            .bind(line.area)
             .execute(&mut *tx)
             .await
             .map_err(|e| Error::Database(e.to_string()))?;
+
+            record_supplier_price(&mut tx, tenant_id, NewSupplierPrice {
+                supplier_id: po.supplier_id,
+                product_id: line.product_id,
+                unit_price: price,
+                min_order_quantity: None,
+                lead_time_days: None,
+                source: PriceSource::PurchaseOrder,
+                source_id: po.id,
+                source_number: &po.order_number,
+                effective_date: po.date,
+            }).await?;
         }
 
         tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
@@ -120,7 +493,7 @@ impl PurchasingService for PostgresPurchasingRepository {
 
         let order = sqlx::query_as::<_, PurchaseOrder>(
             r#"
-            SELECT id, tenant_id, supplier_id, order_number, date, status, total_amount, created_at, updated_at
+            SELECT id, tenant_id, supplier_id, order_number, date, expected_date, status, total_amount, created_at, updated_at, remit_to_address_id, remit_to_address
             FROM purchase_orders
             WHERE id = $1 AND tenant_id = $2
             FOR UPDATE
@@ -139,13 +512,14 @@ impl PurchasingService for PostgresPurchasingRepository {
                 order.status
             )));
         }
+        require_approval(&mut tx, tenant_id, DocumentType::PurchaseOrder, order.id, &order.order_number, order.total_amount, "received").await?;
 
         let updated_order = sqlx::query_as::<_, PurchaseOrder>(
             r#"
             UPDATE purchase_orders
             SET status = 'RECEIVED', updated_at = NOW()
             WHERE id = $1
-            RETURNING id, tenant_id, supplier_id, order_number, date, status, total_amount, created_at, updated_at
+            RETURNING id, tenant_id, supplier_id, order_number, date, expected_date, status, total_amount, created_at, updated_at, remit_to_address_id, remit_to_address
             "#
         )
         .bind(order_id)
@@ -153,11 +527,15 @@ impl PurchasingService for PostgresPurchasingRepository {
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
+        // Weight and area fall back to the received quantity when the product is stocked in that unit.
         let lines = sqlx::query_as::<_, PurchaseOrderLine>(
             r#"
-            SELECT id, order_id, product_id, quantity, unit_price, total_price
-            FROM purchase_order_lines
-            WHERE order_id = $1
+            SELECT l.id, l.order_id, l.product_id, l.quantity, l.unit_price, l.total_price,
+                   COALESCE(l.weight, CASE WHEN p.unit_of_measure = 'KG' THEN l.quantity END) AS weight,
+                   COALESCE(l.area, CASE WHEN p.unit_of_measure = 'SQ_FT' THEN l.quantity END) AS area
+            FROM purchase_order_lines l
+            JOIN products p ON p.id = l.product_id
+            WHERE l.order_id = $1
             "#
         )
         .bind(order_id)
@@ -165,41 +543,179 @@ impl PurchasingService for PostgresPurchasingRepository {
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
+        let date = Utc::now().date_naive();
+        let receipt_number = next_document_number(&mut tx, tenant_id, "purchase_receipts", "receipt_number", "GRN", date).await?;
+        let receipt_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO purchase_receipts (tenant_id, order_id, receipt_number, date) VALUES ($1, $2, $3, $4) RETURNING id"
+        )
+        .bind(tenant_id)
+        .bind(order_id)
+        .bind(&receipt_number)
+        .bind(date)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let (mut inventory_value, mut received_value) = (Decimal::ZERO, Decimal::ZERO);
         for line in lines {
-            sqlx::query(
+            let record = insert_transaction(&mut tx, tenant_id, CreateInventoryTransaction {
+                product_id: line.product_id,
+                quantity: line.quantity,
+                transaction_type: TransactionType::Purchase,
+                reference_id: Some(order_id),
+                notes: Some(format!("Received PO {} ({})", order.order_number, receipt_number)),
+                reason_code: None,
+                unit_cost: Some(line.unit_price),
+            }).await?;
+
+            let receipt_line_id: Uuid = sqlx::query_scalar(
                 r#"
-                INSERT INTO inventory_transactions 
-                (tenant_id, product_id, quantity, transaction_type, reference_id, notes)
-                VALUES ($1, $2, $3, $4, $5, $6)
+                INSERT INTO purchase_receipt_lines (receipt_id, order_line_id, product_id, quantity, unit_cost, weight, area, transaction_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING id
                 "#
             )
-            .bind(tenant_id)
+            .bind(receipt_id)
+            .bind(line.id)
             .bind(line.product_id)
             .bind(line.quantity)
-            .bind(TransactionType::Purchase)
-            .bind(order_id)
-            .bind(format!("Received PO {}", order.order_number))
-            .execute(&mut *tx)
+            .bind(line.unit_price)
+            .bind(line.weight)
+            .bind(line.area)
+            .bind(record.id)
+            .fetch_one(&mut *tx)
             .await
             .map_err(|e| Error::Database(e.to_string()))?;
 
-            sqlx::query(
-                r#"
-                UPDATE products
-                SET stock_quantity = stock_quantity + $1, updated_at = NOW()
-                WHERE id = $2 AND tenant_id = $3
-                "#
-            )
-            .bind(line.quantity)
-            .bind(line.product_id)
-            .bind(tenant_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| Error::Database(e.to_string()))?;
+            hold_for_inspection(&mut tx, tenant_id, InspectionType::Receipt, line.product_id, line.quantity, Some(receipt_line_id), None).await?;
+
+            inventory_value += record.total_cost.unwrap_or_default();
+            received_value += (line.quantity * line.unit_price).round_dp(2);
         }
 
+        // Receipts accrue to GRNI at PO price; any gap to the inventory value (standard costing) is PPV.
+        let memo = format!("Received PO {}", order.order_number);
+        let variance = received_value - inventory_value;
+        let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "RCV", date).await?;
+        post_journal_entry(&mut tx, tenant_id, &entry_number, date, &memo, &[
+            LedgerLine::debit("1300", inventory_value, &memo),
+            LedgerLine::debit("5400", variance.max(Decimal::ZERO), &memo),
+            LedgerLine::credit("5400", (-variance).max(Decimal::ZERO), &memo),
+            LedgerLine::credit("2050", received_value, &memo),
+        ]).await?;
+
         tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
 
         Ok(updated_order)
     }
 }
+
+/// New orders and bills need an active supplier.
+pub async fn check_supplier_active(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, supplier_id: Uuid) -> Result<(), Error> {
+    let (name, is_active): (String, bool) = sqlx::query_as("SELECT name, is_active FROM suppliers WHERE id = $1 AND tenant_id = $2")
+        .bind(supplier_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Supplier not found".to_string()))?;
+
+    if !is_active {
+        return Err(Error::BusinessRule(format!("Supplier {} is inactive", name)));
+    }
+    Ok(())
+}
+
+/// Moves what the tenant owes the supplier: up when a bill posts, down when it is paid.
+pub async fn adjust_supplier_balance(tx: &mut Transaction<'_, Postgres>, supplier_id: Uuid, amount: Decimal) -> Result<(), Error> {
+    sqlx::query("UPDATE suppliers SET balance = balance + $1, updated_at = NOW() WHERE id = $2")
+        .bind(amount)
+        .bind(supplier_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// A price to add to the supplier's history.
+pub struct NewSupplierPrice<'a> {
+    pub supplier_id: Uuid,
+    pub product_id: Uuid,
+    pub unit_price: Decimal,
+    pub min_order_quantity: Option<Decimal>,
+    pub lead_time_days: Option<i32>,
+    pub source: PriceSource,
+    pub source_id: Uuid,
+    pub source_number: &'a str,
+    pub effective_date: NaiveDate,
+}
+
+pub async fn record_supplier_price(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, price: NewSupplierPrice<'_>) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO supplier_price_history (tenant_id, supplier_id, product_id, unit_price, min_order_quantity, lead_time_days,
+                                            source, source_id, source_number, effective_date)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#
+    )
+    .bind(tenant_id)
+    .bind(price.supplier_id)
+    .bind(price.product_id)
+    .bind(price.unit_price)
+    .bind(price.min_order_quantity)
+    .bind(price.lead_time_days)
+    .bind(price.source)
+    .bind(price.source_id)
+    .bind(price.source_number)
+    .bind(price.effective_date)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// The supplier's latest quoted or ordered price for the product, falling back to its cost.
+pub async fn default_price(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, supplier_id: Uuid, product_id: Uuid) -> Result<Decimal, Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(
+            (SELECT unit_price FROM supplier_price_history
+             WHERE tenant_id = $1 AND supplier_id = $2 AND product_id = $3
+             ORDER BY effective_date DESC, created_at DESC
+             LIMIT 1),
+            p.cost_price)
+        FROM products p
+        WHERE p.id = $3 AND p.tenant_id = $1
+        "#
+    )
+    .bind(tenant_id)
+    .bind(supplier_id)
+    .bind(product_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Product not found".to_string()))
+}
+
+/// Days the supplier last quoted or was ordered at for the product, falling back to the product's lead time.
+pub async fn default_lead_time(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, supplier_id: Uuid, product_id: Uuid) -> Result<i32, Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(
+            (SELECT lead_time_days FROM supplier_price_history
+             WHERE tenant_id = $1 AND supplier_id = $2 AND product_id = $3 AND lead_time_days IS NOT NULL
+             ORDER BY effective_date DESC, created_at DESC
+             LIMIT 1),
+            p.lead_time_days)
+        FROM products p
+        WHERE p.id = $3 AND p.tenant_id = $1
+        "#
+    )
+    .bind(tenant_id)
+    .bind(supplier_id)
+    .bind(product_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Product not found".to_string()))
+}
@@ -0,0 +1,753 @@
+use smart_erp_core::models::quality::*;
+use smart_erp_core::models::inventory::{AdjustmentReason, CreateInventoryTransaction, TransactionType};
+use smart_erp_core::error::Error;
+use crate::db::inventory::insert_transaction;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresQualityRepository {
+    pool: PgPool,
+}
+
+impl PostgresQualityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_plans(&self, tenant_id: Uuid) -> Result<Vec<InspectionPlan>, Error> {
+        sqlx::query_as::<_, InspectionPlan>("SELECT * FROM inspection_plans WHERE tenant_id = $1 ORDER BY name")
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_plan(&self, tenant_id: Uuid, plan_id: Uuid) -> Result<InspectionPlanDetail, Error> {
+        let plan = sqlx::query_as::<_, InspectionPlan>("SELECT * FROM inspection_plans WHERE id = $1 AND tenant_id = $2")
+            .bind(plan_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Inspection plan not found".to_string()))?;
+
+        let characteristics = sqlx::query_as::<_, InspectionCharacteristic>(
+            "SELECT * FROM inspection_characteristics WHERE plan_id = $1 ORDER BY sequence"
+        )
+        .bind(plan_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(InspectionPlanDetail { plan, characteristics })
+    }
+
+    pub async fn create_plan(&self, tenant_id: Uuid, req: CreateInspectionPlan) -> Result<InspectionPlanDetail, Error> {
+        if req.name.trim().is_empty() {
+            return Err(Error::BusinessRule("Inspection plan name is required".to_string()));
+        }
+        if req.sample_size.is_some_and(|s| s <= Decimal::ZERO) {
+            return Err(Error::BusinessRule("Sample size must be positive".to_string()));
+        }
+        if req.characteristics.iter().any(|c| c.name.trim().is_empty()) {
+            return Err(Error::BusinessRule("Name every characteristic".to_string()));
+        }
+        if req.characteristics.iter().any(|c| matches!((c.lower_limit, c.upper_limit), (Some(l), Some(u)) if l > u)) {
+            return Err(Error::BusinessRule("Lower limits cannot be above upper limits".to_string()));
+        }
+        if req.routing_operation_id.is_some() && req.inspection_type != InspectionType::Production {
+            return Err(Error::BusinessRule("Only production inspections can be tied to an operation".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM products WHERE id = $1 AND tenant_id = $2)")
+            .bind(req.product_id)
+            .bind(tenant_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if !exists {
+            return Err(Error::NotFound("Product not found".to_string()));
+        }
+
+        let plan = sqlx::query_as::<_, InspectionPlan>(
+            r#"
+            INSERT INTO inspection_plans (tenant_id, product_id, name, inspection_type, routing_operation_id, sample_size, notes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.product_id)
+        .bind(req.name.trim())
+        .bind(req.inspection_type)
+        .bind(req.routing_operation_id)
+        .bind(req.sample_size)
+        .bind(req.notes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for (i, characteristic) in req.characteristics.into_iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO inspection_characteristics (plan_id, sequence, name, unit, lower_limit, upper_limit)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#
+            )
+            .bind(plan.id)
+            .bind((i as i32 + 1) * 10)
+            .bind(characteristic.name.trim())
+            .bind(characteristic.unit)
+            .bind(characteristic.lower_limit)
+            .bind(characteristic.upper_limit)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_plan(tenant_id, plan.id).await
+    }
+
+    pub async fn update_plan(&self, tenant_id: Uuid, plan_id: Uuid, req: UpdateInspectionPlan) -> Result<InspectionPlan, Error> {
+        if req.sample_size.is_some_and(|s| s <= Decimal::ZERO) {
+            return Err(Error::BusinessRule("Sample size must be positive".to_string()));
+        }
+
+        sqlx::query_as::<_, InspectionPlan>(
+            r#"
+            UPDATE inspection_plans
+            SET name = COALESCE($1, name), sample_size = COALESCE($2, sample_size), is_active = COALESCE($3, is_active),
+                notes = COALESCE($4, notes), updated_at = NOW()
+            WHERE id = $5 AND tenant_id = $6
+            RETURNING *
+            "#
+        )
+        .bind(req.name)
+        .bind(req.sample_size)
+        .bind(req.is_active)
+        .bind(req.notes)
+        .bind(plan_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Inspection plan not found".to_string()))
+    }
+
+    pub async fn list_inspections(&self, tenant_id: Uuid, query: InspectionQuery) -> Result<Vec<QualityInspection>, Error> {
+        sqlx::query_as::<_, QualityInspection>(
+            r#"
+            SELECT * FROM quality_inspections
+            WHERE tenant_id = $1 AND ($2::varchar IS NULL OR status = $2) AND ($3::uuid IS NULL OR product_id = $3)
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.status)
+        .bind(query.product_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_inspection(&self, tenant_id: Uuid, inspection_id: Uuid) -> Result<InspectionDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let detail = load_detail(&mut tx, tenant_id, inspection_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    pub async fn create_inspection(&self, tenant_id: Uuid, req: CreateInspection) -> Result<InspectionDetail, Error> {
+        if req.quantity <= Decimal::ZERO {
+            return Err(Error::BusinessRule("Inspection quantity must be positive".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        if let Some(plan_id) = req.plan_id {
+            let plan_product: Option<Uuid> = sqlx::query_scalar("SELECT product_id FROM inspection_plans WHERE id = $1 AND tenant_id = $2")
+                .bind(plan_id)
+                .bind(tenant_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            if plan_product.ok_or(Error::NotFound("Inspection plan not found".to_string()))? != req.product_id {
+                return Err(Error::BusinessRule("The inspection plan is for a different product".to_string()));
+            }
+        }
+
+        let inspection = match req.work_order_id {
+            Some(work_order_id) => {
+                let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM work_orders WHERE id = $1 AND tenant_id = $2)")
+                    .bind(work_order_id)
+                    .bind(tenant_id)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(|e| Error::Database(e.to_string()))?;
+                if !exists {
+                    return Err(Error::NotFound("Work Order not found".to_string()));
+                }
+                insert_inspection(&mut tx, tenant_id, NewInspection {
+                    plan_id: req.plan_id,
+                    product_id: req.product_id,
+                    source_type: InspectionSource::Production,
+                    receipt_line_id: None,
+                    work_order_id: Some(work_order_id),
+                    parent_id: None,
+                    quantity: req.quantity,
+                    quarantined: false,
+                    notes: req.notes,
+                }).await?
+            }
+            None => {
+                let (sku, stock, quarantined): (String, Decimal, Decimal) = sqlx::query_as(
+                    "SELECT sku, stock_quantity, quarantined_quantity FROM products WHERE id = $1 AND tenant_id = $2 FOR UPDATE"
+                )
+                .bind(req.product_id)
+                .bind(tenant_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?
+                .ok_or(Error::NotFound("Product not found".to_string()))?;
+                if stock - quarantined < req.quantity {
+                    return Err(Error::BusinessRule(format!(
+                        "Only {} of {} is available to quarantine",
+                        (stock - quarantined).max(Decimal::ZERO), sku
+                    )));
+                }
+
+                let inspection = insert_inspection(&mut tx, tenant_id, NewInspection {
+                    plan_id: req.plan_id,
+                    product_id: req.product_id,
+                    source_type: InspectionSource::Manual,
+                    receipt_line_id: None,
+                    work_order_id: None,
+                    parent_id: None,
+                    quantity: req.quantity,
+                    quarantined: true,
+                    notes: req.notes,
+                }).await?;
+                adjust_quarantine(&mut tx, req.product_id, req.quantity).await?;
+                inspection
+            }
+        };
+
+        let detail = load_detail(&mut tx, tenant_id, inspection.id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    /// Records the inspector's results. A pass releases the whole quantity; a failure releases
+    /// only what was not rejected and keeps the rest in quarantine until it is dispositioned.
+    pub async fn record_inspection(&self, tenant_id: Uuid, inspection_id: Uuid, req: RecordInspection) -> Result<InspectionDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let inspection = lock_inspection(&mut tx, tenant_id, inspection_id).await?;
+        if inspection.status != InspectionStatus::Pending {
+            return Err(Error::BusinessRule(format!("Inspection {} has already been recorded", inspection.inspection_number)));
+        }
+
+        let inspector: Option<String> = sqlx::query_scalar("SELECT status FROM employees WHERE id = $1 AND tenant_id = $2")
+            .bind(req.inspector_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if inspector.ok_or(Error::NotFound("Employee not found".to_string()))? != "ACTIVE" {
+            return Err(Error::BusinessRule("The inspector is not an active employee".to_string()));
+        }
+
+        let characteristics = load_characteristics(&mut tx, inspection.plan_id).await?;
+        let results = evaluate_results(&characteristics, req.results)?;
+        if results.is_empty() {
+            return Err(Error::BusinessRule("Record at least one result".to_string()));
+        }
+
+        let passed = results.iter().all(|r| r.passed);
+        let rejected = if passed {
+            Decimal::ZERO
+        } else {
+            req.rejected_quantity.unwrap_or(inspection.quantity)
+        };
+        if rejected < Decimal::ZERO || rejected > inspection.quantity || (!passed && rejected.is_zero()) {
+            return Err(Error::BusinessRule(format!(
+                "A failed inspection rejects between 0 and {} units",
+                inspection.quantity
+            )));
+        }
+
+        for result in results {
+            sqlx::query(
+                r#"
+                INSERT INTO inspection_results (inspection_id, characteristic_id, name, measured_value, passed, notes)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#
+            )
+            .bind(inspection_id)
+            .bind(result.characteristic_id)
+            .bind(result.name)
+            .bind(result.measured_value)
+            .bind(result.passed)
+            .bind(result.notes)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        let accepted = inspection.quantity - rejected;
+        sqlx::query(
+            r#"
+            UPDATE quality_inspections
+            SET status = $1, accepted_quantity = $2, rejected_quantity = $3, inspector_id = $4, inspected_at = NOW(),
+                notes = COALESCE($5, notes), updated_at = NOW()
+            WHERE id = $6
+            "#
+        )
+        .bind(if passed { InspectionStatus::Passed } else { InspectionStatus::Failed })
+        .bind(accepted)
+        .bind(rejected)
+        .bind(req.inspector_id)
+        .bind(req.notes)
+        .bind(inspection_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        if inspection.quarantined && accepted > Decimal::ZERO {
+            adjust_quarantine(&mut tx, inspection.product_id, -accepted).await?;
+        }
+
+        let detail = load_detail(&mut tx, tenant_id, inspection_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    /// Decides what happens to rejected stock: released as is, sent for rework and
+    /// re-inspection, scrapped, or returned to the supplier it was received from.
+    pub async fn dispose(&self, tenant_id: Uuid, inspection_id: Uuid, req: CreateDisposition, created_by: Uuid) -> Result<InspectionDetail, Error> {
+        if req.quantity <= Decimal::ZERO {
+            return Err(Error::BusinessRule("Disposition quantity must be positive".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let inspection = lock_inspection(&mut tx, tenant_id, inspection_id).await?;
+        if !inspection.quarantined {
+            return Err(Error::BusinessRule("In-process inspections hold no stock to disposition".to_string()));
+        }
+        if inspection.status != InspectionStatus::Failed {
+            return Err(Error::BusinessRule("Only rejected stock from a failed inspection can be dispositioned".to_string()));
+        }
+        if req.quantity > inspection.quarantined_quantity() {
+            return Err(Error::BusinessRule(format!(
+                "Only {} remains in quarantine on {}",
+                inspection.quarantined_quantity(), inspection.inspection_number
+            )));
+        }
+
+        let (mut transaction_id, mut rework_inspection_id) = (None, None);
+        match req.action {
+            DispositionAction::Release => {
+                adjust_quarantine(&mut tx, inspection.product_id, -req.quantity).await?;
+            }
+            DispositionAction::Rework => {
+                // The reworked stock stays in quarantine under a new inspection.
+                let rework = insert_inspection(&mut tx, tenant_id, NewInspection {
+                    plan_id: inspection.plan_id,
+                    product_id: inspection.product_id,
+                    source_type: InspectionSource::Rework,
+                    receipt_line_id: inspection.receipt_line_id,
+                    work_order_id: inspection.work_order_id,
+                    parent_id: Some(inspection.id),
+                    quantity: req.quantity,
+                    quarantined: true,
+                    notes: req.notes.clone(),
+                }).await?;
+                rework_inspection_id = Some(rework.id);
+            }
+            DispositionAction::Scrap => {
+                adjust_quarantine(&mut tx, inspection.product_id, -req.quantity).await?;
+                transaction_id = Some(scrap(&mut tx, tenant_id, &inspection, req.quantity).await?);
+            }
+            DispositionAction::ReturnToVendor => {
+                let receipt_line_id = inspection.receipt_line_id
+                    .ok_or(Error::BusinessRule("Only stock received from a supplier can be returned to them".to_string()))?;
+                adjust_quarantine(&mut tx, inspection.product_id, -req.quantity).await?;
+                transaction_id = Some(return_to_vendor(&mut tx, tenant_id, &inspection, receipt_line_id, req.quantity).await?);
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO quality_dispositions (inspection_id, action, quantity, transaction_id, rework_inspection_id, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(inspection_id)
+        .bind(req.action)
+        .bind(req.quantity)
+        .bind(transaction_id)
+        .bind(rework_inspection_id)
+        .bind(req.notes)
+        .bind(created_by)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        sqlx::query("UPDATE quality_inspections SET disposed_quantity = disposed_quantity + $1, updated_at = NOW() WHERE id = $2")
+            .bind(req.quantity)
+            .bind(inspection_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let detail = load_detail(&mut tx, tenant_id, inspection_id).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(detail)
+    }
+
+    /// Products with stock held in quarantine.
+    pub async fn list_quarantine(&self, tenant_id: Uuid) -> Result<Vec<QuarantineLine>, Error> {
+        sqlx::query_as::<_, QuarantineLine>(
+            r#"
+            SELECT id AS product_id, sku, name, stock_quantity, quarantined_quantity,
+                   stock_quantity - quarantined_quantity AS available_quantity
+            FROM products
+            WHERE tenant_id = $1 AND quarantined_quantity > 0
+            ORDER BY sku
+            "#
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+}
+
+struct NewInspection {
+    plan_id: Option<Uuid>,
+    product_id: Uuid,
+    source_type: InspectionSource,
+    receipt_line_id: Option<Uuid>,
+    work_order_id: Option<Uuid>,
+    parent_id: Option<Uuid>,
+    quantity: Decimal,
+    quarantined: bool,
+    notes: Option<String>,
+}
+
+/// Holds newly received or produced stock in quarantine when the product has an active
+/// inspection plan for that kind of receipt. Plans tied to an operation are in-process
+/// checks and are not triggered here.
+pub async fn hold_for_inspection(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    inspection_type: InspectionType,
+    product_id: Uuid,
+    quantity: Decimal,
+    receipt_line_id: Option<Uuid>,
+    work_order_id: Option<Uuid>,
+) -> Result<Option<QualityInspection>, Error> {
+    if quantity <= Decimal::ZERO {
+        return Ok(None);
+    }
+
+    let plan_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM inspection_plans
+        WHERE tenant_id = $1 AND product_id = $2 AND inspection_type = $3 AND is_active AND routing_operation_id IS NULL
+        ORDER BY created_at
+        LIMIT 1
+        "#
+    )
+    .bind(tenant_id)
+    .bind(product_id)
+    .bind(inspection_type)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let Some(plan_id) = plan_id else {
+        return Ok(None);
+    };
+
+    let inspection = insert_inspection(tx, tenant_id, NewInspection {
+        plan_id: Some(plan_id),
+        product_id,
+        source_type: match inspection_type {
+            InspectionType::Receipt => InspectionSource::Receipt,
+            InspectionType::Production => InspectionSource::Production,
+        },
+        receipt_line_id,
+        work_order_id,
+        parent_id: None,
+        quantity,
+        quarantined: true,
+        notes: None,
+    }).await?;
+    adjust_quarantine(tx, product_id, quantity).await?;
+
+    Ok(Some(inspection))
+}
+
+/// Fails when covering the quantity would dip into stock held in quarantine. Stock that is
+/// simply short is left to the caller.
+pub async fn check_unquarantined(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, product_id: Uuid, quantity: Decimal) -> Result<(), Error> {
+    let (sku, stock, quarantined): (String, Decimal, Decimal) = sqlx::query_as(
+        "SELECT sku, stock_quantity, quarantined_quantity FROM products WHERE id = $1 AND tenant_id = $2 FOR UPDATE"
+    )
+    .bind(product_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+    if quarantined > Decimal::ZERO && stock - quarantined < quantity {
+        return Err(Error::BusinessRule(format!(
+            "Only {} of {} is available: {} is quarantined pending inspection",
+            (stock - quarantined).max(Decimal::ZERO), sku, quarantined
+        )));
+    }
+    Ok(())
+}
+
+/// Rejected stock shipped back to the supplier on a vendor credit.
+pub struct QuarantineReturn<'a> {
+    pub inspection_id: Uuid,
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    pub transaction_id: Uuid,
+    pub notes: &'a str,
+    pub created_by: Uuid,
+}
+
+/// Releases the returned stock from quarantine and records the return against the inspection.
+/// The stock movement and accounting belong to the credit.
+pub async fn return_from_quarantine(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, ret: QuarantineReturn<'_>) -> Result<QualityInspection, Error> {
+    let QuarantineReturn { inspection_id, product_id, quantity, transaction_id, notes, created_by } = ret;
+    let inspection = lock_inspection(tx, tenant_id, inspection_id).await?;
+    if inspection.product_id != product_id {
+        return Err(Error::BusinessRule(format!("Inspection {} is for a different product", inspection.inspection_number)));
+    }
+    if inspection.status != InspectionStatus::Failed || inspection.receipt_line_id.is_none() {
+        return Err(Error::BusinessRule("Only rejected stock received from a supplier can be returned to them".to_string()));
+    }
+    if quantity > inspection.quarantined_quantity() {
+        return Err(Error::BusinessRule(format!(
+            "Only {} remains in quarantine on {}",
+            inspection.quarantined_quantity(), inspection.inspection_number
+        )));
+    }
+
+    adjust_quarantine(tx, product_id, -quantity).await?;
+    sqlx::query(
+        r#"
+        INSERT INTO quality_dispositions (inspection_id, action, quantity, transaction_id, notes, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#
+    )
+    .bind(inspection.id)
+    .bind(DispositionAction::ReturnToVendor)
+    .bind(quantity)
+    .bind(transaction_id)
+    .bind(notes)
+    .bind(created_by)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    sqlx::query("UPDATE quality_inspections SET disposed_quantity = disposed_quantity + $1, updated_at = NOW() WHERE id = $2")
+        .bind(quantity)
+        .bind(inspection.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(inspection)
+}
+
+async fn insert_inspection(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, new: NewInspection) -> Result<QualityInspection, Error> {
+    let date = Utc::now().date_naive();
+    let inspection_number = next_document_number(tx, tenant_id, "quality_inspections", "inspection_number", "QC", date).await?;
+
+    sqlx::query_as::<_, QualityInspection>(
+        r#"
+        INSERT INTO quality_inspections (tenant_id, inspection_number, plan_id, product_id, source_type, receipt_line_id,
+                                         work_order_id, parent_id, quantity, quarantined, notes)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#
+    )
+    .bind(tenant_id)
+    .bind(inspection_number)
+    .bind(new.plan_id)
+    .bind(new.product_id)
+    .bind(new.source_type)
+    .bind(new.receipt_line_id)
+    .bind(new.work_order_id)
+    .bind(new.parent_id)
+    .bind(new.quantity)
+    .bind(new.quarantined)
+    .bind(new.notes)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))
+}
+
+async fn adjust_quarantine(tx: &mut Transaction<'_, Postgres>, product_id: Uuid, quantity: Decimal) -> Result<(), Error> {
+    sqlx::query("UPDATE products SET quarantined_quantity = GREATEST(quarantined_quantity + $1, 0), updated_at = NOW() WHERE id = $2")
+        .bind(quantity)
+        .bind(product_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+async fn lock_inspection(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, inspection_id: Uuid) -> Result<QualityInspection, Error> {
+    sqlx::query_as::<_, QualityInspection>("SELECT * FROM quality_inspections WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+        .bind(inspection_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Inspection not found".to_string()))
+}
+
+async fn load_characteristics(tx: &mut Transaction<'_, Postgres>, plan_id: Option<Uuid>) -> Result<Vec<InspectionCharacteristic>, Error> {
+    sqlx::query_as::<_, InspectionCharacteristic>("SELECT * FROM inspection_characteristics WHERE plan_id = $1 ORDER BY sequence")
+        .bind(plan_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+}
+
+async fn load_detail(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, inspection_id: Uuid) -> Result<InspectionDetail, Error> {
+    let inspection = sqlx::query_as::<_, QualityInspection>("SELECT * FROM quality_inspections WHERE id = $1 AND tenant_id = $2")
+        .bind(inspection_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Inspection not found".to_string()))?;
+
+    let characteristics = load_characteristics(tx, inspection.plan_id).await?;
+
+    let results = sqlx::query_as::<_, InspectionResult>(
+        r#"
+        SELECT r.* FROM inspection_results r
+        LEFT JOIN inspection_characteristics c ON c.id = r.characteristic_id
+        WHERE r.inspection_id = $1
+        ORDER BY c.sequence NULLS LAST, r.name
+        "#
+    )
+    .bind(inspection_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let dispositions = sqlx::query_as::<_, QualityDisposition>(
+        "SELECT * FROM quality_dispositions WHERE inspection_id = $1 ORDER BY created_at"
+    )
+    .bind(inspection_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    Ok(InspectionDetail { inspection, characteristics, results, dispositions })
+}
+
+/// Writes rejected stock off to Inventory Adjustments.
+async fn scrap(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, inspection: &QualityInspection, quantity: Decimal) -> Result<Uuid, Error> {
+    let record = insert_transaction(tx, tenant_id, CreateInventoryTransaction {
+        product_id: inspection.product_id,
+        quantity: -quantity,
+        transaction_type: TransactionType::Adjustment,
+        reference_id: Some(inspection.id),
+        notes: Some(format!("Scrapped on {}", inspection.inspection_number)),
+        reason_code: Some(AdjustmentReason::QualityReject),
+        unit_cost: None,
+    }).await?;
+
+    let value = record.total_cost.unwrap_or_default();
+    let memo = format!("Quality reject {}", inspection.inspection_number);
+    let date = Utc::now().date_naive();
+    let entry_number = next_document_number(tx, tenant_id, "journal_entries", "entry_number", "SCR", date).await?;
+    post_journal_entry(tx, tenant_id, &entry_number, date, &memo, &[
+        LedgerLine::debit("5900", value, &memo),
+        LedgerLine::credit("1300", value, &memo),
+    ]).await?;
+
+    Ok(record.id)
+}
+
+/// Sends unbilled stock back to the supplier. The receipt accrued GRNI at PO price, so the
+/// return reverses it at that price with any gap to the stock value going to PPV. Quantities
+/// already billed are returned on a vendor credit instead.
+async fn return_to_vendor(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, inspection: &QualityInspection, receipt_line_id: Uuid, quantity: Decimal) -> Result<Uuid, Error> {
+    let (received, billed, returned, unit_cost, order_id, order_number): (Decimal, Decimal, Decimal, Decimal, Uuid, String) = sqlx::query_as(
+        r#"
+        SELECT rl.quantity, rl.billed_quantity, rl.returned_quantity, rl.unit_cost, o.id, o.order_number
+        FROM purchase_receipt_lines rl
+        JOIN purchase_receipts r ON r.id = rl.receipt_id
+        JOIN purchase_orders o ON o.id = r.order_id
+        WHERE rl.id = $1 AND r.tenant_id = $2
+        FOR UPDATE OF rl
+        "#
+    )
+    .bind(receipt_line_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Receipt line not found".to_string()))?;
+
+    let unbilled = received - billed - returned;
+    if quantity > unbilled {
+        return Err(Error::BusinessRule(format!(
+            "Only {} of the receipt is unbilled; return billed quantities on a vendor credit",
+            unbilled.max(Decimal::ZERO)
+        )));
+    }
+
+    sqlx::query("UPDATE purchase_receipt_lines SET returned_quantity = returned_quantity + $1 WHERE id = $2")
+        .bind(quantity)
+        .bind(receipt_line_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+    let record = insert_transaction(tx, tenant_id, CreateInventoryTransaction {
+        product_id: inspection.product_id,
+        quantity: -quantity,
+        transaction_type: TransactionType::ReturnToVendor,
+        reference_id: Some(order_id),
+        notes: Some(format!("Returned to vendor PO {} ({})", order_number, inspection.inspection_number)),
+        reason_code: None,
+        unit_cost: None,
+    }).await?;
+
+    let inventory_value = record.total_cost.unwrap_or_default();
+    let returned_value = (quantity * unit_cost).round_dp(2);
+    let variance = inventory_value - returned_value;
+    let memo = format!("Return to vendor PO {}", order_number);
+    let date = Utc::now().date_naive();
+    let entry_number = next_document_number(tx, tenant_id, "journal_entries", "entry_number", "RTV", date).await?;
+    post_journal_entry(tx, tenant_id, &entry_number, date, &memo, &[
+        LedgerLine::debit("2050", returned_value, &memo),
+        LedgerLine::debit("5400", variance.max(Decimal::ZERO), &memo),
+        LedgerLine::credit("5400", (-variance).max(Decimal::ZERO), &memo),
+        LedgerLine::credit("1300", inventory_value, &memo),
+    ]).await?;
+
+    Ok(record.id)
+}
@@ -0,0 +1,224 @@
+use smart_erp_core::models::replenishment::*;
+use smart_erp_core::models::purchasing::PurchaseOrder;
+use smart_erp_core::models::address::AddressType;
+use smart_erp_core::error::Error;
+use crate::db::address::{document_address, Party};
+use crate::db::numbering::next_document_number;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+const STOCK_POSITION_QUERY: &str = r#"
+    SELECT p.id AS product_id, p.sku, p.name,
+           p.stock_quantity - p.quarantined_quantity AS on_hand,
+           COALESCE(so.qty, 0) + COALESCE(wo.qty, 0) AS committed,
+           COALESCE(po.qty, 0) AS on_order,
+           COALESCE(p.reorder_point, 0) AS reorder_point,
+           p.reorder_quantity, p.min_order_qty, p.lead_time_days,
+           ROUND(COALESCE(usage.qty, 0) / 90, 4) AS avg_daily_usage,
+           p.cost_price AS unit_cost,
+           p.preferred_vendor_id,
+           s.name AS supplier_name
+    FROM products p
+    LEFT JOIN suppliers s ON s.id = p.preferred_vendor_id
+    LEFT JOIN LATERAL (
+        SELECT SUM(l.quantity) AS qty
+        FROM sales_order_lines l JOIN sales_orders o ON o.id = l.order_id
+        WHERE o.tenant_id = p.tenant_id AND l.product_id = p.id AND o.status = 'CONFIRMED'
+    ) so ON TRUE
+    LEFT JOIN LATERAL (
+        SELECT SUM(ri.quantity * w.quantity) AS qty
+        FROM work_orders w JOIN recipe_ingredients ri ON ri.recipe_id = w.recipe_id
+        WHERE w.tenant_id = p.tenant_id AND ri.input_product_id = p.id AND w.status IN ('PLANNED', 'IN_PROGRESS')
+    ) wo ON TRUE
+    LEFT JOIN LATERAL (
+        SELECT SUM(l.quantity) AS qty
+        FROM purchase_order_lines l JOIN purchase_orders o ON o.id = l.order_id
+        WHERE o.tenant_id = p.tenant_id AND l.product_id = p.id AND o.status IN ('DRAFT', 'ORDERED')
+    ) po ON TRUE
+    LEFT JOIN LATERAL (
+        SELECT SUM(ABS(t.quantity)) AS qty
+        FROM inventory_transactions t
+        WHERE t.tenant_id = p.tenant_id AND t.product_id = p.id
+          AND t.transaction_type IN ('SALE', 'PRODUCTION_OUT')
+          AND t.created_at >= NOW() - INTERVAL '90 days'
+    ) usage ON TRUE
+    WHERE p.tenant_id = $1 AND p.is_active = true AND p.item_type IN ('INVENTORY', 'ASSEMBLY')
+    ORDER BY s.name NULLS LAST, p.name
+"#;
+
+pub struct PostgresReplenishmentRepository {
+    pool: PgPool,
+}
+
+impl PostgresReplenishmentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_reorder_settings(&self, tenant_id: Uuid, product_id: Uuid) -> Result<ReorderSettings, Error> {
+        sqlx::query_as::<_, ReorderSettings>(
+            "SELECT id AS product_id, COALESCE(reorder_point, 0) AS reorder_point, reorder_quantity, min_order_qty, lead_time_days, preferred_vendor_id FROM products WHERE id = $1 AND tenant_id = $2"
+        )
+        .bind(product_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Product not found".to_string()))
+    }
+
+    pub async fn update_reorder_settings(&self, tenant_id: Uuid, product_id: Uuid, settings: UpdateReorderSettings) -> Result<ReorderSettings, Error> {
+        if settings.reorder_point.is_some_and(|v| v < Decimal::ZERO)
+            || settings.reorder_quantity.is_some_and(|v| v < Decimal::ZERO)
+            || settings.min_order_qty.is_some_and(|v| v < Decimal::ZERO)
+            || settings.lead_time_days.is_some_and(|v| v < 0)
+        {
+            return Err(Error::BusinessRule("Reorder settings cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, ReorderSettings>(
+            r#"
+            UPDATE products
+            SET reorder_point = COALESCE($3, reorder_point),
+                reorder_quantity = COALESCE($4, reorder_quantity),
+                min_order_qty = COALESCE($5, min_order_qty),
+                lead_time_days = COALESCE($6, lead_time_days),
+                preferred_vendor_id = CASE WHEN $8 THEN NULL ELSE COALESCE($7, preferred_vendor_id) END,
+                updated_at = NOW()
+            WHERE id = $1 AND tenant_id = $2
+            RETURNING id AS product_id, COALESCE(reorder_point, 0) AS reorder_point, reorder_quantity, min_order_qty, lead_time_days, preferred_vendor_id
+            "#
+        )
+        .bind(product_id)
+        .bind(tenant_id)
+        .bind(settings.reorder_point)
+        .bind(settings.reorder_quantity)
+        .bind(settings.min_order_qty)
+        .bind(settings.lead_time_days)
+        .bind(settings.preferred_vendor_id)
+        .bind(settings.clear_preferred_vendor)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Product not found".to_string()))
+    }
+
+    pub async fn stock_positions(&self, tenant_id: Uuid) -> Result<Vec<StockPosition>, Error> {
+        sqlx::query_as::<_, StockPosition>(STOCK_POSITION_QUERY)
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn suggestions(&self, tenant_id: Uuid) -> Result<Vec<SupplierReplenishment>, Error> {
+        let positions = self.stock_positions(tenant_id).await?;
+        Ok(group_by_supplier(&positions, Utc::now().date_naive()))
+    }
+
+    /// Creates one DRAFT purchase order per preferred supplier from the current suggestions.
+    /// Products without a preferred supplier, or whose preferred supplier is inactive, are left
+    /// for manual ordering.
+    pub async fn generate_purchase_orders(&self, tenant_id: Uuid, req: GenerateReplenishmentOrders) -> Result<Vec<PurchaseOrder>, Error> {
+        let date = req.date.unwrap_or_else(|| Utc::now().date_naive());
+        let positions = self.stock_positions(tenant_id).await?;
+        let groups = group_by_supplier(&positions, date);
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let mut orders = Vec::new();
+
+        for group in groups {
+            let supplier_id = match group.supplier_id {
+                Some(id) => id,
+                None => continue,
+            };
+            if let Some(ids) = &req.supplier_ids {
+                if !ids.contains(&supplier_id) {
+                    continue;
+                }
+            }
+            let active: Option<bool> = sqlx::query_scalar("SELECT is_active FROM suppliers WHERE id = $1 AND tenant_id = $2")
+                .bind(supplier_id)
+                .bind(tenant_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            if active != Some(true) {
+                continue;
+            }
+
+            let order_number = next_document_number(&mut tx, tenant_id, "purchase_orders", "order_number", "RPL", date).await?;
+            let remit_to = document_address(&mut tx, tenant_id, Party::Supplier(supplier_id), AddressType::RemitTo, None).await?;
+
+            let po = sqlx::query_as::<_, PurchaseOrder>(
+                r#"
+                INSERT INTO purchase_orders (tenant_id, supplier_id, order_number, date, total_amount, status, remit_to_address_id, remit_to_address)
+                VALUES ($1, $2, $3, $4, $5, 'DRAFT', $6, $7)
+                RETURNING id, tenant_id, supplier_id, order_number, date, expected_date, status, total_amount, created_at, updated_at, remit_to_address_id, remit_to_address
+                "#
+            )
+            .bind(tenant_id)
+            .bind(supplier_id)
+            .bind(order_number)
+            .bind(date)
+            .bind(group.total_amount)
+            .bind(remit_to.as_ref().map(|a| a.id))
+            .bind(remit_to.as_ref().map(|a| a.formatted()))
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            for line in group.lines {
+                sqlx::query(
+                    r#"
+                    INSERT INTO purchase_order_lines (order_id, product_id, quantity, unit_price)
+                    VALUES ($1, $2, $3, $4)
+                    "#
+                )
+                .bind(po.id)
+                .bind(line.product_id)
+                .bind(line.suggested_quantity)
+                .bind(line.unit_cost)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            }
+
+            orders.push(po);
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(orders)
+    }
+
+    // --- Low Stock Report ---
+    pub async fn low_stock_report(&self, tenant_id: Uuid) -> Result<LowStockReport, Error> {
+        let positions = self.stock_positions(tenant_id).await?;
+        let as_of = Utc::now().date_naive();
+
+        let mut lines = Vec::new();
+        let mut total_shortfall_value = Decimal::ZERO;
+
+        for p in positions.iter().filter(|p| p.reorder_point > Decimal::ZERO && p.available() <= p.reorder_point) {
+            let shortfall = p.reorder_point - p.available();
+            total_shortfall_value += shortfall * p.unit_cost;
+            lines.push(LowStockLine {
+                product_id: p.product_id,
+                sku: p.sku.clone(),
+                name: p.name.clone(),
+                on_hand: p.on_hand,
+                available: p.available(),
+                on_order: p.on_order,
+                reorder_point: p.reorder_point,
+                shortfall,
+                suggested_quantity: p.suggested_order_quantity(),
+                preferred_vendor: p.supplier_name.clone(),
+            });
+        }
+
+        Ok(LowStockReport { as_of, lines, total_shortfall_value })
+    }
+}
@@ -102,7 +102,12 @@ impl PostgresReportsRepository {
             "SELECT s.name, b.bill_number, b.total_amount, b.amount_paid, CURRENT_DATE - b.due_date as days_past
              FROM bills b JOIN suppliers s ON b.supplier_id = s.id
              WHERE b.tenant_id = $1 AND b.status != 'PAID'
-             ORDER BY s.name, days_past DESC"
+             UNION ALL
+             -- Unapplied vendor credits count against the supplier as current
+             SELECT s.name, c.credit_number, -c.total_amount, -c.amount_applied, 0
+             FROM vendor_credits c JOIN suppliers s ON c.supplier_id = s.id
+             WHERE c.tenant_id = $1 AND c.status != 'APPLIED'
+             ORDER BY 1, 5 DESC"
         ).bind(tenant_id).fetch_all(&self.pool).await.map_err(|e| Error::Database(e.to_string()))?;
 
         let mut lines: Vec<AgingLine> = Vec::new();
@@ -150,12 +155,12 @@ impl PostgresReportsRepository {
     // --- General Ledger ---
     pub async fn general_ledger(&self, tenant_id: Uuid) -> Result<GeneralLedger, Error> {
         let rows = sqlx::query_as::<_, (chrono::NaiveDate, String, String, String, Decimal, Decimal)>(
-            "SELECT je.date, COALESCE(a.account_number, ''), a.name, COALESCE(jel.description, je.memo, ''),
+            "SELECT je.date, COALESCE(a.account_number, ''), a.name, COALESCE(jel.memo, je.memo, ''),
                     COALESCE(jel.debit, 0), COALESCE(jel.credit, 0)
              FROM journal_entries je
-             JOIN journal_entry_lines jel ON jel.journal_entry_id = je.id
+             JOIN journal_entry_lines jel ON jel.entry_id = je.id
              JOIN accounts a ON a.id = jel.account_id
-             WHERE je.tenant_id = $1
+             WHERE je.tenant_id = $1 AND je.status = 'POSTED'
              ORDER BY je.date DESC, a.account_number"
         ).bind(tenant_id).fetch_all(&self.pool).await.map_err(|e| Error::Database(e.to_string()))?;
 
@@ -0,0 +1,612 @@
+use smart_erp_core::models::requisition::*;
+use smart_erp_core::models::auth::Role;
+use smart_erp_core::models::purchasing::PurchaseOrder;
+use smart_erp_core::models::address::AddressType;
+use smart_erp_core::error::Error;
+use crate::db::address::{document_address, Party};
+use crate::db::numbering::next_document_number;
+use crate::db::purchasing::check_supplier_active;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresRequisitionRepository {
+    pool: PgPool,
+}
+
+impl PostgresRequisitionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // --- Approval Rules ---
+    pub async fn list_rules(&self, tenant_id: Uuid) -> Result<Vec<RequisitionApprovalRule>, Error> {
+        sqlx::query_as::<_, RequisitionApprovalRule>(
+            "SELECT * FROM requisition_approval_rules WHERE tenant_id = $1 ORDER BY sequence, min_amount, name"
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_rule(&self, tenant_id: Uuid, req: CreateRequisitionApprovalRule) -> Result<RequisitionApprovalRule, Error> {
+        if req.name.trim().is_empty() {
+            return Err(Error::BusinessRule("Rule name is required".to_string()));
+        }
+        if req.min_amount.is_some_and(|a| a < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Minimum amount cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, RequisitionApprovalRule>(
+            r#"
+            INSERT INTO requisition_approval_rules (tenant_id, name, min_amount, department, category, approver_role, sequence)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.name.trim())
+        .bind(req.min_amount.unwrap_or(Decimal::ZERO))
+        .bind(req.department)
+        .bind(req.category)
+        .bind(req.approver_role)
+        .bind(req.sequence.unwrap_or(1))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Changes apply to requisitions submitted from now on; steps already raised are kept.
+    pub async fn update_rule(&self, tenant_id: Uuid, rule_id: Uuid, req: UpdateRequisitionApprovalRule) -> Result<RequisitionApprovalRule, Error> {
+        if req.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(Error::BusinessRule("Rule name is required".to_string()));
+        }
+        if req.min_amount.is_some_and(|a| a < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Minimum amount cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, RequisitionApprovalRule>(
+            r#"
+            UPDATE requisition_approval_rules
+            SET name = COALESCE($1, name),
+                min_amount = COALESCE($2, min_amount),
+                department = COALESCE($3, department),
+                category = COALESCE($4, category),
+                approver_role = COALESCE($5, approver_role),
+                sequence = COALESCE($6, sequence),
+                is_active = COALESCE($7, is_active),
+                updated_at = NOW()
+            WHERE id = $8 AND tenant_id = $9
+            RETURNING *
+            "#
+        )
+        .bind(req.name.map(|n| n.trim().to_string()))
+        .bind(req.min_amount)
+        .bind(req.department)
+        .bind(req.category)
+        .bind(req.approver_role)
+        .bind(req.sequence)
+        .bind(req.is_active)
+        .bind(rule_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Approval rule not found".to_string()))
+    }
+
+    // --- Requisitions ---
+    pub async fn list_requisitions(&self, tenant_id: Uuid, query: RequisitionQuery, role: &str) -> Result<Vec<PurchaseRequisition>, Error> {
+        let mut requisitions = sqlx::query_as::<_, PurchaseRequisition>(
+            r#"
+            SELECT * FROM purchase_requisitions
+            WHERE tenant_id = $1 AND ($2::varchar IS NULL OR status = $2)
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.status)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        if query.awaiting_me {
+            let approvals = sqlx::query_as::<_, RequisitionApproval>(
+                r#"
+                SELECT a.* FROM requisition_approvals a
+                JOIN purchase_requisitions r ON r.id = a.requisition_id
+                WHERE r.tenant_id = $1 AND r.status = 'PENDING'
+                ORDER BY a.sequence
+                "#
+            )
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            requisitions.retain(|r| {
+                let steps: Vec<RequisitionApproval> = approvals.iter().filter(|a| a.requisition_id == r.id).cloned().collect();
+                current_steps(&steps).iter().any(|s| can_approve(role, &s.approver_role))
+            });
+        }
+
+        Ok(requisitions)
+    }
+
+    pub async fn get_requisition(&self, tenant_id: Uuid, requisition_id: Uuid) -> Result<RequisitionDetail, Error> {
+        let requisition = sqlx::query_as::<_, PurchaseRequisition>(
+            "SELECT * FROM purchase_requisitions WHERE id = $1 AND tenant_id = $2"
+        )
+        .bind(requisition_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Requisition not found".to_string()))?;
+
+        let lines = sqlx::query_as::<_, PurchaseRequisitionLine>(
+            "SELECT * FROM purchase_requisition_lines WHERE requisition_id = $1 ORDER BY sort_order"
+        )
+        .bind(requisition.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let approvals = sqlx::query_as::<_, RequisitionApproval>(
+            "SELECT * FROM requisition_approvals WHERE requisition_id = $1 ORDER BY sequence, rule_name"
+        )
+        .bind(requisition.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let history = sqlx::query_as::<_, RequisitionEvent>(
+            r#"
+            SELECT e.id, e.requisition_id, e.action, e.user_id, u.username, e.comment, e.created_at
+            FROM requisition_events e
+            LEFT JOIN users u ON u.id = e.user_id
+            WHERE e.requisition_id = $1
+            ORDER BY e.created_at, e.id
+            "#
+        )
+        .bind(requisition.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(RequisitionDetail { requisition, lines, approvals, history })
+    }
+
+    /// Prices default to the product's cost and suppliers to its preferred vendor.
+    pub async fn create_requisition(&self, tenant_id: Uuid, req: CreateRequisition, user_id: Uuid) -> Result<RequisitionDetail, Error> {
+        req.validate()?;
+        let today = Utc::now().date_naive();
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let requisition_number = next_document_number(&mut tx, tenant_id, "purchase_requisitions", "requisition_number", "REQ", today).await?;
+        let requisition = sqlx::query_as::<_, PurchaseRequisition>(
+            r#"
+            INSERT INTO purchase_requisitions (tenant_id, requisition_number, requested_by, department, needed_by, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(requisition_number)
+        .bind(user_id)
+        .bind(req.department.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()))
+        .bind(req.needed_by)
+        .bind(req.notes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for (index, line) in req.lines.iter().enumerate() {
+            let (cost_price, preferred_vendor_id): (Decimal, Option<Uuid>) = sqlx::query_as(
+                "SELECT cost_price, preferred_vendor_id FROM products WHERE id = $1 AND tenant_id = $2"
+            )
+            .bind(line.product_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO purchase_requisition_lines (requisition_id, product_id, supplier_id, quantity, estimated_price, sort_order)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#
+            )
+            .bind(requisition.id)
+            .bind(line.product_id)
+            .bind(line.supplier_id.or(preferred_vendor_id))
+            .bind(line.quantity)
+            .bind(line.estimated_price.unwrap_or(cost_price))
+            .bind(index as i32)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE purchase_requisitions
+            SET total_amount = (SELECT COALESCE(SUM(amount), 0) FROM purchase_requisition_lines WHERE requisition_id = $1)
+            WHERE id = $1
+            "#
+        )
+        .bind(requisition.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        record_event(&mut tx, requisition.id, RequisitionAction::Created, user_id, None).await?;
+        if req.submit {
+            submit_requisition(&mut tx, tenant_id, requisition.id, user_id).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_requisition(tenant_id, requisition.id).await
+    }
+
+    pub async fn submit(&self, tenant_id: Uuid, requisition_id: Uuid, user_id: Uuid, role: &str) -> Result<RequisitionDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let requisition = lock_requisition(&mut tx, tenant_id, requisition_id).await?;
+        if requisition.requested_by != user_id && role != Role::Admin.to_string() {
+            return Err(Error::BusinessRule("Only the requester or an administrator can submit a requisition".to_string()));
+        }
+        if requisition.status != RequisitionStatus::Draft {
+            return Err(Error::BusinessRule(format!("Requisition is already {}", requisition.status)));
+        }
+        submit_requisition(&mut tx, tenant_id, requisition.id, user_id).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_requisition(tenant_id, requisition_id).await
+    }
+
+    /// Approves the current step the caller's role allows. The requisition is approved once
+    /// every step is. Requesters cannot approve their own requisitions.
+    pub async fn approve(&self, tenant_id: Uuid, requisition_id: Uuid, user_id: Uuid, role: &str, req: RequisitionDecision) -> Result<RequisitionDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let step = decide_step(&mut tx, tenant_id, requisition_id, user_id, role).await?;
+        set_step_status(&mut tx, step, ApprovalStatus::Approved, user_id, req.comment.as_deref()).await?;
+        record_event(&mut tx, requisition_id, RequisitionAction::Approved, user_id, req.comment.as_deref()).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE purchase_requisitions
+            SET status = 'APPROVED', decided_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+              AND NOT EXISTS (SELECT 1 FROM requisition_approvals WHERE requisition_id = $1 AND status = 'PENDING')
+            "#
+        )
+        .bind(requisition_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_requisition(tenant_id, requisition_id).await
+    }
+
+    /// A single rejection rejects the whole requisition.
+    pub async fn reject(&self, tenant_id: Uuid, requisition_id: Uuid, user_id: Uuid, role: &str, req: RequisitionDecision) -> Result<RequisitionDetail, Error> {
+        let comment = required_comment(req.comment, "Give a reason for the rejection")?;
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let step = decide_step(&mut tx, tenant_id, requisition_id, user_id, role).await?;
+        set_step_status(&mut tx, step, ApprovalStatus::Rejected, user_id, Some(&comment)).await?;
+        record_event(&mut tx, requisition_id, RequisitionAction::Rejected, user_id, Some(&comment)).await?;
+        set_status(&mut tx, requisition_id, RequisitionStatus::Rejected).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_requisition(tenant_id, requisition_id).await
+    }
+
+    pub async fn comment(&self, tenant_id: Uuid, requisition_id: Uuid, user_id: Uuid, req: RequisitionDecision) -> Result<RequisitionDetail, Error> {
+        let comment = required_comment(req.comment, "Comment cannot be empty")?;
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        lock_requisition(&mut tx, tenant_id, requisition_id).await?;
+        record_event(&mut tx, requisition_id, RequisitionAction::Commented, user_id, Some(&comment)).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_requisition(tenant_id, requisition_id).await
+    }
+
+    pub async fn cancel(&self, tenant_id: Uuid, requisition_id: Uuid, user_id: Uuid, role: &str, req: RequisitionDecision) -> Result<RequisitionDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let requisition = lock_requisition(&mut tx, tenant_id, requisition_id).await?;
+        if requisition.requested_by != user_id && role != Role::Admin.to_string() {
+            return Err(Error::BusinessRule("Only the requester or an administrator can cancel a requisition".to_string()));
+        }
+        if !matches!(requisition.status, RequisitionStatus::Draft | RequisitionStatus::Pending | RequisitionStatus::Approved) {
+            return Err(Error::BusinessRule(format!("Cannot cancel a {} requisition", requisition.status)));
+        }
+        set_status(&mut tx, requisition_id, RequisitionStatus::Cancelled).await?;
+        record_event(&mut tx, requisition_id, RequisitionAction::Cancelled, user_id, req.comment.as_deref()).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_requisition(tenant_id, requisition_id).await
+    }
+
+    /// Turns approved requisitions into DRAFT purchase orders, one per supplier across all of them.
+    pub async fn convert(&self, tenant_id: Uuid, req: ConvertRequisitions, user_id: Uuid) -> Result<Vec<PurchaseOrder>, Error> {
+        if req.requisition_ids.is_empty() {
+            return Err(Error::BusinessRule("Select at least one requisition".to_string()));
+        }
+        let date = req.date.unwrap_or_else(|| Utc::now().date_naive());
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        for id in &req.requisition_ids {
+            let requisition = lock_requisition(&mut tx, tenant_id, *id).await?;
+            if requisition.status != RequisitionStatus::Approved {
+                return Err(Error::BusinessRule(format!(
+                    "Requisition {} is {}, only APPROVED requisitions can be converted",
+                    requisition.requisition_number, requisition.status
+                )));
+            }
+        }
+
+        let lines: Vec<(Uuid, Uuid, Option<Uuid>, String, Decimal, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT l.id, l.product_id, l.supplier_id, p.sku, l.quantity, l.estimated_price
+            FROM purchase_requisition_lines l
+            JOIN purchase_requisitions r ON r.id = l.requisition_id
+            JOIN products p ON p.id = l.product_id
+            WHERE l.requisition_id = ANY($1)
+            ORDER BY r.requisition_number, l.sort_order
+            "#
+        )
+        .bind(&req.requisition_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut groups: BTreeMap<Uuid, Vec<(Uuid, Uuid, Decimal, Decimal)>> = BTreeMap::new();
+        for (line_id, product_id, supplier_id, sku, quantity, price) in lines {
+            let supplier_id = supplier_id.ok_or(Error::BusinessRule(format!("No supplier for {}; set one on the line or the product", sku)))?;
+            groups.entry(supplier_id).or_default().push((line_id, product_id, quantity, price));
+        }
+
+        let mut orders = Vec::new();
+        for (supplier_id, lines) in groups {
+            check_supplier_active(&mut tx, tenant_id, supplier_id).await?;
+
+            let order_number = next_document_number(&mut tx, tenant_id, "purchase_orders", "order_number", "PO", date).await?;
+            let total_amount: Decimal = lines.iter().map(|(_, _, quantity, price)| quantity * price).sum();
+            let remit_to = document_address(&mut tx, tenant_id, Party::Supplier(supplier_id), AddressType::RemitTo, None).await?;
+
+            let po = sqlx::query_as::<_, PurchaseOrder>(
+                r#"
+                INSERT INTO purchase_orders (tenant_id, supplier_id, order_number, date, total_amount, status, remit_to_address_id, remit_to_address)
+                VALUES ($1, $2, $3, $4, $5, 'DRAFT', $6, $7)
+                RETURNING id, tenant_id, supplier_id, order_number, date, expected_date, status, total_amount, created_at, updated_at, remit_to_address_id, remit_to_address
+                "#
+            )
+            .bind(tenant_id)
+            .bind(supplier_id)
+            .bind(order_number)
+            .bind(date)
+            .bind(total_amount)
+            .bind(remit_to.as_ref().map(|a| a.id))
+            .bind(remit_to.as_ref().map(|a| a.formatted()))
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            for (line_id, product_id, quantity, price) in lines {
+                sqlx::query(
+                    r#"
+                    INSERT INTO purchase_order_lines (order_id, product_id, quantity, unit_price)
+                    VALUES ($1, $2, $3, $4)
+                    "#
+                )
+                .bind(po.id)
+                .bind(product_id)
+                .bind(quantity)
+                .bind(price)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+
+                sqlx::query("UPDATE purchase_requisition_lines SET purchase_order_id = $1 WHERE id = $2")
+                    .bind(po.id)
+                    .bind(line_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| Error::Database(e.to_string()))?;
+            }
+
+            orders.push(po);
+        }
+
+        for id in &req.requisition_ids {
+            let numbers: Vec<String> = sqlx::query_scalar(
+                r#"
+                SELECT DISTINCT po.order_number FROM purchase_requisition_lines l
+                JOIN purchase_orders po ON po.id = l.purchase_order_id
+                WHERE l.requisition_id = $1
+                ORDER BY po.order_number
+                "#
+            )
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            set_status(&mut tx, *id, RequisitionStatus::Converted).await?;
+            record_event(&mut tx, *id, RequisitionAction::Converted, user_id, Some(&numbers.join(", "))).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(orders)
+    }
+}
+
+async fn lock_requisition(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, requisition_id: Uuid) -> Result<PurchaseRequisition, Error> {
+    sqlx::query_as::<_, PurchaseRequisition>(
+        "SELECT * FROM purchase_requisitions WHERE id = $1 AND tenant_id = $2 FOR UPDATE"
+    )
+    .bind(requisition_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Requisition not found".to_string()))
+}
+
+/// Raises one approval step per matching rule. With no rule matching the requisition is
+/// approved straight away.
+async fn submit_requisition(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, requisition_id: Uuid, user_id: Uuid) -> Result<(), Error> {
+    let requisition = lock_requisition(tx, tenant_id, requisition_id).await?;
+
+    let categories: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT p.category FROM purchase_requisition_lines l
+        JOIN products p ON p.id = l.product_id
+        WHERE l.requisition_id = $1 AND p.category IS NOT NULL
+        "#
+    )
+    .bind(requisition.id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let rules = sqlx::query_as::<_, RequisitionApprovalRule>(
+        "SELECT * FROM requisition_approval_rules WHERE tenant_id = $1 AND is_active = TRUE ORDER BY sequence, name"
+    )
+    .bind(tenant_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let mut steps = 0;
+    for rule in rules.iter().filter(|r| r.applies_to(requisition.total_amount, requisition.department.as_deref(), &categories)) {
+        sqlx::query(
+            r#"
+            INSERT INTO requisition_approvals (requisition_id, rule_id, rule_name, sequence, approver_role)
+            VALUES ($1, $2, $3, $4, $5)
+            "#
+        )
+        .bind(requisition.id)
+        .bind(rule.id)
+        .bind(&rule.name)
+        .bind(rule.sequence)
+        .bind(rule.approver_role)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+        steps += 1;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE purchase_requisitions
+        SET status = $1, submitted_at = NOW(), decided_at = CASE WHEN $1 = 'APPROVED' THEN NOW() END, updated_at = NOW()
+        WHERE id = $2
+        "#
+    )
+    .bind(if steps == 0 { RequisitionStatus::Approved } else { RequisitionStatus::Pending })
+    .bind(requisition.id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let note = (steps == 0).then_some("No approval rule applies");
+    record_event(tx, requisition.id, RequisitionAction::Submitted, user_id, note).await
+}
+
+/// Finds the current step the user may decide on.
+async fn decide_step(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, requisition_id: Uuid, user_id: Uuid, role: &str) -> Result<Uuid, Error> {
+    let requisition = lock_requisition(tx, tenant_id, requisition_id).await?;
+    if requisition.status != RequisitionStatus::Pending {
+        return Err(Error::BusinessRule(format!("Requisition is {}, not waiting on approval", requisition.status)));
+    }
+    if requisition.requested_by == user_id {
+        return Err(Error::BusinessRule("You cannot decide on your own requisition".to_string()));
+    }
+
+    let approvals = sqlx::query_as::<_, RequisitionApproval>(
+        "SELECT * FROM requisition_approvals WHERE requisition_id = $1 ORDER BY sequence, rule_name"
+    )
+    .bind(requisition.id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let current = current_steps(&approvals);
+    current
+        .iter()
+        .find(|s| can_approve(role, &s.approver_role))
+        .map(|s| s.id)
+        .ok_or_else(|| {
+            let roles: Vec<String> = current.iter().map(|s| s.approver_role.to_string()).collect();
+            Error::BusinessRule(format!("Waiting on approval from {}", roles.join(", ")))
+        })
+}
+
+async fn set_step_status(tx: &mut Transaction<'_, Postgres>, step_id: Uuid, status: ApprovalStatus, user_id: Uuid, comment: Option<&str>) -> Result<(), Error> {
+    sqlx::query(
+        "UPDATE requisition_approvals SET status = $1, decided_by = $2, decided_at = NOW(), comment = $3 WHERE id = $4"
+    )
+    .bind(status)
+    .bind(user_id)
+    .bind(comment)
+    .bind(step_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+async fn set_status(tx: &mut Transaction<'_, Postgres>, requisition_id: Uuid, status: RequisitionStatus) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        UPDATE purchase_requisitions
+        SET status = $1, decided_at = CASE WHEN $1 IN ('REJECTED', 'CANCELLED') THEN NOW() ELSE decided_at END, updated_at = NOW()
+        WHERE id = $2
+        "#
+    )
+    .bind(status)
+    .bind(requisition_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Stamped with the clock rather than the transaction start so events from one request stay in order.
+async fn record_event(tx: &mut Transaction<'_, Postgres>, requisition_id: Uuid, action: RequisitionAction, user_id: Uuid, comment: Option<&str>) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO requisition_events (requisition_id, action, user_id, comment, created_at)
+        VALUES ($1, $2, $3, $4, clock_timestamp())
+        "#
+    )
+    .bind(requisition_id)
+    .bind(action)
+    .bind(user_id)
+    .bind(comment)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+fn required_comment(comment: Option<String>, message: &str) -> Result<String, Error> {
+    comment
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .ok_or(Error::BusinessRule(message.to_string()))
+}
@@ -0,0 +1,407 @@
+use smart_erp_core::models::rfq::*;
+use smart_erp_core::models::purchasing::{PriceSource, PurchaseOrder};
+use smart_erp_core::models::address::AddressType;
+use smart_erp_core::error::Error;
+use crate::db::address::{document_address, Party};
+use crate::db::numbering::next_document_number;
+use crate::db::purchasing::{check_supplier_active, record_supplier_price, NewSupplierPrice};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresRfqRepository {
+    pool: PgPool,
+}
+
+impl PostgresRfqRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_rfqs(&self, tenant_id: Uuid, query: RfqQuery) -> Result<Vec<Rfq>, Error> {
+        sqlx::query_as::<_, Rfq>(
+            r#"
+            SELECT * FROM rfqs
+            WHERE tenant_id = $1 AND ($2::varchar IS NULL OR status = $2)
+            ORDER BY issue_date DESC, created_at DESC
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.status)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_rfq(&self, tenant_id: Uuid, rfq_id: Uuid) -> Result<RfqDetail, Error> {
+        let rfq = sqlx::query_as::<_, Rfq>("SELECT * FROM rfqs WHERE id = $1 AND tenant_id = $2")
+            .bind(rfq_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("RFQ not found".to_string()))?;
+
+        let lines = sqlx::query_as::<_, RfqLine>(
+            r#"
+            SELECT l.id, l.rfq_id, l.product_id, p.sku, p.name, l.quantity, l.sort_order
+            FROM rfq_lines l
+            JOIN products p ON p.id = l.product_id
+            WHERE l.rfq_id = $1
+            ORDER BY l.sort_order
+            "#
+        )
+        .bind(rfq.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let suppliers = sqlx::query_as::<_, RfqSupplier>(
+            r#"
+            SELECT rs.id, rs.rfq_id, rs.supplier_id, s.name AS supplier_name, rs.status, rs.responded_at, rs.valid_until, rs.notes
+            FROM rfq_suppliers rs
+            JOIN suppliers s ON s.id = rs.supplier_id
+            WHERE rs.rfq_id = $1
+            ORDER BY s.name
+            "#
+        )
+        .bind(rfq.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let quotes = sqlx::query_as::<_, RfqQuote>(
+            r#"
+            SELECT q.id, q.rfq_supplier_id, rs.supplier_id, q.rfq_line_id, q.unit_price, q.lead_time_days, q.min_order_quantity, q.notes
+            FROM rfq_quotes q
+            JOIN rfq_suppliers rs ON rs.id = q.rfq_supplier_id
+            WHERE rs.rfq_id = $1
+            "#
+        )
+        .bind(rfq.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(RfqDetail { rfq, lines, suppliers, quotes })
+    }
+
+    pub async fn create_rfq(&self, tenant_id: Uuid, req: CreateRfq, user_id: Uuid) -> Result<RfqDetail, Error> {
+        req.validate()?;
+        let issue_date = req.issue_date.unwrap_or_else(|| Utc::now().date_naive());
+        if req.response_due.is_some_and(|d| d < issue_date) {
+            return Err(Error::BusinessRule("Responses cannot be due before the RFQ is issued".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let rfq_number = next_document_number(&mut tx, tenant_id, "rfqs", "rfq_number", "RFQ", issue_date).await?;
+        let rfq = sqlx::query_as::<_, Rfq>(
+            r#"
+            INSERT INTO rfqs (tenant_id, rfq_number, title, issue_date, response_due, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(rfq_number)
+        .bind(req.title)
+        .bind(issue_date)
+        .bind(req.response_due)
+        .bind(req.notes)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for (index, line) in req.lines.iter().enumerate() {
+            let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM products WHERE id = $1 AND tenant_id = $2)")
+                .bind(line.product_id)
+                .bind(tenant_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            if !exists {
+                return Err(Error::NotFound("Product not found".to_string()));
+            }
+
+            sqlx::query("INSERT INTO rfq_lines (rfq_id, product_id, quantity, sort_order) VALUES ($1, $2, $3, $4)")
+                .bind(rfq.id)
+                .bind(line.product_id)
+                .bind(line.quantity)
+                .bind(index as i32)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        let mut supplier_ids = req.supplier_ids.clone();
+        supplier_ids.sort();
+        supplier_ids.dedup();
+        for supplier_id in supplier_ids {
+            check_supplier_active(&mut tx, tenant_id, supplier_id).await?;
+            sqlx::query("INSERT INTO rfq_suppliers (rfq_id, supplier_id) VALUES ($1, $2)")
+                .bind(rfq.id)
+                .bind(supplier_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_rfq(tenant_id, rfq.id).await
+    }
+
+    /// Records a supplier's quote and adds its prices to the supplier's price history.
+    pub async fn record_quote(&self, tenant_id: Uuid, rfq_id: Uuid, req: RecordQuote) -> Result<RfqDetail, Error> {
+        req.validate()?;
+        let today = Utc::now().date_naive();
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let rfq = lock_open_rfq(&mut tx, tenant_id, rfq_id).await?;
+        let rfq_supplier_id = invited_supplier(&mut tx, rfq.id, req.supplier_id).await?;
+
+        sqlx::query("DELETE FROM rfq_quotes WHERE rfq_supplier_id = $1")
+            .bind(rfq_supplier_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        for line in &req.lines {
+            let product_id: Uuid = sqlx::query_scalar("SELECT product_id FROM rfq_lines WHERE id = $1 AND rfq_id = $2")
+                .bind(line.rfq_line_id)
+                .bind(rfq.id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?
+                .ok_or(Error::BusinessRule("Quote line is not part of the RFQ".to_string()))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO rfq_quotes (rfq_supplier_id, rfq_line_id, unit_price, lead_time_days, min_order_quantity, notes)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#
+            )
+            .bind(rfq_supplier_id)
+            .bind(line.rfq_line_id)
+            .bind(line.unit_price)
+            .bind(line.lead_time_days)
+            .bind(line.min_order_quantity)
+            .bind(&line.notes)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            record_supplier_price(&mut tx, tenant_id, NewSupplierPrice {
+                supplier_id: req.supplier_id,
+                product_id,
+                unit_price: line.unit_price,
+                min_order_quantity: line.min_order_quantity,
+                lead_time_days: line.lead_time_days,
+                source: PriceSource::Quote,
+                source_id: rfq.id,
+                source_number: &rfq.rfq_number,
+                effective_date: today,
+            }).await?;
+        }
+
+        sqlx::query(
+            "UPDATE rfq_suppliers SET status = 'RESPONDED', responded_at = NOW(), valid_until = $1, notes = COALESCE($2, notes) WHERE id = $3"
+        )
+        .bind(req.valid_until)
+        .bind(req.notes)
+        .bind(rfq_supplier_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_rfq(tenant_id, rfq_id).await
+    }
+
+    pub async fn decline(&self, tenant_id: Uuid, rfq_id: Uuid, req: DeclineRfq) -> Result<RfqDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let rfq = lock_open_rfq(&mut tx, tenant_id, rfq_id).await?;
+        let rfq_supplier_id = invited_supplier(&mut tx, rfq.id, req.supplier_id).await?;
+
+        sqlx::query("DELETE FROM rfq_quotes WHERE rfq_supplier_id = $1")
+            .bind(rfq_supplier_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        sqlx::query("UPDATE rfq_suppliers SET status = 'DECLINED', responded_at = NOW(), notes = COALESCE($1, notes) WHERE id = $2")
+            .bind(req.notes)
+            .bind(rfq_supplier_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_rfq(tenant_id, rfq_id).await
+    }
+
+    pub async fn comparison(&self, tenant_id: Uuid, rfq_id: Uuid) -> Result<QuoteComparison, Error> {
+        let detail = self.get_rfq(tenant_id, rfq_id).await?;
+        Ok(QuoteComparison::build(detail))
+    }
+
+    /// Turns the supplier's quote into a DRAFT purchase order at the quoted prices, raising
+    /// quantities to the supplier's minimums. Lines the supplier did not quote are left off.
+    pub async fn award(&self, tenant_id: Uuid, rfq_id: Uuid, req: AwardRfq) -> Result<PurchaseOrder, Error> {
+        let date = req.date.unwrap_or_else(|| Utc::now().date_naive());
+        let detail = self.get_rfq(tenant_id, rfq_id).await?;
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let rfq = lock_open_rfq(&mut tx, tenant_id, rfq_id).await?;
+        let supplier = detail
+            .suppliers
+            .iter()
+            .find(|s| s.supplier_id == req.supplier_id)
+            .ok_or(Error::BusinessRule("Supplier was not invited to quote".to_string()))?;
+        if supplier.status != RfqSupplierStatus::Responded {
+            return Err(Error::BusinessRule(format!("{} has not quoted", supplier.supplier_name)));
+        }
+        if supplier.valid_until.is_some_and(|d| d < date) {
+            return Err(Error::BusinessRule(format!("{}'s quote expired on {}", supplier.supplier_name, supplier.valid_until.unwrap_or(date))));
+        }
+        check_supplier_active(&mut tx, tenant_id, supplier.supplier_id).await?;
+
+        let lines: Vec<(&RfqLine, &RfqQuote)> = detail
+            .lines
+            .iter()
+            .filter_map(|line| {
+                detail
+                    .quotes
+                    .iter()
+                    .find(|q| q.rfq_line_id == line.id && q.rfq_supplier_id == supplier.id)
+                    .map(|q| (line, q))
+            })
+            .collect();
+        let total_amount: Decimal = lines
+            .iter()
+            .map(|(line, quote)| (quote.order_quantity(line.quantity) * quote.unit_price).round_dp(2))
+            .sum();
+
+        let lead_time_days = lines.iter().filter_map(|(_, quote)| quote.lead_time_days).max();
+        let expected_date = lead_time_days.map(|days| date + chrono::Duration::days(days.into()));
+
+        let order_number = next_document_number(&mut tx, tenant_id, "purchase_orders", "order_number", "PO", date).await?;
+        let remit_to = document_address(&mut tx, tenant_id, Party::Supplier(supplier.supplier_id), AddressType::RemitTo, None).await?;
+        let po = sqlx::query_as::<_, PurchaseOrder>(
+            r#"
+            INSERT INTO purchase_orders (tenant_id, supplier_id, order_number, date, expected_date, total_amount, status,
+                                         remit_to_address_id, remit_to_address)
+            VALUES ($1, $2, $3, $4, $5, $6, 'DRAFT', $7, $8)
+            RETURNING id, tenant_id, supplier_id, order_number, date, expected_date, status, total_amount, created_at, updated_at, remit_to_address_id, remit_to_address
+            "#
+        )
+        .bind(tenant_id)
+        .bind(supplier.supplier_id)
+        .bind(order_number)
+        .bind(date)
+        .bind(expected_date)
+        .bind(total_amount)
+        .bind(remit_to.as_ref().map(|a| a.id))
+        .bind(remit_to.as_ref().map(|a| a.formatted()))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for (line, quote) in lines {
+            sqlx::query(
+                r#"
+                INSERT INTO purchase_order_lines (order_id, product_id, quantity, unit_price)
+                VALUES ($1, $2, $3, $4)
+                "#
+            )
+            .bind(po.id)
+            .bind(line.product_id)
+            .bind(quote.order_quantity(line.quantity))
+            .bind(quote.unit_price)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            record_supplier_price(&mut tx, tenant_id, NewSupplierPrice {
+                supplier_id: po.supplier_id,
+                product_id: line.product_id,
+                unit_price: quote.unit_price,
+                min_order_quantity: quote.min_order_quantity,
+                lead_time_days: quote.lead_time_days,
+                source: PriceSource::PurchaseOrder,
+                source_id: po.id,
+                source_number: &po.order_number,
+                effective_date: po.date,
+            }).await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE rfq_suppliers
+            SET status = CASE WHEN id = $1 THEN 'AWARDED' WHEN status = 'DECLINED' THEN status ELSE 'LOST' END
+            WHERE rfq_id = $2
+            "#
+        )
+        .bind(supplier.id)
+        .bind(rfq.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        sqlx::query(
+            "UPDATE rfqs SET status = 'AWARDED', awarded_supplier_id = $1, purchase_order_id = $2, updated_at = NOW() WHERE id = $3"
+        )
+        .bind(po.supplier_id)
+        .bind(po.id)
+        .bind(rfq.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(po)
+    }
+
+    pub async fn cancel(&self, tenant_id: Uuid, rfq_id: Uuid) -> Result<Rfq, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let rfq = lock_open_rfq(&mut tx, tenant_id, rfq_id).await?;
+        let rfq = sqlx::query_as::<_, Rfq>("UPDATE rfqs SET status = 'CANCELLED', updated_at = NOW() WHERE id = $1 RETURNING *")
+            .bind(rfq.id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(rfq)
+    }
+}
+
+async fn lock_open_rfq(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, rfq_id: Uuid) -> Result<Rfq, Error> {
+    let rfq = sqlx::query_as::<_, Rfq>("SELECT * FROM rfqs WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+        .bind(rfq_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("RFQ not found".to_string()))?;
+    if rfq.status != RfqStatus::Open {
+        return Err(Error::BusinessRule(format!("RFQ {} is {}", rfq.rfq_number, rfq.status)));
+    }
+    Ok(rfq)
+}
+
+async fn invited_supplier(tx: &mut Transaction<'_, Postgres>, rfq_id: Uuid, supplier_id: Uuid) -> Result<Uuid, Error> {
+    sqlx::query_scalar("SELECT id FROM rfq_suppliers WHERE rfq_id = $1 AND supplier_id = $2")
+        .bind(rfq_id)
+        .bind(supplier_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::BusinessRule("Supplier was not invited to quote".to_string()))
+}
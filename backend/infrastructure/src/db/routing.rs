@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+
+use smart_erp_core::models::routing::*;
+use smart_erp_core::models::manufacturing::{Recipe, WorkOrder, WorkOrderStatus};
+use smart_erp_core::error::Error;
+use crate::db::manufacturing::WORK_ORDER_COLUMNS;
+use chrono::{Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresRoutingRepository {
+    pool: PgPool,
+}
+
+impl PostgresRoutingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_work_centers(&self, tenant_id: Uuid) -> Result<Vec<WorkCenter>, Error> {
+        sqlx::query_as::<_, WorkCenter>("SELECT * FROM work_centers WHERE tenant_id = $1 ORDER BY code")
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn create_work_center(&self, tenant_id: Uuid, req: CreateWorkCenter) -> Result<WorkCenter, Error> {
+        if req.code.trim().is_empty() || req.name.trim().is_empty() {
+            return Err(Error::BusinessRule("Work center code and name are required".to_string()));
+        }
+        let hours_per_day = req.hours_per_day.unwrap_or(Decimal::from(8));
+        let capacity_units = req.capacity_units.unwrap_or(1);
+        let working_days = req.working_days.unwrap_or_else(|| vec![1, 2, 3, 4, 5]);
+        validate_capacity(hours_per_day, capacity_units, &working_days)?;
+
+        sqlx::query_as::<_, WorkCenter>(
+            r#"
+            INSERT INTO work_centers (tenant_id, code, name, description, hours_per_day, capacity_units, working_days)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.code.trim())
+        .bind(req.name.trim())
+        .bind(req.description)
+        .bind(hours_per_day)
+        .bind(capacity_units)
+        .bind(working_days)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn update_work_center(&self, tenant_id: Uuid, work_center_id: Uuid, req: UpdateWorkCenter) -> Result<WorkCenter, Error> {
+        let current = self.get_work_center(tenant_id, work_center_id).await?;
+        validate_capacity(
+            req.hours_per_day.unwrap_or(current.hours_per_day),
+            req.capacity_units.unwrap_or(current.capacity_units),
+            req.working_days.as_deref().unwrap_or(&current.working_days),
+        )?;
+
+        sqlx::query_as::<_, WorkCenter>(
+            r#"
+            UPDATE work_centers
+            SET name = COALESCE($1, name), description = COALESCE($2, description), hours_per_day = COALESCE($3, hours_per_day),
+                capacity_units = COALESCE($4, capacity_units), working_days = COALESCE($5, working_days),
+                is_active = COALESCE($6, is_active), updated_at = NOW()
+            WHERE id = $7 AND tenant_id = $8
+            RETURNING *
+            "#
+        )
+        .bind(req.name)
+        .bind(req.description)
+        .bind(req.hours_per_day)
+        .bind(req.capacity_units)
+        .bind(req.working_days)
+        .bind(req.is_active)
+        .bind(work_center_id)
+        .bind(tenant_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    async fn get_work_center(&self, tenant_id: Uuid, work_center_id: Uuid) -> Result<WorkCenter, Error> {
+        sqlx::query_as::<_, WorkCenter>("SELECT * FROM work_centers WHERE id = $1 AND tenant_id = $2")
+            .bind(work_center_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Work center not found".to_string()))
+    }
+
+    pub async fn list_calendar(&self, tenant_id: Uuid, work_center_id: Uuid) -> Result<Vec<CalendarException>, Error> {
+        self.get_work_center(tenant_id, work_center_id).await?;
+        sqlx::query_as::<_, CalendarException>(
+            "SELECT * FROM work_center_calendar WHERE work_center_id = $1 AND date >= CURRENT_DATE ORDER BY date"
+        )
+        .bind(work_center_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Sets the available hours for specific days, replacing any earlier exception for the day.
+    pub async fn set_calendar(&self, tenant_id: Uuid, work_center_id: Uuid, days: Vec<SetCalendarException>) -> Result<Vec<CalendarException>, Error> {
+        self.get_work_center(tenant_id, work_center_id).await?;
+        if days.iter().any(|d| d.available_hours < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Available hours cannot be negative".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        for day in days {
+            sqlx::query(
+                r#"
+                INSERT INTO work_center_calendar (work_center_id, date, available_hours, reason)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (work_center_id, date) DO UPDATE
+                SET available_hours = EXCLUDED.available_hours, reason = EXCLUDED.reason
+                "#
+            )
+            .bind(work_center_id)
+            .bind(day.date)
+            .bind(day.available_hours)
+            .bind(day.reason)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.list_calendar(tenant_id, work_center_id).await
+    }
+
+    /// Capacity and booked hours per day (the next four weeks by default).
+    pub async fn work_center_load(&self, tenant_id: Uuid, work_center_id: Uuid, query: WorkCenterLoadQuery) -> Result<WorkCenterLoad, Error> {
+        let from = query.from.unwrap_or_else(|| Utc::now().date_naive());
+        let to = query.to.unwrap_or(from + Duration::days(27));
+        if from > to || (to - from).num_days() > SCHEDULING_HORIZON_DAYS {
+            return Err(Error::BusinessRule("Choose a period of up to a year with 'from' before 'to'".to_string()));
+        }
+
+        let work_center = self.get_work_center(tenant_id, work_center_id).await?;
+
+        let exceptions = sqlx::query_as::<_, CalendarException>(
+            "SELECT * FROM work_center_calendar WHERE work_center_id = $1 AND date BETWEEN $2 AND $3"
+        )
+        .bind(work_center_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let allocations = sqlx::query_as::<_, LoadAllocation>(
+            r#"
+            SELECT l.load_date, l.work_order_id, o.name AS operation_name, l.hours
+            FROM work_center_loads l JOIN work_order_operations o ON o.id = l.work_order_operation_id
+            WHERE l.work_center_id = $1 AND l.load_date BETWEEN $2 AND $3
+            ORDER BY l.load_date, o.sequence
+            "#
+        )
+        .bind(work_center_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(WorkCenterLoad::build(work_center, from, to, &exceptions, allocations))
+    }
+
+    pub async fn list_routings(&self, tenant_id: Uuid) -> Result<Vec<Routing>, Error> {
+        sqlx::query_as::<_, Routing>("SELECT * FROM routings WHERE tenant_id = $1 ORDER BY name")
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_routing(&self, tenant_id: Uuid, routing_id: Uuid) -> Result<RoutingDetail, Error> {
+        let routing = sqlx::query_as::<_, Routing>("SELECT * FROM routings WHERE id = $1 AND tenant_id = $2")
+            .bind(routing_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Routing not found".to_string()))?;
+
+        let operations = sqlx::query_as::<_, RoutingOperation>(
+            "SELECT * FROM routing_operations WHERE routing_id = $1 ORDER BY sequence"
+        )
+        .bind(routing_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(RoutingDetail { routing, operations })
+    }
+
+    pub async fn create_routing(&self, tenant_id: Uuid, req: CreateRouting) -> Result<RoutingDetail, Error> {
+        if req.name.trim().is_empty() || req.operations.is_empty() {
+            return Err(Error::BusinessRule("A routing needs a name and at least one operation".to_string()));
+        }
+        if req.operations.iter().any(|o| o.setup_hours < Decimal::ZERO || o.run_hours_per_batch < Decimal::ZERO || o.wait_days < 0) {
+            return Err(Error::BusinessRule("Operation times cannot be negative".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let routing = sqlx::query_as::<_, Routing>(
+            "INSERT INTO routings (tenant_id, name, description) VALUES ($1, $2, $3) RETURNING *"
+        )
+        .bind(tenant_id)
+        .bind(req.name.trim())
+        .bind(req.description)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        for (index, operation) in req.operations.into_iter().enumerate() {
+            let known: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM work_centers WHERE id = $1 AND tenant_id = $2)")
+                .bind(operation.work_center_id)
+                .bind(tenant_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            if !known {
+                return Err(Error::BusinessRule(format!("Work center for operation '{}' not found", operation.name)));
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO routing_operations (routing_id, sequence, name, work_center_id, setup_hours, run_hours_per_batch, wait_days, description)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#
+            )
+            .bind(routing.id)
+            .bind((index as i32 + 1) * 10)
+            .bind(operation.name)
+            .bind(operation.work_center_id)
+            .bind(operation.setup_hours)
+            .bind(operation.run_hours_per_batch)
+            .bind(operation.wait_days)
+            .bind(operation.description)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_routing(tenant_id, routing.id).await
+    }
+
+    pub async fn set_recipe_routing(&self, tenant_id: Uuid, recipe_id: Uuid, req: SetRecipeRouting) -> Result<Recipe, Error> {
+        if let Some(routing_id) = req.routing_id {
+            self.get_routing(tenant_id, routing_id).await?;
+        }
+
+        sqlx::query_as::<_, Recipe>(
+            "UPDATE recipes SET routing_id = $1, updated_at = NOW() WHERE id = $2 AND tenant_id = $3 RETURNING *"
+        )
+        .bind(req.routing_id)
+        .bind(recipe_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Recipe not found".to_string()))
+    }
+
+    pub async fn get_schedule(&self, tenant_id: Uuid, work_order_id: Uuid) -> Result<WorkOrderSchedule, Error> {
+        let work_order = sqlx::query_as::<_, WorkOrder>(&format!(
+            "SELECT {} FROM work_orders WHERE id = $1 AND tenant_id = $2",
+            WORK_ORDER_COLUMNS
+        ))
+        .bind(work_order_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Work Order not found".to_string()))?;
+
+        let operations = sqlx::query_as::<_, WorkOrderOperation>(
+            "SELECT * FROM work_order_operations WHERE work_order_id = $1 ORDER BY sequence"
+        )
+        .bind(work_order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(WorkOrderSchedule { work_order, operations })
+    }
+
+    /// Books the recipe's routing against work center capacity, replacing any earlier schedule
+    /// for the order. Sets the work order start date and scheduled end.
+    pub async fn schedule_work_order(&self, tenant_id: Uuid, work_order_id: Uuid, req: ScheduleWorkOrder) -> Result<WorkOrderSchedule, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let work_order = sqlx::query_as::<_, WorkOrder>(&format!(
+            "SELECT {} FROM work_orders WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+            WORK_ORDER_COLUMNS
+        ))
+        .bind(work_order_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Work Order not found".to_string()))?;
+
+        if work_order.status == WorkOrderStatus::Completed || work_order.status == WorkOrderStatus::Cancelled {
+            return Err(Error::BusinessRule(format!("Cannot schedule work order with status {:?}.", work_order.status)));
+        }
+
+        let routing_id: Option<Uuid> = sqlx::query_scalar("SELECT routing_id FROM recipes WHERE id = $1")
+            .bind(work_order.recipe_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        let routing_id = routing_id.ok_or(Error::BusinessRule("The work order's recipe has no routing".to_string()))?;
+
+        let routing_operations = sqlx::query_as::<_, RoutingOperation>(
+            "SELECT * FROM routing_operations WHERE routing_id = $1 ORDER BY sequence"
+        )
+        .bind(routing_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        // Rescheduling releases the order's own capacity first.
+        sqlx::query("DELETE FROM work_order_operations WHERE work_order_id = $1")
+            .bind(work_order_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut calendars = load_calendars(&mut tx, tenant_id, &routing_operations).await?;
+
+        let today = Utc::now().date_naive();
+        let anchor = match req.direction {
+            ScheduleDirection::Forward => req.start_date.or(work_order.start_date).unwrap_or(today).max(today),
+            ScheduleDirection::Backward => req.due_date.ok_or(Error::BusinessRule("Backward scheduling needs a due date".to_string()))?,
+        };
+        let operations: Vec<OperationToSchedule> = routing_operations
+            .iter()
+            .map(|o| OperationToSchedule {
+                routing_operation_id: Some(o.id),
+                sequence: o.sequence,
+                name: o.name.clone(),
+                work_center_id: o.work_center_id,
+                hours: o.hours_for(work_order.quantity), // Work order quantity is in batches
+                wait_days: o.wait_days,
+            })
+            .collect();
+
+        let scheduled = schedule(&mut calendars, &operations, req.direction, anchor, today)?;
+
+        for operation in &scheduled {
+            let operation_id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO work_order_operations (work_order_id, routing_operation_id, sequence, name, work_center_id, planned_hours, scheduled_start, scheduled_end)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING id
+                "#
+            )
+            .bind(work_order_id)
+            .bind(operation.routing_operation_id)
+            .bind(operation.sequence)
+            .bind(&operation.name)
+            .bind(operation.work_center_id)
+            .bind(operation.hours)
+            .bind(operation.start)
+            .bind(operation.end)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            for (date, hours) in &operation.allocations {
+                sqlx::query(
+                    "INSERT INTO work_center_loads (work_center_id, work_order_operation_id, work_order_id, load_date, hours) VALUES ($1, $2, $3, $4, $5)"
+                )
+                .bind(operation.work_center_id)
+                .bind(operation_id)
+                .bind(work_order_id)
+                .bind(date)
+                .bind(hours)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?;
+            }
+        }
+
+        let start = scheduled.iter().map(|o| o.start).min();
+        let end = scheduled.iter().map(|o| o.end).max();
+        sqlx::query("UPDATE work_orders SET start_date = COALESCE($1, start_date), scheduled_end = $2, updated_at = NOW() WHERE id = $3")
+            .bind(start)
+            .bind(end)
+            .bind(work_order_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_schedule(tenant_id, work_order_id).await
+    }
+}
+
+fn validate_capacity(hours_per_day: Decimal, capacity_units: i32, working_days: &[i32]) -> Result<(), Error> {
+    if hours_per_day < Decimal::ZERO || hours_per_day > Decimal::from(24) {
+        return Err(Error::BusinessRule("Hours per day must be between 0 and 24".to_string()));
+    }
+    if capacity_units < 1 {
+        return Err(Error::BusinessRule("Capacity units must be at least 1".to_string()));
+    }
+    if working_days.iter().any(|d| !(1..=7).contains(d)) {
+        return Err(Error::BusinessRule("Working days are ISO weekdays from 1 (Monday) to 7 (Sunday)".to_string()));
+    }
+    Ok(())
+}
+
+/// Capacity calendars for the work centers the operations use, with hours already booked from
+/// today on.
+async fn load_calendars(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, operations: &[RoutingOperation]) -> Result<HashMap<Uuid, CapacityCalendar>, Error> {
+    let mut calendars = HashMap::new();
+    for work_center_id in operations.iter().map(|o| o.work_center_id) {
+        if calendars.contains_key(&work_center_id) {
+            continue;
+        }
+
+        let Some(work_center) = sqlx::query_as::<_, WorkCenter>(
+            "SELECT * FROM work_centers WHERE id = $1 AND tenant_id = $2 AND is_active = true"
+        )
+        .bind(work_center_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))? else { continue };
+
+        let exceptions = sqlx::query_as::<_, CalendarException>(
+            "SELECT * FROM work_center_calendar WHERE work_center_id = $1 AND date >= CURRENT_DATE"
+        )
+        .bind(work_center_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let loads: Vec<(NaiveDate, Decimal)> = sqlx::query_as(
+            "SELECT load_date, SUM(hours) FROM work_center_loads WHERE work_center_id = $1 AND load_date >= CURRENT_DATE GROUP BY load_date"
+        )
+        .bind(work_center_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        calendars.insert(work_center_id, CapacityCalendar::new(work_center, &exceptions, &loads));
+    }
+    Ok(calendars)
+}
@@ -1,13 +1,26 @@
 use async_trait::async_trait;
 use smart_erp_core::models::sales::{
-    CreateCustomer, CreateSalesOrder, Customer, SalesOrder, SalesOrderLine, SalesOrderStatus,
-    SalesService,
+    CreateCustomer, CreateSalesOrder, Customer, CustomerCenter, CustomerCredit, ReleaseCreditHold,
+    SalesOrder, SalesOrderLine, SalesOrderStatus, SalesService, SetCreditLimit,
 };
-use smart_erp_core::models::inventory::TransactionType;
+use smart_erp_core::models::accounting::{Invoice, Payment};
+use smart_erp_core::models::address::{AddressType, PartyAddress, PartyContact};
+use smart_erp_core::models::transactions::CreditMemo;
+use smart_erp_core::models::inventory::{CreateInventoryTransaction, TransactionType};
 use smart_erp_core::error::Error;
+use crate::db::address::{document_address, insert_address, insert_contact, Party};
+use crate::db::inventory::insert_transaction;
+use crate::db::quality::check_unquarantined;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use crate::db::payment_terms::active_terms;
+use chrono::Utc;
+use rust_decimal::Decimal;
 use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
+const ORDER_COLUMNS: &str = "id, tenant_id, customer_id, order_number, date, status, total_amount, created_at, updated_at, hold_reason, credit_override_by, credit_override_at, credit_override_reason, billing_address_id, billing_address, shipping_address_id, shipping_address";
+
 pub struct PostgresSalesRepository {
     pool: PgPool,
 }
@@ -27,6 +40,152 @@ impl PostgresSalesRepository {
         .map_err(|e| Error::Database(e.to_string()))?;
         Ok(rows)
     }
+
+    pub async fn set_credit_limit(&self, tenant_id: Uuid, customer_id: Uuid, req: SetCreditLimit) -> Result<Customer, Error> {
+        if req.credit_limit.is_some_and(|limit| limit < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Credit limit cannot be negative".to_string()));
+        }
+
+        sqlx::query_as::<_, Customer>(
+            r#"
+            UPDATE customers SET credit_limit = $1, credit_hold = $2, updated_at = NOW()
+            WHERE id = $3 AND tenant_id = $4
+            RETURNING *
+            "#
+        )
+        .bind(req.credit_limit)
+        .bind(req.credit_hold)
+        .bind(customer_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Customer not found".to_string()))
+    }
+
+    /// Confirms a held order over its credit check. The hold reason is kept for the record.
+    pub async fn release_hold(&self, tenant_id: Uuid, order_id: Uuid, req: ReleaseCreditHold, user_id: Uuid) -> Result<SalesOrder, Error> {
+        if req.reason.trim().is_empty() {
+            return Err(Error::BusinessRule("Give a reason for overriding the credit hold".to_string()));
+        }
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let order = lock_order(&mut tx, tenant_id, order_id).await?;
+        if order.status != SalesOrderStatus::OnHold {
+            return Err(Error::BusinessRule("Only orders on credit hold can be released".to_string()));
+        }
+
+        let order = sqlx::query_as::<_, SalesOrder>(&format!(
+            r#"
+            UPDATE sales_orders
+            SET status = 'CONFIRMED', credit_override_by = $1, credit_override_at = NOW(),
+                credit_override_reason = $2, updated_at = NOW()
+            WHERE id = $3
+            RETURNING {}
+            "#,
+            ORDER_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(req.reason.trim())
+        .bind(order_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(order)
+    }
+
+    pub async fn customer_center(&self, tenant_id: Uuid, customer_id: Uuid) -> Result<CustomerCenter, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let customer = sqlx::query_as::<_, Customer>("SELECT * FROM customers WHERE id = $1 AND tenant_id = $2")
+            .bind(customer_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Customer not found".to_string()))?;
+        let credit = customer_credit(&mut tx, tenant_id, customer_id, None).await?;
+
+        let addresses = sqlx::query_as::<_, PartyAddress>(
+            "SELECT * FROM party_addresses WHERE customer_id = $1 ORDER BY address_type, is_default DESC, created_at"
+        )
+        .bind(customer_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let contacts = sqlx::query_as::<_, PartyContact>(
+            "SELECT * FROM party_contacts WHERE customer_id = $1 ORDER BY is_primary DESC, name"
+        )
+        .bind(customer_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let open_invoices = sqlx::query_as::<_, Invoice>(
+            r#"
+            SELECT id, tenant_id, customer_id, sales_order_id, invoice_number, date, due_date, status, total_amount,
+                   amount_paid, created_at, updated_at, payment_terms_id, discount_date, discount_amount, discount_taken,
+                   billing_address_id, billing_address, shipping_address_id, shipping_address
+            FROM invoices
+            WHERE tenant_id = $1 AND customer_id = $2 AND status NOT IN ('PAID', 'CANCELLED')
+            ORDER BY due_date, invoice_number
+            "#
+        )
+        .bind(tenant_id)
+        .bind(customer_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let orders = sqlx::query_as::<_, SalesOrder>(&format!(
+            "SELECT {} FROM sales_orders WHERE tenant_id = $1 AND customer_id = $2 ORDER BY date DESC, created_at DESC",
+            ORDER_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(customer_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let payments = sqlx::query_as::<_, Payment>(
+            r#"
+            SELECT p.id, p.tenant_id, p.invoice_id, p.amount, p.date, p.method, p.reference, p.created_at, p.updated_at, p.discount_amount
+            FROM payments p JOIN invoices i ON i.id = p.invoice_id
+            WHERE p.tenant_id = $1 AND i.customer_id = $2
+            ORDER BY p.date DESC, p.created_at DESC
+            "#
+        )
+        .bind(tenant_id)
+        .bind(customer_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let credit_memos = sqlx::query_as::<_, CreditMemo>(
+            "SELECT * FROM credit_memos WHERE tenant_id = $1 AND customer_id = $2 ORDER BY date DESC, created_at DESC"
+        )
+        .bind(tenant_id)
+        .bind(customer_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(CustomerCenter {
+            customer,
+            balance: credit.open_receivables,
+            available_credit: credit.available(),
+            credit,
+            addresses,
+            contacts,
+            open_invoices,
+            orders,
+            payments,
+            credit_memos,
+        })
+    }
 }
 
 #[async_trait]
@@ -36,10 +195,21 @@ impl SalesService for PostgresSalesRepository {
         tenant_id: Uuid,
         customer: CreateCustomer,
     ) -> Result<Customer, Error> {
+        if customer.credit_limit.is_some_and(|limit| limit < Decimal::ZERO) {
+            return Err(Error::BusinessRule("Credit limit cannot be negative".to_string()));
+        }
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let terms = match customer.payment_terms_id {
+            Some(id) => Some(active_terms(&mut tx, tenant_id, id).await?.name),
+            None => None,
+        };
+
+        // Customers start on the tenant's Net 30 terms unless told otherwise
         let record = sqlx::query_as::<_, Customer>(
             r#"
-            INSERT INTO customers (tenant_id, name, email, phone, address)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO customers (tenant_id, name, email, phone, terms, payment_terms_id, credit_limit)
+            VALUES ($1, $2, $3, $4, COALESCE($5, 'Net 30'),
+                    (SELECT id FROM payment_terms WHERE tenant_id = $1 AND name = COALESCE($5, 'Net 30') AND is_active), $6)
             RETURNING *
             "#
         )
@@ -47,11 +217,22 @@ impl SalesService for PostgresSalesRepository {
         .bind(customer.name)
         .bind(customer.email)
         .bind(customer.phone)
-        .bind(customer.address)
-        .fetch_one(&self.pool)
+        .bind(terms)
+        .bind(customer.credit_limit)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
+        let party = Party::Customer(record.id);
+        for address in customer.addresses {
+            insert_address(&mut tx, tenant_id, party, address).await?;
+        }
+        for contact in customer.contacts {
+            insert_contact(&mut tx, tenant_id, party, contact).await?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
         Ok(record)
     }
 
@@ -66,18 +247,35 @@ impl SalesService for PostgresSalesRepository {
             .map(|line| line.quantity * line.unit_price)
             .sum();
 
-        let so = sqlx::query_as::<_, SalesOrder>(
+        // Orders that would take the customer over their limit wait on a manager
+        let credit = customer_credit(&mut tx, tenant_id, order.customer_id, None).await?;
+        let hold_reason = credit.hold_reason(total_amount);
+        let status = if hold_reason.is_some() { SalesOrderStatus::OnHold } else { SalesOrderStatus::Draft };
+
+        let party = Party::Customer(order.customer_id);
+        let billing = document_address(&mut tx, tenant_id, party, AddressType::Billing, order.billing_address_id).await?;
+        let shipping = document_address(&mut tx, tenant_id, party, AddressType::Shipping, order.shipping_address_id).await?;
+
+        let so = sqlx::query_as::<_, SalesOrder>(&format!(
             r#"
-            INSERT INTO sales_orders (tenant_id, customer_id, order_number, date, total_amount, status)
-            VALUES ($1, $2, $3, $4, $5, 'DRAFT')
-            RETURNING id, tenant_id, customer_id, order_number, date, status, total_amount, created_at, updated_at
-            "#
-        )
+            INSERT INTO sales_orders (tenant_id, customer_id, order_number, date, total_amount, status, hold_reason,
+                                      billing_address_id, billing_address, shipping_address_id, shipping_address)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING {}
+            "#,
+            ORDER_COLUMNS
+        ))
         .bind(tenant_id)
         .bind(order.customer_id)
         .bind(order.order_number)
         .bind(order.date)
         .bind(total_amount)
+        .bind(status)
+        .bind(hold_reason)
+        .bind(billing.as_ref().map(|a| a.id))
+        .bind(billing.as_ref().map(|a| a.formatted()))
+        .bind(shipping.as_ref().map(|a| a.id))
+        .bind(shipping.as_ref().map(|a| a.formatted()))
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
@@ -103,23 +301,53 @@ impl SalesService for PostgresSalesRepository {
         Ok(so)
     }
 
-    async fn ship_order(&self, tenant_id: Uuid, order_id: Uuid) -> Result<SalesOrder, Error> {
+    async fn confirm_order(&self, tenant_id: Uuid, order_id: Uuid) -> Result<SalesOrder, Error> {
         let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let order = lock_order(&mut tx, tenant_id, order_id).await?;
+
+        match order.status {
+            SalesOrderStatus::Draft => {}
+            SalesOrderStatus::OnHold => {
+                return Err(Error::BusinessRule(format!(
+                    "Order is on credit hold ({}); a manager must release it",
+                    order.hold_reason.unwrap_or_default()
+                )));
+            }
+            status => {
+                return Err(Error::BusinessRule(format!("Cannot confirm order with status {:?}. Must be DRAFT.", status)));
+            }
+        }
 
-        let order = sqlx::query_as::<_, SalesOrder>(
+        // The customer's position may have changed since the order was entered
+        let credit = customer_credit(&mut tx, tenant_id, order.customer_id, Some(order_id)).await?;
+        let hold_reason = credit.hold_reason(order.total_amount);
+        let status = if hold_reason.is_some() { SalesOrderStatus::OnHold } else { SalesOrderStatus::Confirmed };
+
+        let updated_order = sqlx::query_as::<_, SalesOrder>(&format!(
             r#"
-            SELECT id, tenant_id, customer_id, order_number, date, status, total_amount, created_at, updated_at
-            FROM sales_orders
-            WHERE id = $1 AND tenant_id = $2
-            FOR UPDATE
-            "#
-        )
+            UPDATE sales_orders
+            SET status = $1, hold_reason = $2, updated_at = NOW()
+            WHERE id = $3
+            RETURNING {}
+            "#,
+            ORDER_COLUMNS
+        ))
+        .bind(status)
+        .bind(hold_reason)
         .bind(order_id)
-        .bind(tenant_id)
-        .fetch_optional(&mut *tx)
+        .fetch_one(&mut *tx)
         .await
-        .map_err(|e| Error::Database(e.to_string()))?
-        .ok_or(Error::NotFound("Sales Order not found".to_string()))?;
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(updated_order)
+    }
+
+    async fn ship_order(&self, tenant_id: Uuid, order_id: Uuid) -> Result<SalesOrder, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let order = lock_order(&mut tx, tenant_id, order_id).await?;
 
         if order.status != SalesOrderStatus::Confirmed {
             return Err(Error::BusinessRule(format!(
@@ -128,14 +356,15 @@ impl SalesService for PostgresSalesRepository {
             )));
         }
 
-        let updated_order = sqlx::query_as::<_, SalesOrder>(
+        let updated_order = sqlx::query_as::<_, SalesOrder>(&format!(
             r#"
             UPDATE sales_orders
             SET status = 'SHIPPED', updated_at = NOW()
             WHERE id = $1
-            RETURNING id, tenant_id, customer_id, order_number, date, status, total_amount, created_at, updated_at
-            "#
-        )
+            RETURNING {}
+            "#,
+            ORDER_COLUMNS
+        ))
         .bind(order_id)
         .fetch_one(&mut *tx)
         .await
@@ -153,39 +382,30 @@ impl SalesService for PostgresSalesRepository {
         .await
         .map_err(|e| Error::Database(e.to_string()))?;
 
+        let mut cost_of_sales = Decimal::ZERO;
         for line in lines {
-            sqlx::query(
-                r#"
-                INSERT INTO inventory_transactions 
-                (tenant_id, product_id, quantity, transaction_type, reference_id, notes)
-                VALUES ($1, $2, $3, $4, $5, $6)
-                "#
-            )
-            .bind(tenant_id)
-            .bind(line.product_id)
-            .bind(line.quantity)
-            .bind(TransactionType::Sale)
-            .bind(order_id)
-            .bind(format!("Shipped SO {}", order.order_number))
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| Error::Database(e.to_string()))?;
+            check_unquarantined(&mut tx, tenant_id, line.product_id, line.quantity).await?;
+            let record = insert_transaction(&mut tx, tenant_id, CreateInventoryTransaction {
+                product_id: line.product_id,
+                quantity: -line.quantity,
+                transaction_type: TransactionType::Sale,
+                reference_id: Some(order_id),
+                notes: Some(format!("Shipped SO {}", order.order_number)),
+                reason_code: None,
+                unit_cost: None,
+            }).await?;
 
-            sqlx::query(
-                r#"
-                UPDATE products
-                SET stock_quantity = stock_quantity - $1, updated_at = NOW()
-                WHERE id = $2 AND tenant_id = $3
-                "#
-            )
-            .bind(line.quantity)
-            .bind(line.product_id)
-            .bind(tenant_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| Error::Database(e.to_string()))?;
+            cost_of_sales += record.total_cost.unwrap_or_default();
         }
 
+        let memo = format!("Cost of sales SO {}", order.order_number);
+        let date = Utc::now().date_naive();
+        let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "SHP", date).await?;
+        post_journal_entry(&mut tx, tenant_id, &entry_number, date, &memo, &[
+            LedgerLine::debit("5000", cost_of_sales, &memo),
+            LedgerLine::credit("1300", cost_of_sales, &memo),
+        ]).await?;
+
         tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
 
         Ok(updated_order)
@@ -210,3 +430,46 @@ impl SalesService for PostgresSalesRepository {
         Ok(records)
     }
 }
+
+async fn lock_order(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, order_id: Uuid) -> Result<SalesOrder, Error> {
+    sqlx::query_as::<_, SalesOrder>(&format!(
+        "SELECT {} FROM sales_orders WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+        ORDER_COLUMNS
+    ))
+    .bind(order_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Sales Order not found".to_string()))
+}
+
+/// Locks the customer so concurrent orders are checked one at a time. Held orders and
+/// `exclude_order` are left out of the open order total.
+pub async fn customer_credit(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, customer_id: Uuid, exclude_order: Option<Uuid>) -> Result<CustomerCredit, Error> {
+    sqlx::query_as::<_, CustomerCredit>(
+        r#"
+        SELECT c.credit_limit, c.credit_hold,
+               COALESCE((SELECT SUM(i.total_amount - i.amount_paid) FROM invoices i
+                         WHERE i.customer_id = c.id AND i.status NOT IN ('PAID', 'CANCELLED')), 0)
+             - COALESCE((SELECT SUM(m.total_amount) FROM credit_memos m
+                         WHERE m.customer_id = c.id AND m.status = 'OPEN'), 0) AS open_receivables,
+               COALESCE((SELECT SUM(o.total_amount) FROM sales_orders o
+                         WHERE o.customer_id = c.id
+                           AND o.status IN ('DRAFT', 'CONFIRMED', 'SHIPPED')
+                           AND ($3::uuid IS NULL OR o.id <> $3)
+                           AND NOT EXISTS (SELECT 1 FROM invoices i
+                                           WHERE i.sales_order_id = o.id AND i.status <> 'CANCELLED')), 0) AS open_orders
+        FROM customers c
+        WHERE c.id = $1 AND c.tenant_id = $2
+        FOR UPDATE OF c
+        "#
+    )
+    .bind(customer_id)
+    .bind(tenant_id)
+    .bind(exclude_order)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Customer not found".to_string()))
+}
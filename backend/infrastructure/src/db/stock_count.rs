@@ -0,0 +1,468 @@
+use smart_erp_core::models::stock_count::*;
+use smart_erp_core::models::inventory::{CreateInventoryTransaction, TransactionType};
+use smart_erp_core::error::Error;
+use crate::db::inventory::insert_transaction;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+const SESSION_COLUMNS: &str = "id, tenant_id, session_number, count_type, status, is_blind, location, schedule_id, snapshot_at, submitted_at, approved_at, approved_by, journal_entry_id, notes, created_at, updated_at";
+const LINE_COLUMNS: &str = "id, session_id, product_id, location, snapshot_quantity, counted_quantity, unit_cost, reason_code, notes, counted_at";
+const SCHEDULE_COLUMNS: &str = "id, tenant_id, name, frequency_days, items_per_count, is_blind, location, next_count_date, last_run_date, is_active, created_at, updated_at";
+
+pub struct PostgresStockCountRepository {
+    pool: PgPool,
+}
+
+impl PostgresStockCountRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_session(&self, tenant_id: Uuid, req: CreateCountSession) -> Result<CountSessionDetail, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let session = open_session(&mut tx, tenant_id, &req, None).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_session_detail(tenant_id, session.id).await
+    }
+
+    pub async fn list_sessions(&self, tenant_id: Uuid) -> Result<Vec<CountSession>, Error> {
+        sqlx::query_as::<_, CountSession>(&format!(
+            "SELECT {} FROM inventory_count_sessions WHERE tenant_id = $1 ORDER BY created_at DESC",
+            SESSION_COLUMNS
+        ))
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_session(&self, tenant_id: Uuid, session_id: Uuid) -> Result<CountSession, Error> {
+        sqlx::query_as::<_, CountSession>(&format!(
+            "SELECT {} FROM inventory_count_sessions WHERE id = $1 AND tenant_id = $2",
+            SESSION_COLUMNS
+        ))
+        .bind(session_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Count session not found".to_string()))
+    }
+
+    /// Count sheet for a session. Blind sessions hide the frozen quantity until submitted.
+    pub async fn get_session_detail(&self, tenant_id: Uuid, session_id: Uuid) -> Result<CountSessionDetail, Error> {
+        let session = self.get_session(tenant_id, session_id).await?;
+        let hide_snapshot = session.is_blind && session.status == CountSessionStatus::Open;
+
+        let lines = self.lines_with_products(session_id).await?
+            .into_iter()
+            .map(|(line, sku, name)| CountSheetLine {
+                id: line.id,
+                product_id: line.product_id,
+                sku,
+                product_name: name,
+                location: line.location,
+                snapshot_quantity: if hide_snapshot { None } else { Some(line.snapshot_quantity) },
+                counted_quantity: line.counted_quantity,
+                variance: if hide_snapshot { None } else { line.counted_quantity.map(|c| c - line.snapshot_quantity) },
+                reason_code: line.reason_code,
+                notes: line.notes,
+            })
+            .collect();
+
+        Ok(CountSessionDetail { session, lines })
+    }
+
+    /// Records counted quantities. Products counted in a location that was not on the sheet
+    /// get a new line with a zero snapshot.
+    pub async fn record_counts(&self, tenant_id: Uuid, session_id: Uuid, counts: RecordCounts) -> Result<CountSessionDetail, Error> {
+        let session = self.get_session(tenant_id, session_id).await?;
+        if session.status != CountSessionStatus::Open {
+            return Err(Error::BusinessRule("Counts can only be entered on an open session".to_string()));
+        }
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        for line in counts.lines {
+            if line.counted_quantity < Decimal::ZERO {
+                return Err(Error::BusinessRule("Counted quantity cannot be negative".to_string()));
+            }
+
+            let result = sqlx::query(
+                r#"
+                INSERT INTO inventory_count_lines (session_id, product_id, location, snapshot_quantity, counted_quantity, unit_cost, reason_code, notes, counted_at)
+                SELECT $1, p.id, $3, 0, $4, p.cost_price, $5, $6, NOW()
+                FROM products p WHERE p.id = $2 AND p.tenant_id = $7
+                ON CONFLICT (session_id, product_id, (COALESCE(location, ''))) DO UPDATE
+                SET counted_quantity = EXCLUDED.counted_quantity,
+                    reason_code = EXCLUDED.reason_code,
+                    notes = COALESCE(EXCLUDED.notes, inventory_count_lines.notes),
+                    counted_at = NOW()
+                "#
+            )
+            .bind(session_id)
+            .bind(line.product_id)
+            .bind(line.location.as_ref().or(session.location.as_ref()))
+            .bind(line.counted_quantity)
+            .bind(line.reason_code)
+            .bind(line.notes)
+            .bind(tenant_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            if result.rows_affected() == 0 {
+                return Err(Error::NotFound("Product not found".to_string()));
+            }
+        }
+
+        sqlx::query("UPDATE inventory_count_sessions SET updated_at = NOW() WHERE id = $1")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        self.get_session_detail(tenant_id, session_id).await
+    }
+
+    pub async fn submit_session(&self, tenant_id: Uuid, session_id: Uuid) -> Result<CountSession, Error> {
+        let session = self.get_session(tenant_id, session_id).await?;
+        if session.status != CountSessionStatus::Open {
+            return Err(Error::BusinessRule("Only open sessions can be submitted".to_string()));
+        }
+
+        let uncounted: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM inventory_count_lines WHERE session_id = $1 AND counted_quantity IS NULL"
+        )
+        .bind(session_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        if uncounted > 0 {
+            return Err(Error::BusinessRule(format!("{} line(s) have not been counted", uncounted)));
+        }
+
+        self.set_status(tenant_id, session_id, CountSessionStatus::Submitted, "submitted_at = NOW(),").await
+    }
+
+    pub async fn variance_report(&self, tenant_id: Uuid, session_id: Uuid) -> Result<CountVarianceReport, Error> {
+        let session = self.get_session(tenant_id, session_id).await?;
+        if session.is_blind && session.status == CountSessionStatus::Open {
+            return Err(Error::BusinessRule("Variances of a blind count are available after submission".to_string()));
+        }
+
+        let lines = self.lines_with_products(session_id).await?;
+        Ok(CountVarianceReport::from_lines(session_id, &lines))
+    }
+
+    /// Approves a submitted session: posts one ADJUSTMENT per product with a variance and
+    /// books the valuation difference against the Inventory Adjustments account.
+    pub async fn approve_session(&self, tenant_id: Uuid, session_id: Uuid, approved_by: Uuid) -> Result<CountSession, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let session = lock_session(&mut tx, tenant_id, session_id).await?;
+        if session.status != CountSessionStatus::Submitted {
+            return Err(Error::BusinessRule("Only submitted sessions can be approved".to_string()));
+        }
+
+        let lines = self.lines_with_products(session_id).await?;
+        let report = CountVarianceReport::from_lines(session_id, &lines);
+
+        let (mut gain, mut loss) = (Decimal::ZERO, Decimal::ZERO);
+        for line in &report.lines {
+            let record = insert_transaction(&mut tx, tenant_id, CreateInventoryTransaction {
+                product_id: line.product_id,
+                quantity: line.variance,
+                transaction_type: TransactionType::Adjustment,
+                reference_id: Some(session_id),
+                notes: Some(format!("Count {}", session.session_number)),
+                reason_code: Some(line.reason_code.clone()),
+                unit_cost: Some(line.unit_cost),
+            }).await?;
+
+            if line.variance > Decimal::ZERO {
+                gain += record.total_cost.unwrap_or_default();
+            } else {
+                loss += record.total_cost.unwrap_or_default();
+            }
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE products SET last_counted_at = NOW()
+            WHERE tenant_id = $1 AND id IN (SELECT product_id FROM inventory_count_lines WHERE session_id = $2)
+            "#
+        )
+        .bind(tenant_id)
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let memo = format!("Inventory count {}", session.session_number);
+        let journal_entry_id = post_journal_entry(
+            &mut tx,
+            tenant_id,
+            &session.session_number,
+            Utc::now().date_naive(),
+            &memo,
+            &[
+                LedgerLine::debit("1300", gain, "Count gains"),
+                LedgerLine::credit("5900", gain, "Count gains"),
+                LedgerLine::debit("5900", loss, "Count losses"),
+                LedgerLine::credit("1300", loss, "Count losses"),
+            ],
+        ).await?;
+
+        let approved = sqlx::query_as::<_, CountSession>(&format!(
+            r#"
+            UPDATE inventory_count_sessions
+            SET status = 'APPROVED', approved_at = NOW(), approved_by = $2, journal_entry_id = $3, updated_at = NOW()
+            WHERE id = $1 AND tenant_id = $4 AND status = 'SUBMITTED'
+            RETURNING {}
+            "#,
+            SESSION_COLUMNS
+        ))
+        .bind(session_id)
+        .bind(approved_by)
+        .bind(journal_entry_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::BusinessRule("Only submitted sessions can be approved".to_string()))?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(approved)
+    }
+
+    pub async fn cancel_session(&self, tenant_id: Uuid, session_id: Uuid) -> Result<CountSession, Error> {
+        let session = self.get_session(tenant_id, session_id).await?;
+        if !matches!(session.status, CountSessionStatus::Open | CountSessionStatus::Submitted) {
+            return Err(Error::BusinessRule("Only open or submitted sessions can be cancelled".to_string()));
+        }
+
+        self.set_status(tenant_id, session_id, CountSessionStatus::Cancelled, "").await
+    }
+
+    // --- Cycle Count Schedules ---
+    pub async fn create_schedule(&self, tenant_id: Uuid, req: CreateCycleCountSchedule) -> Result<CycleCountSchedule, Error> {
+        if req.frequency_days <= 0 || req.items_per_count <= 0 {
+            return Err(Error::BusinessRule("Frequency and items per count must be positive".to_string()));
+        }
+
+        sqlx::query_as::<_, CycleCountSchedule>(&format!(
+            r#"
+            INSERT INTO cycle_count_schedules (tenant_id, name, frequency_days, items_per_count, is_blind, location, next_count_date)
+            VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, CURRENT_DATE))
+            RETURNING {}
+            "#,
+            SCHEDULE_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(req.name)
+        .bind(req.frequency_days)
+        .bind(req.items_per_count)
+        .bind(req.is_blind)
+        .bind(req.location)
+        .bind(req.next_count_date)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn list_schedules(&self, tenant_id: Uuid) -> Result<Vec<CycleCountSchedule>, Error> {
+        sqlx::query_as::<_, CycleCountSchedule>(&format!(
+            "SELECT {} FROM cycle_count_schedules WHERE tenant_id = $1 ORDER BY next_count_date, name",
+            SCHEDULE_COLUMNS
+        ))
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    /// Opens a CYCLE session for every active schedule that is due, picking the items that
+    /// have gone longest without a count, then rolls each schedule forward.
+    pub async fn run_due_schedules(&self, tenant_id: Uuid) -> Result<Vec<CountSession>, Error> {
+        let today = Utc::now().date_naive();
+        let schedules = sqlx::query_as::<_, CycleCountSchedule>(&format!(
+            "SELECT {} FROM cycle_count_schedules WHERE tenant_id = $1 AND is_active = true AND next_count_date <= $2",
+            SCHEDULE_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(today)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let mut sessions = Vec::new();
+
+        for schedule in schedules {
+            let product_ids: Vec<Uuid> = sqlx::query_scalar(
+                r#"
+                SELECT p.id FROM products p
+                WHERE p.tenant_id = $1 AND p.is_active = true AND p.item_type IN ('INVENTORY', 'ASSEMBLY')
+                  AND NOT EXISTS (
+                      SELECT 1 FROM inventory_count_lines l JOIN inventory_count_sessions s ON s.id = l.session_id
+                      WHERE l.product_id = p.id AND s.status IN ('OPEN', 'SUBMITTED')
+                  )
+                ORDER BY p.last_counted_at NULLS FIRST, p.name
+                LIMIT $2
+                "#
+            )
+            .bind(tenant_id)
+            .bind(schedule.items_per_count as i64)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+            if !product_ids.is_empty() {
+                let req = CreateCountSession {
+                    count_type: CountType::Cycle,
+                    is_blind: schedule.is_blind,
+                    location: schedule.location.clone(),
+                    product_ids: Some(product_ids),
+                    notes: Some(format!("Cycle count: {}", schedule.name)),
+                };
+                sessions.push(open_session(&mut tx, tenant_id, &req, Some(schedule.id)).await?);
+            }
+
+            sqlx::query(
+                "UPDATE cycle_count_schedules SET last_run_date = $2, next_count_date = $3, updated_at = NOW() WHERE id = $1"
+            )
+            .bind(schedule.id)
+            .bind(today)
+            .bind(today + Duration::days(schedule.frequency_days as i64))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(sessions)
+    }
+
+    async fn lines_with_products(&self, session_id: Uuid) -> Result<Vec<(CountLine, String, String)>, Error> {
+        let lines = sqlx::query_as::<_, CountLine>(&format!(
+            "SELECT {} FROM inventory_count_lines WHERE session_id = $1",
+            LINE_COLUMNS
+        ))
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let product_ids: Vec<Uuid> = lines.iter().map(|l| l.product_id).collect();
+        let products: Vec<(Uuid, String, String)> = sqlx::query_as(
+            "SELECT id, sku, name FROM products WHERE id = ANY($1)"
+        )
+        .bind(&product_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut result: Vec<(CountLine, String, String)> = lines
+            .into_iter()
+            .map(|line| {
+                let (sku, name) = products.iter()
+                    .find(|(id, _, _)| *id == line.product_id)
+                    .map(|(_, sku, name)| (sku.clone(), name.clone()))
+                    .unwrap_or_default();
+                (line, sku, name)
+            })
+            .collect();
+        result.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.location.cmp(&b.0.location)));
+
+        Ok(result)
+    }
+
+    async fn set_status(&self, tenant_id: Uuid, session_id: Uuid, status: CountSessionStatus, extra: &str) -> Result<CountSession, Error> {
+        sqlx::query_as::<_, CountSession>(&format!(
+            "UPDATE inventory_count_sessions SET status = $2, {} updated_at = NOW() WHERE id = $1 AND tenant_id = $3 RETURNING {}",
+            extra, SESSION_COLUMNS
+        ))
+        .bind(session_id)
+        .bind(status)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Count session not found".to_string()))
+    }
+}
+
+async fn lock_session(tx: &mut Transaction<'_, Postgres>, tenant_id: Uuid, session_id: Uuid) -> Result<CountSession, Error> {
+    sqlx::query_as::<_, CountSession>(&format!(
+        "SELECT {} FROM inventory_count_sessions WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+        SESSION_COLUMNS
+    ))
+    .bind(session_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Count session not found".to_string()))
+}
+
+/// Creates a session and freezes on-hand quantity and cost for every product on the sheet.
+async fn open_session(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    req: &CreateCountSession,
+    schedule_id: Option<Uuid>,
+) -> Result<CountSession, Error> {
+    let session_number = next_document_number(tx, tenant_id, "inventory_count_sessions", "session_number", "CNT", Utc::now().date_naive()).await?;
+
+    let session = sqlx::query_as::<_, CountSession>(&format!(
+        r#"
+        INSERT INTO inventory_count_sessions (tenant_id, session_number, count_type, is_blind, location, schedule_id, notes)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {}
+        "#,
+        SESSION_COLUMNS
+    ))
+    .bind(tenant_id)
+    .bind(session_number)
+    .bind(&req.count_type)
+    .bind(req.is_blind)
+    .bind(&req.location)
+    .bind(schedule_id)
+    .bind(&req.notes)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO inventory_count_lines (session_id, product_id, location, snapshot_quantity, unit_cost)
+        SELECT $1, p.id, $2, p.stock_quantity, p.cost_price
+        FROM products p
+        WHERE p.tenant_id = $3 AND p.is_active = true AND p.item_type IN ('INVENTORY', 'ASSEMBLY')
+          AND ($4::uuid[] IS NULL OR p.id = ANY($4))
+        "#
+    )
+    .bind(session.id)
+    .bind(&req.location)
+    .bind(tenant_id)
+    .bind(&req.product_ids)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?;
+
+    if inserted.rows_affected() == 0 {
+        return Err(Error::BusinessRule("No inventory items to count".to_string()));
+    }
+
+    Ok(session)
+}
@@ -0,0 +1,205 @@
+use smart_erp_core::models::stock_ledger::*;
+use smart_erp_core::error::Error;
+use chrono::{Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// SALE and PRODUCTION_OUT rows are stored with positive quantities even though they issue stock.
+pub const SIGNED_QUANTITY: &str = "CASE WHEN t.transaction_type IN ('SALE', 'PRODUCTION_OUT') THEN -ABS(t.quantity) ELSE t.quantity END";
+
+/// Movements with running balances, worked backwards from current on-hand so the balance
+/// always agrees with `products.stock_quantity`. Filters: $1 tenant, $2 product (optional),
+/// $3 from date, $4 to date (inclusive).
+fn movements_query() -> String {
+    format!(
+        r#"
+        WITH moves AS (
+            SELECT t.id, t.product_id, t.created_at, t.transaction_type, t.reference_id, t.reason_code, t.notes,
+                   {signed} AS quantity,
+                   p.stock_quantity - COALESCE(SUM({signed}) OVER (
+                       PARTITION BY t.product_id ORDER BY t.created_at DESC, t.id DESC
+                       ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+                   ), 0) AS running_balance
+            FROM inventory_transactions t
+            JOIN products p ON p.id = t.product_id
+            WHERE t.tenant_id = $1 AND ($2::uuid IS NULL OR t.product_id = $2)
+        )
+        SELECT m.id AS transaction_id, m.product_id, p.sku, p.name AS product_name, m.created_at,
+               m.transaction_type, m.quantity, m.running_balance,
+               CASE
+                   WHEN po.id IS NOT NULL THEN 'PURCHASE_ORDER'
+                   WHEN so.id IS NOT NULL THEN 'SALES_ORDER'
+                   WHEN wo.id IS NOT NULL THEN 'WORK_ORDER'
+                   WHEN cs.id IS NOT NULL THEN 'COUNT_SESSION'
+                   WHEN vc.id IS NOT NULL THEN 'VENDOR_CREDIT'
+               END AS reference_type,
+               m.reference_id,
+               COALESCE(po.order_number, so.order_number, r.name, cs.session_number, vc.credit_number) AS reference_number,
+               m.reason_code, m.notes
+        FROM moves m
+        JOIN products p ON p.id = m.product_id
+        LEFT JOIN purchase_orders po ON po.id = m.reference_id AND m.transaction_type IN ('PURCHASE', 'RETURN_TO_VENDOR')
+        LEFT JOIN sales_orders so ON so.id = m.reference_id AND m.transaction_type = 'SALE'
+        LEFT JOIN work_orders wo ON wo.id = m.reference_id AND m.transaction_type IN ('PRODUCTION_IN', 'PRODUCTION_OUT')
+        LEFT JOIN recipes r ON r.id = wo.recipe_id
+        LEFT JOIN inventory_count_sessions cs ON cs.id = m.reference_id AND m.transaction_type = 'ADJUSTMENT'
+        LEFT JOIN vendor_credits vc ON vc.id = m.reference_id AND m.transaction_type = 'RETURN_TO_VENDOR'
+        WHERE m.created_at >= $3::date AND m.created_at < ($4::date + 1)
+        ORDER BY m.created_at, m.id
+        "#,
+        signed = SIGNED_QUANTITY
+    )
+}
+
+pub struct PostgresStockLedgerRepository {
+    pool: PgPool,
+}
+
+impl PostgresStockLedgerRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn stock_card(&self, tenant_id: Uuid, product_id: Uuid, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<StockCard, Error> {
+        let (from, to) = resolve_period(from, to)?;
+
+        let (sku, name): (String, String) = sqlx::query_as(
+            "SELECT sku, name FROM products WHERE id = $1 AND tenant_id = $2"
+        )
+        .bind(product_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?
+        .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+        let movements = self.movements(tenant_id, Some(product_id), from, to).await?;
+        let opening_balance = self.quantity_as_of(tenant_id, product_id, from - Duration::days(1)).await?;
+
+        let total_in: Decimal = movements.iter().filter(|m| m.quantity > Decimal::ZERO).map(|m| m.quantity).sum();
+        let total_out: Decimal = movements.iter().filter(|m| m.quantity < Decimal::ZERO).map(|m| -m.quantity).sum();
+
+        Ok(StockCard {
+            product_id,
+            sku,
+            name,
+            from,
+            to,
+            opening_balance,
+            total_in,
+            total_out,
+            closing_balance: opening_balance + total_in - total_out,
+            movements,
+        })
+    }
+
+    /// Movements across all products (or one) for a period, newest last.
+    pub async fn movements(&self, tenant_id: Uuid, product_id: Option<Uuid>, from: NaiveDate, to: NaiveDate) -> Result<Vec<StockMovement>, Error> {
+        sqlx::query_as::<_, StockMovement>(&movements_query())
+            .bind(tenant_id)
+            .bind(product_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn list_movements(&self, tenant_id: Uuid, query: MovementQuery) -> Result<Vec<StockMovement>, Error> {
+        let (from, to) = resolve_period(query.from, query.to)?;
+        self.movements(tenant_id, query.product_id, from, to).await
+    }
+
+    /// On-hand at the end of `as_of`: current stock less every signed movement after that day.
+    pub async fn stock_on_hand(&self, tenant_id: Uuid, as_of: Option<NaiveDate>) -> Result<StockOnHandReport, Error> {
+        let as_of = as_of.unwrap_or_else(|| Utc::now().date_naive());
+
+        let lines = sqlx::query_as::<_, StockOnHandLine>(&format!(
+            r#"
+            SELECT p.id AS product_id, p.sku, p.name,
+                   p.stock_quantity - COALESCE(later.qty, 0) AS quantity,
+                   p.cost_price AS unit_cost,
+                   ROUND((p.stock_quantity - COALESCE(later.qty, 0)) * p.cost_price, 2) AS value
+            FROM products p
+            LEFT JOIN LATERAL (
+                SELECT SUM({}) AS qty FROM inventory_transactions t
+                WHERE t.tenant_id = p.tenant_id AND t.product_id = p.id AND t.created_at >= ($2::date + 1)
+            ) later ON TRUE
+            WHERE p.tenant_id = $1 AND p.item_type IN ('INVENTORY', 'ASSEMBLY')
+            ORDER BY p.name
+            "#,
+            SIGNED_QUANTITY
+        ))
+        .bind(tenant_id)
+        .bind(as_of)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let total_value = lines.iter().map(|l| l.value).sum();
+
+        Ok(StockOnHandReport { as_of, lines, total_value })
+    }
+
+    pub async fn movement_summary(&self, tenant_id: Uuid, query: MovementQuery) -> Result<MovementSummary, Error> {
+        let (from, to) = resolve_period(query.from, query.to)?;
+
+        let lines = sqlx::query_as::<_, MovementSummaryLine>(&format!(
+            r#"
+            SELECT t.transaction_type,
+                   COUNT(*) AS transaction_count,
+                   COALESCE(SUM(GREATEST({signed}, 0)), 0) AS quantity_in,
+                   COALESCE(SUM(GREATEST(-({signed}), 0)), 0) AS quantity_out,
+                   COALESCE(SUM({signed}), 0) AS net_quantity
+            FROM inventory_transactions t
+            WHERE t.tenant_id = $1 AND ($2::uuid IS NULL OR t.product_id = $2)
+              AND t.created_at >= $3::date AND t.created_at < ($4::date + 1)
+            GROUP BY t.transaction_type
+            ORDER BY t.transaction_type
+            "#,
+            signed = SIGNED_QUANTITY
+        ))
+        .bind(tenant_id)
+        .bind(query.product_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let total_in = lines.iter().map(|l| l.quantity_in).sum();
+        let total_out = lines.iter().map(|l| l.quantity_out).sum();
+
+        Ok(MovementSummary { from, to, product_id: query.product_id, lines, total_in, total_out })
+    }
+
+    async fn quantity_as_of(&self, tenant_id: Uuid, product_id: Uuid, as_of: NaiveDate) -> Result<Decimal, Error> {
+        sqlx::query_scalar::<_, Decimal>(&format!(
+            r#"
+            SELECT p.stock_quantity - COALESCE((
+                SELECT SUM({}) FROM inventory_transactions t
+                WHERE t.tenant_id = p.tenant_id AND t.product_id = p.id AND t.created_at >= ($3::date + 1)
+            ), 0)
+            FROM products p WHERE p.id = $1 AND p.tenant_id = $2
+            "#,
+            SIGNED_QUANTITY
+        ))
+        .bind(product_id)
+        .bind(tenant_id)
+        .bind(as_of)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+}
+
+/// Defaults to the 30 days ending today.
+fn resolve_period(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<(NaiveDate, NaiveDate), Error> {
+    let to = to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = from.unwrap_or(to - Duration::days(30));
+    if from > to {
+        return Err(Error::BusinessRule("'from' date must not be after 'to' date".to_string()));
+    }
+    Ok((from, to))
+}
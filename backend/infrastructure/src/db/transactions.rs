@@ -1,6 +1,14 @@
 use smart_erp_core::models::transactions::*;
+use smart_erp_core::models::approval::DocumentType;
 use smart_erp_core::error::Error;
-use sqlx::PgPool;
+use crate::db::approval::{approval_required, open_request};
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use crate::db::payment_terms::{document_terms, TermsParty};
+use crate::db::purchasing::{adjust_supplier_balance, check_supplier_active};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 pub struct PostgresTransactionsRepository {
@@ -38,12 +46,29 @@ impl PostgresTransactionsRepository {
             .bind(tenant_id).fetch_all(&self.pool).await.map_err(|e| Error::Database(e.to_string()))
     }
 
+    /// Bills entered here have no receipt behind them, so AP is credited against purchases
+    /// clearing until a landed cost allocation or an adjusting entry moves the cost on.
     pub async fn create_bill(&self, tenant_id: Uuid, bill: CreateBill) -> Result<Bill, Error> {
-        sqlx::query_as::<_, Bill>(
-            "INSERT INTO bills (tenant_id, supplier_id, bill_number, date, due_date, total_amount, terms, notes) VALUES ($1, $2, $3, COALESCE($4, CURRENT_DATE), $5, $6, $7, $8) RETURNING *")
-            .bind(tenant_id).bind(bill.supplier_id).bind(bill.bill_number).bind(bill.date)
-            .bind(bill.due_date).bind(bill.total_amount).bind(bill.terms).bind(bill.notes)
-            .fetch_one(&self.pool).await.map_err(|e| Error::Database(e.to_string()))
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        check_supplier_active(&mut tx, tenant_id, bill.supplier_id).await?;
+        let date = bill.date.unwrap_or_else(|| Utc::now().date_naive());
+        let total_amount = Decimal::try_from(bill.total_amount).map_err(|_| Error::BusinessRule("Invalid bill amount".to_string()))?.round_dp(2);
+        let (terms, schedule) = document_terms(&mut tx, tenant_id, bill.payment_terms_id, TermsParty::Supplier(bill.supplier_id), date, bill.due_date, total_amount).await?;
+        let memo = format!("Bill {}", bill.bill_number);
+        let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "BIL", date).await?;
+        let journal_entry_id = post_journal_entry(&mut tx, tenant_id, &entry_number, date, &memo, &[
+            LedgerLine::debit("1450", total_amount, &memo),
+            LedgerLine::credit("2000", total_amount, &memo),
+        ]).await?;
+        let record = sqlx::query_as::<_, Bill>(
+            "INSERT INTO bills (tenant_id, supplier_id, bill_number, date, due_date, total_amount, terms, notes, payment_terms_id, discount_date, discount_amount, journal_entry_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) RETURNING *")
+            .bind(tenant_id).bind(bill.supplier_id).bind(bill.bill_number).bind(date)
+            .bind(schedule.due_date).bind(total_amount).bind(bill.terms.or(terms.as_ref().map(|t| t.name.clone()))).bind(bill.notes)
+            .bind(terms.map(|t| t.id)).bind(schedule.discount_date).bind(schedule.discount_amount).bind(journal_entry_id)
+            .fetch_one(&mut *tx).await.map_err(|e| Error::Database(e.to_string()))?;
+        adjust_supplier_balance(&mut tx, record.supplier_id, record.total_amount).await?;
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(record)
     }
 
     // --- Sales Receipts ---
@@ -66,12 +91,21 @@ impl PostgresTransactionsRepository {
             .bind(tenant_id).fetch_all(&self.pool).await.map_err(|e| Error::Database(e.to_string()))
     }
 
-    pub async fn create_credit_memo(&self, tenant_id: Uuid, cm: CreateCreditMemo) -> Result<CreditMemo, Error> {
-        sqlx::query_as::<_, CreditMemo>(
-            "INSERT INTO credit_memos (tenant_id, customer_id, memo_number, total_amount, notes) VALUES ($1, $2, $3, $4, $5) RETURNING *")
+    /// Memos covered by an approval rule are held until approved.
+    pub async fn create_credit_memo(&self, tenant_id: Uuid, cm: CreateCreditMemo, user_id: Uuid) -> Result<CreditMemo, Error> {
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let total_amount = Decimal::try_from(cm.total_amount).map_err(|_| Error::BusinessRule("Invalid credit memo amount".to_string()))?.round_dp(2);
+        let held = approval_required(&mut tx, tenant_id, DocumentType::CreditMemo, total_amount).await?;
+        let record = sqlx::query_as::<_, CreditMemo>(
+            "INSERT INTO credit_memos (tenant_id, customer_id, memo_number, total_amount, notes, status) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *")
             .bind(tenant_id).bind(cm.customer_id).bind(cm.memo_number)
-            .bind(cm.total_amount).bind(cm.notes)
-            .fetch_one(&self.pool).await.map_err(|e| Error::Database(e.to_string()))
+            .bind(total_amount).bind(cm.notes).bind(if held { "PENDING_APPROVAL" } else { "OPEN" })
+            .fetch_one(&mut *tx).await.map_err(|e| Error::Database(e.to_string()))?;
+        if held {
+            open_request(&mut tx, tenant_id, DocumentType::CreditMemo, record.id, &record.memo_number, record.total_amount, user_id).await?;
+        }
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        Ok(record)
     }
 
     // --- Journal Entries ---
@@ -85,11 +119,15 @@ impl PostgresTransactionsRepository {
             .bind(entry_id).fetch_all(&self.pool).await.map_err(|e| Error::Database(e.to_string()))
     }
 
-    pub async fn create_journal_entry(&self, tenant_id: Uuid, je: CreateJournalEntry) -> Result<JournalEntry, Error> {
+    /// Entries covered by an approval rule are held until approved.
+    pub async fn create_journal_entry(&self, tenant_id: Uuid, je: CreateJournalEntry, user_id: Uuid) -> Result<JournalEntry, Error> {
         let mut tx = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+        let total_debit = je.lines.iter().map(|l| Decimal::try_from(l.debit).unwrap_or_default()).sum::<Decimal>().round_dp(2);
+        let held = approval_required(&mut tx, tenant_id, DocumentType::JournalEntry, total_debit).await?;
         let entry = sqlx::query_as::<_, JournalEntry>(
-            "INSERT INTO journal_entries (tenant_id, entry_number, date, memo, is_adjusting) VALUES ($1, $2, COALESCE($3, CURRENT_DATE), $4, $5) RETURNING *")
+            "INSERT INTO journal_entries (tenant_id, entry_number, date, memo, is_adjusting, status) VALUES ($1, $2, COALESCE($3, CURRENT_DATE), $4, $5, $6) RETURNING *")
             .bind(tenant_id).bind(&je.entry_number).bind(je.date).bind(&je.memo).bind(je.is_adjusting)
+            .bind(if held { "PENDING_APPROVAL" } else { "POSTED" })
             .fetch_one(&mut *tx).await.map_err(|e| Error::Database(e.to_string()))?;
 
         for (i, line) in je.lines.iter().enumerate() {
@@ -97,6 +135,9 @@ impl PostgresTransactionsRepository {
                 .bind(entry.id).bind(line.account_id).bind(line.debit).bind(line.credit).bind(&line.memo).bind(i as i32)
                 .execute(&mut *tx).await.map_err(|e| Error::Database(e.to_string()))?;
         }
+        if held {
+            open_request(&mut tx, tenant_id, DocumentType::JournalEntry, entry.id, &entry.entry_number, total_debit, user_id).await?;
+        }
         tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
         Ok(entry)
     }
@@ -0,0 +1,344 @@
+use smart_erp_core::models::vendor_credit::*;
+use smart_erp_core::models::transactions::Bill;
+use smart_erp_core::models::inventory::{CreateInventoryTransaction, TransactionType};
+use smart_erp_core::error::Error;
+use crate::db::inventory::insert_transaction;
+use crate::db::ledger::{post_journal_entry, LedgerLine};
+use crate::db::numbering::next_document_number;
+use crate::db::purchasing::adjust_supplier_balance;
+use crate::db::quality::{check_unquarantined, return_from_quarantine, QuarantineReturn};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub struct PostgresVendorCreditRepository {
+    pool: PgPool,
+}
+
+impl PostgresVendorCreditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_credits(&self, tenant_id: Uuid, query: VendorCreditQuery) -> Result<Vec<VendorCredit>, Error> {
+        sqlx::query_as::<_, VendorCredit>(
+            r#"
+            SELECT * FROM vendor_credits
+            WHERE tenant_id = $1
+              AND ($2::uuid IS NULL OR supplier_id = $2)
+              AND ($3::varchar IS NULL OR status = $3)
+            ORDER BY date DESC, created_at DESC
+            "#
+        )
+        .bind(tenant_id)
+        .bind(query.supplier_id)
+        .bind(query.status)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))
+    }
+
+    pub async fn get_credit(&self, tenant_id: Uuid, credit_id: Uuid) -> Result<VendorCreditDetail, Error> {
+        let credit = sqlx::query_as::<_, VendorCredit>("SELECT * FROM vendor_credits WHERE id = $1 AND tenant_id = $2")
+            .bind(credit_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Vendor credit not found".to_string()))?;
+
+        let lines = sqlx::query_as::<_, VendorCreditLine>("SELECT * FROM vendor_credit_lines WHERE credit_id = $1 ORDER BY sort_order")
+            .bind(credit.id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let applications = sqlx::query_as::<_, VendorCreditApplication>(
+            r#"
+            SELECT a.id, a.credit_id, a.bill_id, b.bill_number, a.bill_payment_id, a.amount, a.date, a.created_at
+            FROM vendor_credit_applications a
+            JOIN bills b ON b.id = a.bill_id
+            WHERE a.credit_id = $1
+            ORDER BY a.date, a.created_at
+            "#
+        )
+        .bind(credit.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(VendorCreditDetail { credit, lines, applications })
+    }
+
+    /// Records the credit, ships returned stock out and takes the credit off what is owed. Returned
+    /// stock leaves inventory at its current cost; the gap to the credited price, and any price
+    /// allowance, goes to purchase price variance.
+    pub async fn create_credit(&self, tenant_id: Uuid, req: CreateVendorCredit, user_id: Uuid) -> Result<VendorCreditDetail, Error> {
+        req.validate()?;
+        let date = req.date.unwrap_or_else(|| Utc::now().date_naive());
+        let total_amount = req.total();
+
+        let mut tx: Transaction<'_, Postgres> = self.pool.begin().await.map_err(|e| Error::Database(e.to_string()))?;
+
+        let supplier_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM suppliers WHERE id = $1 AND tenant_id = $2)")
+            .bind(req.supplier_id)
+            .bind(tenant_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        if !supplier_exists {
+            return Err(Error::NotFound("Supplier not found".to_string()));
+        }
+        if let Some(bill_id) = req.bill_id {
+            let bill_supplier: Uuid = sqlx::query_scalar("SELECT supplier_id FROM bills WHERE id = $1 AND tenant_id = $2")
+                .bind(bill_id)
+                .bind(tenant_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| Error::Database(e.to_string()))?
+                .ok_or(Error::NotFound("Bill not found".to_string()))?;
+            if bill_supplier != req.supplier_id {
+                return Err(Error::BusinessRule("The bill is from a different supplier".to_string()));
+            }
+        }
+
+        let credit_number = next_document_number(&mut tx, tenant_id, "vendor_credits", "credit_number", "VC", date).await?;
+        let credit = sqlx::query_as::<_, VendorCredit>(
+            r#"
+            INSERT INTO vendor_credits (tenant_id, supplier_id, credit_number, supplier_reference, bill_id, date, total_amount, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#
+        )
+        .bind(tenant_id)
+        .bind(req.supplier_id)
+        .bind(credit_number)
+        .bind(req.supplier_reference)
+        .bind(req.bill_id)
+        .bind(date)
+        .bind(total_amount)
+        .bind(req.notes)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        let memo = format!("Vendor credit {}", credit.credit_number);
+        let (mut inventory_value, mut variance) = (Decimal::ZERO, Decimal::ZERO);
+        let mut account_lines = Vec::new();
+        for (index, line) in req.lines.into_iter().enumerate() {
+            let amount = (line.quantity * line.unit_price).round_dp(2);
+            let mut transaction_id = None;
+            let mut receipt_line_id = line.receipt_line_id;
+            let description = match line.product_id {
+                Some(product_id) => {
+                    let name: String = sqlx::query_scalar("SELECT name FROM products WHERE id = $1 AND tenant_id = $2")
+                        .bind(product_id)
+                        .bind(tenant_id)
+                        .fetch_optional(&mut *tx)
+                        .await
+                        .map_err(|e| Error::Database(e.to_string()))?
+                        .ok_or(Error::NotFound("Product not found".to_string()))?;
+
+                    if line.return_stock {
+                        if line.inspection_id.is_none() {
+                            check_unquarantined(&mut tx, tenant_id, product_id, line.quantity).await?;
+                        }
+                        let record = insert_transaction(&mut tx, tenant_id, CreateInventoryTransaction {
+                            product_id,
+                            quantity: -line.quantity,
+                            transaction_type: TransactionType::ReturnToVendor,
+                            reference_id: Some(credit.id),
+                            notes: Some(format!("Returned to vendor ({})", credit.credit_number)),
+                            reason_code: None,
+                            unit_cost: None,
+                        }).await?;
+                        transaction_id = Some(record.id);
+
+                        if let Some(inspection_id) = line.inspection_id {
+                            let inspection = return_from_quarantine(&mut tx, tenant_id, QuarantineReturn {
+                                inspection_id,
+                                product_id,
+                                quantity: line.quantity,
+                                transaction_id: record.id,
+                                notes: &memo,
+                                created_by: user_id,
+                            }).await?;
+                            receipt_line_id = receipt_line_id.or(inspection.receipt_line_id);
+                        }
+                        if let Some(receipt_line_id) = receipt_line_id {
+                            record_return(&mut tx, tenant_id, credit.supplier_id, receipt_line_id, product_id, line.quantity).await?;
+                        }
+
+                        let value = record.total_cost.unwrap_or_default();
+                        inventory_value += value;
+                        variance += amount - value;
+                    } else {
+                        variance += amount;
+                    }
+                    line.description.unwrap_or(name)
+                }
+                None => {
+                    let account_id = line.account_id.ok_or(Error::BusinessRule("Each line credits either a product or an account".to_string()))?;
+                    let (account_number, name): (String, String) = sqlx::query_as("SELECT account_number, name FROM accounts WHERE id = $1 AND tenant_id = $2")
+                        .bind(account_id)
+                        .bind(tenant_id)
+                        .fetch_optional(&mut *tx)
+                        .await
+                        .map_err(|e| Error::Database(e.to_string()))?
+                        .ok_or(Error::NotFound("Account not found".to_string()))?;
+                    account_lines.push(LedgerLine::credit(&account_number, amount, &memo));
+                    line.description.unwrap_or(name)
+                }
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO vendor_credit_lines (credit_id, product_id, account_id, receipt_line_id, inspection_id, description,
+                                                 quantity, unit_price, amount, returned, transaction_id, sort_order)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                "#
+            )
+            .bind(credit.id)
+            .bind(line.product_id)
+            .bind(line.account_id)
+            .bind(receipt_line_id)
+            .bind(line.inspection_id)
+            .bind(description)
+            .bind(line.quantity)
+            .bind(line.unit_price)
+            .bind(amount)
+            .bind(line.return_stock)
+            .bind(transaction_id)
+            .bind(index as i32)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        }
+
+        let mut ledger = vec![
+            LedgerLine::debit("2000", total_amount, &memo),
+            LedgerLine::credit("1300", inventory_value, &memo),
+            LedgerLine::credit("5400", variance.max(Decimal::ZERO), &memo),
+            LedgerLine::debit("5400", (-variance).max(Decimal::ZERO), &memo),
+        ];
+        ledger.extend(account_lines);
+        let entry_number = next_document_number(&mut tx, tenant_id, "journal_entries", "entry_number", "VCR", date).await?;
+        let journal_entry_id = post_journal_entry(&mut tx, tenant_id, &entry_number, date, &memo, &ledger).await?;
+
+        sqlx::query("UPDATE vendor_credits SET journal_entry_id = $1 WHERE id = $2")
+            .bind(journal_entry_id)
+            .bind(credit.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        adjust_supplier_balance(&mut tx, credit.supplier_id, -total_amount).await?;
+
+        tx.commit().await.map_err(|e| Error::Database(e.to_string()))?;
+        self.get_credit(tenant_id, credit.id).await
+    }
+}
+
+/// Counts stock shipped back against the receipt it came in on.
+async fn record_return(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    supplier_id: Uuid,
+    receipt_line_id: Uuid,
+    product_id: Uuid,
+    quantity: Decimal,
+) -> Result<(), Error> {
+    let (line_product_id, order_supplier_id, received, returned): (Uuid, Uuid, Decimal, Decimal) = sqlx::query_as(
+        r#"
+        SELECT rl.product_id, o.supplier_id, rl.quantity, rl.returned_quantity
+        FROM purchase_receipt_lines rl
+        JOIN purchase_receipts r ON r.id = rl.receipt_id
+        JOIN purchase_orders o ON o.id = r.order_id
+        WHERE rl.id = $1 AND r.tenant_id = $2
+        FOR UPDATE OF rl
+        "#
+    )
+    .bind(receipt_line_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| Error::Database(e.to_string()))?
+    .ok_or(Error::NotFound("Receipt line not found".to_string()))?;
+
+    if line_product_id != product_id || order_supplier_id != supplier_id {
+        return Err(Error::BusinessRule("The receipt line is for a different product or supplier".to_string()));
+    }
+    if quantity > received - returned {
+        return Err(Error::BusinessRule(format!("Only {} of the receipt is left to return", received - returned)));
+    }
+
+    sqlx::query("UPDATE purchase_receipt_lines SET returned_quantity = returned_quantity + $1 WHERE id = $2")
+        .bind(quantity)
+        .bind(receipt_line_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Applies the supplier's open credits to a bill being paid. The credits already came off
+/// accounts payable when they were recorded, so applying them only moves the balance between
+/// documents.
+pub async fn apply_vendor_credits(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    bill: &Bill,
+    bill_payment_id: Uuid,
+    date: NaiveDate,
+    applications: &[CreditApplication],
+) -> Result<(), Error> {
+    for application in applications {
+        if application.amount <= Decimal::ZERO {
+            return Err(Error::BusinessRule("Applied credit amounts must be positive".to_string()));
+        }
+        let credit = sqlx::query_as::<_, VendorCredit>("SELECT * FROM vendor_credits WHERE id = $1 AND tenant_id = $2 FOR UPDATE")
+            .bind(application.credit_id)
+            .bind(tenant_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .ok_or(Error::NotFound("Vendor credit not found".to_string()))?;
+        if credit.supplier_id != bill.supplier_id {
+            return Err(Error::BusinessRule(format!("Vendor credit {} is from a different supplier", credit.credit_number)));
+        }
+        if application.amount > credit.remaining() {
+            return Err(Error::BusinessRule(format!(
+                "Only {} is left on vendor credit {}",
+                credit.remaining(), credit.credit_number
+            )));
+        }
+
+        let status = if application.amount == credit.remaining() { "APPLIED" } else { "PARTIALLY_APPLIED" };
+        sqlx::query("UPDATE vendor_credits SET amount_applied = amount_applied + $1, status = $2, updated_at = NOW() WHERE id = $3")
+            .bind(application.amount)
+            .bind(status)
+            .bind(credit.id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO vendor_credit_applications (tenant_id, credit_id, bill_id, bill_payment_id, amount, date)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(tenant_id)
+        .bind(credit.id)
+        .bind(bill.id)
+        .bind(bill_payment_id)
+        .bind(application.amount)
+        .bind(date)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,18 @@
+-- Replenishment: reorder parameters used alongside reorder_point / preferred_vendor_id
+
+ALTER TABLE products ADD COLUMN IF NOT EXISTS reorder_quantity DECIMAL(10, 2) NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS min_order_qty DECIMAL(10, 2) NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS lead_time_days INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_products_preferred_vendor ON products(tenant_id, preferred_vendor_id);
+CREATE INDEX IF NOT EXISTS idx_inventory_tenant_type_date ON inventory_transactions(tenant_id, transaction_type, created_at DESC);
+
+-- Counters behind generated document numbers (PREFIX-YYYYMMDD-NNN).
+-- One row per tenant, table and day stem; the row is locked while a number is taken.
+CREATE TABLE IF NOT EXISTS document_sequences (
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    table_name VARCHAR(63) NOT NULL,
+    stem VARCHAR(50) NOT NULL,
+    last_value INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (tenant_id, table_name, stem)
+);
@@ -0,0 +1,64 @@
+-- Physical Inventory Counts & Adjustments
+
+-- Reason code for ADJUSTMENT transactions (DAMAGE, SHRINKAGE, FOUND, COUNT_CORRECTION, OTHER)
+ALTER TABLE inventory_transactions ADD COLUMN IF NOT EXISTS reason_code VARCHAR(30);
+
+ALTER TABLE products ADD COLUMN IF NOT EXISTS last_counted_at TIMESTAMPTZ;
+
+-- Cycle Count Schedules
+CREATE TABLE IF NOT EXISTS cycle_count_schedules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    frequency_days INTEGER NOT NULL CHECK (frequency_days > 0),
+    items_per_count INTEGER NOT NULL CHECK (items_per_count > 0),
+    is_blind BOOLEAN NOT NULL DEFAULT TRUE,
+    location VARCHAR(100),
+    next_count_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    last_run_date DATE,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, name)
+);
+
+-- Count Sessions (snapshot of on-hand frozen at creation)
+CREATE TABLE IF NOT EXISTS inventory_count_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    session_number VARCHAR(50) NOT NULL,
+    count_type VARCHAR(20) NOT NULL DEFAULT 'FULL' CHECK (count_type IN ('FULL', 'CYCLE')),
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN' CHECK (status IN ('OPEN', 'SUBMITTED', 'APPROVED', 'CANCELLED')),
+    is_blind BOOLEAN NOT NULL DEFAULT FALSE,
+    location VARCHAR(100),
+    schedule_id UUID REFERENCES cycle_count_schedules(id) ON DELETE SET NULL,
+    snapshot_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    submitted_at TIMESTAMPTZ,
+    approved_at TIMESTAMPTZ,
+    approved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    journal_entry_id UUID REFERENCES journal_entries(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, session_number)
+);
+CREATE INDEX IF NOT EXISTS idx_count_sessions_tenant_status ON inventory_count_sessions(tenant_id, status);
+
+CREATE TABLE IF NOT EXISTS inventory_count_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID NOT NULL REFERENCES inventory_count_sessions(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    location VARCHAR(100),
+    snapshot_quantity DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    counted_quantity DECIMAL(10, 2),
+    unit_cost DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    reason_code VARCHAR(30),
+    notes TEXT,
+    counted_at TIMESTAMPTZ
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_count_lines_unique ON inventory_count_lines(session_id, product_id, COALESCE(location, ''));
+
+-- Inventory adjustment account for count variances
+INSERT INTO accounts (tenant_id, account_number, name, account_type, is_system)
+SELECT t.id, '5900', 'Inventory Adjustments', 'COST_OF_GOODS_SOLD', true FROM tenants t
+ON CONFLICT DO NOTHING;
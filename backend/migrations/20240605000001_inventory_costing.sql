@@ -0,0 +1,53 @@
+-- Inventory Costing (FIFO / Moving Average / Standard)
+
+CREATE TABLE IF NOT EXISTS inventory_settings (
+    tenant_id UUID PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+    costing_method VARCHAR(20) NOT NULL DEFAULT 'FIFO' CHECK (costing_method IN ('FIFO', 'MOVING_AVERAGE', 'STANDARD')),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE products ADD COLUMN IF NOT EXISTS average_cost DECIMAL(12, 4) NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS standard_cost DECIMAL(12, 4) NOT NULL DEFAULT 0;
+UPDATE products SET average_cost = cost_price, standard_cost = cost_price WHERE average_cost = 0 AND standard_cost = 0;
+
+ALTER TABLE inventory_transactions ADD COLUMN IF NOT EXISTS unit_cost DECIMAL(12, 4);
+ALTER TABLE inventory_transactions ADD COLUMN IF NOT EXISTS total_cost DECIMAL(14, 2);
+
+-- Cost layers: one per receipt, drawn down oldest first
+CREATE TABLE IF NOT EXISTS cost_layers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    transaction_id UUID REFERENCES inventory_transactions(id) ON DELETE SET NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    original_quantity DECIMAL(10, 2) NOT NULL,
+    remaining_quantity DECIMAL(10, 2) NOT NULL,
+    unit_cost DECIMAL(12, 4) NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_cost_layers_open ON cost_layers(product_id, received_at) WHERE remaining_quantity > 0;
+
+CREATE TABLE IF NOT EXISTS cost_layer_consumptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    layer_id UUID NOT NULL REFERENCES cost_layers(id) ON DELETE CASCADE,
+    transaction_id UUID NOT NULL REFERENCES inventory_transactions(id) ON DELETE CASCADE,
+    quantity DECIMAL(10, 2) NOT NULL,
+    unit_cost DECIMAL(12, 4) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_layer_consumptions_txn ON cost_layer_consumptions(transaction_id);
+
+-- Opening layers for stock on hand before costing was enabled
+INSERT INTO cost_layers (tenant_id, product_id, original_quantity, remaining_quantity, unit_cost)
+SELECT p.tenant_id, p.id, p.stock_quantity, p.stock_quantity, p.cost_price
+FROM products p
+WHERE p.stock_quantity > 0
+  AND NOT EXISTS (SELECT 1 FROM cost_layers l WHERE l.product_id = p.id);
+
+INSERT INTO accounts (tenant_id, account_number, name, account_type, is_system)
+SELECT t.id, v.account_number, v.name, v.account_type, true
+FROM tenants t
+CROSS JOIN (VALUES
+    ('2050', 'Goods Received Not Invoiced', 'OTHER_CURRENT_LIABILITY'),
+    ('5400', 'Purchase Price Variance', 'COST_OF_GOODS_SOLD')
+) AS v(account_number, name, account_type)
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,56 @@
+-- Purchase Receipts and Landed Cost Allocation
+
+ALTER TABLE purchase_order_lines ADD COLUMN IF NOT EXISTS weight DECIMAL(12, 3);
+ALTER TABLE purchase_order_lines ADD COLUMN IF NOT EXISTS area DECIMAL(12, 3);
+
+-- One receipt (GRN) per receiving event against a PO
+CREATE TABLE IF NOT EXISTS purchase_receipts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    order_id UUID NOT NULL REFERENCES purchase_orders(id) ON DELETE CASCADE,
+    receipt_number VARCHAR(50) NOT NULL,
+    date DATE NOT NULL DEFAULT CURRENT_DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, receipt_number)
+);
+CREATE INDEX IF NOT EXISTS idx_purchase_receipts_order ON purchase_receipts(order_id);
+
+CREATE TABLE IF NOT EXISTS purchase_receipt_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    receipt_id UUID NOT NULL REFERENCES purchase_receipts(id) ON DELETE CASCADE,
+    order_line_id UUID NOT NULL REFERENCES purchase_order_lines(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    quantity DECIMAL(10, 2) NOT NULL,
+    unit_cost DECIMAL(12, 4) NOT NULL,
+    weight DECIMAL(12, 3),
+    area DECIMAL(12, 3),
+    landed_cost DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    transaction_id UUID REFERENCES inventory_transactions(id) ON DELETE SET NULL
+);
+CREATE INDEX IF NOT EXISTS idx_purchase_receipt_lines_receipt ON purchase_receipt_lines(receipt_id);
+
+-- Freight, duty and brokerage bills spread across received lines
+CREATE TABLE IF NOT EXISTS landed_cost_allocations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE RESTRICT,
+    allocation_method VARCHAR(20) NOT NULL CHECK (allocation_method IN ('VALUE', 'QUANTITY', 'WEIGHT', 'AREA')),
+    amount DECIMAL(12, 2) NOT NULL,
+    capitalized_amount DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    expensed_amount DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    journal_entry_id UUID REFERENCES journal_entries(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_landed_cost_allocations_bill ON landed_cost_allocations(bill_id);
+
+CREATE TABLE IF NOT EXISTS landed_cost_allocation_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    allocation_id UUID NOT NULL REFERENCES landed_cost_allocations(id) ON DELETE CASCADE,
+    receipt_line_id UUID NOT NULL REFERENCES purchase_receipt_lines(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    basis DECIMAL(14, 4) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL,
+    capitalized_amount DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    expensed_amount DECIMAL(12, 2) NOT NULL DEFAULT 0
+);
@@ -0,0 +1,41 @@
+-- Three-way Match: Purchase Order / Receipt / Vendor Bill
+
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS purchase_order_id UUID REFERENCES purchase_orders(id) ON DELETE SET NULL;
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS receipt_id UUID REFERENCES purchase_receipts(id) ON DELETE SET NULL;
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS match_status VARCHAR(20) NOT NULL DEFAULT 'UNMATCHED'
+    CHECK (match_status IN ('UNMATCHED', 'MATCHED', 'EXCEPTION', 'APPROVED'));
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS match_approved_by UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS match_approved_at TIMESTAMPTZ;
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS journal_entry_id UUID REFERENCES journal_entries(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_bills_purchase_order ON bills(purchase_order_id);
+
+ALTER TABLE bill_lines ADD COLUMN IF NOT EXISTS receipt_line_id UUID REFERENCES purchase_receipt_lines(id) ON DELETE SET NULL;
+-- Part of the billed quantity covered by the receipt (the rest is an over-bill)
+ALTER TABLE bill_lines ADD COLUMN IF NOT EXISTS matched_quantity DECIMAL(10, 2) NOT NULL DEFAULT 0;
+
+-- Quantity already billed against each receipt line, so it cannot be billed twice
+ALTER TABLE purchase_receipt_lines ADD COLUMN IF NOT EXISTS billed_quantity DECIMAL(10, 2) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS purchase_match_settings (
+    tenant_id UUID PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+    quantity_tolerance_percent DECIMAL(5, 2) NOT NULL DEFAULT 0,
+    price_tolerance_percent DECIMAL(5, 2) NOT NULL DEFAULT 2,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS bill_match_exceptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    bill_line_id UUID NOT NULL REFERENCES bill_lines(id) ON DELETE CASCADE,
+    exception_type VARCHAR(20) NOT NULL CHECK (exception_type IN ('QUANTITY', 'PRICE')),
+    expected DECIMAL(12, 4) NOT NULL,
+    actual DECIMAL(12, 4) NOT NULL,
+    variance_percent DECIMAL(8, 2) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_bill_match_exceptions_bill ON bill_match_exceptions(bill_id);
+
+-- Bills entered without a purchase order are held here until their cost is allocated
+INSERT INTO accounts (tenant_id, account_number, name, account_type, is_system)
+SELECT t.id, '1450', 'Purchases Clearing', 'OTHER_CURRENT_ASSET', true FROM tenants t
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,8 @@
+-- Multi-level Bill of Materials: conversion costs and rolled-up standard cost per recipe
+
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS labor_cost DECIMAL(12, 2) NOT NULL DEFAULT 0;    -- Per batch
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS overhead_cost DECIMAL(12, 2) NOT NULL DEFAULT 0; -- Per batch
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS standard_unit_cost DECIMAL(12, 4);               -- Per output unit, from the last rollup
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS cost_rolled_up_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_recipe_ingredients_input ON recipe_ingredients(input_product_id);
@@ -0,0 +1,50 @@
+-- Material Requirements Planning
+
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS lead_time_days INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS mrp_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    run_number VARCHAR(50) NOT NULL,
+    horizon_start DATE NOT NULL,
+    horizon_end DATE NOT NULL,
+    period_days INTEGER NOT NULL DEFAULT 7,
+    planned_order_count INTEGER NOT NULL DEFAULT 0,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, run_number)
+);
+
+-- Time-phased netting per product and period
+CREATE TABLE IF NOT EXISTS mrp_requirements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id UUID NOT NULL REFERENCES mrp_runs(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    gross_requirement DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    scheduled_receipts DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    planned_receipts DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    projected_on_hand DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    net_requirement DECIMAL(12, 2) NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_mrp_requirements_run ON mrp_requirements(run_id, product_id, period_start);
+
+CREATE TABLE IF NOT EXISTS mrp_planned_orders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    run_id UUID NOT NULL REFERENCES mrp_runs(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    order_type VARCHAR(20) NOT NULL CHECK (order_type IN ('WORK_ORDER', 'PURCHASE_ORDER')),
+    recipe_id UUID REFERENCES recipes(id) ON DELETE SET NULL,
+    supplier_id UUID REFERENCES suppliers(id) ON DELETE SET NULL,
+    quantity DECIMAL(12, 2) NOT NULL,
+    batches DECIMAL(12, 2),
+    release_date DATE NOT NULL,
+    due_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PLANNED' CHECK (status IN ('PLANNED', 'FIRMED')),
+    work_order_id UUID REFERENCES work_orders(id) ON DELETE SET NULL,
+    purchase_order_id UUID REFERENCES purchase_orders(id) ON DELETE SET NULL,
+    firmed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_mrp_planned_orders_tenant ON mrp_planned_orders(tenant_id, status, release_date);
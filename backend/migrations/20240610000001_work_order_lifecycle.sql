@@ -0,0 +1,53 @@
+-- Work order lifecycle: material issue, partial production, scrap and yield
+
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS produced_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0; -- Good output units
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS scrapped_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0; -- Output units scrapped
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS material_cost DECIMAL(14, 2) NOT NULL DEFAULT 0;     -- Net value issued
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS output_cost DECIMAL(14, 2) NOT NULL DEFAULT 0;       -- Value received as output
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS started_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS work_order_materials (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    planned_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    issued_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    returned_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    scrapped_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    issued_cost DECIMAL(14, 2) NOT NULL DEFAULT 0, -- Net of returns
+    UNIQUE(work_order_id, product_id)
+);
+
+-- Existing work orders plan their recipe quantities
+INSERT INTO work_order_materials (work_order_id, product_id, planned_quantity)
+SELECT w.id, ri.input_product_id, SUM(ri.quantity * w.quantity)
+FROM work_orders w JOIN recipe_ingredients ri ON ri.recipe_id = w.recipe_id
+GROUP BY w.id, ri.input_product_id
+ON CONFLICT (work_order_id, product_id) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS work_order_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    quantity DECIMAL(12, 4) NOT NULL,
+    scrapped_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    unit_cost DECIMAL(14, 4) NOT NULL DEFAULT 0,
+    total_cost DECIMAL(14, 2) NOT NULL DEFAULT 0,
+    transaction_id UUID REFERENCES inventory_transactions(id) ON DELETE SET NULL,
+    notes TEXT,
+    reported_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS work_order_scrap (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    report_id UUID REFERENCES work_order_reports(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    quantity DECIMAL(12, 4) NOT NULL,
+    reason VARCHAR(30) NOT NULL CHECK (reason IN ('CUTTING_WASTE', 'DEFECT', 'DAMAGE', 'MACHINE_ERROR', 'OTHER')),
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_order_reports_order ON work_order_reports(work_order_id);
+CREATE INDEX IF NOT EXISTS idx_work_order_scrap_order ON work_order_scrap(work_order_id);
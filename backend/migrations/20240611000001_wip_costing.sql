@@ -0,0 +1,47 @@
+-- Work order costing: WIP account, labor time, overhead rates and variances
+
+INSERT INTO accounts (tenant_id, account_number, name, account_type, is_system)
+SELECT t.id, '1310', 'Work in Process', 'OTHER_CURRENT_ASSET', true FROM tenants t
+ON CONFLICT DO NOTHING;
+
+-- Labor and overhead are absorbed out of, and variances posted to, the COGS sub-accounts
+UPDATE accounts SET is_system = true
+WHERE account_number IN ('5100', '5200', '5300');
+
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS labor_hours DECIMAL(12, 2) NOT NULL DEFAULT 0;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS labor_cost DECIMAL(14, 2) NOT NULL DEFAULT 0;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS overhead_cost DECIMAL(14, 2) NOT NULL DEFAULT 0;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS material_variance DECIMAL(14, 2) NOT NULL DEFAULT 0;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS labor_variance DECIMAL(14, 2) NOT NULL DEFAULT 0;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS overhead_variance DECIMAL(14, 2) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS overhead_rates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    basis VARCHAR(20) NOT NULL CHECK (basis IN ('LABOR_HOURS', 'LABOR_COST', 'MATERIAL_COST')),
+    rate DECIMAL(12, 4) NOT NULL, -- Per labor hour, or a percentage of labor or material cost
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS labor_time_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    employee_id UUID NOT NULL REFERENCES employees(id) ON DELETE RESTRICT,
+    work_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    hours DECIMAL(8, 2) NOT NULL CHECK (hours > 0),
+    hourly_rate DECIMAL(10, 2) NOT NULL,
+    labor_cost DECIMAL(14, 2) NOT NULL,
+    overhead_cost DECIMAL(14, 2) NOT NULL DEFAULT 0,
+    journal_entry_id UUID REFERENCES journal_entries(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_labor_time_entries_order ON labor_time_entries(work_order_id);
+CREATE INDEX IF NOT EXISTS idx_labor_time_entries_employee ON labor_time_entries(tenant_id, employee_id, work_date);
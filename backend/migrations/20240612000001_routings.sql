@@ -0,0 +1,79 @@
+-- Work centers, routings and finite capacity scheduling
+
+CREATE TABLE IF NOT EXISTS work_centers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    code VARCHAR(30) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    hours_per_day DECIMAL(5, 2) NOT NULL DEFAULT 8 CHECK (hours_per_day >= 0 AND hours_per_day <= 24),
+    capacity_units INTEGER NOT NULL DEFAULT 1 CHECK (capacity_units > 0), -- Parallel machines or crews
+    working_days INTEGER[] NOT NULL DEFAULT '{1,2,3,4,5}',               -- ISO weekdays, Monday = 1
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, code)
+);
+
+-- Holidays, shutdowns and overtime: replaces the normal hours for the day
+CREATE TABLE IF NOT EXISTS work_center_calendar (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    work_center_id UUID NOT NULL REFERENCES work_centers(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    available_hours DECIMAL(6, 2) NOT NULL CHECK (available_hours >= 0),
+    reason VARCHAR(255),
+    UNIQUE(work_center_id, date)
+);
+
+CREATE TABLE IF NOT EXISTS routings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS routing_operations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    routing_id UUID NOT NULL REFERENCES routings(id) ON DELETE CASCADE,
+    sequence INTEGER NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    work_center_id UUID NOT NULL REFERENCES work_centers(id) ON DELETE RESTRICT,
+    setup_hours DECIMAL(8, 2) NOT NULL DEFAULT 0,
+    run_hours_per_batch DECIMAL(8, 2) NOT NULL DEFAULT 0,
+    wait_days INTEGER NOT NULL DEFAULT 0, -- e.g. hides resting after liming before the next step
+    description TEXT,
+    UNIQUE(routing_id, sequence)
+);
+
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS routing_id UUID REFERENCES routings(id) ON DELETE SET NULL;
+
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS scheduled_end DATE;
+
+CREATE TABLE IF NOT EXISTS work_order_operations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    routing_operation_id UUID REFERENCES routing_operations(id) ON DELETE SET NULL,
+    sequence INTEGER NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    work_center_id UUID NOT NULL REFERENCES work_centers(id) ON DELETE RESTRICT,
+    planned_hours DECIMAL(10, 2) NOT NULL,
+    scheduled_start DATE NOT NULL,
+    scheduled_end DATE NOT NULL
+);
+
+-- Hours booked per work center and day
+CREATE TABLE IF NOT EXISTS work_center_loads (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    work_center_id UUID NOT NULL REFERENCES work_centers(id) ON DELETE CASCADE,
+    work_order_operation_id UUID NOT NULL REFERENCES work_order_operations(id) ON DELETE CASCADE,
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    load_date DATE NOT NULL,
+    hours DECIMAL(8, 2) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_routing_operations_routing ON routing_operations(routing_id, sequence);
+CREATE INDEX IF NOT EXISTS idx_work_order_operations_order ON work_order_operations(work_order_id, sequence);
+CREATE INDEX IF NOT EXISTS idx_work_center_loads_center_date ON work_center_loads(work_center_id, load_date);
@@ -0,0 +1,40 @@
+-- Recipe versions, effective dates and by-products
+
+-- Recipe versions share a name. Each version is its own recipe row, so a work order stays
+-- pinned to the version it was created from while the formulation moves on.
+ALTER TABLE recipes DROP CONSTRAINT IF EXISTS recipes_tenant_id_name_key;
+
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'APPROVED'; -- DRAFT, APPROVED
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS effective_from DATE; -- Effective from the start while NULL
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS effective_to DATE; -- Open-ended while NULL
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS approved_by UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS approved_at TIMESTAMPTZ;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_recipes_name_version ON recipes(tenant_id, name, version);
+
+-- Co-products and by-products made alongside the primary output. Each takes its share of the
+-- batch cost; the primary output carries what is left.
+CREATE TABLE IF NOT EXISTS recipe_outputs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    recipe_id UUID NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    output_type VARCHAR(20) NOT NULL DEFAULT 'BY_PRODUCT', -- CO_PRODUCT, BY_PRODUCT
+    quantity DECIMAL(12, 4) NOT NULL,                       -- Per batch
+    cost_share DECIMAL(5, 2) NOT NULL DEFAULT 0,            -- Percent of the batch cost
+    UNIQUE(recipe_id, product_id)
+);
+
+CREATE TABLE IF NOT EXISTS work_order_outputs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    output_type VARCHAR(20) NOT NULL,
+    cost_share DECIMAL(5, 2) NOT NULL DEFAULT 0,
+    planned_quantity DECIMAL(12, 4) NOT NULL,
+    produced_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    output_cost DECIMAL(14, 2) NOT NULL DEFAULT 0,
+    UNIQUE(work_order_id, product_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_recipe_outputs_product ON recipe_outputs(product_id);
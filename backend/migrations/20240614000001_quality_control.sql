@@ -0,0 +1,82 @@
+-- Quality control: inspection plans, inspections and quarantine stock
+
+-- Stock on hand that is held by quality control and cannot be shipped or consumed.
+ALTER TABLE products ADD COLUMN IF NOT EXISTS quarantined_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0;
+
+-- Quantity sent back to the supplier after failing inspection; it can no longer be billed.
+ALTER TABLE purchase_receipt_lines ADD COLUMN IF NOT EXISTS returned_quantity DECIMAL(10, 2) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS inspection_plans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    inspection_type VARCHAR(20) NOT NULL, -- RECEIPT, PRODUCTION
+    routing_operation_id UUID REFERENCES routing_operations(id) ON DELETE SET NULL, -- In-process check at an operation
+    sample_size DECIMAL(12, 4),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- A characteristic without limits is a pass/fail check.
+CREATE TABLE IF NOT EXISTS inspection_characteristics (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    plan_id UUID NOT NULL REFERENCES inspection_plans(id) ON DELETE CASCADE,
+    sequence INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    unit VARCHAR(20),
+    lower_limit DECIMAL(14, 4),
+    upper_limit DECIMAL(14, 4)
+);
+
+CREATE TABLE IF NOT EXISTS quality_inspections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    inspection_number VARCHAR(50) NOT NULL,
+    plan_id UUID REFERENCES inspection_plans(id) ON DELETE SET NULL,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    source_type VARCHAR(20) NOT NULL, -- RECEIPT, PRODUCTION, REWORK, MANUAL
+    receipt_line_id UUID REFERENCES purchase_receipt_lines(id) ON DELETE SET NULL,
+    work_order_id UUID REFERENCES work_orders(id) ON DELETE SET NULL,
+    parent_id UUID REFERENCES quality_inspections(id) ON DELETE SET NULL, -- Inspection the rework came from
+    quantity DECIMAL(12, 4) NOT NULL,
+    quarantined BOOLEAN NOT NULL DEFAULT TRUE, -- False for in-process checks on stock not yet made
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING', -- PENDING, PASSED, FAILED
+    accepted_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    rejected_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    disposed_quantity DECIMAL(12, 4) NOT NULL DEFAULT 0,
+    inspector_id UUID REFERENCES employees(id) ON DELETE SET NULL,
+    inspected_at TIMESTAMPTZ,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, inspection_number)
+);
+
+CREATE TABLE IF NOT EXISTS inspection_results (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    inspection_id UUID NOT NULL REFERENCES quality_inspections(id) ON DELETE CASCADE,
+    characteristic_id UUID REFERENCES inspection_characteristics(id) ON DELETE SET NULL,
+    name VARCHAR(255) NOT NULL,
+    measured_value DECIMAL(14, 4),
+    passed BOOLEAN NOT NULL,
+    notes TEXT
+);
+
+CREATE TABLE IF NOT EXISTS quality_dispositions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    inspection_id UUID NOT NULL REFERENCES quality_inspections(id) ON DELETE CASCADE,
+    action VARCHAR(20) NOT NULL, -- RELEASE, REWORK, SCRAP, RETURN_TO_VENDOR
+    quantity DECIMAL(12, 4) NOT NULL,
+    transaction_id UUID REFERENCES inventory_transactions(id) ON DELETE SET NULL,
+    rework_inspection_id UUID REFERENCES quality_inspections(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_inspection_plans_product ON inspection_plans(tenant_id, product_id, inspection_type);
+CREATE INDEX IF NOT EXISTS idx_quality_inspections_status ON quality_inspections(tenant_id, status);
+CREATE INDEX IF NOT EXISTS idx_quality_inspections_product ON quality_inspections(product_id);
@@ -0,0 +1,22 @@
+-- Shop floor labor: clock-in/clock-out against work order operations
+
+ALTER TABLE labor_time_entries ADD COLUMN IF NOT EXISTS work_order_operation_id UUID REFERENCES work_order_operations(id) ON DELETE SET NULL;
+ALTER TABLE labor_time_entries ADD COLUMN IF NOT EXISTS entry_method VARCHAR(20) NOT NULL DEFAULT 'MANUAL'; -- MANUAL, CLOCK
+ALTER TABLE labor_time_entries ADD COLUMN IF NOT EXISTS clock_in TIMESTAMPTZ;
+ALTER TABLE labor_time_entries ADD COLUMN IF NOT EXISTS clock_out TIMESTAMPTZ;
+
+-- Open clock-ins. Clocking out removes the row and posts a labor time entry.
+CREATE TABLE IF NOT EXISTS labor_clocks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    employee_id UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    work_order_operation_id UUID REFERENCES work_order_operations(id) ON DELETE SET NULL,
+    clock_in TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE(employee_id) -- One job at a time
+);
+
+CREATE INDEX IF NOT EXISTS idx_labor_time_entries_operation ON labor_time_entries(work_order_operation_id);
+CREATE INDEX IF NOT EXISTS idx_labor_clocks_order ON labor_clocks(work_order_id);
@@ -0,0 +1,41 @@
+-- Hide nesting: pattern pieces per recipe and cutting layouts per work order
+
+CREATE TABLE IF NOT EXISTS pattern_pieces (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    recipe_id UUID NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    outline JSONB NOT NULL,                          -- [{x, y}, ...] in hide units
+    quantity INTEGER NOT NULL DEFAULT 1 CHECK (quantity > 0), -- Per output unit
+    min_grade VARCHAR(1) NOT NULL DEFAULT 'C',       -- A, B, C
+    allow_rotation BOOLEAN NOT NULL DEFAULT TRUE,
+    area DECIMAL(14, 2) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS nesting_layouts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    recipe_id UUID NOT NULL REFERENCES recipes(id) ON DELETE RESTRICT,
+    hide_reference VARCHAR(100),
+    hide JSONB NOT NULL,       -- Outline, defect zones and quality regions
+    placements JSONB NOT NULL,
+    pieces JSONB NOT NULL,     -- Requested and placed count per pattern piece
+    resolution DECIMAL(8, 3) NOT NULL,
+    spacing DECIMAL(8, 3) NOT NULL DEFAULT 0,
+    usable_area DECIMAL(14, 2) NOT NULL,
+    placed_area DECIMAL(14, 2) NOT NULL,
+    expected_yield DECIMAL(5, 2) NOT NULL, -- Percent of the usable area
+    requested_units INTEGER NOT NULL,
+    complete_units INTEGER NOT NULL,
+    actual_cut_area DECIMAL(14, 2),        -- Good pieces cut, once recorded
+    actual_yield DECIMAL(5, 2),
+    recorded_at TIMESTAMPTZ,
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_pattern_pieces_recipe ON pattern_pieces(recipe_id);
+CREATE INDEX IF NOT EXISTS idx_nesting_layouts_order ON nesting_layouts(work_order_id);
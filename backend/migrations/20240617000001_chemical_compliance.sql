@@ -0,0 +1,89 @@
+-- Chemical lots, restricted substances and waste disposal for environmental compliance
+
+-- Chemicals are consumed by lot so every use can be traced and expired lots blocked.
+ALTER TABLE products ADD COLUMN IF NOT EXISTS is_chemical BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Substances regulators ask us to report on, e.g. chromium or formaldehyde. Quantities in kg.
+CREATE TABLE IF NOT EXISTS restricted_substances (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    cas_number VARCHAR(20),
+    regulation VARCHAR(255),
+    reporting_threshold DECIMAL(14, 4), -- Per reporting period
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, name)
+);
+
+-- Share of each restricted substance in a chemical product
+CREATE TABLE IF NOT EXISTS chemical_substances (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    substance_id UUID NOT NULL REFERENCES restricted_substances(id) ON DELETE RESTRICT,
+    concentration DECIMAL(7, 4) NOT NULL CHECK (concentration > 0 AND concentration <= 100), -- Percent by weight
+    UNIQUE(product_id, substance_id)
+);
+
+CREATE TABLE IF NOT EXISTS chemical_lots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    lot_number VARCHAR(100) NOT NULL,
+    supplier_id UUID REFERENCES suppliers(id) ON DELETE SET NULL,
+    sds_reference VARCHAR(255) NOT NULL, -- Safety data sheet number or link
+    hazard_class VARCHAR(100) NOT NULL,  -- e.g. GHS05 Corrosive
+    received_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    expiry_date DATE,
+    quantity DECIMAL(12, 4) NOT NULL CHECK (quantity > 0),
+    remaining_quantity DECIMAL(12, 4) NOT NULL CHECK (remaining_quantity >= 0),
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, product_id, lot_number)
+);
+
+-- Lot usage per work order; returns are negative.
+CREATE TABLE IF NOT EXISTS chemical_consumptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    lot_id UUID NOT NULL REFERENCES chemical_lots(id) ON DELETE RESTRICT,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    work_order_id UUID NOT NULL REFERENCES work_orders(id) ON DELETE CASCADE,
+    quantity DECIMAL(12, 4) NOT NULL,
+    consumed_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS waste_disposals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    disposal_number VARCHAR(50) NOT NULL,
+    waste_type VARCHAR(20) NOT NULL, -- EFFLUENT, SLUDGE, SOLID, CHEMICAL
+    description VARCHAR(255) NOT NULL,
+    quantity DECIMAL(14, 4) NOT NULL CHECK (quantity > 0),
+    unit VARCHAR(20) NOT NULL,
+    disposal_date DATE NOT NULL,
+    manifest_number VARCHAR(100), -- Required for everything but permitted effluent discharge
+    carrier VARCHAR(255),
+    facility VARCHAR(255),
+    disposal_cost DECIMAL(14, 2), -- Booked to 7600 from the carrier's bill
+    work_order_id UUID REFERENCES work_orders(id) ON DELETE SET NULL,
+    lot_id UUID REFERENCES chemical_lots(id) ON DELETE SET NULL, -- Chemical written off, e.g. expired
+    transaction_id UUID REFERENCES inventory_transactions(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, disposal_number)
+);
+
+CREATE TABLE IF NOT EXISTS waste_disposal_substances (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    disposal_id UUID NOT NULL REFERENCES waste_disposals(id) ON DELETE CASCADE,
+    substance_id UUID NOT NULL REFERENCES restricted_substances(id) ON DELETE RESTRICT,
+    quantity DECIMAL(14, 4) NOT NULL CHECK (quantity >= 0), -- kg
+    UNIQUE(disposal_id, substance_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_chemical_lots_product ON chemical_lots(tenant_id, product_id, expiry_date);
+CREATE INDEX IF NOT EXISTS idx_chemical_consumptions_order ON chemical_consumptions(work_order_id);
+CREATE INDEX IF NOT EXISTS idx_chemical_consumptions_date ON chemical_consumptions(tenant_id, consumed_date);
+CREATE INDEX IF NOT EXISTS idx_waste_disposals_date ON waste_disposals(tenant_id, disposal_date);
@@ -0,0 +1,12 @@
+-- Supplier balances are now kept up to date from bills and payments. Bill payments made
+-- before this tracked only the bill, so rebuild the balance from what is still open.
+-- Bills held on match exceptions are not owed until approved.
+UPDATE suppliers s
+SET balance = COALESCE((
+    SELECT SUM(b.total_amount - b.amount_paid)
+    FROM bills b
+    WHERE b.supplier_id = s.id AND b.match_status <> 'EXCEPTION'
+), 0);
+
+CREATE INDEX IF NOT EXISTS idx_bills_supplier ON bills(supplier_id, date);
+CREATE INDEX IF NOT EXISTS idx_suppliers_tenant_active ON suppliers(tenant_id, is_active);
@@ -0,0 +1,70 @@
+-- Structured payment terms with early-payment discounts
+
+CREATE TABLE IF NOT EXISTS payment_terms (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    terms_type VARCHAR(20) NOT NULL DEFAULT 'NET' CHECK (terms_type IN ('NET', 'DAY_OF_MONTH')),
+    net_days INTEGER NOT NULL DEFAULT 0 CHECK (net_days >= 0),
+    day_of_month INTEGER CHECK (day_of_month BETWEEN 1 AND 31),
+    discount_percent DECIMAL(5, 2) NOT NULL DEFAULT 0 CHECK (discount_percent >= 0 AND discount_percent < 100),
+    discount_days INTEGER NOT NULL DEFAULT 0 CHECK (discount_days >= 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, name),
+    CHECK (terms_type <> 'DAY_OF_MONTH' OR day_of_month IS NOT NULL)
+);
+
+INSERT INTO payment_terms (tenant_id, name, terms_type, net_days, day_of_month, discount_percent, discount_days)
+SELECT t.id, v.name, v.terms_type, v.net_days, v.day_of_month, v.discount_percent, v.discount_days
+FROM tenants t
+CROSS JOIN (VALUES
+    ('Due on receipt', 'NET', 0, NULL::integer, 0::numeric, 0),
+    ('Net 15', 'NET', 15, NULL, 0, 0),
+    ('Net 30', 'NET', 30, NULL, 0, 0),
+    ('Net 60', 'NET', 60, NULL, 0, 0),
+    ('2/10 Net 30', 'NET', 30, NULL, 2, 10),
+    ('1/10 Net 30', 'NET', 30, NULL, 1, 10),
+    ('15th of next month', 'DAY_OF_MONTH', 0, 15, 0, 0)
+) AS v(name, terms_type, net_days, day_of_month, discount_percent, discount_days)
+ON CONFLICT DO NOTHING;
+
+-- Default terms for new invoices and bills
+ALTER TABLE customers ADD COLUMN IF NOT EXISTS payment_terms_id UUID REFERENCES payment_terms(id) ON DELETE SET NULL;
+ALTER TABLE suppliers ADD COLUMN IF NOT EXISTS payment_terms_id UUID REFERENCES payment_terms(id) ON DELETE SET NULL;
+
+UPDATE customers c SET payment_terms_id = pt.id
+FROM payment_terms pt
+WHERE pt.tenant_id = c.tenant_id AND pt.name = c.terms AND c.payment_terms_id IS NULL;
+
+UPDATE suppliers s SET payment_terms_id = pt.id
+FROM payment_terms pt
+WHERE pt.tenant_id = s.tenant_id AND pt.name = s.terms AND s.payment_terms_id IS NULL;
+
+-- Discount available if paid by discount_date, and how much of it has been taken
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS payment_terms_id UUID REFERENCES payment_terms(id) ON DELETE SET NULL;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS discount_date DATE;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS discount_amount DECIMAL(12, 2) NOT NULL DEFAULT 0;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS discount_taken DECIMAL(12, 2) NOT NULL DEFAULT 0;
+
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS payment_terms_id UUID REFERENCES payment_terms(id) ON DELETE SET NULL;
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS discount_date DATE;
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS discount_amount DECIMAL(12, 2) NOT NULL DEFAULT 0;
+ALTER TABLE bills ADD COLUMN IF NOT EXISTS discount_taken DECIMAL(12, 2) NOT NULL DEFAULT 0;
+
+UPDATE bills b SET payment_terms_id = pt.id
+FROM payment_terms pt
+WHERE pt.tenant_id = b.tenant_id AND pt.name = b.terms AND b.payment_terms_id IS NULL;
+
+-- Amount settled by discount alongside the cash paid
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS discount_amount DECIMAL(12, 2) NOT NULL DEFAULT 0;
+ALTER TABLE bill_payments ADD COLUMN IF NOT EXISTS discount_amount DECIMAL(12, 2) NOT NULL DEFAULT 0;
+
+-- Discounts taken on bills; discounts given to customers go to 4200
+INSERT INTO accounts (tenant_id, account_number, name, account_type, is_system)
+SELECT t.id, '4950', 'Purchase Discounts', 'OTHER_INCOME', true FROM tenants t
+ON CONFLICT DO NOTHING;
+
+UPDATE accounts SET is_system = true
+WHERE account_number = '4200';
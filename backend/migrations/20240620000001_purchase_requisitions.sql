@@ -0,0 +1,80 @@
+-- Purchase requisitions routed through approval rules before they become purchase orders
+
+ALTER TABLE products ADD COLUMN IF NOT EXISTS category VARCHAR(100);
+CREATE INDEX IF NOT EXISTS idx_products_tenant_category ON products(tenant_id, category);
+
+-- A rule applies to a requisition at or above min_amount, and when set, only to the given
+-- department or to requisitions with a product in the given category. Each rule that applies
+-- needs an approval from a user holding approver_role; lower sequences approve first.
+CREATE TABLE IF NOT EXISTS requisition_approval_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    min_amount DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    department VARCHAR(100),
+    category VARCHAR(100),
+    approver_role VARCHAR(50) NOT NULL,
+    sequence INTEGER NOT NULL DEFAULT 1,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS purchase_requisitions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    requisition_number VARCHAR(50) NOT NULL,
+    requested_by UUID NOT NULL REFERENCES users(id) ON DELETE RESTRICT,
+    department VARCHAR(100),
+    needed_by DATE,
+    status VARCHAR(20) NOT NULL DEFAULT 'DRAFT'
+        CHECK (status IN ('DRAFT', 'PENDING', 'APPROVED', 'REJECTED', 'CONVERTED', 'CANCELLED')),
+    total_amount DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    notes TEXT,
+    submitted_at TIMESTAMPTZ,
+    decided_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, requisition_number)
+);
+CREATE INDEX IF NOT EXISTS idx_purchase_requisitions_status ON purchase_requisitions(tenant_id, status);
+
+CREATE TABLE IF NOT EXISTS purchase_requisition_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    requisition_id UUID NOT NULL REFERENCES purchase_requisitions(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    supplier_id UUID REFERENCES suppliers(id) ON DELETE SET NULL,
+    quantity DECIMAL(10, 2) NOT NULL CHECK (quantity > 0),
+    estimated_price DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    amount DECIMAL(12, 2) GENERATED ALWAYS AS (ROUND(quantity * estimated_price, 2)) STORED,
+    purchase_order_id UUID REFERENCES purchase_orders(id) ON DELETE SET NULL,
+    sort_order INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_purchase_requisition_lines_requisition ON purchase_requisition_lines(requisition_id);
+
+-- One step per rule that applied when the requisition was submitted
+CREATE TABLE IF NOT EXISTS requisition_approvals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    requisition_id UUID NOT NULL REFERENCES purchase_requisitions(id) ON DELETE CASCADE,
+    rule_id UUID REFERENCES requisition_approval_rules(id) ON DELETE SET NULL,
+    rule_name VARCHAR(100) NOT NULL,
+    sequence INTEGER NOT NULL,
+    approver_role VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'APPROVED', 'REJECTED')),
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMPTZ,
+    comment TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_requisition_approvals_requisition ON requisition_approvals(requisition_id, sequence);
+
+CREATE TABLE IF NOT EXISTS requisition_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    requisition_id UUID NOT NULL REFERENCES purchase_requisitions(id) ON DELETE CASCADE,
+    action VARCHAR(20) NOT NULL
+        CHECK (action IN ('CREATED', 'SUBMITTED', 'APPROVED', 'REJECTED', 'COMMENTED', 'CONVERTED', 'CANCELLED')),
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_requisition_events_requisition ON requisition_events(requisition_id, created_at);
@@ -0,0 +1,90 @@
+-- Approval gates for purchase orders, bills, manual journal entries and credit memos
+
+-- A rule applies to a document of its type at or above min_amount. Each rule that applies
+-- needs an approval from a user holding approver_role; lower sequences approve first.
+CREATE TABLE IF NOT EXISTS approval_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    document_type VARCHAR(30) NOT NULL
+        CHECK (document_type IN ('PURCHASE_ORDER', 'BILL', 'JOURNAL_ENTRY', 'CREDIT_MEMO')),
+    name VARCHAR(100) NOT NULL,
+    min_amount DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    approver_role VARCHAR(50) NOT NULL,
+    sequence INTEGER NOT NULL DEFAULT 1,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, document_type, name)
+);
+
+-- One request per attempt at getting a document approved; amount is the document total
+-- at the time, so a later increase needs a fresh approval
+CREATE TABLE IF NOT EXISTS approval_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    document_type VARCHAR(30) NOT NULL,
+    document_id UUID NOT NULL,
+    document_number VARCHAR(50) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING'
+        CHECK (status IN ('PENDING', 'APPROVED', 'REJECTED', 'CANCELLED')),
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_approval_requests_document ON approval_requests(tenant_id, document_type, document_id);
+CREATE INDEX IF NOT EXISTS idx_approval_requests_status ON approval_requests(tenant_id, status);
+
+CREATE TABLE IF NOT EXISTS approval_steps (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    request_id UUID NOT NULL REFERENCES approval_requests(id) ON DELETE CASCADE,
+    rule_id UUID REFERENCES approval_rules(id) ON DELETE SET NULL,
+    rule_name VARCHAR(100) NOT NULL,
+    sequence INTEGER NOT NULL,
+    approver_role VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'APPROVED', 'REJECTED')),
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    on_behalf_of UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMPTZ,
+    comment TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_approval_steps_request ON approval_steps(request_id, sequence);
+
+-- Lets the delegate act with the delegator's role while the delegator is away
+CREATE TABLE IF NOT EXISTS approval_delegations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    delegator_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    delegate_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    document_type VARCHAR(30), -- All document types when NULL
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (ends_on >= starts_on),
+    CHECK (delegate_id <> delegator_id)
+);
+CREATE INDEX IF NOT EXISTS idx_approval_delegations_delegate ON approval_delegations(tenant_id, delegate_id);
+
+CREATE TABLE IF NOT EXISTS approval_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    request_id UUID NOT NULL REFERENCES approval_requests(id) ON DELETE CASCADE,
+    action VARCHAR(20) NOT NULL
+        CHECK (action IN ('REQUESTED', 'APPROVED', 'REJECTED', 'COMMENTED', 'CANCELLED')),
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    on_behalf_of UUID REFERENCES users(id) ON DELETE SET NULL,
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_approval_events_request ON approval_events(request_id, created_at);
+
+-- Manual journal entries and credit memos over a rule's threshold wait for approval
+ALTER TABLE journal_entries ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'POSTED';
+ALTER TABLE journal_entries DROP CONSTRAINT IF EXISTS journal_entries_status_check;
+ALTER TABLE journal_entries ADD CONSTRAINT journal_entries_status_check
+    CHECK (status IN ('PENDING_APPROVAL', 'POSTED', 'REJECTED'));
+
+ALTER TABLE credit_memos DROP CONSTRAINT IF EXISTS credit_memos_status_check;
+ALTER TABLE credit_memos ADD CONSTRAINT credit_memos_status_check
+    CHECK (status IN ('PENDING_APPROVAL', 'OPEN', 'APPLIED', 'REFUNDED', 'REJECTED'));
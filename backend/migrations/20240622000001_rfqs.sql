@@ -0,0 +1,79 @@
+-- Requests for quotation, supplier quotes and per-supplier product price history
+
+CREATE TABLE IF NOT EXISTS rfqs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    rfq_number VARCHAR(50) NOT NULL,
+    title VARCHAR(200),
+    issue_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    response_due DATE,
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN' CHECK (status IN ('OPEN', 'AWARDED', 'CANCELLED')),
+    awarded_supplier_id UUID REFERENCES suppliers(id) ON DELETE SET NULL,
+    purchase_order_id UUID REFERENCES purchase_orders(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, rfq_number)
+);
+CREATE INDEX IF NOT EXISTS idx_rfqs_status ON rfqs(tenant_id, status);
+
+CREATE TABLE IF NOT EXISTS rfq_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    rfq_id UUID NOT NULL REFERENCES rfqs(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    quantity DECIMAL(10, 2) NOT NULL CHECK (quantity > 0),
+    sort_order INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_rfq_lines_rfq ON rfq_lines(rfq_id);
+
+-- Suppliers invited to quote
+CREATE TABLE IF NOT EXISTS rfq_suppliers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    rfq_id UUID NOT NULL REFERENCES rfqs(id) ON DELETE CASCADE,
+    supplier_id UUID NOT NULL REFERENCES suppliers(id) ON DELETE RESTRICT,
+    status VARCHAR(20) NOT NULL DEFAULT 'INVITED'
+        CHECK (status IN ('INVITED', 'RESPONDED', 'DECLINED', 'AWARDED', 'LOST')),
+    responded_at TIMESTAMPTZ,
+    valid_until DATE,
+    notes TEXT,
+    UNIQUE(rfq_id, supplier_id)
+);
+
+-- A supplier's quote for one RFQ line
+CREATE TABLE IF NOT EXISTS rfq_quotes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    rfq_supplier_id UUID NOT NULL REFERENCES rfq_suppliers(id) ON DELETE CASCADE,
+    rfq_line_id UUID NOT NULL REFERENCES rfq_lines(id) ON DELETE CASCADE,
+    unit_price DECIMAL(10, 2) NOT NULL CHECK (unit_price >= 0),
+    lead_time_days INTEGER CHECK (lead_time_days >= 0),
+    min_order_quantity DECIMAL(10, 2) CHECK (min_order_quantity >= 0),
+    notes TEXT,
+    UNIQUE(rfq_supplier_id, rfq_line_id)
+);
+
+-- Every price a supplier quoted or was ordered at; the latest one defaults new PO lines
+CREATE TABLE IF NOT EXISTS supplier_price_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    supplier_id UUID NOT NULL REFERENCES suppliers(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    unit_price DECIMAL(10, 2) NOT NULL,
+    min_order_quantity DECIMAL(10, 2),
+    lead_time_days INTEGER,
+    source VARCHAR(20) NOT NULL CHECK (source IN ('QUOTE', 'PURCHASE_ORDER')),
+    source_id UUID,
+    source_number VARCHAR(50),
+    effective_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_supplier_price_history_lookup
+    ON supplier_price_history(tenant_id, supplier_id, product_id, effective_date DESC, created_at DESC);
+
+-- Start the history from purchase orders already on file
+INSERT INTO supplier_price_history (tenant_id, supplier_id, product_id, unit_price, source, source_id, source_number, effective_date, created_at)
+SELECT po.tenant_id, po.supplier_id, l.product_id, l.unit_price, 'PURCHASE_ORDER', po.id, po.order_number, po.date, po.created_at
+FROM purchase_order_lines l
+JOIN purchase_orders po ON po.id = l.order_id
+WHERE po.status <> 'CANCELLED'
+  AND NOT EXISTS (SELECT 1 FROM supplier_price_history h WHERE h.source_id = po.id AND h.product_id = l.product_id);
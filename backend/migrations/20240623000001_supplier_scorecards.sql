@@ -0,0 +1,16 @@
+-- Supplier scorecards: promised delivery dates on purchase orders
+
+-- Date the supplier promised delivery; on-time delivery is measured against it.
+-- Orders without one are expected one product lead time after they were placed.
+ALTER TABLE purchase_orders ADD COLUMN IF NOT EXISTS expected_date DATE;
+
+UPDATE purchase_orders po
+SET expected_date = po.date + COALESCE((
+    SELECT MAX(p.lead_time_days)
+    FROM purchase_order_lines l
+    JOIN products p ON p.id = l.product_id
+    WHERE l.order_id = po.id
+), 0)
+WHERE po.expected_date IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_purchase_orders_supplier_date ON purchase_orders(tenant_id, supplier_id, date);
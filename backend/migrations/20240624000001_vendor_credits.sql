@@ -0,0 +1,60 @@
+-- Vendor credits: supplier credit notes, returns to vendor and their application against bills
+
+-- Stock sent back to a supplier; stored with a negative quantity
+ALTER TYPE transaction_type ADD VALUE IF NOT EXISTS 'RETURN_TO_VENDOR';
+
+CREATE TABLE IF NOT EXISTS vendor_credits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    supplier_id UUID NOT NULL REFERENCES suppliers(id) ON DELETE RESTRICT,
+    credit_number VARCHAR(50) NOT NULL,
+    supplier_reference VARCHAR(100), -- The supplier's credit note number
+    bill_id UUID REFERENCES bills(id) ON DELETE SET NULL, -- Bill being credited, if any
+    date DATE NOT NULL DEFAULT CURRENT_DATE,
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN' CHECK (status IN ('OPEN', 'PARTIALLY_APPLIED', 'APPLIED')),
+    total_amount DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    amount_applied DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    notes TEXT,
+    journal_entry_id UUID REFERENCES journal_entries(id) ON DELETE SET NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(tenant_id, credit_number)
+);
+CREATE INDEX IF NOT EXISTS idx_vendor_credits_supplier ON vendor_credits(tenant_id, supplier_id, status);
+
+-- A product line credits stock shipped back (returned) or a price allowance; an account line
+-- credits that account.
+CREATE TABLE IF NOT EXISTS vendor_credit_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    credit_id UUID NOT NULL REFERENCES vendor_credits(id) ON DELETE CASCADE,
+    product_id UUID REFERENCES products(id) ON DELETE RESTRICT,
+    account_id UUID REFERENCES accounts(id) ON DELETE RESTRICT,
+    receipt_line_id UUID REFERENCES purchase_receipt_lines(id) ON DELETE SET NULL,
+    inspection_id UUID REFERENCES quality_inspections(id) ON DELETE SET NULL,
+    description TEXT NOT NULL,
+    quantity DECIMAL(10, 2) NOT NULL,
+    unit_price DECIMAL(12, 2) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL,
+    returned BOOLEAN NOT NULL DEFAULT FALSE,
+    transaction_id UUID REFERENCES inventory_transactions(id) ON DELETE SET NULL,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    CHECK ((product_id IS NULL) <> (account_id IS NULL))
+);
+CREATE INDEX IF NOT EXISTS idx_vendor_credit_lines_credit ON vendor_credit_lines(credit_id);
+
+CREATE TABLE IF NOT EXISTS vendor_credit_applications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    credit_id UUID NOT NULL REFERENCES vendor_credits(id) ON DELETE CASCADE,
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    bill_payment_id UUID REFERENCES bill_payments(id) ON DELETE SET NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_vendor_credit_applications_credit ON vendor_credit_applications(credit_id);
+CREATE INDEX IF NOT EXISTS idx_vendor_credit_applications_bill ON vendor_credit_applications(bill_id);
+
+-- Part of a bill payment settled with vendor credits rather than cash
+ALTER TABLE bill_payments ADD COLUMN IF NOT EXISTS credit_amount DECIMAL(12, 2) NOT NULL DEFAULT 0;
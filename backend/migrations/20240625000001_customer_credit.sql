@@ -0,0 +1,16 @@
+-- Customer credit limits and credit holds on sales orders.
+-- A NULL limit means the customer is not credit checked; credit_hold stops every new order.
+ALTER TABLE customers ADD COLUMN IF NOT EXISTS credit_limit DECIMAL(12, 2) CHECK (credit_limit >= 0);
+ALTER TABLE customers ADD COLUMN IF NOT EXISTS credit_hold BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TYPE sales_order_status ADD VALUE IF NOT EXISTS 'ON_HOLD' AFTER 'DRAFT';
+
+-- Held orders wait for a manager to override the credit check
+ALTER TABLE sales_orders ADD COLUMN IF NOT EXISTS hold_reason TEXT;
+ALTER TABLE sales_orders ADD COLUMN IF NOT EXISTS credit_override_by UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE sales_orders ADD COLUMN IF NOT EXISTS credit_override_at TIMESTAMPTZ;
+ALTER TABLE sales_orders ADD COLUMN IF NOT EXISTS credit_override_reason TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_sales_orders_customer ON sales_orders(customer_id, date);
+CREATE INDEX IF NOT EXISTS idx_invoices_customer ON invoices(customer_id, status);
+CREATE INDEX IF NOT EXISTS idx_credit_memos_customer ON credit_memos(customer_id, status);
@@ -0,0 +1,85 @@
+-- Structured addresses and contacts for customers, suppliers and employees.
+-- Each row belongs to exactly one party; one address per type and one contact can be the default.
+CREATE TABLE party_addresses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    customer_id UUID REFERENCES customers(id) ON DELETE CASCADE,
+    supplier_id UUID REFERENCES suppliers(id) ON DELETE CASCADE,
+    employee_id UUID REFERENCES employees(id) ON DELETE CASCADE,
+    address_type VARCHAR(20) NOT NULL CHECK (address_type IN ('BILLING', 'SHIPPING', 'REMIT_TO', 'HOME')),
+    label VARCHAR(100),
+    line1 VARCHAR(255) NOT NULL,
+    line2 VARCHAR(255),
+    city VARCHAR(100),
+    region VARCHAR(100),
+    postal_code VARCHAR(20),
+    country VARCHAR(100),
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (num_nonnulls(customer_id, supplier_id, employee_id) = 1)
+);
+
+CREATE INDEX idx_party_addresses_customer ON party_addresses(customer_id) WHERE customer_id IS NOT NULL;
+CREATE INDEX idx_party_addresses_supplier ON party_addresses(supplier_id) WHERE supplier_id IS NOT NULL;
+CREATE INDEX idx_party_addresses_employee ON party_addresses(employee_id) WHERE employee_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_party_addresses_default
+    ON party_addresses(COALESCE(customer_id, supplier_id, employee_id), address_type) WHERE is_default;
+
+CREATE TABLE party_contacts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    customer_id UUID REFERENCES customers(id) ON DELETE CASCADE,
+    supplier_id UUID REFERENCES suppliers(id) ON DELETE CASCADE,
+    employee_id UUID REFERENCES employees(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    role VARCHAR(20) NOT NULL DEFAULT 'OTHER'
+        CHECK (role IN ('PRIMARY', 'BILLING', 'PURCHASING', 'SALES', 'SHIPPING', 'EMERGENCY', 'OTHER')),
+    title VARCHAR(100),
+    email VARCHAR(255),
+    phone VARCHAR(50),
+    is_primary BOOLEAN NOT NULL DEFAULT FALSE,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (num_nonnulls(customer_id, supplier_id, employee_id) = 1)
+);
+
+CREATE INDEX idx_party_contacts_customer ON party_contacts(customer_id) WHERE customer_id IS NOT NULL;
+CREATE INDEX idx_party_contacts_supplier ON party_contacts(supplier_id) WHERE supplier_id IS NOT NULL;
+CREATE INDEX idx_party_contacts_employee ON party_contacts(employee_id) WHERE employee_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_party_contacts_primary
+    ON party_contacts(COALESCE(customer_id, supplier_id, employee_id)) WHERE is_primary;
+
+-- The old free-text address becomes each party's default address
+INSERT INTO party_addresses (tenant_id, customer_id, address_type, line1, is_default)
+SELECT tenant_id, id, 'BILLING', TRIM(address), TRUE FROM customers WHERE NULLIF(TRIM(address), '') IS NOT NULL;
+INSERT INTO party_addresses (tenant_id, supplier_id, address_type, line1, is_default)
+SELECT tenant_id, id, 'REMIT_TO', TRIM(address), TRUE FROM suppliers WHERE NULLIF(TRIM(address), '') IS NOT NULL;
+INSERT INTO party_addresses (tenant_id, employee_id, address_type, line1, is_default)
+SELECT tenant_id, id, 'HOME', TRIM(address), TRUE FROM employees WHERE NULLIF(TRIM(address), '') IS NOT NULL;
+
+ALTER TABLE customers DROP COLUMN address;
+ALTER TABLE suppliers DROP COLUMN address;
+ALTER TABLE employees DROP COLUMN address;
+
+-- Documents keep a copy of the addresses chosen so later edits don't rewrite them
+ALTER TABLE sales_orders ADD COLUMN IF NOT EXISTS billing_address_id UUID REFERENCES party_addresses(id) ON DELETE SET NULL;
+ALTER TABLE sales_orders ADD COLUMN IF NOT EXISTS billing_address TEXT;
+ALTER TABLE sales_orders ADD COLUMN IF NOT EXISTS shipping_address_id UUID REFERENCES party_addresses(id) ON DELETE SET NULL;
+ALTER TABLE sales_orders ADD COLUMN IF NOT EXISTS shipping_address TEXT;
+
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS billing_address_id UUID REFERENCES party_addresses(id) ON DELETE SET NULL;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS billing_address TEXT;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS shipping_address_id UUID REFERENCES party_addresses(id) ON DELETE SET NULL;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS shipping_address TEXT;
+
+ALTER TABLE purchase_orders ADD COLUMN IF NOT EXISTS remit_to_address_id UUID REFERENCES party_addresses(id) ON DELETE SET NULL;
+ALTER TABLE purchase_orders ADD COLUMN IF NOT EXISTS remit_to_address TEXT;
+
+UPDATE sales_orders o SET billing_address_id = a.id, billing_address = a.line1
+FROM party_addresses a WHERE a.customer_id = o.customer_id AND a.is_default;
+UPDATE invoices i SET billing_address_id = a.id, billing_address = a.line1
+FROM party_addresses a WHERE a.customer_id = i.customer_id AND a.is_default;
+UPDATE purchase_orders o SET remit_to_address_id = a.id, remit_to_address = a.line1
+FROM party_addresses a WHERE a.supplier_id = o.supplier_id AND a.is_default;
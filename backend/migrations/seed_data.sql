@@ -10,8 +10,12 @@ INSERT INTO suppliers (id, tenant_id, name, email, contact_person) VALUES
 ON CONFLICT DO NOTHING;
 
 -- Customer
-INSERT INTO customers (id, tenant_id, name, email, address) VALUES
-('33333333-3333-3333-3333-333333333333', '11111111-1111-1111-1111-111111111111', 'Luxury Shoes Ltd', 'buyer@shoes.com', '123 Fashion Ave')
+INSERT INTO customers (id, tenant_id, name, email) VALUES
+('33333333-3333-3333-3333-333333333333', '11111111-1111-1111-1111-111111111111', 'Luxury Shoes Ltd', 'buyer@shoes.com')
+ON CONFLICT DO NOTHING;
+
+INSERT INTO party_addresses (tenant_id, customer_id, address_type, line1, is_default) VALUES
+('11111111-1111-1111-1111-111111111111', '33333333-3333-3333-3333-333333333333', 'BILLING', '123 Fashion Ave', TRUE)
 ON CONFLICT DO NOTHING;
 
 -- Products
@@ -10,11 +10,18 @@ INSERT INTO suppliers (id, tenant_id, name, email, contact_person) VALUES
 ON CONFLICT DO NOTHING;
 
 -- 2. Customers
-INSERT INTO customers (id, tenant_id, name, email, address) VALUES
-('33333333-0001-0000-0000-000000000001', '11111111-1111-1111-1111-111111111111', 'Luxury Auto Interiors', 'purchasing@luxauto.com', 'Detroit, MI'),
-('33333333-0002-0000-0000-000000000002', '11111111-1111-1111-1111-111111111111', 'Italian Shoe Crafters', 'mario@shoes.it', 'Milan, Italy'),
-('33333333-0003-0000-0000-000000000003', '11111111-1111-1111-1111-111111111111', 'Classic Furniture Works', 'supply@classicworks.com', 'High Point, NC'),
-('33333333-0004-0000-0000-000000000004', '11111111-1111-1111-1111-111111111111', 'Bespoke Bags NYC', 'design@bespokebags.com', 'New York, NY')
+INSERT INTO customers (id, tenant_id, name, email) VALUES
+('33333333-0001-0000-0000-000000000001', '11111111-1111-1111-1111-111111111111', 'Luxury Auto Interiors', 'purchasing@luxauto.com'),
+('33333333-0002-0000-0000-000000000002', '11111111-1111-1111-1111-111111111111', 'Italian Shoe Crafters', 'mario@shoes.it'),
+('33333333-0003-0000-0000-000000000003', '11111111-1111-1111-1111-111111111111', 'Classic Furniture Works', 'supply@classicworks.com'),
+('33333333-0004-0000-0000-000000000004', '11111111-1111-1111-1111-111111111111', 'Bespoke Bags NYC', 'design@bespokebags.com')
+ON CONFLICT DO NOTHING;
+
+INSERT INTO party_addresses (tenant_id, customer_id, address_type, line1, city, region, country, is_default) VALUES
+('11111111-1111-1111-1111-111111111111', '33333333-0001-0000-0000-000000000001', 'BILLING', '1200 Woodward Ave', 'Detroit', 'MI', 'USA', TRUE),
+('11111111-1111-1111-1111-111111111111', '33333333-0002-0000-0000-000000000002', 'BILLING', 'Via Montenapoleone 8', 'Milan', NULL, 'Italy', TRUE),
+('11111111-1111-1111-1111-111111111111', '33333333-0003-0000-0000-000000000003', 'BILLING', '210 N Main St', 'High Point', 'NC', 'USA', TRUE),
+('11111111-1111-1111-1111-111111111111', '33333333-0004-0000-0000-000000000004', 'BILLING', '350 5th Ave', 'New York', 'NY', 'USA', TRUE)
 ON CONFLICT DO NOTHING;
 
 -- 3. Products
@@ -1,15 +1,21 @@
 'use client';
 import { Title, Table, Paper, Group, Text, Badge, SimpleGrid, Card, ThemeIcon, TextInput, Button } from '@mantine/core';
 import { IconFileText, IconPlus, IconSearch } from '@tabler/icons-react';
-import { useQuery } from '@tanstack/react-query';
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
 import { apiClient } from '../api/client';
 import { useState } from 'react';
 
 const STATUS_COLORS: Record<string, string> = { OPEN: 'blue', PARTIALLY_PAID: 'yellow', PAID: 'green', OVERDUE: 'red' };
+const MATCH_COLORS: Record<string, string> = { Unmatched: 'gray', Matched: 'green', Exception: 'red', Approved: 'teal' };
 
 export function Bills() {
     const [search, setSearch] = useState('');
+    const queryClient = useQueryClient();
     const { data: bills = [] } = useQuery({ queryKey: ['bills'], queryFn: async () => (await apiClient.get('/bills')).data });
+    const approveMatch = useMutation({
+        mutationFn: async (id: string) => (await apiClient.post(`/bills/${id}/approve-match`)).data,
+        onSuccess: () => queryClient.invalidateQueries({ queryKey: ['bills'] }),
+    });
 
     const filtered = bills.filter((b: any) => b.bill_number.toLowerCase().includes(search.toLowerCase()));
     const totalOwed = bills.reduce((s: number, b: any) => s + Number(b.total_amount) - Number(b.amount_paid), 0);
@@ -33,7 +39,7 @@ export function Bills() {
             <TextInput placeholder="Search bills..." leftSection={<IconSearch size={16} />} value={search} onChange={e => setSearch(e.currentTarget.value)} mb="md" />
             <Paper withBorder radius="md">
                 <Table striped highlightOnHover>
-                    <Table.Thead><Table.Tr><Table.Th>Bill #</Table.Th><Table.Th>Date</Table.Th><Table.Th>Due Date</Table.Th><Table.Th>Total</Table.Th><Table.Th>Paid</Table.Th><Table.Th>Balance</Table.Th><Table.Th>Status</Table.Th></Table.Tr></Table.Thead>
+                    <Table.Thead><Table.Tr><Table.Th>Bill #</Table.Th><Table.Th>Date</Table.Th><Table.Th>Due Date</Table.Th><Table.Th>Total</Table.Th><Table.Th>Paid</Table.Th><Table.Th>Balance</Table.Th><Table.Th>Status</Table.Th><Table.Th>Match</Table.Th></Table.Tr></Table.Thead>
                     <Table.Tbody>
                         {filtered.map((b: any) => (
                             <Table.Tr key={b.id}>
@@ -44,9 +50,15 @@ export function Bills() {
                                 <Table.Td>${Number(b.amount_paid).toLocaleString()}</Table.Td>
                                 <Table.Td fw={600} c={Number(b.total_amount) - Number(b.amount_paid) > 0 ? 'red' : 'green'}>${(Number(b.total_amount) - Number(b.amount_paid)).toLocaleString()}</Table.Td>
                                 <Table.Td><Badge color={STATUS_COLORS[b.status] || 'gray'} variant="light" size="sm">{b.status}</Badge></Table.Td>
+                                <Table.Td>
+                                    <Group gap="xs">
+                                        <Badge color={MATCH_COLORS[b.match_status] || 'gray'} variant="light" size="sm">{b.match_status}</Badge>
+                                        {b.match_status === 'Exception' && <Button size="compact-xs" variant="light" loading={approveMatch.isPending} onClick={() => approveMatch.mutate(b.id)}>Approve</Button>}
+                                    </Group>
+                                </Table.Td>
                             </Table.Tr>
                         ))}
-                        {filtered.length === 0 && <Table.Tr><Table.Td colSpan={7}><Text ta="center" c="dimmed" py="xl">No bills found</Text></Table.Td></Table.Tr>}
+                        {filtered.length === 0 && <Table.Tr><Table.Td colSpan={8}><Text ta="center" c="dimmed" py="xl">No bills found</Text></Table.Td></Table.Tr>}
                     </Table.Tbody>
                 </Table>
             </Paper>
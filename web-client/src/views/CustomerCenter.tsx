@@ -29,11 +29,21 @@ export function CustomerCenter() {
   });
 
   const createMutation = useMutation({
-    mutationFn: (data: any) => apiClient.post('/sales/customers', data),
+    mutationFn: ({ address, ...data }: any) => apiClient.post('/sales/customers', {
+      ...data,
+      addresses: address ? [{ address_type: 'Billing', line1: address }] : [],
+    }),
     onSuccess: () => { queryClient.invalidateQueries({ queryKey: ['customers'] }); setAddOpen(false); setForm({ name: '', email: '', phone: '', address: '' }); },
   });
 
   const selected = customers.find((c: any) => c.id === selectedId) || customers[0];
+
+  const { data: addresses = [] } = useQuery({
+    queryKey: ['customer-addresses', selected?.id],
+    queryFn: async () => (await apiClient.get(`/sales/customers/${selected.id}/addresses`)).data,
+    enabled: !!selected,
+  });
+  const billingAddress = addresses.find((a: any) => a.address_type === 'Billing' && a.is_default);
   const filteredCustomers = customers.filter((c: any) =>
     c.name.toLowerCase().includes(search.toLowerCase())
   );
@@ -111,7 +121,7 @@ export function CustomerCenter() {
                     <Group mt="xs" gap="xl">
                       <Group gap={5}><IconMail size={16} color="gray" /><Text size="sm">{selected.email || '—'}</Text></Group>
                       <Group gap={5}><IconPhone size={16} color="gray" /><Text size="sm">{selected.phone || '—'}</Text></Group>
-                      <Group gap={5}><IconMapPin size={16} color="gray" /><Text size="sm">{selected.address || '—'}</Text></Group>
+                      <Group gap={5}><IconMapPin size={16} color="gray" /><Text size="sm">{billingAddress ? [billingAddress.line1, billingAddress.city].filter(Boolean).join(', ') : '—'}</Text></Group>
                     </Group>
                   </div>
                   <Stack align="flex-end" gap={0}>
@@ -164,7 +174,7 @@ export function CustomerCenter() {
           <TextInput label="Company Name" required value={form.name} onChange={(e) => setForm({ ...form, name: e.currentTarget.value })} />
           <TextInput label="Email" value={form.email} onChange={(e) => setForm({ ...form, email: e.currentTarget.value })} />
           <TextInput label="Phone" value={form.phone} onChange={(e) => setForm({ ...form, phone: e.currentTarget.value })} />
-          <TextInput label="Address" value={form.address} onChange={(e) => setForm({ ...form, address: e.currentTarget.value })} />
+          <TextInput label="Billing Address" value={form.address} onChange={(e) => setForm({ ...form, address: e.currentTarget.value })} />
           <Button onClick={() => createMutation.mutate(form)} loading={createMutation.isPending} disabled={!form.name}>
             Create Customer
           </Button>
@@ -1,5 +1,5 @@
 'use client';
-import { Title, Table, Paper, Group, Text, Badge, SimpleGrid, Card, ThemeIcon, TextInput, Tabs } from '@mantine/core';
+import { Title, Table, Paper, Group, Text, Badge, SimpleGrid, Card, ThemeIcon, TextInput, Tabs, Select } from '@mantine/core';
 import { IconBox, IconSearch, IconArrowsTransferDown, IconArrowsTransferUp } from '@tabler/icons-react';
 import { useQuery } from '@tanstack/react-query';
 import { apiClient } from '../api/client';
@@ -7,10 +7,20 @@ import { useState } from 'react';
 
 export function InventoryActivities() {
     const [search, setSearch] = useState('');
+    const [cardProduct, setCardProduct] = useState<string | null>(null);
+    const [from, setFrom] = useState('');
+    const [to, setTo] = useState('');
+    const [asOf, setAsOf] = useState('');
+    const range = { from: from || undefined, to: to || undefined };
     const { data: products = [] } = useQuery({ queryKey: ['inv-products'], queryFn: async () => (await apiClient.get('/products')).data });
+    const { data: movements = [] } = useQuery({ queryKey: ['inv-movements', from, to], queryFn: async () => (await apiClient.get('/inventory/movements', { params: range })).data });
+    const { data: summary } = useQuery({ queryKey: ['inv-movement-summary', from, to], queryFn: async () => (await apiClient.get('/inventory/movements/summary', { params: range })).data });
+    const { data: stockCard } = useQuery({ queryKey: ['inv-stock-card', cardProduct, from, to], enabled: !!cardProduct, queryFn: async () => (await apiClient.get(`/products/${cardProduct}/stock-card`, { params: range })).data });
+    const { data: onHand } = useQuery({ queryKey: ['inv-stock-on-hand', asOf], queryFn: async () => (await apiClient.get('/inventory/stock-on-hand', { params: { date: asOf || undefined } })).data });
+    const valuation = onHand?.lines || [];
 
     const filtered = products.filter((p: any) => p.name.toLowerCase().includes(search.toLowerCase()) || (p.sku || '').toLowerCase().includes(search.toLowerCase()));
-    const totalValue = products.reduce((s: number, p: any) => s + Number(p.stock_quantity || 0) * Number(p.unit_price || 0), 0);
+    const totalValue = Number(onHand?.total_value || 0);
     const lowStock = products.filter((p: any) => Number(p.stock_quantity || 0) <= Number(p.reorder_point || 5) && Number(p.stock_quantity || 0) > 0).length;
     const outOfStock = products.filter((p: any) => Number(p.stock_quantity || 0) === 0).length;
 
@@ -26,6 +36,8 @@ export function InventoryActivities() {
             <Tabs defaultValue="stock">
                 <Tabs.List mb="md">
                     <Tabs.Tab value="stock">Stock Status</Tabs.Tab>
+                    <Tabs.Tab value="activity">Activity</Tabs.Tab>
+                    <Tabs.Tab value="card">Stock Card</Tabs.Tab>
                     <Tabs.Tab value="valuation">Inventory Valuation</Tabs.Tab>
                 </Tabs.List>
                 <Tabs.Panel value="stock">
@@ -54,19 +66,86 @@ export function InventoryActivities() {
                         </Table>
                     </Paper>
                 </Tabs.Panel>
+                <Tabs.Panel value="activity">
+                    <Group mb="md">
+                        <TextInput type="date" label="From" value={from} onChange={e => setFrom(e.currentTarget.value)} />
+                        <TextInput type="date" label="To" value={to} onChange={e => setTo(e.currentTarget.value)} />
+                    </Group>
+                    <SimpleGrid cols={5} mb="md">
+                        {(summary?.lines || []).map((l: any) => (
+                            <Card key={l.transaction_type} withBorder radius="md" p="sm">
+                                <Text size="xs" tt="uppercase" c="dimmed" fw={700}>{l.transaction_type.replace(/_/g, ' ')}</Text>
+                                <Text size="lg" fw={700} c={Number(l.net_quantity) < 0 ? 'red' : 'green'}>{Number(l.net_quantity) > 0 ? '+' : ''}{Number(l.net_quantity)}</Text>
+                                <Text size="xs" c="dimmed">{l.transaction_count} movements</Text>
+                            </Card>
+                        ))}
+                    </SimpleGrid>
+                    <Paper withBorder radius="md">
+                        <Table striped highlightOnHover>
+                            <Table.Thead><Table.Tr><Table.Th>Date</Table.Th><Table.Th>Product</Table.Th><Table.Th>Type</Table.Th><Table.Th>Reference</Table.Th><Table.Th style={{ textAlign: 'right' }}>Qty</Table.Th><Table.Th style={{ textAlign: 'right' }}>Balance</Table.Th></Table.Tr></Table.Thead>
+                            <Table.Tbody>
+                                {[...movements].reverse().map((m: any) => (
+                                    <Table.Tr key={m.transaction_id}>
+                                        <Table.Td>{new Date(m.created_at).toLocaleString()}</Table.Td>
+                                        <Table.Td fw={500}>{m.product_name}</Table.Td>
+                                        <Table.Td><Badge variant="light" size="sm">{m.transaction_type.replace(/_/g, ' ')}</Badge></Table.Td>
+                                        <Table.Td><Text size="sm" c="dimmed">{m.reference_number || m.notes || '—'}</Text></Table.Td>
+                                        <Table.Td style={{ textAlign: 'right' }} fw={600} c={Number(m.quantity) < 0 ? 'red' : 'green'}>{Number(m.quantity)}</Table.Td>
+                                        <Table.Td style={{ textAlign: 'right' }}>{Number(m.running_balance)}</Table.Td>
+                                    </Table.Tr>
+                                ))}
+                                {movements.length === 0 && <Table.Tr><Table.Td colSpan={6}><Text ta="center" c="dimmed" py="xl">No inventory movements in this period</Text></Table.Td></Table.Tr>}
+                            </Table.Tbody>
+                        </Table>
+                    </Paper>
+                </Tabs.Panel>
+                <Tabs.Panel value="card">
+                    <Group mb="md" align="flex-end">
+                        <Select label="Product" placeholder="Choose a product" searchable w={320} data={products.map((p: any) => ({ value: p.id, label: `${p.sku} — ${p.name}` }))} value={cardProduct} onChange={setCardProduct} />
+                        <TextInput type="date" label="From" value={from} onChange={e => setFrom(e.currentTarget.value)} />
+                        <TextInput type="date" label="To" value={to} onChange={e => setTo(e.currentTarget.value)} />
+                    </Group>
+                    {stockCard && (
+                        <Paper withBorder radius="md">
+                            <Table striped highlightOnHover>
+                                <Table.Thead><Table.Tr><Table.Th>Date</Table.Th><Table.Th>Type</Table.Th><Table.Th>Reference</Table.Th><Table.Th style={{ textAlign: 'right' }}>In</Table.Th><Table.Th style={{ textAlign: 'right' }}>Out</Table.Th><Table.Th style={{ textAlign: 'right' }}>Balance</Table.Th></Table.Tr></Table.Thead>
+                                <Table.Tbody>
+                                    <Table.Tr><Table.Td fw={700} colSpan={5}>Opening balance ({stockCard.from})</Table.Td><Table.Td style={{ textAlign: 'right' }} fw={700}>{Number(stockCard.opening_balance)}</Table.Td></Table.Tr>
+                                    {stockCard.movements.map((m: any) => (
+                                        <Table.Tr key={m.transaction_id}>
+                                            <Table.Td>{new Date(m.created_at).toLocaleString()}</Table.Td>
+                                            <Table.Td><Badge variant="light" size="sm">{m.transaction_type.replace(/_/g, ' ')}</Badge></Table.Td>
+                                            <Table.Td><Text size="sm" c="dimmed">{m.reference_number || m.notes || '—'}{m.reason_code ? ` (${m.reason_code})` : ''}</Text></Table.Td>
+                                            <Table.Td style={{ textAlign: 'right' }}>{Number(m.quantity) > 0 ? Number(m.quantity) : ''}</Table.Td>
+                                            <Table.Td style={{ textAlign: 'right' }}>{Number(m.quantity) < 0 ? -Number(m.quantity) : ''}</Table.Td>
+                                            <Table.Td style={{ textAlign: 'right' }} fw={600}>{Number(m.running_balance)}</Table.Td>
+                                        </Table.Tr>
+                                    ))}
+                                    <Table.Tr style={{ borderTop: '3px double var(--mantine-color-dark-4)', background: 'var(--mantine-color-dark-6)' }}>
+                                        <Table.Td fw={700} colSpan={3}>Closing balance ({stockCard.to})</Table.Td>
+                                        <Table.Td style={{ textAlign: 'right' }} fw={700}>{Number(stockCard.total_in)}</Table.Td>
+                                        <Table.Td style={{ textAlign: 'right' }} fw={700}>{Number(stockCard.total_out)}</Table.Td>
+                                        <Table.Td style={{ textAlign: 'right' }} fw={700}>{Number(stockCard.closing_balance)}</Table.Td>
+                                    </Table.Tr>
+                                </Table.Tbody>
+                            </Table>
+                        </Paper>
+                    )}
+                </Tabs.Panel>
                 <Tabs.Panel value="valuation">
+                    <TextInput type="date" label="As of" value={asOf} onChange={e => setAsOf(e.currentTarget.value)} mb="md" w={200} />
                     <Paper withBorder radius="md">
                         <Table striped highlightOnHover>
                             <Table.Thead><Table.Tr><Table.Th>Product</Table.Th><Table.Th style={{ textAlign: 'right' }}>Qty</Table.Th><Table.Th style={{ textAlign: 'right' }}>Unit Cost</Table.Th><Table.Th style={{ textAlign: 'right' }}>Total Value</Table.Th><Table.Th style={{ textAlign: 'right' }}>% of Total</Table.Th></Table.Tr></Table.Thead>
                             <Table.Tbody>
-                                {products.map((p: any) => {
-                                    const val = Number(p.stock_quantity || 0) * Number(p.unit_price || 0);
+                                {valuation.map((p: any) => {
+                                    const val = Number(p.value || 0);
                                     const pct = totalValue > 0 ? ((val / totalValue) * 100).toFixed(1) : '0.0';
                                     return (
-                                        <Table.Tr key={p.id}>
+                                        <Table.Tr key={p.product_id}>
                                             <Table.Td fw={500}>{p.name}</Table.Td>
-                                            <Table.Td style={{ textAlign: 'right' }}>{Number(p.stock_quantity || 0)}</Table.Td>
-                                            <Table.Td style={{ textAlign: 'right' }}>${Number(p.unit_price || 0).toLocaleString()}</Table.Td>
+                                            <Table.Td style={{ textAlign: 'right' }}>{Number(p.quantity || 0)}</Table.Td>
+                                            <Table.Td style={{ textAlign: 'right' }}>${Number(p.unit_cost || 0).toLocaleString()}</Table.Td>
                                             <Table.Td style={{ textAlign: 'right' }} fw={600}>${val.toLocaleString()}</Table.Td>
                                             <Table.Td style={{ textAlign: 'right' }}><Badge variant="light" size="sm">{pct}%</Badge></Table.Td>
                                         </Table.Tr>
@@ -12,7 +12,6 @@ interface Supplier {
   name: string;
   email: string | null;
   phone: string | null;
-  address: string | null;
   contact_person: string | null;
 }
 
@@ -75,7 +74,10 @@ export function Purchasing() {
   const [form, setForm] = useState({ name: '', email: '', phone: '', address: '', contact_person: '' });
 
   const createMut = useMutation({
-    mutationFn: (data: any) => apiClient.post('/purchasing/suppliers', data),
+    mutationFn: ({ address, ...data }: any) => apiClient.post('/purchasing/suppliers', {
+      ...data,
+      addresses: address ? [{ address_type: 'RemitTo', line1: address }] : [],
+    }),
     onSuccess: () => { qc.invalidateQueries({ queryKey: ['suppliers'] }); setAddOpen(false); setForm({ name: '', email: '', phone: '', address: '', contact_person: '' }); },
   });
 
@@ -142,7 +144,7 @@ export function Purchasing() {
           <TextInput label="Contact Person" value={form.contact_person} onChange={(e: any) => setForm({ ...form, contact_person: e.currentTarget.value })} />
           <TextInput label="Email" value={form.email} onChange={(e: any) => setForm({ ...form, email: e.currentTarget.value })} />
           <TextInput label="Phone" value={form.phone} onChange={(e: any) => setForm({ ...form, phone: e.currentTarget.value })} />
-          <TextInput label="Address" value={form.address} onChange={(e: any) => setForm({ ...form, address: e.currentTarget.value })} />
+          <TextInput label="Remit-to Address" value={form.address} onChange={(e: any) => setForm({ ...form, address: e.currentTarget.value })} />
           <Button onClick={() => createMut.mutate(form)} loading={createMut.isPending} disabled={!form.name}>Create Vendor</Button>
         </Stack>
       </Modal>